@@ -10,8 +10,20 @@
 // - 需要启用 `doubao-ime` feature
 // - 需要安装 CMake (Windows: choco install cmake)
 
+use super::doubao_ime_credentials::{is_credential_error, DoubaoImeCredentialStore};
+use super::engine::{AsrEngine, PartialTranscriptReceiver, RealtimeAsrSession};
+use super::AsrTranscript;
+use crate::config::AsrProvider;
 use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
 
+#[cfg(feature = "doubao-ime")]
+use super::doubao_ime_credentials::CredentialAction;
+#[cfg(feature = "doubao-ime")]
+use super::engine::{partial_transcript_channel, publish_partial};
+#[cfg(feature = "doubao-ime")]
+use super::AsrErrorKind;
 #[cfg(feature = "doubao-ime")]
 use futures_util::{SinkExt, StreamExt};
 #[cfg(feature = "doubao-ime")]
@@ -82,7 +94,7 @@ impl Default for DoubaoImeClientConfig {
 }
 
 /// 设备凭据
-#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct DeviceCredentials {
     pub device_id: String,
     pub install_id: String,
@@ -112,6 +124,14 @@ impl DoubaoImeRealtimeSession {
     pub async fn wait_for_result(&mut self) -> Result<String> {
         Err(anyhow!("豆包输入法 ASR 功能未启用。"))
     }
+
+    pub fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        None
+    }
+
+    pub async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(not(feature = "doubao-ime"))]
@@ -150,6 +170,14 @@ impl DoubaoImeRealtimeClient {
     }
 }
 
+#[cfg(not(feature = "doubao-ime"))]
+pub async fn ensure_stored_credentials(
+    _store: &DoubaoImeCredentialStore,
+    _client: &reqwest::Client,
+) -> Result<DeviceCredentials> {
+    Err(anyhow!("豆包输入法 ASR 功能未启用。"))
+}
+
 #[cfg(not(feature = "doubao-ime"))]
 pub struct DoubaoImeClient;
 
@@ -173,6 +201,12 @@ impl DoubaoImeClient {
         ))
     }
 
+    pub async fn transcribe_wav_bytes(&mut self, _wav_bytes: &[u8]) -> Result<String> {
+        Err(anyhow!(
+            "豆包输入法 ASR 功能未启用。请使用 `--features doubao-ime` 编译，并确保已安装 CMake。"
+        ))
+    }
+
     pub async fn transcribe_pcm(&mut self, _pcm_bytes: &[u8]) -> Result<String> {
         Err(anyhow!("豆包输入法 ASR 功能未启用。"))
     }
@@ -183,6 +217,7 @@ impl DoubaoImeClient {
 #[cfg(feature = "doubao-ime")]
 mod implementation {
     use super::*;
+    use crate::audio_utils::resample_i16;
     use opus;
 
     // ==================== Protobuf 消息定义 ====================
@@ -591,6 +626,73 @@ mod implementation {
         Ok(token)
     }
 
+    /// 按凭据存储的生命周期取得可用凭据：Token 到期前刷新，设备失效时（受退避限制）重新注册
+    pub async fn ensure_stored_credentials(
+        store: &DoubaoImeCredentialStore,
+        client: &reqwest::Client,
+    ) -> Result<DeviceCredentials> {
+        let _guard = store.lock_refresh().await;
+        match store.next_action() {
+            CredentialAction::Ready(creds) => Ok(creds),
+            CredentialAction::RefreshToken(mut creds) => {
+                tracing::info!("豆包输入法 ASR: 刷新 Token...");
+                match get_asr_token(client, &creds.device_id, &creds.cdid).await {
+                    Ok(token) => {
+                        tracing::info!("豆包输入法 ASR: Token 刷新成功");
+                        store.record_token(token.clone());
+                        creds.token = token;
+                        Ok(creds)
+                    }
+                    Err(e) if !creds.token.is_empty() => {
+                        tracing::warn!(
+                            "豆包输入法 ASR: 刷新 Token 失败，继续使用现有 Token: {}",
+                            e
+                        );
+                        Ok(creds)
+                    }
+                    Err(e) => {
+                        // 网络问题不代表设备失效，其余错误视为设备已不可用
+                        if !matches!(
+                            AsrErrorKind::classify(&e),
+                            AsrErrorKind::Network | AsrErrorKind::Timeout
+                        ) {
+                            store.discard_device();
+                        }
+                        Err(e)
+                    }
+                }
+            }
+            CredentialAction::Register => {
+                tracing::info!("豆包输入法 ASR: 注册新设备...");
+                store.record_register_attempt();
+                let registered = async {
+                    let mut creds = register_device(client).await?;
+                    creds.token = get_asr_token(client, &creds.device_id, &creds.cdid).await?;
+                    Ok::<_, anyhow::Error>(creds)
+                }
+                .await;
+                match registered {
+                    Ok(creds) => {
+                        tracing::info!(
+                            "豆包输入法 ASR: 设备注册成功，device_id={}",
+                            creds.device_id
+                        );
+                        store.record_registered(creds.clone());
+                        Ok(creds)
+                    }
+                    Err(e) => {
+                        store.record_register_failure();
+                        Err(e)
+                    }
+                }
+            }
+            CredentialAction::Backoff(remaining) => Err(anyhow!(
+                "豆包输入法设备注册过于频繁，请 {} 秒后重试",
+                remaining.as_secs().max(1)
+            )),
+        }
+    }
+
     fn build_register_params(cdid: &str) -> Vec<(&'static str, String)> {
         vec![
             ("device_platform", "android".to_string()),
@@ -671,6 +773,8 @@ mod implementation {
     pub struct DoubaoImeRealtimeSession {
         sender: mpsc::Sender<SessionCommand>,
         result_receiver: mpsc::Receiver<Result<String>>,
+        partial_receiver: PartialTranscriptReceiver,
+        finished: bool,
     }
 
     enum SessionCommand {
@@ -688,12 +792,21 @@ mod implementation {
         }
 
         pub async fn finish_audio(&mut self) -> Result<()> {
+            self.finished = true;
             self.sender
                 .send(SessionCommand::Finish)
                 .await
                 .map_err(|_| anyhow!("发送结束标志失败"))
         }
 
+        /// 放弃会话：尚未结束时补发结束标志让服务端断开
+        pub async fn close(&mut self) -> Result<()> {
+            if self.finished {
+                return Ok(());
+            }
+            self.finish_audio().await
+        }
+
         pub async fn wait_for_result(&mut self) -> Result<String> {
             match timeout(
                 Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS),
@@ -706,6 +819,10 @@ mod implementation {
                 Err(_) => Err(anyhow!("转录超时")),
             }
         }
+
+        pub fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+            Some(self.partial_receiver.clone())
+        }
     }
 
     // ==================== 实时客户端 ====================
@@ -794,10 +911,18 @@ mod implementation {
 
             let (cmd_tx, cmd_rx) = mpsc::channel::<SessionCommand>(100);
             let (result_tx, result_rx) = mpsc::channel::<Result<String>>(1);
+            let (partial_tx, partial_rx) = partial_transcript_channel();
 
             tokio::spawn(async move {
-                if let Err(e) =
-                    run_session(ws_stream, cmd_rx, result_tx.clone(), creds, config).await
+                if let Err(e) = run_session(
+                    ws_stream,
+                    cmd_rx,
+                    result_tx.clone(),
+                    partial_tx,
+                    creds,
+                    config,
+                )
+                .await
                 {
                     tracing::error!("豆包输入法会话错误: {}", e);
                     let _ = result_tx.send(Err(e)).await;
@@ -807,6 +932,8 @@ mod implementation {
             Ok(DoubaoImeRealtimeSession {
                 sender: cmd_tx,
                 result_receiver: result_rx,
+                partial_receiver: partial_rx,
+                finished: false,
             })
         }
     }
@@ -823,6 +950,7 @@ mod implementation {
         >,
         mut cmd_rx: mpsc::Receiver<SessionCommand>,
         result_tx: mpsc::Sender<Result<String>>,
+        partial_tx: tokio::sync::watch::Sender<String>,
         creds: DeviceCredentials,
         config: DoubaoImeClientConfig,
     ) -> Result<()> {
@@ -1046,9 +1174,11 @@ mod implementation {
                                             if is_final {
                                                 final_text = candidate_text;
                                                 has_final_result = true;
+                                                publish_partial(&partial_tx, &final_text);
                                                 tracing::info!("豆包输入法 ASR: ✓ 最终结果: {}", final_text);
                                             } else if !has_final_result {
                                                 final_text = candidate_text;
+                                                publish_partial(&partial_tx, &final_text);
                                                 tracing::debug!("豆包输入法 ASR: 更新中间结果: {}", final_text);
                                             }
                                         }
@@ -1111,7 +1241,13 @@ mod implementation {
         }
 
         pub async fn transcribe_wav(&mut self, wav_path: &std::path::Path) -> Result<String> {
-            let mut reader = hound::WavReader::open(wav_path)?;
+            let wav_bytes = std::fs::read(wav_path)?;
+            self.transcribe_wav_bytes(&wav_bytes).await
+        }
+
+        /// 转录内存中的 WAV 数据（16-bit PCM，非 16kHz 时自动重采样）
+        pub async fn transcribe_wav_bytes(&mut self, wav_bytes: &[u8]) -> Result<String> {
+            let mut reader = hound::WavReader::new(std::io::Cursor::new(wav_bytes))?;
             let spec = reader.spec();
 
             if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
@@ -1121,7 +1257,7 @@ mod implementation {
             let samples: Vec<i16> = reader.samples::<i16>().collect::<Result<Vec<_>, _>>()?;
 
             let pcm_data = if spec.sample_rate != 16000 {
-                resample_linear(&samples, spec.sample_rate, 16000)
+                resample_i16(&samples, spec.sample_rate, 16000)
             } else {
                 samples
             };
@@ -1346,27 +1482,6 @@ mod implementation {
         }
     }
 
-    fn resample_linear(samples: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
-        let ratio = from_rate as f64 / to_rate as f64;
-        let new_len = (samples.len() as f64 / ratio) as usize;
-        let mut result = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f64 * ratio;
-            let idx = src_idx as usize;
-            let frac = src_idx - idx as f64;
-
-            if idx + 1 < samples.len() {
-                let sample = samples[idx] as f64 * (1.0 - frac) + samples[idx + 1] as f64 * frac;
-                result.push(sample as i16);
-            } else if idx < samples.len() {
-                result.push(samples[idx]);
-            }
-        }
-
-        result
-    }
-
     #[cfg(test)]
     mod tests {
         use super::{
@@ -1446,4 +1561,100 @@ mod implementation {
 
 // 当功能启用时，从 implementation 模块重新导出
 #[cfg(feature = "doubao-ime")]
-pub use implementation::{DoubaoImeClient, DoubaoImeRealtimeClient, DoubaoImeRealtimeSession};
+pub use implementation::{
+    ensure_stored_credentials, DoubaoImeClient, DoubaoImeRealtimeClient, DoubaoImeRealtimeSession,
+};
+
+// ==================== 统一引擎接口 ====================
+
+impl RealtimeAsrSession for DoubaoImeRealtimeSession {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::DoubaoIme
+    }
+
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(DoubaoImeRealtimeSession::send_audio_chunk(self, pcm_data))
+    }
+
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.finish_audio())
+    }
+
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+        Box::pin(async move {
+            DoubaoImeRealtimeSession::wait_for_result(self)
+                .await
+                .map(AsrTranscript::from_text)
+        })
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(DoubaoImeRealtimeSession::close(self))
+    }
+
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        DoubaoImeRealtimeSession::subscribe_partials(self)
+    }
+}
+
+/// 豆包输入法批量转录引擎
+///
+/// 凭据来自共享的凭据存储，每次转录前按生命周期策略检查（刷新 Token / 重新注册）。
+/// 输入法协议没有热词字段，因此不实现 `with_dictionary`
+pub struct DoubaoImeEngine {
+    store: Arc<DoubaoImeCredentialStore>,
+    http_client: reqwest::Client,
+}
+
+impl DoubaoImeEngine {
+    pub fn new(store: Arc<DoubaoImeCredentialStore>) -> Self {
+        Self {
+            store,
+            http_client: super::utils::shared_http_client(),
+        }
+    }
+
+    async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<String> {
+        let creds = ensure_stored_credentials(&self.store, &self.http_client).await?;
+        let mut client = DoubaoImeClient::with_credentials(self.http_client.clone(), creds);
+        match client.transcribe_wav_bytes(audio_data).await {
+            Ok(text) => {
+                self.store.report_accepted();
+                Ok(text)
+            }
+            Err(e) => {
+                if is_credential_error(&e) {
+                    self.store.report_rejected();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl AsrEngine for DoubaoImeEngine {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::DoubaoIme
+    }
+
+    fn name(&self) -> &str {
+        "豆包输入法"
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(async move {
+            self.transcribe_bytes(audio_data)
+                .await
+                .map(AsrTranscript::from_text)
+        })
+    }
+
+    /// 提前检查凭据有效期（必要时刷新 Token 或注册设备）
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            ensure_stored_credentials(&self.store, &self.http_client)
+                .await
+                .map(|_| ())
+        })
+    }
+}
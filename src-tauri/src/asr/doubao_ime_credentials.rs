@@ -0,0 +1,388 @@
+// 豆包输入法凭据存储与生命周期
+//
+// 设备凭据（device_id / cdid / token）与主配置文件分开保存在 doubao_ime_credentials.json：
+// - Token 超过刷新周期后通过 settings 接口主动刷新，无需重新注册设备
+// - 会话被拒绝时先作废 Token；新 Token 仍被拒绝才判定设备失效并重新注册
+// - 重新注册失败按指数退避节流，避免频繁请求注册接口
+
+use super::doubao_ime::DeviceCredentials;
+use crate::config::AsrCredentials;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Token 刷新周期（服务端未公开有效期，保守地每 12 小时刷新一次）
+const TOKEN_REFRESH_AFTER: Duration = Duration::from_secs(12 * 60 * 60);
+/// 刚获取的 Token 仍被拒绝时，判定为设备失效
+const FRESH_TOKEN_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// 重新注册的退避基数与上限
+const REGISTER_BACKOFF_BASE: Duration = Duration::from_secs(60);
+const REGISTER_BACKOFF_MAX: Duration = Duration::from_secs(60 * 60);
+
+const CREDENTIALS_FILE_NAME: &str = "doubao_ime_credentials.json";
+
+/// 下一步应执行的凭据操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialAction {
+    /// 凭据可直接使用
+    Ready(DeviceCredentials),
+    /// 设备有效，需要刷新 Token
+    RefreshToken(DeviceCredentials),
+    /// 需要注册新设备
+    Register,
+    /// 注册处于退避期，需等待
+    Backoff(Duration),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CredentialFile {
+    credentials: Option<DeviceCredentials>,
+    /// Token 获取时间（Unix 秒，0 表示未知，需要刷新）
+    token_fetched_at: u64,
+    /// 最近一次注册尝试时间（Unix 秒）
+    last_register_attempt_at: u64,
+    /// 连续注册失败（含注册后设备即被拒绝）次数，决定退避时长
+    register_failures: u32,
+}
+
+/// 豆包输入法凭据存储（进程内共享，修改后立即落盘）
+pub struct DoubaoImeCredentialStore {
+    path: Option<PathBuf>,
+    state: Mutex<CredentialFile>,
+    /// 串行化刷新与注册，避免预热与会话同时注册出两台设备
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl DoubaoImeCredentialStore {
+    /// 进程内共享的凭据存储（位于配置目录下）
+    pub fn shared() -> Arc<Self> {
+        static STORE: OnceLock<Arc<DoubaoImeCredentialStore>> = OnceLock::new();
+        Arc::clone(STORE.get_or_init(|| {
+            let path = match credentials_path() {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    tracing::warn!("无法确定豆包输入法凭据路径，凭据仅保存在内存中: {}", e);
+                    None
+                }
+            };
+            Arc::new(Self::open(path))
+        }))
+    }
+
+    /// 从文件加载；`path` 为 None 时只保存在内存中
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let state = path
+            .as_ref()
+            .filter(|p| p.exists())
+            .and_then(|p| match std::fs::read_to_string(p) {
+                Ok(content) => serde_json::from_str(&content)
+                    .map_err(|e| tracing::warn!("豆包输入法凭据文件解析失败，已忽略: {}", e))
+                    .ok(),
+                Err(e) => {
+                    tracing::warn!("读取豆包输入法凭据文件失败: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            state: Mutex::new(state),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// 获取刷新锁（持有期间再调用 `next_action` 决定下一步）
+    pub async fn lock_refresh(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.refresh_lock.lock().await
+    }
+
+    /// 迁移旧版保存在主配置文件中的凭据，返回是否发生了迁移
+    pub fn import_legacy(&self, creds: &AsrCredentials) -> bool {
+        if creds.doubao_ime_device_id.is_empty() || creds.doubao_ime_token.is_empty() {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.credentials.is_some() {
+            return false;
+        }
+        state.credentials = Some(DeviceCredentials {
+            device_id: creds.doubao_ime_device_id.clone(),
+            token: creds.doubao_ime_token.clone(),
+            cdid: creds.doubao_ime_cdid.clone(),
+            ..Default::default()
+        });
+        // 获取时间未知，首次使用时刷新 Token
+        state.token_fetched_at = 0;
+        self.persist(&state);
+        tracing::info!("已将豆包输入法凭据从主配置迁移到独立存储");
+        true
+    }
+
+    /// 当前保存的凭据（不检查有效期）
+    pub fn credentials(&self) -> Option<DeviceCredentials> {
+        self.state.lock().unwrap().credentials.clone()
+    }
+
+    pub fn next_action(&self) -> CredentialAction {
+        self.next_action_at(unix_now())
+    }
+
+    fn next_action_at(&self, now: u64) -> CredentialAction {
+        let state = self.state.lock().unwrap();
+        match &state.credentials {
+            Some(creds) if !creds.device_id.is_empty() => {
+                let token_age = now.saturating_sub(state.token_fetched_at);
+                if creds.token.is_empty() || token_age >= TOKEN_REFRESH_AFTER.as_secs() {
+                    CredentialAction::RefreshToken(creds.clone())
+                } else {
+                    CredentialAction::Ready(creds.clone())
+                }
+            }
+            _ => match register_backoff_remaining(&state, now) {
+                Some(remaining) => CredentialAction::Backoff(remaining),
+                None => CredentialAction::Register,
+            },
+        }
+    }
+
+    /// 记录注册尝试（在发起请求前调用，失败时同样计入退避）
+    pub fn record_register_attempt(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.last_register_attempt_at = unix_now();
+        self.persist(&state);
+    }
+
+    pub fn record_register_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.register_failures = state.register_failures.saturating_add(1);
+        self.persist(&state);
+    }
+
+    /// 保存新注册的设备凭据（含 Token）
+    pub fn record_registered(&self, creds: DeviceCredentials) {
+        self.record_registered_at(creds, unix_now());
+    }
+
+    fn record_registered_at(&self, creds: DeviceCredentials, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.credentials = Some(creds);
+        state.token_fetched_at = now;
+        self.persist(&state);
+    }
+
+    /// 保存刷新后的 Token
+    pub fn record_token(&self, token: String) {
+        self.record_token_at(token, unix_now());
+    }
+
+    fn record_token_at(&self, token: String, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(creds) = state.credentials.as_mut() {
+            creds.token = token;
+            state.token_fetched_at = now;
+            self.persist(&state);
+        }
+    }
+
+    /// 凭据被服务端接受：清除注册退避
+    pub fn report_accepted(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.register_failures > 0 {
+            state.register_failures = 0;
+            self.persist(&state);
+        }
+    }
+
+    /// 凭据被服务端拒绝：旧 Token 只作废 Token，新 Token 仍被拒绝则丢弃设备
+    pub fn report_rejected(&self) {
+        self.report_rejected_at(unix_now());
+    }
+
+    fn report_rejected_at(&self, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        let token_is_fresh =
+            now.saturating_sub(state.token_fetched_at) < FRESH_TOKEN_WINDOW.as_secs();
+        if token_is_fresh {
+            tracing::warn!("豆包输入法 ASR: 新 Token 仍被拒绝，判定设备失效，将重新注册");
+            state.credentials = None;
+            state.register_failures = state.register_failures.saturating_add(1);
+        } else if let Some(creds) = state.credentials.as_mut() {
+            tracing::warn!("豆包输入法 ASR: Token 被拒绝，将刷新 Token");
+            creds.token.clear();
+        }
+        state.token_fetched_at = 0;
+        self.persist(&state);
+    }
+
+    /// Token 刷新失败且无可用 Token：丢弃设备，下次重新注册（计入退避）
+    pub fn discard_device(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.credentials = None;
+        state.token_fetched_at = 0;
+        state.register_failures = state.register_failures.saturating_add(1);
+        self.persist(&state);
+    }
+
+    /// 清除全部凭据与退避状态
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        *state = CredentialFile::default();
+        self.persist(&state);
+    }
+
+    fn persist(&self, state: &CredentialFile) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(state)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| {
+                let temp_path = path.with_extension("json.tmp");
+                std::fs::write(&temp_path, content)?;
+                std::fs::rename(&temp_path, path)?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            tracing::error!("保存豆包输入法凭据失败: {}", e);
+        }
+    }
+}
+
+/// 错误是否表明凭据失效（鉴权失败、任务被拒绝）
+pub fn is_credential_error(error: &anyhow::Error) -> bool {
+    let normalized = error.to_string().to_lowercase();
+    ["taskfailed", "sessionfailed", "token", "auth", "401", "403"]
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+}
+
+fn register_backoff_remaining(state: &CredentialFile, now: u64) -> Option<Duration> {
+    if state.register_failures == 0 {
+        return None;
+    }
+    let exponent = (state.register_failures - 1).min(16);
+    let backoff = REGISTER_BACKOFF_BASE
+        .saturating_mul(1u32 << exponent)
+        .min(REGISTER_BACKOFF_MAX);
+    let elapsed = Duration::from_secs(now.saturating_sub(state.last_register_attempt_at));
+    backoff.checked_sub(elapsed).filter(|d| !d.is_zero())
+}
+
+fn credentials_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?;
+    let app_dir = config_dir.join("PushToTalk");
+    std::fs::create_dir_all(&app_dir)?;
+    Ok(app_dir.join(CREDENTIALS_FILE_NAME))
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn device(token: &str) -> DeviceCredentials {
+        DeviceCredentials {
+            device_id: "42".to_string(),
+            cdid: "cdid".to_string(),
+            token: token.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn refreshes_token_before_expiry_and_after_legacy_import() {
+        let store = DoubaoImeCredentialStore::open(None);
+        assert_eq!(store.next_action_at(NOW), CredentialAction::Register);
+
+        store.record_registered_at(device("t1"), NOW);
+        assert_eq!(
+            store.next_action_at(NOW + 60),
+            CredentialAction::Ready(device("t1"))
+        );
+        assert_eq!(
+            store.next_action_at(NOW + TOKEN_REFRESH_AFTER.as_secs()),
+            CredentialAction::RefreshToken(device("t1"))
+        );
+
+        let legacy = DoubaoImeCredentialStore::open(None);
+        let creds = AsrCredentials {
+            doubao_ime_device_id: "42".to_string(),
+            doubao_ime_token: "old".to_string(),
+            doubao_ime_cdid: "cdid".to_string(),
+            ..Default::default()
+        };
+        assert!(legacy.import_legacy(&creds));
+        assert!(!legacy.import_legacy(&creds));
+        assert!(matches!(
+            legacy.next_action_at(NOW),
+            CredentialAction::RefreshToken(_)
+        ));
+    }
+
+    #[test]
+    fn rejected_stale_token_is_refreshed_but_fresh_token_drops_device() {
+        let store = DoubaoImeCredentialStore::open(None);
+        store.record_registered_at(device("t1"), NOW);
+
+        // 旧 Token 被拒绝：保留设备，只刷新 Token
+        store.report_rejected_at(NOW + FRESH_TOKEN_WINDOW.as_secs() + 1);
+        assert_eq!(
+            store.next_action_at(NOW + 600),
+            CredentialAction::RefreshToken(device(""))
+        );
+
+        // 刚刷新的 Token 仍被拒绝：设备失效，需要重新注册
+        store.record_token_at("t2".to_string(), NOW + 600);
+        store.report_rejected_at(NOW + 601);
+        assert!(store.credentials().is_none());
+    }
+
+    #[test]
+    fn registration_backs_off_exponentially() {
+        let mut state = CredentialFile {
+            last_register_attempt_at: NOW,
+            ..Default::default()
+        };
+        assert_eq!(register_backoff_remaining(&state, NOW), None);
+
+        state.register_failures = 1;
+        assert_eq!(
+            register_backoff_remaining(&state, NOW + 20),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(register_backoff_remaining(&state, NOW + 60), None);
+
+        state.register_failures = 3;
+        assert_eq!(
+            register_backoff_remaining(&state, NOW),
+            Some(Duration::from_secs(240))
+        );
+        state.register_failures = 30;
+        assert_eq!(
+            register_backoff_remaining(&state, NOW),
+            Some(REGISTER_BACKOFF_MAX)
+        );
+
+        let store = DoubaoImeCredentialStore::open(None);
+        store.report_accepted();
+        assert_eq!(store.next_action_at(NOW), CredentialAction::Register);
+    }
+
+    #[test]
+    fn detects_credential_errors() {
+        assert!(is_credential_error(&anyhow::anyhow!("TaskFailed: invalid")));
+        assert!(is_credential_error(&anyhow::anyhow!("HTTP 401")));
+        assert!(!is_credential_error(&anyhow::anyhow!("WebSocket 连接超时")));
+    }
+}
@@ -0,0 +1,304 @@
+// 统一 ASR 引擎抽象
+//
+// - AsrEngine: 一次性（HTTP/批量）转录，输入为完整的 16kHz WAV 数据
+// - RealtimeAsrSession: 流式会话，统一 发送音频块 / 结束 / 等待结果 的流程，并可订阅中间结果
+// - AsrEngineRegistry: 按 AsrProvider 索引的引擎注册表，取代按提供商硬编码的客户端字段
+
+use super::language::resolve_language_mode;
+use super::{
+    AsrTranscript, DoubaoASRClient, DoubaoImeCredentialStore, DoubaoImeEngine, LocalWhisperEngine,
+    OpenAiTranscriptionClient, QwenASRClient, SenseVoiceClient, WebSocketRealtimeClient,
+};
+use crate::config::{AsrConfig, AsrLanguageMode, AsrProvider};
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 批量 ASR 引擎
+///
+/// `transcribe` 只做单次尝试，重试与竞速由调用方（race_strategy）负责
+pub trait AsrEngine: Send + Sync {
+    /// 引擎对应的提供商
+    fn provider(&self) -> AsrProvider;
+
+    /// 用于日志的显示名称
+    fn name(&self) -> &str;
+
+    /// 转录完整的 WAV 音频数据
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>>;
+
+    /// 返回使用新词库的引擎副本；不使用词库的引擎返回 None
+    fn with_dictionary(&self, _dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        None
+    }
+
+    /// 返回使用新识别语言的引擎副本；不区分语言的引擎返回 None
+    fn with_language_mode(&self, _language_mode: AsrLanguageMode) -> Option<Arc<dyn AsrEngine>> {
+        None
+    }
+
+    /// 预热：提前完成握手、凭据检查或模型加载，使随后的 `transcribe` 更快返回
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 实时流式 ASR 会话
+pub trait RealtimeAsrSession: Send {
+    /// 会话对应的提供商
+    fn provider(&self) -> AsrProvider;
+
+    /// 发送音频块（PCM 16-bit, 16kHz, 单声道）
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>>;
+
+    /// 通知服务端音频已结束（千问为 commit，豆包为 finish）
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>>;
+
+    /// 等待最终转录结果（带超时）
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>>;
+
+    /// 放弃会话并释放连接
+    fn close(&mut self) -> BoxFuture<'_, Result<()>>;
+
+    /// 订阅中间识别结果（用户仍在说话时的临时文本）；协议不提供中间结果时返回 None
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        None
+    }
+}
+
+/// 中间识别结果的接收端：始终持有最新的一条完整假设文本，会话结束后通道关闭
+pub type PartialTranscriptReceiver = tokio::sync::watch::Receiver<String>;
+
+/// 创建中间结果通道
+pub(crate) fn partial_transcript_channel() -> (
+    tokio::sync::watch::Sender<String>,
+    PartialTranscriptReceiver,
+) {
+    tokio::sync::watch::channel(String::new())
+}
+
+/// 发布中间结果，与上一条相同时不唤醒订阅者
+pub(crate) fn publish_partial(sender: &tokio::sync::watch::Sender<String>, text: &str) {
+    sender.send_if_modified(|current| {
+        if current == text {
+            return false;
+        }
+        current.clear();
+        current.push_str(text);
+        true
+    });
+}
+
+/// 按提供商索引的批量 ASR 引擎注册表
+///
+/// 内部保存 `Arc<dyn AsrEngine>`，clone 开销很小，适合在 await 前从锁中取出快照
+#[derive(Clone, Default)]
+pub struct AsrEngineRegistry {
+    engines: HashMap<AsrProvider, Arc<dyn AsrEngine>>,
+}
+
+impl AsrEngineRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 根据 ASR 配置初始化所有已填写凭证的引擎（本地 Whisper 仅在启用 `local-whisper` 特性时注册）
+    pub fn from_config(cfg: &AsrConfig, dictionary: &[String]) -> Self {
+        let mut registry = Self::new();
+        let creds = &cfg.credentials;
+
+        if !creds.qwen_api_key.is_empty() {
+            registry.register(Arc::new(
+                QwenASRClient::new(
+                    creds.qwen_api_key.clone(),
+                    dictionary.to_vec(),
+                    resolve_language_mode(&AsrProvider::Qwen, cfg.language_mode),
+                )
+                .with_upload_format(cfg.upload_format(&AsrProvider::Qwen)),
+            ));
+        }
+        if !creds.sensevoice_api_key.is_empty() {
+            registry.register(Arc::new(SenseVoiceClient::new(
+                creds.sensevoice_api_key.clone(),
+                dictionary.to_vec(),
+            )));
+        }
+        if !creds.doubao_app_id.is_empty() && !creds.doubao_access_token.is_empty() {
+            registry.register(Arc::new(
+                DoubaoASRClient::new(
+                    creds.doubao_app_id.clone(),
+                    creds.doubao_access_token.clone(),
+                    dictionary.to_vec(),
+                    resolve_language_mode(&AsrProvider::Doubao, cfg.language_mode),
+                )
+                .with_upload_format(cfg.upload_format(&AsrProvider::Doubao)),
+            ));
+        }
+        if cfg!(feature = "local-whisper") && !cfg.local_whisper.model_path.trim().is_empty() {
+            registry.register(Arc::new(LocalWhisperEngine::new(
+                cfg.local_whisper.clone(),
+                dictionary.to_vec(),
+                resolve_language_mode(&AsrProvider::LocalWhisper, cfg.language_mode),
+            )));
+        }
+        for provider in &cfg.openai_providers {
+            if provider.id.trim().is_empty() || provider.endpoint.trim().is_empty() {
+                continue;
+            }
+            registry.register(Arc::new(OpenAiTranscriptionClient::new(
+                provider.clone(),
+                dictionary.to_vec(),
+                cfg.language_mode,
+            )));
+        }
+        for provider in &cfg.websocket_providers {
+            if provider.id.trim().is_empty() || provider.endpoint.trim().is_empty() {
+                continue;
+            }
+            registry.register(Arc::new(WebSocketRealtimeClient::new(
+                provider.clone(),
+                dictionary.to_vec(),
+            )));
+        }
+        // 豆包输入法无需手动凭证（自动注册，凭据保存在独立存储中），始终可用
+        registry.register(Arc::new(DoubaoImeEngine::new(
+            DoubaoImeCredentialStore::shared(),
+        )));
+
+        registry
+    }
+
+    /// 注册引擎（同一提供商会被覆盖）
+    pub fn register(&mut self, engine: Arc<dyn AsrEngine>) {
+        self.engines.insert(engine.provider(), engine);
+    }
+
+    pub fn get(&self, provider: &AsrProvider) -> Option<Arc<dyn AsrEngine>> {
+        self.engines.get(provider).cloned()
+    }
+
+    pub fn contains(&self, provider: &AsrProvider) -> bool {
+        self.engines.contains_key(provider)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.engines.is_empty()
+    }
+
+    /// 热更新词库：替换所有使用词库的引擎
+    pub fn update_dictionary(&mut self, dictionary: &[String]) {
+        for engine in self.engines.values_mut() {
+            if let Some(updated) = engine.with_dictionary(dictionary) {
+                tracing::info!("热更新: {} ASR 引擎词库已更新", updated.name());
+                *engine = updated;
+            }
+        }
+    }
+
+    /// 热切换识别语言：按各提供商能力降级后替换引擎
+    pub fn update_language_mode(&mut self, language_mode: AsrLanguageMode) {
+        for (provider, engine) in self.engines.iter_mut() {
+            let effective = resolve_language_mode(provider, language_mode);
+            if let Some(updated) = engine.with_language_mode(effective) {
+                tracing::info!(
+                    "热更新: {} ASR 引擎识别语言切换为 {}",
+                    updated.name(),
+                    effective.display_name()
+                );
+                *engine = updated;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AsrCredentials, LocalWhisperConfig, OpenAiAsrProvider};
+
+    #[test]
+    fn registry_only_contains_configured_providers() {
+        let cfg = AsrConfig {
+            credentials: AsrCredentials {
+                qwen_api_key: "qwen-key".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let registry = AsrEngineRegistry::from_config(&cfg, &[]);
+
+        assert!(registry.contains(&AsrProvider::Qwen));
+        assert!(registry.contains(&AsrProvider::DoubaoIme));
+        assert!(!registry.contains(&AsrProvider::Doubao));
+        assert!(!registry.contains(&AsrProvider::SiliconFlow));
+        assert!(!registry.contains(&AsrProvider::LocalWhisper));
+    }
+
+    #[test]
+    fn registry_keys_engines_by_their_provider() {
+        let cfg = AsrConfig {
+            credentials: AsrCredentials {
+                sensevoice_api_key: "sv-key".to_string(),
+                doubao_app_id: "app".to_string(),
+                doubao_access_token: "token".to_string(),
+                ..Default::default()
+            },
+            local_whisper: LocalWhisperConfig {
+                model_path: "ggml-small.bin".to_string(),
+                threads: 0,
+            },
+            openai_providers: vec![
+                OpenAiAsrProvider {
+                    id: "groq".to_string(),
+                    name: "Groq".to_string(),
+                    endpoint: "https://api.groq.com/openai/v1".to_string(),
+                    model: "whisper-large-v3".to_string(),
+                    ..Default::default()
+                },
+                OpenAiAsrProvider {
+                    id: "empty".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let registry = AsrEngineRegistry::from_config(&cfg, &[]);
+
+        assert!(!registry.contains(&AsrProvider::OpenAiCompatible("empty".to_string())));
+        assert_eq!(
+            registry.contains(&AsrProvider::LocalWhisper),
+            cfg!(feature = "local-whisper")
+        );
+        for provider in [
+            AsrProvider::SiliconFlow,
+            AsrProvider::Doubao,
+            AsrProvider::OpenAiCompatible("groq".to_string()),
+        ] {
+            let engine = registry
+                .get(&provider)
+                .expect("engine should be registered");
+            assert_eq!(engine.provider(), provider);
+        }
+    }
+
+    #[test]
+    fn publish_partial_only_notifies_on_change() {
+        let (sender, mut receiver) = partial_transcript_channel();
+
+        publish_partial(&sender, "你好");
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(*receiver.borrow_and_update(), "你好");
+
+        publish_partial(&sender, "你好");
+        assert!(!receiver.has_changed().unwrap());
+
+        publish_partial(&sender, "你好世界");
+        assert_eq!(*receiver.borrow_and_update(), "你好世界");
+
+        drop(sender);
+        assert!(receiver.has_changed().is_err());
+    }
+}
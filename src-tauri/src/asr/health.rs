@@ -0,0 +1,506 @@
+// ASR 提供商健康度与熔断
+//
+// - 记录每个提供商的延迟、成功 / 失败次数与最近一次错误类型
+// - 连续失败达到阈值后熔断（Open），冷却期内直接跳过该提供商，由备用提供商接手
+// - 冷却结束后进入半开（HalfOpen），放行一次探测请求：成功则恢复，失败则重新熔断
+// - 熔断状态变化时通知监听者（前端事件、托盘菜单）
+
+use crate::config::AsrProvider;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// 连续失败多少次后熔断
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+/// 熔断冷却时间
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+/// 平均延迟的指数平滑系数
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+/// 转录错误分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AsrErrorKind {
+    /// 凭证无效或过期
+    Auth,
+    /// 限流或额度耗尽
+    RateLimited,
+    Timeout,
+    /// 连接失败、DNS 等网络问题
+    Network,
+    /// 服务端 5xx
+    Server,
+    /// 录音本身无效（过短、无声音），与提供商无关
+    InvalidAudio,
+    Other,
+}
+
+impl AsrErrorKind {
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() {
+                return AsrErrorKind::Timeout;
+            }
+            if e.is_connect() {
+                return AsrErrorKind::Network;
+            }
+        }
+        Self::classify_message(&error.to_string())
+    }
+
+    fn classify_message(message: &str) -> Self {
+        let msg = message.to_lowercase();
+        let has = |patterns: &[&str]| patterns.iter().any(|p| msg.contains(p));
+
+        if has(&["录音无效", "录音过短", "音频数据为空", "无声音"]) {
+            AsrErrorKind::InvalidAudio
+        } else if has(&[
+            "unauthorized",
+            "forbidden",
+            "invalid api key",
+            "invalidapikey",
+            "access denied",
+            "鉴权",
+            "认证失败",
+        ]) {
+            AsrErrorKind::Auth
+        } else if has(&[
+            "too many requests",
+            "rate limit",
+            "ratelimit",
+            "throttl",
+            "quota",
+            "arrearage",
+            "限流",
+            "额度",
+            "欠费",
+        ]) {
+            AsrErrorKind::RateLimited
+        } else if has(&[
+            "internal server error",
+            "bad gateway",
+            "service unavailable",
+            "gateway timeout",
+        ]) {
+            AsrErrorKind::Server
+        } else if has(&["超时", "timeout", "timed out"]) {
+            AsrErrorKind::Timeout
+        } else if has(&["error sending request", "connection", "dns", "连接"]) {
+            AsrErrorKind::Network
+        } else {
+            AsrErrorKind::Other
+        }
+    }
+
+    /// 在同一提供商上重试是否可能成功（鉴权、额度类错误重试只会浪费时间）
+    pub fn is_retryable(self) -> bool {
+        !matches!(self, AsrErrorKind::Auth | AsrErrorKind::RateLimited)
+    }
+
+    /// 是否计入提供商的失败次数
+    fn counts_against_provider(self) -> bool {
+        self != AsrErrorKind::InvalidAudio
+    }
+}
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    #[default]
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// 提供商健康度快照（发送给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub provider: AsrProvider,
+    pub circuit: CircuitState,
+    pub consecutive_failures: u32,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_latency_ms: Option<u64>,
+    pub avg_latency_ms: Option<u64>,
+    pub last_error_kind: Option<AsrErrorKind>,
+    pub last_error: Option<String>,
+    /// 熔断中：距离允许探测还剩多久
+    pub retry_in_ms: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct ProviderEntry {
+    circuit: CircuitState,
+    consecutive_failures: u32,
+    successes: u64,
+    failures: u64,
+    last_latency_ms: Option<u64>,
+    avg_latency_ms: Option<f64>,
+    last_error_kind: Option<AsrErrorKind>,
+    last_error: Option<String>,
+    opened_at: Option<Instant>,
+    /// 半开状态下探测请求的发出时间（探测被取消时超过冷却时间可再次放行）
+    probe_started_at: Option<Instant>,
+}
+
+impl ProviderEntry {
+    fn record_latency(&mut self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        self.last_latency_ms = Some(ms);
+        self.avg_latency_ms = Some(match self.avg_latency_ms {
+            Some(avg) => avg + LATENCY_EWMA_ALPHA * (ms as f64 - avg),
+            None => ms as f64,
+        });
+    }
+
+    fn snapshot(&self, provider: &AsrProvider, cooldown: Duration, now: Instant) -> ProviderHealth {
+        let retry_in_ms = match (self.circuit, self.opened_at) {
+            (CircuitState::Open, Some(opened_at)) => {
+                Some(cooldown.saturating_sub(now - opened_at).as_millis() as u64)
+            }
+            _ => None,
+        };
+        ProviderHealth {
+            provider: provider.clone(),
+            circuit: self.circuit,
+            consecutive_failures: self.consecutive_failures,
+            successes: self.successes,
+            failures: self.failures,
+            last_latency_ms: self.last_latency_ms,
+            avg_latency_ms: self.avg_latency_ms.map(|avg| avg.round() as u64),
+            last_error_kind: self.last_error_kind,
+            last_error: self.last_error.clone(),
+            retry_in_ms,
+        }
+    }
+}
+
+/// 熔断状态变化回调
+pub type HealthListener = Arc<dyn Fn(&ProviderHealth) + Send + Sync>;
+
+/// 按提供商记录健康度的熔断器（进程内共享，服务重启后保留）
+pub struct AsrHealthTracker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    entries: Mutex<HashMap<AsrProvider, ProviderEntry>>,
+    listener: RwLock<Option<HealthListener>>,
+}
+
+impl Default for AsrHealthTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl AsrHealthTracker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            entries: Mutex::new(HashMap::new()),
+            listener: RwLock::new(None),
+        }
+    }
+
+    /// 设置熔断状态变化回调
+    pub fn set_listener<F>(&self, listener: F)
+    where
+        F: Fn(&ProviderHealth) + Send + Sync + 'static,
+    {
+        *self.listener.write().unwrap() = Some(Arc::new(listener));
+    }
+
+    /// 是否允许向该提供商发起请求（冷却结束时转为半开并放行一次探测）
+    pub fn allow(&self, provider: &AsrProvider) -> bool {
+        self.allow_at(provider, Instant::now())
+    }
+
+    fn allow_at(&self, provider: &AsrProvider, now: Instant) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get_mut(provider) else {
+            return true;
+        };
+        match entry.circuit {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                let cooled = entry
+                    .opened_at
+                    .is_none_or(|opened_at| now - opened_at >= self.cooldown);
+                if cooled {
+                    tracing::info!("{} 熔断冷却结束，放行探测请求", provider.config_key());
+                    entry.circuit = CircuitState::HalfOpen;
+                    entry.probe_started_at = Some(now);
+                }
+                cooled
+            }
+            CircuitState::HalfOpen => {
+                let probe_stale = entry
+                    .probe_started_at
+                    .is_none_or(|started| now - started >= self.cooldown);
+                if probe_stale {
+                    entry.probe_started_at = Some(now);
+                }
+                probe_stale
+            }
+        }
+    }
+
+    pub fn is_open(&self, provider: &AsrProvider) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(provider)
+            .is_some_and(|entry| entry.circuit == CircuitState::Open)
+    }
+
+    pub fn record_success(&self, provider: &AsrProvider, latency: Duration) {
+        self.record_success_at(provider, latency, Instant::now());
+    }
+
+    fn record_success_at(&self, provider: &AsrProvider, latency: Duration, now: Instant) {
+        self.close_circuit_at(provider, now, |entry| {
+            entry.successes += 1;
+            entry.record_latency(latency);
+        });
+    }
+
+    /// 实时会话连接成功：清零连续失败并关闭熔断，成功次数与延迟仍以会话的最终结果为准
+    pub fn record_connected(&self, provider: &AsrProvider) {
+        self.close_circuit_at(provider, Instant::now(), |_| {});
+    }
+
+    fn close_circuit_at(
+        &self,
+        provider: &AsrProvider,
+        now: Instant,
+        update: impl FnOnce(&mut ProviderEntry),
+    ) {
+        let changed = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries.entry(provider.clone()).or_default();
+            entry.consecutive_failures = 0;
+            update(entry);
+
+            let recovered = entry.circuit != CircuitState::Closed;
+            if recovered {
+                tracing::info!("✅{} 已恢复，关闭熔断", provider.config_key());
+                entry.circuit = CircuitState::Closed;
+                entry.opened_at = None;
+                entry.probe_started_at = None;
+            }
+            recovered.then(|| entry.snapshot(provider, self.cooldown, now))
+        };
+        self.notify(changed);
+    }
+
+    /// 记录一次失败，返回错误分类
+    pub fn record_failure(
+        &self,
+        provider: &AsrProvider,
+        error: &anyhow::Error,
+        latency: Duration,
+    ) -> AsrErrorKind {
+        let kind = AsrErrorKind::classify(error);
+        self.record_failure_at(provider, kind, error.to_string(), latency, Instant::now());
+        kind
+    }
+
+    fn record_failure_at(
+        &self,
+        provider: &AsrProvider,
+        kind: AsrErrorKind,
+        message: String,
+        latency: Duration,
+        now: Instant,
+    ) {
+        let changed = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries.entry(provider.clone()).or_default();
+            entry.last_error_kind = Some(kind);
+            entry.last_error = Some(message);
+            if !kind.counts_against_provider() {
+                return;
+            }
+
+            entry.failures += 1;
+            entry.consecutive_failures += 1;
+            entry.record_latency(latency);
+
+            let should_open = match entry.circuit {
+                CircuitState::Closed => entry.consecutive_failures >= self.failure_threshold,
+                CircuitState::HalfOpen => true,
+                CircuitState::Open => false,
+            };
+            if should_open {
+                tracing::warn!(
+                    "⛔{} 连续失败 {} 次 ({:?})，熔断 {}s",
+                    provider.config_key(),
+                    entry.consecutive_failures,
+                    kind,
+                    self.cooldown.as_secs()
+                );
+                entry.circuit = CircuitState::Open;
+                entry.opened_at = Some(now);
+                entry.probe_started_at = None;
+            }
+            should_open.then(|| entry.snapshot(provider, self.cooldown, now))
+        };
+        self.notify(changed);
+    }
+
+    /// 手动重置（单个提供商或全部）
+    pub fn reset(&self, provider: Option<&AsrProvider>) {
+        let now = Instant::now();
+        let reset: Vec<ProviderHealth> = {
+            let mut entries = self.entries.lock().unwrap();
+            let removed: Vec<AsrProvider> = match provider {
+                Some(provider) => entries
+                    .remove_entry(provider)
+                    .map(|(p, _)| p)
+                    .into_iter()
+                    .collect(),
+                None => entries.drain().map(|(p, _)| p).collect(),
+            };
+            removed
+                .iter()
+                .map(|p| ProviderEntry::default().snapshot(p, self.cooldown, now))
+                .collect()
+        };
+        for health in reset {
+            self.notify(Some(health));
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        let entries = self.entries.lock().unwrap();
+        let mut snapshot: Vec<ProviderHealth> = entries
+            .iter()
+            .map(|(provider, entry)| entry.snapshot(provider, self.cooldown, now))
+            .collect();
+        snapshot.sort_by_key(|health| health.provider.config_key());
+        snapshot
+    }
+
+    fn notify(&self, changed: Option<ProviderHealth>) {
+        let Some(health) = changed else {
+            return;
+        };
+        let listener = self.listener.read().unwrap().clone();
+        if let Some(listener) = listener {
+            listener(&health);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn fail(tracker: &AsrHealthTracker, provider: &AsrProvider, now: Instant) {
+        tracker.record_failure_at(
+            provider,
+            AsrErrorKind::Server,
+            "503 Service Unavailable".to_string(),
+            Duration::from_millis(100),
+            now,
+        );
+    }
+
+    #[test]
+    fn classifies_common_provider_errors() {
+        let kind = |msg: &str| AsrErrorKind::classify(&anyhow::anyhow!(msg.to_string()));
+        assert_eq!(
+            kind("API 请求失败 (401 Unauthorized): bad key"),
+            AsrErrorKind::Auth
+        );
+        assert_eq!(
+            kind("API 请求失败 (429 Too Many Requests)"),
+            AsrErrorKind::RateLimited
+        );
+        assert_eq!(kind("转录超时 (3000ms)"), AsrErrorKind::Timeout);
+        assert_eq!(kind("502 Bad Gateway"), AsrErrorKind::Server);
+        assert_eq!(kind("录音无效，已跳过"), AsrErrorKind::InvalidAudio);
+        assert_eq!(kind("无法解析转录结果"), AsrErrorKind::Other);
+        assert!(!AsrErrorKind::Auth.is_retryable());
+        assert!(AsrErrorKind::Timeout.is_retryable());
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures_and_recovers_after_probe() {
+        let tracker = AsrHealthTracker::new(2, Duration::from_secs(30));
+        let transitions = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&transitions);
+        tracker.set_listener(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let provider = AsrProvider::Qwen;
+        let start = Instant::now();
+        fail(&tracker, &provider, start);
+        assert!(tracker.allow_at(&provider, start));
+        fail(&tracker, &provider, start);
+        assert!(tracker.is_open(&provider));
+        assert!(!tracker.allow_at(&provider, start + Duration::from_secs(10)));
+
+        // 冷却结束：只放行一次探测
+        let probe_at = start + Duration::from_secs(30);
+        assert!(tracker.allow_at(&provider, probe_at));
+        assert!(!tracker.allow_at(&provider, probe_at));
+
+        tracker.record_success_at(&provider, Duration::from_millis(200), probe_at);
+        assert!(tracker.allow_at(&provider, probe_at));
+        assert_eq!(transitions.load(Ordering::SeqCst), 2);
+
+        let health = &tracker.snapshot()[0];
+        assert_eq!(health.circuit, CircuitState::Closed);
+        assert_eq!((health.failures, health.successes), (2, 1));
+        assert_eq!(health.last_latency_ms, Some(200));
+    }
+
+    #[test]
+    fn realtime_connect_closes_circuit_without_counting_a_result() {
+        let tracker = AsrHealthTracker::new(1, Duration::from_secs(30));
+        let provider = AsrProvider::DoubaoIme;
+        let start = Instant::now();
+
+        fail(&tracker, &provider, start);
+        assert!(tracker.allow_at(&provider, start + Duration::from_secs(30)));
+        tracker.record_connected(&provider);
+
+        let health = &tracker.snapshot()[0];
+        assert_eq!(health.circuit, CircuitState::Closed);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!((health.failures, health.successes), (1, 0));
+    }
+
+    #[test]
+    fn failed_probe_reopens_and_invalid_audio_is_ignored() {
+        let tracker = AsrHealthTracker::new(1, Duration::from_secs(30));
+        let provider = AsrProvider::Doubao;
+        let start = Instant::now();
+
+        tracker.record_failure_at(
+            &provider,
+            AsrErrorKind::InvalidAudio,
+            "录音无效".to_string(),
+            Duration::ZERO,
+            start,
+        );
+        assert!(!tracker.is_open(&provider));
+
+        fail(&tracker, &provider, start);
+        let probe_at = start + Duration::from_secs(31);
+        assert!(tracker.allow_at(&provider, probe_at));
+        fail(&tracker, &provider, probe_at);
+        assert!(tracker.is_open(&provider));
+        assert!(!tracker.allow_at(&provider, probe_at + Duration::from_secs(1)));
+
+        tracker.reset(Some(&provider));
+        assert!(tracker.allow(&provider));
+        assert!(tracker.snapshot().is_empty());
+    }
+}
@@ -1,27 +1,17 @@
+use crate::asr::language::doubao_context_data as build_context_data;
+use crate::asr::transcript::segments_from_utterances;
 use crate::asr::utils;
-use crate::config::AsrLanguageMode;
-use crate::dictionary_utils::entries_to_words;
+use crate::asr::{encode_for_upload, resolve_upload_format, AsrEngine, AsrTranscript};
+use crate::config::{AsrLanguageMode, AsrProvider, AudioUploadFormat};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use std::time::Instant;
 
 const DOUBAO_API_URL: &str = "https://openspeech.bytedance.com/api/v3/auc/bigmodel/recognize/flash";
 const RESOURCE_ID: &str = "volc.bigasr.auc_turbo";
 
-fn build_context_data(language_mode: AsrLanguageMode) -> serde_json::Value {
-    match language_mode {
-        AsrLanguageMode::Auto => serde_json::json!([
-            {"text": "当前场景为技术听写，中英文混合"},
-            {"text": "保留英文专有名词和技术术语，如 Kubernetes, GPT-4o, Claude"},
-            {"text": "保留语气词，去除尾部句号"},
-        ]),
-        AsrLanguageMode::Zh => serde_json::json!([
-            {"text": "你好，请问有什么可以帮您的"},
-            {"text": "豆包语音识别真的不错呀"},
-            {"text": "当前聊天的场景是日常聊天，因此保留语气词，去除尾部句号"},
-        ]),
-    }
-}
-
 #[derive(Clone)]
 pub struct DoubaoASRClient {
     app_id: String,
@@ -29,6 +19,7 @@ pub struct DoubaoASRClient {
     client: reqwest::Client,
     dictionary: Vec<String>,
     language_mode: AsrLanguageMode,
+    upload_format: AudioUploadFormat,
 }
 
 impl DoubaoASRClient {
@@ -41,28 +32,32 @@ impl DoubaoASRClient {
         Self {
             app_id,
             access_key,
-            client: utils::create_http_client(),
+            client: utils::shared_http_client(),
             dictionary,
             language_mode,
+            upload_format: AudioUploadFormat::Wav,
         }
     }
 
+    /// 上传前将录音编码为指定格式（接口不支持时自动降级）
+    pub fn with_upload_format(mut self, format: AudioUploadFormat) -> Self {
+        self.upload_format = resolve_upload_format(&AsrProvider::Doubao, format);
+        self
+    }
+
     /// 热更新词库
     pub fn update_dictionary(&mut self, dictionary: Vec<String>) {
         self.dictionary = dictionary;
     }
 
-    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<String> {
-        let audio_base64 = general_purpose::STANDARD.encode(audio_data);
-        tracing::info!("豆包 ASR: 音频数据大小 {} bytes", audio_data.len());
+    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<AsrTranscript> {
+        let upload = encode_for_upload(audio_data, self.upload_format);
+        let audio_base64 = general_purpose::STANDARD.encode(&upload.data);
+        tracing::info!("豆包 ASR: 音频数据大小 {} bytes", upload.data.len());
 
         // 构建词库 hotwords JSON（提纯后）
         let corpus = if !self.dictionary.is_empty() {
-            let purified_words = entries_to_words(&self.dictionary);
-            let hotwords: Vec<serde_json::Value> = purified_words
-                .iter()
-                .map(|w| serde_json::json!({"word": w}))
-                .collect();
+            let hotwords = utils::doubao_hotwords(&self.dictionary);
             let context_data = build_context_data(self.language_mode);
             let context = serde_json::json!({
                 "context_type": "dialog_ctx",
@@ -72,7 +67,7 @@ impl DoubaoASRClient {
             .to_string();
             tracing::info!(
                 "豆包 HTTP ASR 词库: {} 个词（已提纯）, context={}",
-                purified_words.len(),
+                hotwords.len(),
                 context
             );
             Some(serde_json::json!({"context": context}))
@@ -94,19 +89,26 @@ impl DoubaoASRClient {
         //NOTE: 实验性功能，能提升性能
         request_obj["model_version"] = "400".into();
         request_obj["enable_ddc"] = true.into();
+        // 返回分句与逐词时间戳
+        request_obj["show_utterances"] = true.into();
+
+        let mut audio_obj = serde_json::json!({"data": audio_base64});
+        if upload.format == AudioUploadFormat::OggOpus {
+            audio_obj["format"] = "ogg".into();
+            audio_obj["codec"] = "opus".into();
+        }
 
         let request_body = serde_json::json!({
             "user": {
                 "uid": &self.app_id
             },
-            "audio": {
-                "data": audio_base64
-            },
+            "audio": audio_obj,
             "request": request_obj
         });
 
         let request_id = uuid::Uuid::new_v4().to_string();
 
+        let request_start = Instant::now();
         let response = self
             .client
             .post(DOUBAO_API_URL)
@@ -137,6 +139,7 @@ impl DoubaoASRClient {
             status_code,
             api_message
         );
+        upload.log_request("豆包", request_start.elapsed());
 
         if status_code != "20000000" {
             anyhow::bail!("豆包 ASR 失败 ({}): {}", status_code, api_message);
@@ -154,8 +157,39 @@ impl DoubaoASRClient {
             .to_string();
 
         utils::strip_trailing_punctuation(&mut text);
-        tracing::info!("豆包 ASR 转录完成: {}", text);
-        Ok(text)
+        let segments = segments_from_utterances(&result["result"]["utterances"]);
+        tracing::info!("豆包 ASR 转录完成: {} ({} 个分句)", text, segments.len());
+        Ok(AsrTranscript::new(text, segments))
+    }
+}
+
+impl AsrEngine for DoubaoASRClient {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::Doubao
+    }
+
+    fn name(&self) -> &str {
+        "豆包"
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(self.transcribe_bytes(audio_data))
+    }
+
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.update_dictionary(dictionary.to_vec());
+        Some(Arc::new(client))
+    }
+
+    fn with_language_mode(&self, language_mode: AsrLanguageMode) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.language_mode = language_mode;
+        Some(Arc::new(client))
+    }
+
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(utils::prewarm_http(&self.client, DOUBAO_API_URL))
     }
 }
 
@@ -1,7 +1,9 @@
 mod doubao;
+mod openai_compatible;
 mod qwen;
 mod sensevoice;
 
 pub use doubao::DoubaoASRClient;
+pub use openai_compatible::OpenAiTranscriptionClient;
 pub use qwen::QwenASRClient;
 pub use sensevoice::SenseVoiceClient;
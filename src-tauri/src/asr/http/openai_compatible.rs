@@ -0,0 +1,357 @@
+// OpenAI 兼容的 `/v1/audio/transcriptions` 转写客户端
+//
+// 适用于 OpenAI、Groq、LocalAI、自建 faster-whisper 等服务：
+// multipart 上传录音（WAV / FLAC / Ogg Opus），可选 language / prompt / response_format 字段
+// response_format 为 verbose_json 时解析分段与逐词时间戳
+
+use crate::asr::utils;
+use crate::asr::{
+    encode_for_upload, resolve_upload_format, AsrEngine, AsrTranscript, TranscriptSegment,
+    TranscriptWord,
+};
+use crate::config::{
+    normalize_transcriptions_endpoint, AsrLanguageMode, AsrProvider, AudioUploadFormat,
+    OpenAiAsrProvider,
+};
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 提示词的最大字符数（Whisper 系模型只保留最后约 224 个 token）
+const MAX_PROMPT_CHARS: usize = 200;
+
+#[derive(Clone)]
+pub struct OpenAiTranscriptionClient {
+    config: OpenAiAsrProvider,
+    endpoint: String,
+    client: reqwest::Client,
+    dictionary: Vec<String>,
+    language_mode: AsrLanguageMode,
+    upload_format: AudioUploadFormat,
+}
+
+impl OpenAiTranscriptionClient {
+    pub fn new(
+        config: OpenAiAsrProvider,
+        dictionary: Vec<String>,
+        language_mode: AsrLanguageMode,
+    ) -> Self {
+        // 上传格式来自实例配置（服务不支持时自动降级）
+        let upload_format = resolve_upload_format(
+            &AsrProvider::OpenAiCompatible(config.id.clone()),
+            config.upload_format,
+        );
+        Self {
+            endpoint: normalize_transcriptions_endpoint(&config.endpoint),
+            config,
+            client: utils::shared_http_client(),
+            dictionary,
+            language_mode,
+            upload_format,
+        }
+    }
+
+    /// 热更新词库
+    pub fn update_dictionary(&mut self, dictionary: Vec<String>) {
+        self.dictionary = dictionary;
+    }
+
+    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<AsrTranscript> {
+        let name = &self.config.name;
+        tracing::info!("开始使用 {} 转录音频数据: {} bytes", name, audio_data.len());
+
+        let upload = encode_for_upload(audio_data, self.upload_format);
+        let mut form = reqwest::multipart::Form::new()
+            .text("model", self.config.model.clone())
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(upload.data.to_vec())
+                    .file_name(upload.file_name())
+                    .mime_str(upload.mime_type())?,
+            );
+        if let Some(language) = self.language() {
+            form = form.text("language", language);
+        }
+        let prompt = self.prompt();
+        if !prompt.is_empty() {
+            form = form.text("prompt", prompt);
+        }
+        let response_format = self.config.response_format.trim();
+        if !response_format.is_empty() {
+            form = form.text("response_format", response_format.to_string());
+        }
+        if response_format == "verbose_json" {
+            form = form
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        }
+
+        tracing::info!("发送请求到 {}: {}", name, self.endpoint);
+
+        let mut request = self.client.post(&self.endpoint).multipart(form);
+        // 自建服务通常无需鉴权
+        if !self.config.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.config.api_key));
+        }
+        let request_start = Instant::now();
+        let response = request.send().await?;
+
+        let status = response.status();
+        tracing::info!("{} API 响应状态: {}", name, status);
+        upload.log_request(name, request_start.elapsed());
+
+        let body = response.text().await?;
+        if !status.is_success() {
+            tracing::error!("{} API 错误响应: {}", name, body);
+            anyhow::bail!("{} API 请求失败 ({}): {}", name, status, body);
+        }
+        tracing::info!("{} API 响应: {}", name, body);
+
+        let (mut text, segments) = parse_transcription_response(response_format, &body)
+            .ok_or_else(|| anyhow::anyhow!("无法解析 {} 转录结果", name))?;
+
+        utils::strip_trailing_punctuation(&mut text);
+        tracing::info!("{} 转录完成: {} ({} 个分段)", name, text, segments.len());
+        Ok(AsrTranscript::new(text, segments))
+    }
+
+    /// 显式配置的语言优先，否则跟随 language_mode（自动模式不传，由服务端检测）
+    fn language(&self) -> Option<String> {
+        let configured = self.config.language.trim();
+        if !configured.is_empty() {
+            return Some(configured.to_string());
+        }
+        self.language_mode.iso_code().map(str::to_string)
+    }
+
+    fn prompt(&self) -> String {
+        utils::build_dictionary_prompt(
+            self.config.prompt.trim(),
+            &self.dictionary,
+            MAX_PROMPT_CHARS,
+        )
+    }
+}
+
+/// 解析转写响应：`text` 格式直接返回正文，其余（json / verbose_json）读取 `text` 字段，
+/// 并在存在时解析 `segments` / `words`
+fn parse_transcription_response(
+    response_format: &str,
+    body: &str,
+) -> Option<(String, Vec<TranscriptSegment>)> {
+    if response_format == "text" {
+        return Some((body.trim().to_string(), Vec::new()));
+    }
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let text = value["text"].as_str()?.trim().to_string();
+    Some((text, parse_verbose_segments(&value)))
+}
+
+/// verbose_json：时间单位为秒；分段置信度取 exp(avg_logprob)。
+/// 顶层 `words` 按时间归入所在分段，没有分段时整体作为一个分段
+fn parse_verbose_segments(value: &serde_json::Value) -> Vec<TranscriptSegment> {
+    let mut segments: Vec<TranscriptSegment> = value["segments"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(TranscriptSegment {
+                        text: item["text"].as_str()?.trim().to_string(),
+                        start_ms: seconds_to_ms(&item["start"]),
+                        end_ms: seconds_to_ms(&item["end"]),
+                        confidence: item["avg_logprob"]
+                            .as_f64()
+                            .map(|p| p.exp().clamp(0.0, 1.0) as f32),
+                        words: Vec::new(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let words: Vec<TranscriptWord> = value["words"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let text = item["word"].as_str()?.trim();
+                    if text.is_empty() {
+                        return None;
+                    }
+                    Some(TranscriptWord {
+                        text: text.to_string(),
+                        start_ms: seconds_to_ms(&item["start"]),
+                        end_ms: seconds_to_ms(&item["end"]),
+                        confidence: item["probability"]
+                            .as_f64()
+                            .map(|p| p.clamp(0.0, 1.0) as f32),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if words.is_empty() {
+        return segments;
+    }
+    if segments.is_empty() {
+        let text = value["text"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        return vec![TranscriptSegment {
+            text,
+            start_ms: words.first().and_then(|w| w.start_ms),
+            end_ms: words.last().and_then(|w| w.end_ms),
+            confidence: None,
+            words,
+        }];
+    }
+
+    for word in words {
+        let start = word.start_ms.unwrap_or(0);
+        // 归入最后一个开始时间不晚于该词的分段
+        let index = segments
+            .iter()
+            .rposition(|s| s.start_ms.is_some_and(|s| s <= start))
+            .unwrap_or(0);
+        segments[index].words.push(word);
+    }
+    segments
+}
+
+fn seconds_to_ms(value: &serde_json::Value) -> Option<u64> {
+    value
+        .as_f64()
+        .filter(|s| *s >= 0.0)
+        .map(|s| (s * 1000.0).round() as u64)
+}
+
+impl AsrEngine for OpenAiTranscriptionClient {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::OpenAiCompatible(self.config.id.clone())
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(self.transcribe_bytes(audio_data))
+    }
+
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.update_dictionary(dictionary.to_vec());
+        Some(Arc::new(client))
+    }
+
+    fn with_language_mode(&self, language_mode: AsrLanguageMode) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.language_mode = language_mode;
+        Some(Arc::new(client))
+    }
+
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(utils::prewarm_http(&self.client, &self.endpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(language: &str, language_mode: AsrLanguageMode) -> OpenAiTranscriptionClient {
+        OpenAiTranscriptionClient::new(
+            OpenAiAsrProvider {
+                id: "local".to_string(),
+                name: "faster-whisper".to_string(),
+                endpoint: "http://127.0.0.1:8000/v1".to_string(),
+                model: "large-v3".to_string(),
+                language: language.to_string(),
+                prompt: "以下是会议记录。".to_string(),
+                ..Default::default()
+            },
+            vec!["Tauri".to_string(), "豆包".to_string()],
+            language_mode,
+        )
+    }
+
+    #[test]
+    fn request_fields_follow_config_and_dictionary() {
+        let auto = client("", AsrLanguageMode::Auto);
+        assert_eq!(
+            auto.endpoint,
+            "http://127.0.0.1:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(auto.language(), None);
+        assert_eq!(auto.prompt(), "以下是会议记录。Tauri，豆包");
+        assert_eq!(
+            auto.provider(),
+            AsrProvider::OpenAiCompatible("local".to_string())
+        );
+
+        assert_eq!(
+            client("", AsrLanguageMode::Zh).language().as_deref(),
+            Some("zh")
+        );
+        assert_eq!(
+            client("en", AsrLanguageMode::Zh).language().as_deref(),
+            Some("en")
+        );
+    }
+
+    fn text_of(response_format: &str, body: &str) -> Option<String> {
+        parse_transcription_response(response_format, body).map(|(text, _)| text)
+    }
+
+    #[test]
+    fn parses_json_verbose_json_and_text_responses() {
+        assert_eq!(
+            text_of("json", r#"{"text":" 你好 "}"#).as_deref(),
+            Some("你好")
+        );
+        assert_eq!(
+            text_of(
+                "verbose_json",
+                r#"{"task":"transcribe","text":"hello","segments":[]}"#
+            )
+            .as_deref(),
+            Some("hello")
+        );
+        assert_eq!(
+            text_of("text", "hello world\n").as_deref(),
+            Some("hello world")
+        );
+        assert_eq!(text_of("", r#"{"error":"x"}"#), None);
+    }
+
+    #[test]
+    fn verbose_json_words_are_assigned_to_segments() {
+        let body = r#"{
+            "text": "hello world again",
+            "segments": [
+                {"text": " hello world", "start": 0.0, "end": 1.2, "avg_logprob": -0.1},
+                {"text": " again", "start": 1.2, "end": 2.0, "avg_logprob": -1.5}
+            ],
+            "words": [
+                {"word": "hello", "start": 0.0, "end": 0.5},
+                {"word": "world", "start": 0.5, "end": 1.2},
+                {"word": "again", "start": 1.25, "end": 2.0, "probability": 0.3}
+            ]
+        }"#;
+        let (text, segments) = parse_transcription_response("verbose_json", body).unwrap();
+        assert_eq!(text, "hello world again");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "hello world");
+        assert_eq!(segments[0].words.len(), 2);
+        assert_eq!(segments[1].start_ms, Some(1200));
+        assert_eq!(segments[1].words[0].start_ms, Some(1250));
+        assert_eq!(segments[1].words[0].confidence, Some(0.3));
+        assert!(segments[0].confidence.unwrap() > segments[1].confidence.unwrap());
+    }
+}
@@ -1,26 +1,22 @@
+use crate::asr::language::qwen_language_code as asr_language_code;
 use crate::asr::utils;
-use crate::config::AsrLanguageMode;
-use crate::dictionary_utils::entries_to_words;
+use crate::asr::{encode_for_upload, resolve_upload_format, AsrEngine, AsrTranscript};
+use crate::config::{AsrLanguageMode, AsrProvider, AudioUploadFormat};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
-use std::time::Duration;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const QWEN_API_URL: &str =
     "https://dashscope.aliyuncs.com/api/v1/services/aigc/multimodal-generation/generation";
 const MODEL: &str = "qwen3-asr-flash";
 const MAX_RETRIES: u32 = 2;
 
-fn asr_language_code(language_mode: AsrLanguageMode) -> &'static str {
-    match language_mode {
-        AsrLanguageMode::Auto => "auto",
-        AsrLanguageMode::Zh => "zh",
-    }
-}
-
 fn build_request_body(
     language_mode: AsrLanguageMode,
     corpus_text: &str,
-    audio_base64: &str,
+    audio_uri: &str,
 ) -> serde_json::Value {
     serde_json::json!({
         "model": MODEL,
@@ -32,7 +28,7 @@ fn build_request_body(
                 },
                 {
                     "role": "user",
-                    "content": [{"audio": format!("data:audio/wav;base64,{}", audio_base64)}]
+                    "content": [{"audio": audio_uri}]
                 }
             ]
         },
@@ -56,19 +52,27 @@ pub struct QwenASRClient {
     max_retries: u32,
     dictionary: Vec<String>,
     language_mode: AsrLanguageMode,
+    upload_format: AudioUploadFormat,
 }
 
 impl QwenASRClient {
     pub fn new(api_key: String, dictionary: Vec<String>, language_mode: AsrLanguageMode) -> Self {
         Self {
             api_key,
-            client: utils::create_http_client(),
+            client: utils::shared_http_client(),
             max_retries: MAX_RETRIES,
             dictionary,
             language_mode,
+            upload_format: AudioUploadFormat::Wav,
         }
     }
 
+    /// 上传前将录音编码为指定格式（data URI 不支持时自动降级）
+    pub fn with_upload_format(mut self, format: AudioUploadFormat) -> Self {
+        self.upload_format = resolve_upload_format(&AsrProvider::Qwen, format);
+        self
+    }
+
     /// 热更新词库
     pub fn update_dictionary(&mut self, dictionary: Vec<String>) {
         self.dictionary = dictionary;
@@ -104,26 +108,31 @@ impl QwenASRClient {
     }
 
     pub(crate) async fn transcribe_from_memory(&self, audio_data: &[u8]) -> Result<String> {
-        let audio_base64 = general_purpose::STANDARD.encode(audio_data);
-        tracing::info!("音频数据大小: {} bytes", audio_data.len());
-
-        // 词库提纯（去除 |auto 后缀）后用顿号分隔
-        let purified_words = entries_to_words(&self.dictionary);
-        let corpus_text = purified_words.join("、");
+        let upload = encode_for_upload(audio_data, self.upload_format);
+        let audio_uri = format!(
+            "data:{};base64,{}",
+            upload.mime_type(),
+            general_purpose::STANDARD.encode(&upload.data)
+        );
+        tracing::info!("音频数据大小: {} bytes", upload.data.len());
+
+        // 词库按权重排序、截断后用顿号分隔
+        let (word_count, corpus_text) = utils::qwen_corpus_text(&self.dictionary);
         if !corpus_text.is_empty() {
             tracing::info!(
                 "Qwen HTTP ASR 词库: {} 个词（已提纯）, corpus={}",
-                purified_words.len(),
+                word_count,
                 corpus_text
             );
         } else {
             tracing::info!("Qwen HTTP ASR 词库: 未配置");
         }
 
-        let request_body = build_request_body(self.language_mode, &corpus_text, &audio_base64);
+        let request_body = build_request_body(self.language_mode, &corpus_text, &audio_uri);
 
         tracing::info!("发送请求到: {}", QWEN_API_URL);
 
+        let request_start = Instant::now();
         let response = self
             .client
             .post(QWEN_API_URL)
@@ -135,6 +144,7 @@ impl QwenASRClient {
 
         let status = response.status();
         tracing::info!("API 响应状态: {}", status);
+        upload.log_request("千问", request_start.elapsed());
 
         if !status.is_success() {
             let error_text = response.text().await?;
@@ -167,6 +177,40 @@ impl QwenASRClient {
     }
 }
 
+impl AsrEngine for QwenASRClient {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::Qwen
+    }
+
+    fn name(&self) -> &str {
+        "千问"
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(async move {
+            self.transcribe_from_memory(audio_data)
+                .await
+                .map(AsrTranscript::from_text)
+        })
+    }
+
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.update_dictionary(dictionary.to_vec());
+        Some(Arc::new(client))
+    }
+
+    fn with_language_mode(&self, language_mode: AsrLanguageMode) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.language_mode = language_mode;
+        Some(Arc::new(client))
+    }
+
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(utils::prewarm_http(&self.client, QWEN_API_URL))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::build_request_body;
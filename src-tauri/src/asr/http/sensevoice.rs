@@ -1,68 +1,60 @@
-use crate::asr::utils;
+use super::OpenAiTranscriptionClient;
+use crate::asr::{AsrEngine, AsrTranscript};
+use crate::config::{AsrLanguageMode, AsrProvider, OpenAiAsrProvider};
 use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
 
 const SENSEVOICE_API_URL: &str = "https://api.siliconflow.cn/v1/audio/transcriptions";
 const MODEL: &str = "FunAudioLLM/SenseVoiceSmall";
 
+/// 硅基流动 SenseVoice：固定地址与模型的 OpenAI 兼容转写
 #[derive(Clone)]
 pub struct SenseVoiceClient {
-    api_key: String,
-    client: reqwest::Client,
+    inner: OpenAiTranscriptionClient,
 }
 
 impl SenseVoiceClient {
-    pub fn new(api_key: String) -> Self {
-        Self {
+    pub fn new(api_key: String, dictionary: Vec<String>) -> Self {
+        let config = OpenAiAsrProvider {
+            id: "siliconflow".to_string(),
+            name: "SenseVoice".to_string(),
+            endpoint: SENSEVOICE_API_URL.to_string(),
             api_key,
-            client: utils::create_http_client(),
+            model: MODEL.to_string(),
+            // 语言由 SenseVoice 自动检测；词库作为 prompt 发送，响应格式使用服务端默认的 json
+            ..Default::default()
+        };
+        Self {
+            inner: OpenAiTranscriptionClient::new(config, dictionary, AsrLanguageMode::Auto),
         }
     }
 
-    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<String> {
-        tracing::info!(
-            "开始使用 SenseVoice 转录音频数据: {} bytes",
-            audio_data.len()
-        );
-
-        let form = reqwest::multipart::Form::new().text("model", MODEL).part(
-            "file",
-            reqwest::multipart::Part::bytes(audio_data.to_vec())
-                .file_name("audio.wav")
-                .mime_str("audio/wav")?,
-        );
-
-        tracing::info!("发送请求到 SenseVoice: {}", SENSEVOICE_API_URL);
-
-        let response = self
-            .client
-            .post(SENSEVOICE_API_URL)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .multipart(form)
-            .send()
-            .await?;
+    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<AsrTranscript> {
+        self.inner.transcribe_bytes(audio_data).await
+    }
+}
 
-        let status = response.status();
-        tracing::info!("SenseVoice API 响应状态: {}", status);
+impl AsrEngine for SenseVoiceClient {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::SiliconFlow
+    }
 
-        if !status.is_success() {
-            let error_text = response.text().await?;
-            tracing::error!("SenseVoice API 错误响应: {}", error_text);
-            anyhow::bail!("SenseVoice API 请求失败 ({}): {}", status, error_text);
-        }
+    fn name(&self) -> &str {
+        "SenseVoice"
+    }
 
-        let result: serde_json::Value = response.json().await?;
-        tracing::info!(
-            "SenseVoice API 响应: {}",
-            serde_json::to_string_pretty(&result)?
-        );
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(self.transcribe_bytes(audio_data))
+    }
 
-        let mut text = result["text"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("无法解析 SenseVoice 转录结果"))?
-            .to_string();
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        let mut client = self.clone();
+        client.inner.update_dictionary(dictionary.to_vec());
+        Some(Arc::new(client))
+    }
 
-        utils::strip_trailing_punctuation(&mut text);
-        tracing::info!("SenseVoice 转录完成: {}", text);
-        Ok(text)
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        self.inner.prewarm()
     }
 }
@@ -0,0 +1,132 @@
+// 识别语言能力表
+//
+// 各提供商能识别的语言不同，这里集中维护：
+// - 能力表：提供商支持哪些 AsrLanguageMode
+// - 降级：不支持的模式回退为自动识别，并记录警告
+// - 映射：千问语言代码、豆包对话上下文等提供商专属参数
+
+use crate::config::{AsrLanguageMode, AsrProvider};
+
+use AsrLanguageMode::{Auto, En, Ja, Ko, Yue, Zh, ZhEn};
+
+/// 提供商支持的语言模式（顺序即快捷键循环切换的顺序）
+pub fn supported_language_modes(provider: &AsrProvider) -> &'static [AsrLanguageMode] {
+    match provider {
+        // qwen3-asr 支持显式指定以上全部语言
+        AsrProvider::Qwen => &[Auto, Zh, ZhEn, En, Yue, Ja, Ko],
+        // 豆包大模型默认识别中英文及粤语等方言，通过对话上下文引导，不支持日韩语
+        AsrProvider::Doubao => &[Auto, Zh, ZhEn, En, Yue],
+        // 豆包输入法固定为中文输入场景（支持中英夹杂）
+        AsrProvider::DoubaoIme => &[Auto, Zh, ZhEn],
+        // SenseVoice 接口不接受语言参数，只能自动检测
+        AsrProvider::SiliconFlow => &[Auto],
+        // Whisper 系模型支持显式指定语言
+        AsrProvider::LocalWhisper | AsrProvider::OpenAiCompatible(_) => {
+            &[Auto, Zh, ZhEn, En, Yue, Ja, Ko]
+        }
+        // 自建流式服务的语言由部署的模型决定，协议不接受语言参数
+        AsrProvider::WebSocket(_) => &[Auto],
+    }
+}
+
+pub fn is_language_supported(provider: &AsrProvider, mode: AsrLanguageMode) -> bool {
+    supported_language_modes(provider).contains(&mode)
+}
+
+/// 将请求的语言模式解析为提供商实际使用的模式，不支持时降级为自动识别
+pub fn resolve_language_mode(
+    provider: &AsrProvider,
+    requested: AsrLanguageMode,
+) -> AsrLanguageMode {
+    if is_language_supported(provider, requested) {
+        return requested;
+    }
+    tracing::warn!(
+        "{} 不支持识别语言「{}」，已降级为「{}」",
+        provider.config_key(),
+        requested.display_name(),
+        Auto.display_name()
+    );
+    Auto
+}
+
+/// 快捷键循环切换：当前提供商支持的下一个语言模式
+pub fn next_language_mode(provider: &AsrProvider, current: AsrLanguageMode) -> AsrLanguageMode {
+    let modes = supported_language_modes(provider);
+    match modes.iter().position(|m| *m == current) {
+        Some(index) => modes[(index + 1) % modes.len()],
+        None => modes[0],
+    }
+}
+
+/// 千问 `language` 参数
+pub(crate) fn qwen_language_code(language_mode: AsrLanguageMode) -> &'static str {
+    // 中英混说使用 zh：普通话模式本身支持夹杂英文
+    language_mode.iso_code().unwrap_or("auto")
+}
+
+/// 豆包对话上下文（`context_data`），用于引导识别语言与风格
+pub(crate) fn doubao_context_data(language_mode: AsrLanguageMode) -> serde_json::Value {
+    match language_mode {
+        Auto | ZhEn => serde_json::json!([
+            {"text": "当前场景为技术听写，中英文混合"},
+            {"text": "保留英文专有名词和技术术语，如 Kubernetes, GPT-4o, Claude"},
+            {"text": "保留语气词，去除尾部句号"},
+        ]),
+        En => serde_json::json!([
+            {"text": "The following is English dictation."},
+            {"text": "Keep technical terms and product names as spoken, such as Kubernetes and GPT-4o."},
+        ]),
+        Yue => serde_json::json!([
+            {"text": "以下係粵語對話，請用繁體中文輸出粵語口語"},
+            {"text": "保留語氣詞，去除尾部句號"},
+        ]),
+        // Ja / Ko 不在豆包能力表内，解析时已降级；此处按中文处理兜底
+        Zh | Ja | Ko => serde_json::json!([
+            {"text": "你好，请问有什么可以帮您的"},
+            {"text": "豆包语音识别真的不错呀"},
+            {"text": "当前聊天的场景是日常聊天，因此保留语气词，去除尾部句号"},
+        ]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_languages_downgrade_to_auto() {
+        assert_eq!(resolve_language_mode(&AsrProvider::Qwen, Yue), Yue);
+        assert_eq!(resolve_language_mode(&AsrProvider::Doubao, Ja), Auto);
+        assert_eq!(resolve_language_mode(&AsrProvider::DoubaoIme, En), Auto);
+        assert_eq!(resolve_language_mode(&AsrProvider::SiliconFlow, Zh), Auto);
+        for provider in [
+            AsrProvider::Qwen,
+            AsrProvider::Doubao,
+            AsrProvider::DoubaoIme,
+            AsrProvider::SiliconFlow,
+            AsrProvider::LocalWhisper,
+        ] {
+            assert!(is_language_supported(&provider, Auto));
+        }
+    }
+
+    #[test]
+    fn next_language_cycles_within_supported_modes() {
+        let provider = AsrProvider::DoubaoIme;
+        assert_eq!(next_language_mode(&provider, Auto), Zh);
+        assert_eq!(next_language_mode(&provider, ZhEn), Auto);
+        // 当前模式不受支持时从头开始
+        assert_eq!(next_language_mode(&provider, Ko), Auto);
+        assert_eq!(next_language_mode(&AsrProvider::SiliconFlow, Auto), Auto);
+    }
+
+    #[test]
+    fn provider_specific_mappings() {
+        assert_eq!(qwen_language_code(Auto), "auto");
+        assert_eq!(qwen_language_code(ZhEn), "zh");
+        assert_eq!(qwen_language_code(Yue), "yue");
+        assert_eq!(doubao_context_data(ZhEn), doubao_context_data(Auto));
+        assert_ne!(doubao_context_data(En), doubao_context_data(Zh));
+    }
+}
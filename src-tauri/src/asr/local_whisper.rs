@@ -0,0 +1,433 @@
+// 本地 Whisper ASR（whisper.cpp / ggml，CPU 推理）
+//
+// 完全离线：输入与 HTTP 引擎相同的 16kHz 单声道 WAV，在进程内完成转录
+//
+// 编译要求：
+// - 需要启用 `local-whisper` feature
+// - 需要安装 CMake 和 Clang（编译 whisper.cpp 与生成绑定）
+
+use super::engine::AsrEngine;
+use super::{AsrTranscript, TranscriptSegment, TranscriptWord};
+use crate::config::{AsrLanguageMode, AsrProvider, LocalWhisperConfig};
+use anyhow::{anyhow, Result};
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+
+/// Whisper 要求的输入采样率
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// 初始提示词的最大字符数（whisper 只保留最后约 224 个 token）
+const MAX_PROMPT_CHARS: usize = 200;
+
+/// 中文模式下的引导语，促使模型输出简体中文
+const ZH_PROMPT_PREFIX: &str = "以下是普通话的句子。";
+const ZH_EN_PROMPT_PREFIX: &str = "以下是普通话与英文混合的句子。";
+const YUE_PROMPT_PREFIX: &str = "以下係粵語嘅句子。";
+
+#[derive(Clone)]
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+pub struct LocalWhisperEngine {
+    config: LocalWhisperConfig,
+    dictionary: Vec<String>,
+    language_mode: AsrLanguageMode,
+    /// 模型在首次转录时加载，词库热更新产生的副本共享同一个模型
+    #[cfg(feature = "local-whisper")]
+    context: Arc<tokio::sync::OnceCell<Arc<whisper_rs::WhisperContext>>>,
+}
+
+impl LocalWhisperEngine {
+    pub fn new(
+        config: LocalWhisperConfig,
+        dictionary: Vec<String>,
+        language_mode: AsrLanguageMode,
+    ) -> Self {
+        Self {
+            config,
+            dictionary,
+            language_mode,
+            #[cfg(feature = "local-whisper")]
+            context: Arc::new(tokio::sync::OnceCell::new()),
+        }
+    }
+
+    pub fn update_dictionary(&mut self, dictionary: Vec<String>) {
+        self.dictionary = dictionary;
+    }
+
+    #[cfg(feature = "local-whisper")]
+    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<AsrTranscript> {
+        let samples = decode_wav_to_f32(audio_data)?;
+        tracing::info!(
+            "开始使用本地 Whisper 转录: {:.1}s 音频",
+            samples.len() as f32 / WHISPER_SAMPLE_RATE as f32
+        );
+
+        let context = self.load_context().await?;
+        let prompt = build_initial_prompt(&self.dictionary, self.language_mode);
+        let language = whisper_language(self.language_mode);
+        let threads = effective_threads(self.config.threads);
+
+        let start = std::time::Instant::now();
+        let segments = tokio::task::spawn_blocking(move || {
+            run_whisper(&context, &samples, language, &prompt, threads)
+        })
+        .await
+        .map_err(|e| anyhow!("本地 Whisper 推理任务异常: {}", e))??;
+
+        let mut text: String = segments.iter().map(|s| s.text.as_str()).collect();
+        super::utils::strip_trailing_punctuation(&mut text);
+        tracing::info!(
+            "本地 Whisper 转录完成 ({}ms, {} 线程): {}",
+            start.elapsed().as_millis(),
+            threads,
+            text
+        );
+        Ok(AsrTranscript::new(text, segments))
+    }
+
+    #[cfg(not(feature = "local-whisper"))]
+    pub async fn transcribe_bytes(&self, _audio_data: &[u8]) -> Result<AsrTranscript> {
+        Err(anyhow!(
+            "本地 Whisper 功能未启用。请使用 `--features local-whisper` 编译，并确保已安装 CMake 和 Clang。"
+        ))
+    }
+
+    #[cfg(feature = "local-whisper")]
+    async fn load_context(&self) -> Result<Arc<whisper_rs::WhisperContext>> {
+        let model_path = self.config.model_path.clone();
+        self.context
+            .get_or_try_init(|| async move {
+                tracing::info!("加载本地 Whisper 模型: {}", model_path);
+                let start = std::time::Instant::now();
+                let context = tokio::task::spawn_blocking(move || {
+                    whisper_rs::WhisperContext::new_with_params(
+                        &model_path,
+                        whisper_rs::WhisperContextParameters::default(),
+                    )
+                    .map_err(|e| anyhow!("加载 Whisper 模型失败 ({}): {:?}", model_path, e))
+                })
+                .await
+                .map_err(|e| anyhow!("加载 Whisper 模型任务异常: {}", e))??;
+                tracing::info!(
+                    "本地 Whisper 模型加载完成 ({}ms)",
+                    start.elapsed().as_millis()
+                );
+                Ok(Arc::new(context))
+            })
+            .await
+            .cloned()
+    }
+}
+
+#[cfg(feature = "local-whisper")]
+fn run_whisper(
+    context: &whisper_rs::WhisperContext,
+    samples: &[f32],
+    language: &'static str,
+    prompt: &str,
+    threads: u32,
+) -> Result<Vec<TranscriptSegment>> {
+    use whisper_rs::{FullParams, SamplingStrategy};
+
+    let mut state = context
+        .create_state()
+        .map_err(|e| anyhow!("创建 Whisper 推理状态失败: {:?}", e))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(threads as i32);
+    params.set_language(Some(language));
+    if !prompt.is_empty() {
+        params.set_initial_prompt(prompt);
+    }
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    // 逐 token 时间戳，用于生成逐词信息
+    params.set_token_timestamps(true);
+
+    state
+        .full(params, samples)
+        .map_err(|e| anyhow!("Whisper 推理失败: {:?}", e))?;
+
+    let read_err = |e: whisper_rs::WhisperError| anyhow!("读取 Whisper 结果失败: {:?}", e);
+    let eot = context.token_eot();
+    let n_segments = state.full_n_segments().map_err(read_err)?;
+    let mut segments = Vec::with_capacity(n_segments.max(0) as usize);
+    for i in 0..n_segments {
+        let text = state.full_get_segment_text_lossy(i).map_err(read_err)?;
+
+        let mut tokens = Vec::new();
+        for j in 0..state.full_n_tokens(i).map_err(read_err)? {
+            // 时间戳、语言等特殊 token 的 id 均不小于 eot
+            if state.full_get_token_id(i, j).map_err(read_err)? >= eot {
+                continue;
+            }
+            let data = state.full_get_token_data(i, j).map_err(read_err)?;
+            tokens.push(WhisperTokenPiece {
+                bytes: state.full_get_token_bytes(i, j).map_err(read_err)?,
+                t0: data.t0,
+                t1: data.t1,
+                probability: data.p,
+            });
+        }
+
+        let confidence = (!tokens.is_empty())
+            .then(|| tokens.iter().map(|t| t.probability).sum::<f32>() / tokens.len() as f32);
+        segments.push(TranscriptSegment {
+            text: text.trim().to_string(),
+            start_ms: centiseconds_to_ms(state.full_get_segment_t0(i).map_err(read_err)?),
+            end_ms: centiseconds_to_ms(state.full_get_segment_t1(i).map_err(read_err)?),
+            confidence,
+            words: words_from_tokens(&tokens),
+        });
+    }
+    Ok(segments)
+}
+
+/// 单个文本 token（whisper 的时间单位为 10ms）
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+struct WhisperTokenPiece {
+    bytes: Vec<u8>,
+    t0: i64,
+    t1: i64,
+    probability: f32,
+}
+
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn centiseconds_to_ms(t: i64) -> Option<u64> {
+    u64::try_from(t).ok().map(|t| t * 10)
+}
+
+/// 将 BPE token 合并为词：
+/// - 汉字等多字节字符可能被拆到多个 token，凑齐完整 UTF-8 后再处理
+/// - 以空白开头的 token 开始新词（英文等以空格分词的语言）
+/// - CJK 字符没有空格分隔，每个 token 单独成词
+///
+/// 词的置信度取组成 token 中的最小概率
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn words_from_tokens(tokens: &[WhisperTokenPiece]) -> Vec<TranscriptWord> {
+    let mut words = Vec::new();
+    let mut current: Option<TranscriptWord> = None;
+    let mut pending: Vec<u8> = Vec::new();
+    let mut pending_t0 = 0;
+    let mut pending_probability = 1.0f32;
+
+    for token in tokens {
+        if pending.is_empty() {
+            pending_t0 = token.t0;
+            pending_probability = 1.0;
+        }
+        pending.extend_from_slice(&token.bytes);
+        pending_probability = pending_probability.min(token.probability);
+
+        let piece = match std::str::from_utf8(&pending) {
+            Ok(s) => s.to_string(),
+            // 字符尚未完整，等待后续 token
+            Err(e) if e.error_len().is_none() => continue,
+            Err(_) => String::from_utf8_lossy(&pending).into_owned(),
+        };
+        pending.clear();
+
+        let trimmed = piece.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let starts_new_word = piece.starts_with(char::is_whitespace)
+            || trimmed.starts_with(is_cjk)
+            || current.as_ref().is_none_or(|w| w.text.ends_with(is_cjk));
+
+        match current.as_mut() {
+            Some(word) if !starts_new_word => {
+                word.text.push_str(trimmed);
+                word.end_ms = centiseconds_to_ms(token.t1);
+                word.confidence = word.confidence.map(|c| c.min(pending_probability));
+            }
+            _ => {
+                words.extend(current.take());
+                current = Some(TranscriptWord {
+                    text: trimmed.to_string(),
+                    start_ms: centiseconds_to_ms(pending_t0),
+                    end_ms: centiseconds_to_ms(token.t1),
+                    confidence: Some(pending_probability),
+                });
+            }
+        }
+    }
+    words.extend(current);
+    words
+}
+
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'   // 日文假名
+        | '\u{3400}'..='\u{4dbf}' // CJK 扩展 A
+        | '\u{4e00}'..='\u{9fff}' // CJK 统一汉字
+        | '\u{ac00}'..='\u{d7af}' // 韩文音节
+        | '\u{f900}'..='\u{faff}' // CJK 兼容汉字
+    )
+}
+
+impl AsrEngine for LocalWhisperEngine {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::LocalWhisper
+    }
+
+    fn name(&self) -> &str {
+        "本地 Whisper"
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(self.transcribe_bytes(audio_data))
+    }
+
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        let mut engine = self.clone();
+        engine.update_dictionary(dictionary.to_vec());
+        Some(Arc::new(engine))
+    }
+
+    fn with_language_mode(&self, language_mode: AsrLanguageMode) -> Option<Arc<dyn AsrEngine>> {
+        let mut engine = self.clone();
+        engine.language_mode = language_mode;
+        Some(Arc::new(engine))
+    }
+
+    /// 提前加载模型，首次转录无需等待
+    #[cfg(feature = "local-whisper")]
+    fn prewarm(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move { self.load_context().await.map(|_| ()) })
+    }
+}
+
+/// 解码 16kHz WAV 为 whisper 需要的 f32 单声道样本
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn decode_wav_to_f32(audio_data: &[u8]) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(audio_data))?;
+    let spec = reader.spec();
+    if spec.sample_rate != WHISPER_SAMPLE_RATE {
+        anyhow::bail!(
+            "本地 Whisper 需要 {}Hz 音频，实际为 {}Hz",
+            WHISPER_SAMPLE_RATE,
+            spec.sample_rate
+        );
+    }
+
+    let channels = spec.channels.max(1) as usize;
+    let samples: Vec<i16> = reader.samples::<i16>().collect::<Result<_, _>>()?;
+    Ok(samples
+        .chunks(channels)
+        .map(|frame| frame.iter().map(|&s| s as f32).sum::<f32>() / (channels as f32 * 32768.0))
+        .collect())
+}
+
+/// 由词库生成初始提示词，引导模型输出专有名词
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn build_initial_prompt(dictionary: &[String], language_mode: AsrLanguageMode) -> String {
+    let prefix = match language_mode {
+        AsrLanguageMode::Zh => ZH_PROMPT_PREFIX,
+        AsrLanguageMode::ZhEn => ZH_EN_PROMPT_PREFIX,
+        AsrLanguageMode::Yue => YUE_PROMPT_PREFIX,
+        _ => "",
+    };
+    super::utils::build_dictionary_prompt(prefix, dictionary, MAX_PROMPT_CHARS)
+}
+
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn whisper_language(language_mode: AsrLanguageMode) -> &'static str {
+    // 中英混说按中文识别，依靠提示词保留英文
+    language_mode.iso_code().unwrap_or("auto")
+}
+
+/// 推理线程数：0 表示自动（可用核心数，最多 8 个）
+#[cfg_attr(not(feature = "local-whisper"), allow(dead_code))]
+fn effective_threads(configured: u32) -> u32 {
+    if configured > 0 {
+        return configured;
+    }
+    std::thread::available_parallelism()
+        .map(|n| (n.get() as u32).min(8))
+        .unwrap_or(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(sample_rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+            for &s in samples {
+                writer.write_sample(s).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn decode_wav_downmixes_to_normalized_mono() {
+        let data = wav_bytes(16000, 2, &[16384, 16384, -32768, 0]);
+        let samples = decode_wav_to_f32(&data).unwrap();
+        assert_eq!(samples, vec![0.5, -0.5]);
+
+        assert!(decode_wav_to_f32(&wav_bytes(44100, 1, &[0])).is_err());
+    }
+
+    #[test]
+    fn initial_prompt_uses_dictionary_within_budget() {
+        let dictionary = vec!["Tauri".to_string(), " ".to_string(), "豆包".to_string()];
+        assert_eq!(
+            build_initial_prompt(&dictionary, AsrLanguageMode::Auto),
+            "Tauri，豆包"
+        );
+        assert!(
+            build_initial_prompt(&dictionary, AsrLanguageMode::Zh).starts_with(ZH_PROMPT_PREFIX)
+        );
+        assert!(build_initial_prompt(&dictionary, AsrLanguageMode::ZhEn)
+            .starts_with(ZH_EN_PROMPT_PREFIX));
+        assert_eq!(whisper_language(AsrLanguageMode::ZhEn), "zh");
+        assert_eq!(whisper_language(AsrLanguageMode::Ja), "ja");
+
+        let long: Vec<String> = (0..100).map(|i| format!("词条{}", i)).collect();
+        let prompt = build_initial_prompt(&long, AsrLanguageMode::Auto);
+        assert!(prompt.chars().count() <= MAX_PROMPT_CHARS);
+    }
+
+    #[test]
+    fn tokens_merge_into_words() {
+        let piece = |bytes: &[u8], t0: i64, t1: i64, probability: f32| WhisperTokenPiece {
+            bytes: bytes.to_vec(),
+            t0,
+            t1,
+            probability,
+        };
+        let hao = "好".as_bytes();
+        let tokens = vec![
+            piece(b" Hel", 0, 20, 0.9),
+            piece(b"lo", 20, 40, 0.6),
+            piece(b" world", 40, 80, 0.95),
+            piece("你".as_bytes(), 80, 100, 0.8),
+            // “好”被拆成两个 token
+            piece(&hao[..1], 100, 110, 0.7),
+            piece(&hao[1..], 110, 120, 0.9),
+        ];
+
+        let words = words_from_tokens(&tokens);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello", "world", "你", "好"]);
+        assert_eq!(words[0].start_ms, Some(0));
+        assert_eq!(words[0].end_ms, Some(400));
+        assert_eq!(words[0].confidence, Some(0.6));
+        assert_eq!(words[3].start_ms, Some(1000));
+        assert_eq!(words[3].confidence, Some(0.7));
+    }
+}
@@ -0,0 +1,507 @@
+// 长录音分段转写
+//
+// 单次请求有上限：HTTP 接口限制请求体大小与单次尝试超时（attempt_timeout_ms），实时会话有空闲与时长限制。
+// 超过阈值的录音在停顿处切分后逐段转写，再按顺序拼接：
+// - HTTP 引擎：各段并行转写（本地 Whisper 本身占满 CPU，串行），每段仍走竞速 / 重试策略
+// - 实时会话：见 realtime::RollingRealtimeSession，接近时长上限时在停顿处换新会话
+// 找不到停顿只能硬切时，相邻两段重叠 1 秒，拼接时去掉重复的文字
+
+use super::health::AsrHealthTracker;
+use super::race_strategy::transcribe_with_strategy;
+use super::{AsrEngine, AsrTranscript, TranscriptSegment};
+use crate::audio_utils::VOICE_RMS_THRESHOLD;
+use crate::config::{AsrProvider, AsrRaceStrategy, VadConfig, VadEngine};
+use crate::vad::{smooth_speech_frames, GmmVad};
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// 超过该时长的录音才分段
+const LONG_FORM_THRESHOLD_SECS: usize = 60;
+/// 每段时长范围，在此区间内找最安静的位置切分
+const MIN_SEGMENT_SECS: usize = 20;
+const MAX_SEGMENT_SECS: usize = 40;
+/// 能量分析的帧长
+const FRAME_MS: usize = 20;
+/// 切分点需要连续安静的时长
+const CUT_WINDOW_MS: usize = 300;
+/// 硬切时相邻两段的重叠时长
+pub(crate) const OVERLAP_MS: usize = 1000;
+const MAX_PARALLEL_SEGMENTS: usize = 4;
+/// 去重时比较的最大字符数（1 秒语音约 5 个汉字或 3 个英文单词）
+const MAX_OVERLAP_CHARS: usize = 32;
+/// 硬切处上一段末尾可能残缺（识别错）的字符数
+const MAX_TRUNCATED_CHARS: usize = 3;
+
+/// 录音中的一段（采样下标）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioSegment {
+    pub start: usize,
+    pub end: usize,
+    /// 与上一段重叠（硬切），拼接时需要去重
+    pub overlaps_previous: bool,
+}
+
+/// 在停顿处切分录音
+///
+/// 停顿判定按 VAD 配置：GMM 引擎要求窗口内没有语音帧；RMS 引擎的静音阈值取 VAD 阈值与
+/// 底噪（帧能量 10% 分位）两倍 RMS 中的较大者，嘈杂环境下也能找到停顿。
+/// 每段在 [MIN_SEGMENT_SECS, MAX_SEGMENT_SECS] 内选最安静的 300ms 停顿窗口中点切分，
+/// 整个区间都在说话时在上限处硬切并与下一段重叠
+pub fn plan_segments(samples: &[i16], sample_rate: u32, vad: &VadConfig) -> Vec<AudioSegment> {
+    let frame_len = (sample_rate as usize * FRAME_MS / 1000).max(1);
+    let energies: Vec<f64> = samples
+        .chunks(frame_len)
+        .map(|frame| {
+            frame
+                .iter()
+                .map(|&s| (s as f64 / 32768.0).powi(2))
+                .sum::<f64>()
+                / frame.len() as f64
+        })
+        .collect();
+    let total = energies.len();
+
+    let frames_per_sec = 1000 / FRAME_MS;
+    let min_frames = MIN_SEGMENT_SECS * frames_per_sec;
+    let max_frames = MAX_SEGMENT_SECS * frames_per_sec;
+    let window = CUT_WINDOW_MS / FRAME_MS;
+    let overlap_frames = OVERLAP_MS / FRAME_MS;
+
+    let mut sorted = energies.clone();
+    sorted.sort_by(f64::total_cmp);
+    let noise_floor = sorted.get(total / 10).copied().unwrap_or(0.0);
+    let median = sorted.get(total / 2).copied().unwrap_or(0.0);
+    // 底噪阈值不超过中位能量的 1/4，避免一直说话时把语音当成停顿
+    let silence = (VOICE_RMS_THRESHOLD as f64)
+        .powi(2)
+        .max((noise_floor * 4.0).min(median / 4.0));
+
+    let mut prefix = Vec::with_capacity(total + 1);
+    prefix.push(0.0);
+    for energy in &energies {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + energy);
+    }
+
+    // GMM：逐帧语音判决的前缀计数，窗口内计数为 0 且能量低于静音阈值才算停顿
+    // （长时间平稳的声音会被 GMM 吸收为底噪，仍需能量阈值兜底，避免在连续说话中切断）
+    let speech_prefix = match vad.engine {
+        VadEngine::Rms => None,
+        VadEngine::Gmm => {
+            let pcm: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+            let frames = GmmVad::analyze(&pcm, sample_rate, vad.aggressiveness);
+            let mut counts = Vec::with_capacity(total + 1);
+            counts.push(0usize);
+            for is_speech in smooth_speech_frames(&frames) {
+                counts.push(counts.last().copied().unwrap_or(0) + is_speech as usize);
+            }
+            Some(counts)
+        }
+    };
+    let is_pause = |pos: usize, energy: f64| match &speech_prefix {
+        Some(counts) => counts[pos + window] == counts[pos] && energy <= silence,
+        None => energy <= silence,
+    };
+
+    let mut cuts = Vec::new();
+    let (mut start, mut overlaps) = (0, false);
+    while total - start > max_frames {
+        let quietest = (start + min_frames..=start + max_frames - window)
+            .map(|pos| (pos, (prefix[pos + window] - prefix[pos]) / window as f64))
+            .filter(|&(pos, energy)| is_pause(pos, energy))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match quietest {
+            Some((pos, _)) => {
+                let cut = pos + window / 2;
+                cuts.push((start, cut, overlaps));
+                start = cut;
+                overlaps = false;
+            }
+            _ => {
+                let cut = start + max_frames;
+                cuts.push((start, cut, overlaps));
+                start = cut - overlap_frames;
+                overlaps = true;
+            }
+        }
+    }
+    cuts.push((start, total, overlaps));
+
+    cuts.into_iter()
+        .map(|(start, end, overlaps_previous)| AudioSegment {
+            start: start * frame_len,
+            end: (end * frame_len).min(samples.len()),
+            overlaps_previous,
+        })
+        .collect()
+}
+
+/// 转写录音：短录音直接走调度策略，长录音分段（并行）转写后拼接
+pub async fn transcribe_long_form(
+    engines: Vec<Arc<dyn AsrEngine>>,
+    audio_data: Vec<u8>,
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+    vad: &VadConfig,
+) -> Result<AsrTranscript> {
+    let pcm = read_mono_pcm(&audio_data)
+        .filter(|(rate, samples)| samples.len() > LONG_FORM_THRESHOLD_SECS * *rate as usize);
+    let Some((sample_rate, samples)) = pcm else {
+        return transcribe_with_strategy(engines, audio_data, strategy, health).await;
+    };
+
+    let segments = plan_segments(&samples, sample_rate, vad);
+    // 本地推理本身已占满 CPU，并行只会互相拖慢
+    let concurrency = if engines
+        .first()
+        .is_some_and(|e| e.provider() == AsrProvider::LocalWhisper)
+    {
+        1
+    } else {
+        MAX_PARALLEL_SEGMENTS
+    };
+    tracing::info!(
+        "长录音 {:.1}s，在停顿处切分为 {} 段（硬切 {} 处，并发 {}）",
+        samples.len() as f32 / sample_rate as f32,
+        segments.len(),
+        segments.iter().filter(|s| s.overlaps_previous).count(),
+        concurrency
+    );
+
+    let mut segment_audio = Vec::with_capacity(segments.len());
+    for segment in &segments {
+        segment_audio.push(write_wav(
+            sample_rate,
+            &samples[segment.start..segment.end],
+        )?);
+    }
+
+    let results: Vec<Result<AsrTranscript>> = stream::iter(segment_audio)
+        .map(|wav| transcribe_with_strategy(engines.clone(), wav, strategy, health))
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    let count = segments.len();
+    let mut parts = Vec::with_capacity(count);
+    for (index, (segment, result)) in segments.into_iter().zip(results).enumerate() {
+        let transcript =
+            result.map_err(|e| e.context(format!("第 {}/{} 段转写失败", index + 1, count)))?;
+        parts.push(SegmentTranscript {
+            transcript,
+            offset_ms: segment.start as u64 * 1000 / sample_rate as u64,
+            overlaps_previous: segment.overlaps_previous,
+        });
+    }
+    Ok(stitch_transcripts(parts))
+}
+
+/// 解析 16-bit 单声道 WAV；其他格式返回 None（不分段）
+fn read_mono_pcm(wav: &[u8]) -> Option<(u32, Vec<i16>)> {
+    let reader = hound::WavReader::new(Cursor::new(wav)).ok()?;
+    let spec = reader.spec();
+    if spec.channels != 1
+        || spec.bits_per_sample != 16
+        || spec.sample_format != hound::SampleFormat::Int
+    {
+        return None;
+    }
+    let samples = reader
+        .into_samples::<i16>()
+        .collect::<Result<_, _>>()
+        .ok()?;
+    Some((spec.sample_rate, samples))
+}
+
+fn write_wav(sample_rate: u32, samples: &[i16]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// 一段的转写结果及其在整段录音中的位置
+pub struct SegmentTranscript {
+    pub transcript: AsrTranscript,
+    pub offset_ms: u64,
+    pub overlaps_previous: bool,
+}
+
+/// 按顺序拼接各段结果：文本去重后连接，分段时间戳平移到整段录音的时间轴
+///
+/// 各段的候选结果（n-best）只覆盖局部，拼接后不再保留
+pub fn stitch_transcripts(parts: Vec<SegmentTranscript>) -> AsrTranscript {
+    let mut text = String::new();
+    let mut segments = Vec::new();
+    for part in parts {
+        append_transcript_text(&mut text, part.transcript.text(), part.overlaps_previous);
+        segments.extend(
+            part.transcript
+                .segments
+                .into_iter()
+                .map(|segment| shift_segment(segment, part.offset_ms)),
+        );
+    }
+    AsrTranscript::new(text, segments)
+}
+
+fn shift_segment(mut segment: TranscriptSegment, offset_ms: u64) -> TranscriptSegment {
+    let shift = |t: &mut Option<u64>| {
+        if let Some(t) = t.as_mut() {
+            *t += offset_ms;
+        }
+    };
+    shift(&mut segment.start_ms);
+    shift(&mut segment.end_ms);
+    for word in &mut segment.words {
+        shift(&mut word.start_ms);
+        shift(&mut word.end_ms);
+    }
+    segment
+}
+
+/// 追加下一段文本：重叠时去掉重复部分；在停顿处切分的中文补逗号，英文补空格
+pub(crate) fn append_transcript_text(text: &mut String, next: &str, overlaps_previous: bool) {
+    let mut next = next.trim();
+    if overlaps_previous {
+        let (keep, skip) = find_overlap(text, next);
+        if skip > 0 {
+            text.truncate(keep);
+            text.truncate(text.trim_end().len());
+            next = next[skip..].trim_start_matches(|c: char| !c.is_alphanumeric());
+        }
+    }
+    if next.is_empty() {
+        return;
+    }
+
+    if let (Some(last), Some(first)) = (text.chars().last(), next.chars().next()) {
+        if is_cjk(last) && is_cjk(first) {
+            if !overlaps_previous {
+                text.push('，');
+            }
+        } else if !is_cjk(last) && !is_cjk(first) && !is_cjk_punctuation(last) {
+            text.push(' ');
+        }
+    }
+    text.push_str(next);
+}
+
+/// 查找上一段结尾与下一段开头的重复部分（只比较字母、数字与汉字，忽略大小写）
+///
+/// 返回 (上一段保留的字节数, 下一段跳过的字节数)。硬切处上一段的最后几个字可能被截断而识别错，
+/// 允许丢弃上一段末尾至多 MAX_TRUNCATED_CHARS 个字后再匹配，以下一段的完整版本为准
+fn find_overlap(previous: &str, next: &str) -> (usize, usize) {
+    let mut tail: Vec<(usize, char)> = previous
+        .char_indices()
+        .rev()
+        .filter(|(_, c)| c.is_alphanumeric())
+        .take(MAX_OVERLAP_CHARS + MAX_TRUNCATED_CHARS)
+        .map(|(i, c)| (i, c.to_ascii_lowercase()))
+        .collect();
+    tail.reverse();
+    // (字符结束的字节位置, 字符)
+    let head: Vec<(usize, char)> = next
+        .char_indices()
+        .filter(|(_, c)| c.is_alphanumeric())
+        .take(MAX_OVERLAP_CHARS)
+        .map(|(i, c)| (i + c.len_utf8(), c.to_ascii_lowercase()))
+        .collect();
+
+    for dropped in 0..=MAX_TRUNCATED_CHARS.min(tail.len()) {
+        let available = tail.len() - dropped;
+        // 丢弃末尾字符后要求更长的匹配，避免误删
+        let min_len = if dropped == 0 { 2 } else { 3 };
+        for len in (min_len..=available.min(head.len())).rev() {
+            let matched = tail[available - len..available]
+                .iter()
+                .zip(&head[..len])
+                .all(|(a, b)| a.1 == b.1);
+            if !matched {
+                continue;
+            }
+
+            let mut keep = tail.get(available).map_or(previous.len(), |(i, _)| *i);
+            let mut skip = head[len - 1].0;
+            // 匹配止于英文单词中间时，整词以下一段为准（上一段末尾的单词可能被截断）
+            let word_tail = next[..skip]
+                .chars()
+                .rev()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .count();
+            if word_tail > 0 && next[skip..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+                skip -= word_tail;
+                keep = tail[available - word_tail].0;
+            }
+            return (keep, skip);
+        }
+    }
+    (previous.len(), 0)
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{AC00}'..='\u{D7AF}')
+}
+
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(c, '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stitched(parts: &[(&str, bool)]) -> String {
+        let mut text = String::new();
+        for (next, overlaps) in parts {
+            append_transcript_text(&mut text, next, *overlaps);
+        }
+        text
+    }
+
+    /// 说话（400Hz 方波）与停顿交替：speech_secs 说话后停 pause_ms
+    fn speech_with_pauses(total_secs: usize, speech_secs: usize, pause_ms: usize) -> Vec<i16> {
+        let rate = 16000;
+        let period = speech_secs * rate + pause_ms * rate / 1000;
+        (0..total_secs * rate)
+            .map(|i| {
+                if i % period < speech_secs * rate {
+                    if (i / 20) % 2 == 0 {
+                        4000
+                    } else {
+                        -4000
+                    }
+                } else {
+                    3
+                }
+            })
+            .collect()
+    }
+
+    fn vad_configs() -> [VadConfig; 2] {
+        [
+            VadConfig::default(),
+            VadConfig {
+                engine: VadEngine::Gmm,
+                ..VadConfig::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn cuts_long_recordings_inside_pauses() {
+        let samples = speech_with_pauses(100, 9, 600);
+        for vad in vad_configs() {
+            let segments = plan_segments(&samples, 16000, &vad);
+
+            assert!(segments.len() >= 3);
+            assert_eq!(segments[0].start, 0);
+            assert_eq!(segments.last().unwrap().end, samples.len());
+            for pair in segments.windows(2) {
+                assert_eq!(pair[0].end, pair[1].start);
+                assert!(!pair[1].overlaps_previous);
+                // 切分点落在停顿里
+                assert_eq!(samples[pair[0].end].abs(), 3);
+            }
+            for segment in &segments {
+                let secs = (segment.end - segment.start) / 16000;
+                assert!(secs <= MAX_SEGMENT_SECS);
+            }
+        }
+    }
+
+    #[test]
+    fn overlaps_hard_cuts_without_pauses() {
+        let samples = speech_with_pauses(90, 90, 0);
+        for vad in vad_configs() {
+            let segments = plan_segments(&samples, 16000, &vad);
+
+            assert_eq!(segments.len(), 3);
+            assert_eq!(segments[0].end, MAX_SEGMENT_SECS * 16000);
+            assert!(segments[1].overlaps_previous);
+            assert_eq!(segments[0].end - segments[1].start, OVERLAP_MS * 16);
+        }
+    }
+
+    #[test]
+    fn joins_pause_cuts_with_separators() {
+        assert_eq!(
+            stitched(&[("今天先讨论项目进度", false), ("然后安排下周的工作", false)]),
+            "今天先讨论项目进度，然后安排下周的工作"
+        );
+        assert_eq!(
+            stitched(&[("let's start", false), ("with the roadmap", false)]),
+            "let's start with the roadmap"
+        );
+        assert_eq!(
+            stitched(&[("第一段。", false), ("第二段", false)]),
+            "第一段。第二段"
+        );
+    }
+
+    #[test]
+    fn removes_duplicated_overlap() {
+        assert_eq!(
+            stitched(&[("我们讨论一下项目的进", false), ("项目的进度安排", true)]),
+            "我们讨论一下项目的进度安排"
+        );
+        // 上一段末尾被截断识别错的字以下一段为准
+        assert_eq!(
+            stitched(&[("我们讨论一下项目的近", false), ("项目的进度安排", true)]),
+            "我们讨论一下项目的进度安排"
+        );
+        // 截断在单词中间时保留下一段的完整单词
+        assert_eq!(
+            stitched(&[
+                ("please review the wor", false),
+                ("Review the world map", true)
+            ]),
+            "please review the world map"
+        );
+        // 没有重复时原样连接
+        assert_eq!(
+            stitched(&[("前一段", false), ("后一段", true)]),
+            "前一段后一段"
+        );
+    }
+
+    #[test]
+    fn stitching_shifts_segment_timestamps() {
+        let segment = |text: &str, start, end| TranscriptSegment {
+            text: text.to_string(),
+            start_ms: Some(start),
+            end_ms: Some(end),
+            ..Default::default()
+        };
+        let transcript = stitch_transcripts(vec![
+            SegmentTranscript {
+                transcript: AsrTranscript::new("第一句", vec![segment("第一句", 0, 900)]),
+                offset_ms: 0,
+                overlaps_previous: false,
+            },
+            SegmentTranscript {
+                transcript: AsrTranscript::new("第二句", vec![segment("第二句", 100, 800)]),
+                offset_ms: 30_000,
+                overlaps_previous: false,
+            },
+        ]);
+
+        assert_eq!(transcript.text(), "第一句，第二句");
+        assert_eq!(transcript.segments[1].start_ms, Some(30_100));
+        assert_eq!(transcript.segments[1].end_ms, Some(30_800));
+    }
+}
@@ -1,15 +1,40 @@
 pub mod doubao_ime;
+mod doubao_ime_credentials;
+mod engine;
+mod health;
 pub mod http;
+mod language;
+mod local_whisper;
+mod long_form;
+mod prewarm;
 mod race_strategy;
 pub mod realtime;
+mod transcript;
+mod upload_encoding;
 mod utils;
 
 pub use doubao_ime::{
+    ensure_stored_credentials as ensure_doubao_ime_credentials,
     DeviceCredentials as DoubaoImeCredentials, DoubaoImeClient, DoubaoImeClientConfig,
-    DoubaoImeRealtimeClient, DoubaoImeRealtimeSession,
+    DoubaoImeEngine, DoubaoImeRealtimeClient, DoubaoImeRealtimeSession,
 };
-pub use http::{DoubaoASRClient, QwenASRClient, SenseVoiceClient};
-pub use race_strategy::{transcribe_doubao_sensevoice_race, transcribe_with_fallback_clients};
+pub use doubao_ime_credentials::{is_credential_error, DoubaoImeCredentialStore};
+pub use engine::{AsrEngine, AsrEngineRegistry, PartialTranscriptReceiver, RealtimeAsrSession};
+pub use health::{AsrErrorKind, AsrHealthTracker, CircuitState, ProviderHealth};
+pub use http::{DoubaoASRClient, OpenAiTranscriptionClient, QwenASRClient, SenseVoiceClient};
+pub use language::{next_language_mode, resolve_language_mode, supported_language_modes};
+pub use local_whisper::LocalWhisperEngine;
+pub use long_form::transcribe_long_form;
+pub use prewarm::ConnectionPrewarmer;
+pub use race_strategy::transcribe_with_strategy;
 pub use realtime::{
     DoubaoRealtimeClient, DoubaoRealtimeSession, QwenRealtimeClient, RealtimeSession,
+    RealtimeSessionFactory, RollingRealtimeSession, WebSocketRealtimeClient,
+    WebSocketRealtimeSession,
 };
+pub use transcript::{AsrTranscript, TranscriptSegment, TranscriptWord};
+pub use upload_encoding::{
+    decode_flac_to_wav, encode_for_upload, resolve_upload_format, supported_upload_formats,
+    EncodedAudio,
+};
+pub use utils::shared_http_client;
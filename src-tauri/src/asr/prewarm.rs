@@ -0,0 +1,112 @@
+// 连接预热
+//
+// 快捷键按下时与录音并行地为即将使用的引擎建立连接（DNS / TLS 握手、凭据检查、模型加载），
+// 松开时直接复用连接池中的空闲连接。短时间内重复按键不会重复预热
+
+use super::engine::AsrEngine;
+use crate::config::AsrProvider;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 同一提供商两次预热的最小间隔（连接池中的空闲连接在此期间仍然可用）
+pub const DEFAULT_PREWARM_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct ConnectionPrewarmer {
+    interval: Duration,
+    last_warmed: Mutex<HashMap<AsrProvider, Instant>>,
+}
+
+impl Default for ConnectionPrewarmer {
+    fn default() -> Self {
+        Self::new(DEFAULT_PREWARM_INTERVAL)
+    }
+}
+
+impl ConnectionPrewarmer {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_warmed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 后台并行预热引擎，不阻塞调用方
+    pub fn prewarm(self: &Arc<Self>, engines: Vec<Arc<dyn AsrEngine>>) {
+        for engine in engines {
+            let provider = engine.provider();
+            if !self.claim(&provider) {
+                continue;
+            }
+            let prewarmer = Arc::clone(self);
+            tokio::spawn(async move {
+                let start = Instant::now();
+                match engine.prewarm().await {
+                    Ok(()) => tracing::debug!(
+                        "{} 预热完成 ({}ms)",
+                        engine.name(),
+                        start.elapsed().as_millis()
+                    ),
+                    Err(e) => {
+                        tracing::debug!("{} 预热失败: {}", engine.name(), e);
+                        // 失败不占用间隔，下次按键时重试
+                        prewarmer.release(&provider);
+                    }
+                }
+            });
+        }
+    }
+
+    /// 连接可能已失效（服务重启、网络切换），下次按键强制重新预热
+    pub fn invalidate(&self) {
+        self.last_warmed.lock().unwrap().clear();
+    }
+
+    fn claim(&self, provider: &AsrProvider) -> bool {
+        self.claim_at(provider, Instant::now())
+    }
+
+    fn claim_at(&self, provider: &AsrProvider, now: Instant) -> bool {
+        let mut last_warmed = self.last_warmed.lock().unwrap();
+        if let Some(last) = last_warmed.get(provider) {
+            if now.saturating_duration_since(*last) < self.interval {
+                return false;
+            }
+        }
+        last_warmed.insert(provider.clone(), now);
+        true
+    }
+
+    fn release(&self, provider: &AsrProvider) {
+        self.last_warmed.lock().unwrap().remove(provider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_each_provider_once_per_interval() {
+        let prewarmer = ConnectionPrewarmer::new(Duration::from_secs(30));
+        let now = Instant::now();
+
+        assert!(prewarmer.claim_at(&AsrProvider::Qwen, now));
+        assert!(!prewarmer.claim_at(&AsrProvider::Qwen, now + Duration::from_secs(10)));
+        assert!(prewarmer.claim_at(&AsrProvider::SiliconFlow, now + Duration::from_secs(10)));
+        assert!(prewarmer.claim_at(&AsrProvider::Qwen, now + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn released_or_invalidated_providers_can_be_claimed_again() {
+        let prewarmer = ConnectionPrewarmer::new(Duration::from_secs(30));
+        let now = Instant::now();
+
+        assert!(prewarmer.claim_at(&AsrProvider::Doubao, now));
+        prewarmer.release(&AsrProvider::Doubao);
+        assert!(prewarmer.claim_at(&AsrProvider::Doubao, now));
+
+        prewarmer.invalidate();
+        assert!(prewarmer.claim_at(&AsrProvider::Doubao, now));
+    }
+}
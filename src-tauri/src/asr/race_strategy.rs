@@ -1,176 +1,589 @@
-use super::{DoubaoASRClient, QwenASRClient, SenseVoiceClient};
+// ASR 多提供商调度策略
+//
+// - Sequential: 顺序故障转移，前一个彻底失败后才启动下一个
+// - Race: 全并行竞速，第一个成功的结果胜出，其余请求随即取消
+// - Hedged: 对冲请求，超过 hedge_delay 仍无结果（或已失败）才启动下一个
+// - BestOfN: 等待全部完成后按评分择优
+//
+// 重试、退避与单次超时都由 AsrRaceStrategy 决定，对每个引擎独立生效；
+// 每次尝试的结果都会记入 AsrHealthTracker，熔断中的引擎直接跳过
+
+use super::health::AsrHealthTracker;
+use super::{AsrEngine, AsrTranscript};
+use crate::config::{AsrRacePolicy, AsrRaceStrategy};
 use anyhow::Result;
-use std::sync::{Arc, Mutex};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::sync::Arc;
 use std::time::Duration;
 
-pub async fn transcribe_with_fallback_clients(
-    qwen_client: QwenASRClient,
-    sensevoice_client: SenseVoiceClient,
+/// 转录结果评分函数（分数越高越好）
+pub type TranscriptScorer = fn(&AsrTranscript) -> f64;
+
+/// 默认评分：有效字符（字母、数字、汉字）越多越好
+pub fn default_transcript_score(transcript: &AsrTranscript) -> f64 {
+    transcript
+        .text()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .count() as f64
+}
+
+/// 按策略在多个引擎之间调度转录
+///
+/// `engines` 的顺序即优先级；只有一个引擎时各策略都退化为带重试的单引擎转录
+pub async fn transcribe_with_strategy(
+    engines: Vec<Arc<dyn AsrEngine>>,
+    audio_data: Vec<u8>,
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+) -> Result<AsrTranscript> {
+    transcribe_with_scorer(
+        engines,
+        audio_data,
+        strategy,
+        health,
+        default_transcript_score,
+    )
+    .await
+}
+
+/// 同 `transcribe_with_strategy`，BestOfN 策略使用自定义评分函数
+pub async fn transcribe_with_scorer(
+    engines: Vec<Arc<dyn AsrEngine>>,
     audio_data: Vec<u8>,
-) -> Result<String> {
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+    scorer: TranscriptScorer,
+) -> Result<AsrTranscript> {
+    if engines.is_empty() {
+        return Err(anyhow::anyhow!("没有可用的 ASR 引擎"));
+    }
+    let engines = skip_open_circuits(engines, health);
+
+    let names: Vec<&str> = engines.iter().map(|e| e.name()).collect();
     tracing::info!(
-        "启动主备并行转录 (内存模式), 音频大小: {} bytes",
+        "ASR 调度: {:?}, 引擎: {}, 音频大小: {} bytes",
+        strategy.policy,
+        names.join("+"),
         audio_data.len()
     );
 
-    let audio_data_sensevoice = audio_data.clone();
-    let sensevoice_result: Arc<Mutex<Option<Result<String>>>> = Arc::new(Mutex::new(None));
-    let sensevoice_result_clone = Arc::clone(&sensevoice_result);
-
-    let sensevoice_handle = tokio::spawn(async move {
-        tracing::info!("🚀 SenseVoice 任务启动");
-        let result = sensevoice_client
-            .transcribe_bytes(&audio_data_sensevoice)
-            .await;
-        match &result {
-            Ok(text) => tracing::info!("✅SenseVoice 转录成功: {}", text),
-            Err(e) => tracing::error!("❌SenseVoice 转录失败: {}", e),
+    match strategy.policy {
+        AsrRacePolicy::Sequential => run_sequential(&engines, &audio_data, strategy, health).await,
+        AsrRacePolicy::Race => {
+            run_hedged(engines, &audio_data, strategy, health, Duration::ZERO).await
+        }
+        AsrRacePolicy::Hedged => {
+            let delay = Duration::from_millis(strategy.hedge_delay_ms);
+            run_hedged(engines, &audio_data, strategy, health, delay).await
+        }
+        AsrRacePolicy::BestOfN => {
+            run_best_of(&engines, &audio_data, strategy, health, scorer).await
         }
-        *sensevoice_result_clone.lock().unwrap() = Some(result);
-    });
+    }
+}
 
-    let max_retries = 2;
-    let mut qwen_last_error = None;
+/// 去掉熔断中的引擎；全部熔断时仍按原顺序尝试，避免用户拿不到任何结果
+fn skip_open_circuits(
+    engines: Vec<Arc<dyn AsrEngine>>,
+    health: &AsrHealthTracker,
+) -> Vec<Arc<dyn AsrEngine>> {
+    let (available, skipped): (Vec<_>, Vec<_>) = engines
+        .into_iter()
+        .partition(|engine| health.allow(&engine.provider()));
 
-    for attempt in 0..=max_retries {
-        if attempt > 0 {
-            tracing::warn!("⏳千问第{} 次重试前，检查 SenseVoice 结果...", attempt);
+    if available.is_empty() {
+        tracing::warn!("⛔所有 ASR 引擎都处于熔断状态，仍按原顺序尝试");
+        return skipped;
+    }
+    for engine in &skipped {
+        tracing::warn!("⛔{} 熔断中，跳过", engine.name());
+    }
+    available
+}
 
-            if let Some(sv_result) = sensevoice_result.lock().unwrap().as_ref() {
-                match sv_result {
-                    Ok(text) => {
-                        tracing::info!("✅千问重试前发现 SenseVoice 已成功，立即使用: {}", text);
-                        return Ok(text.clone());
-                    }
-                    Err(e) => {
-                        tracing::warn!("⚠️ SenseVoice 也失败了: {}，继续千问重试", e);
-                    }
+async fn run_sequential(
+    engines: &[Arc<dyn AsrEngine>],
+    audio_data: &[u8],
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+) -> Result<AsrTranscript> {
+    let mut errors = Vec::new();
+
+    for (index, engine) in engines.iter().enumerate() {
+        match transcribe_with_retry(engine.as_ref(), audio_data, strategy, health).await {
+            Ok(text) => return Ok(text),
+            Err(e) => {
+                if index + 1 < engines.len() {
+                    tracing::warn!("⚠️ {} 全部失败，切换下一个引擎", engine.name());
                 }
+                errors.push((engine.name().to_string(), e));
             }
-
-            tokio::time::sleep(Duration::from_millis(500)).await;
         }
+    }
 
-        tracing::info!("🔄 千问第{} 次尝试(共{} 次)", attempt + 1, max_retries + 1);
-        match qwen_client.transcribe_from_memory(&audio_data).await {
-            Ok(text) => {
-                tracing::info!("✅千问转录成功: {}", text);
-                return Ok(text);
-            }
-            Err(e) => {
-                tracing::error!("❌千问第{} 次尝试失败 {}", attempt + 1, e);
-                qwen_last_error = Some(e);
+    Err(combine_errors(errors))
+}
+
+/// 竞速与对冲共用的实现：`hedge_delay` 为零时一次性启动全部引擎
+///
+/// 对冲请求按固定节奏启动（第 k 个在 k × `hedge_delay`），中途有引擎失败不会推迟后续对冲
+async fn run_hedged(
+    engines: Vec<Arc<dyn AsrEngine>>,
+    audio_data: &[u8],
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+    hedge_delay: Duration,
+) -> Result<AsrTranscript> {
+    let mut remaining = engines.into_iter();
+    let mut pending = FuturesUnordered::new();
+    let mut errors = Vec::new();
+
+    let initial = if hedge_delay.is_zero() {
+        remaining.len()
+    } else {
+        1
+    };
+    for engine in remaining.by_ref().take(initial) {
+        tracing::info!("🚀 {} 任务启动", engine.name());
+        pending.push(named_attempt(engine, audio_data, strategy, health));
+    }
+
+    let hedge_timer = tokio::time::sleep(hedge_delay);
+    tokio::pin!(hedge_timer);
+
+    loop {
+        let has_more = !remaining.as_slice().is_empty();
+        tokio::select! {
+            Some((name, result)) = pending.next() => match result {
+                Ok(transcript) => {
+                    tracing::info!("✅{} 胜出: {}", name, transcript.text());
+                    // pending 被丢弃时其余请求随之取消
+                    return Ok(transcript);
+                }
+                Err(e) => {
+                    errors.push((name.clone(), e));
+                    if pending.is_empty() {
+                        match remaining.next() {
+                            Some(engine) => {
+                                tracing::warn!("⚠️ {} 失败，立即启动 {}", name, engine.name());
+                                pending.push(named_attempt(engine, audio_data, strategy, health));
+                            }
+                            None => break,
+                        }
+                    }
+                }
+            },
+            _ = &mut hedge_timer, if has_more => {
+                if let Some(engine) = remaining.next() {
+                    tracing::info!(
+                        "⏳ {}ms 内无结果，启动对冲请求: {}",
+                        hedge_delay.as_millis(),
+                        engine.name()
+                    );
+                    pending.push(named_attempt(engine, audio_data, strategy, health));
+                }
+                let next = hedge_timer.deadline() + hedge_delay;
+                hedge_timer.as_mut().reset(next);
             }
+            else => break,
         }
     }
 
-    tracing::warn!("⚠️ 千问全部失败，等待 SenseVoice 最终结果...");
-    let _ = sensevoice_handle.await;
+    Err(combine_errors(errors))
+}
+
+async fn run_best_of(
+    engines: &[Arc<dyn AsrEngine>],
+    audio_data: &[u8],
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+    scorer: TranscriptScorer,
+) -> Result<AsrTranscript> {
+    let results = futures_util::future::join_all(
+        engines
+            .iter()
+            .map(|engine| transcribe_with_retry(engine.as_ref(), audio_data, strategy, health)),
+    )
+    .await;
 
-    if let Some(result) = sensevoice_result.lock().unwrap().take() {
+    let mut candidates: Vec<(&str, AsrTranscript, f64)> = Vec::new();
+    let mut errors = Vec::new();
+    for (engine, result) in engines.iter().zip(results) {
         match result {
-            Ok(text) => {
-                tracing::info!("✅使用 SenseVoice 备用结果: {}", text);
-                return Ok(text);
-            }
-            Err(sensevoice_error) => {
-                tracing::error!("❌两个 API 都失败了");
-                tracing::error!("   千问错误: {:?}", qwen_last_error);
-                tracing::error!("   SenseVoice 错误: {:?}", sensevoice_error);
-                return Err(anyhow::anyhow!(
-                    "两个 API 都失败- 千问: {:?}, SenseVoice: {}",
-                    qwen_last_error,
-                    sensevoice_error
-                ));
+            Ok(transcript) => {
+                let score = scorer(&transcript);
+                tracing::info!("{} 评分: {:.2}", engine.name(), score);
+                candidates.push((engine.name(), transcript, score));
             }
+            Err(e) => errors.push((engine.name().to_string(), e)),
         }
     }
 
-    Err(anyhow::anyhow!("所有API都失败"))
-}
+    // 稳定排序：同分时保留顺序靠前的引擎
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+    let mut candidates = candidates.into_iter();
+    let Some((name, best, _)) = candidates.next() else {
+        return Err(combine_errors(errors));
+    };
+
+    // 其余引擎的结果作为候选，供 LLM 润色辨别同音词
+    let others: Vec<String> = candidates
+        .flat_map(|(_, transcript, _)| {
+            let alternatives = transcript.alternatives().to_vec();
+            std::iter::once(transcript.into_text()).chain(alternatives)
+        })
+        .collect();
+    let transcript = best.with_alternatives(others);
 
-pub async fn transcribe_doubao_sensevoice_race(
-    doubao_client: DoubaoASRClient,
-    sensevoice_client: SenseVoiceClient,
-    audio_data: Vec<u8>,
-) -> Result<String> {
     tracing::info!(
-        "启动豆包+SenseVoice并行转录, 音频大小: {} bytes",
-        audio_data.len()
+        "✅择优采用 {} 的结果: {} ({} 个候选)",
+        name,
+        transcript.text(),
+        transcript.alternatives().len()
     );
+    Ok(transcript)
+}
 
-    let audio_data_sensevoice = audio_data.clone();
-    let sensevoice_result: Arc<Mutex<Option<Result<String>>>> = Arc::new(Mutex::new(None));
-    let sensevoice_result_clone = Arc::clone(&sensevoice_result);
-
-    let sensevoice_handle = tokio::spawn(async move {
-        tracing::info!("🚀 SenseVoice 任务启动");
-        let result = sensevoice_client
-            .transcribe_bytes(&audio_data_sensevoice)
-            .await;
-        match &result {
-            Ok(text) => tracing::info!("✅SenseVoice 转录成功: {}", text),
-            Err(e) => tracing::error!("❌SenseVoice 转录失败: {}", e),
-        }
-        *sensevoice_result_clone.lock().unwrap() = Some(result);
-    });
+async fn named_attempt(
+    engine: Arc<dyn AsrEngine>,
+    audio_data: &[u8],
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+) -> (String, Result<AsrTranscript>) {
+    let result = transcribe_with_retry(engine.as_ref(), audio_data, strategy, health).await;
+    (engine.name().to_string(), result)
+}
 
-    let max_retries = 2;
-    let mut doubao_last_error = None;
+/// 单引擎转录：按策略重试，退避时间逐次翻倍，每次尝试受超时限制
+///
+/// 鉴权 / 限流类错误或引擎已熔断时不再重试
+async fn transcribe_with_retry(
+    engine: &dyn AsrEngine,
+    audio_data: &[u8],
+    strategy: &AsrRaceStrategy,
+    health: &AsrHealthTracker,
+) -> Result<AsrTranscript> {
+    let provider = engine.provider();
+    let max_retries = strategy.max_retries;
+    let mut last_error = None;
 
     for attempt in 0..=max_retries {
         if attempt > 0 {
-            tracing::warn!("⏳豆包第{} 次重试前，检查 SenseVoice 结果...", attempt);
+            let backoff = retry_backoff(strategy, attempt);
+            tracing::warn!(
+                "{}第 {} 次重试转录（等待 {}ms）...",
+                engine.name(),
+                attempt,
+                backoff.as_millis()
+            );
+            tokio::time::sleep(backoff).await;
+        }
 
-            if let Some(sv_result) = sensevoice_result.lock().unwrap().as_ref() {
-                match sv_result {
-                    Ok(text) => {
-                        tracing::info!("✅豆包重试前发现 SenseVoice 已成功，立即使用: {}", text);
-                        return Ok(text.clone());
-                    }
-                    Err(e) => {
-                        tracing::warn!("⚠️ SenseVoice 也失败了: {}，继续豆包重试", e);
-                    }
-                }
+        let attempt_start = std::time::Instant::now();
+        let result = if strategy.attempt_timeout_ms > 0 {
+            let timeout = Duration::from_millis(strategy.attempt_timeout_ms);
+            match tokio::time::timeout(timeout, engine.transcribe(audio_data)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!("转录超时 ({}ms)", timeout.as_millis())),
             }
+        } else {
+            engine.transcribe(audio_data).await
+        };
 
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-
-        tracing::info!("🔄 豆包第{} 次尝试(共{} 次)", attempt + 1, max_retries + 1);
-        match doubao_client.transcribe_bytes(&audio_data).await {
-            Ok(text) => {
-                tracing::info!("✅豆包转录成功: {}", text);
-                return Ok(text);
+        match result {
+            Ok(transcript) => {
+                health.record_success(&provider, attempt_start.elapsed());
+                tracing::info!("✅{}转录成功: {}", engine.name(), transcript.text());
+                return Ok(transcript);
             }
             Err(e) => {
-                tracing::error!("❌豆包第{} 次尝试失败: {}", attempt + 1, e);
-                doubao_last_error = Some(e);
+                let kind = health.record_failure(&provider, &e, attempt_start.elapsed());
+                tracing::error!(
+                    "❌{}转录失败 (尝试 {}/{}, {:?}): {}",
+                    engine.name(),
+                    attempt + 1,
+                    max_retries + 1,
+                    kind,
+                    e
+                );
+                last_error = Some(e);
+
+                if !kind.is_retryable() || health.is_open(&provider) {
+                    if attempt < max_retries {
+                        tracing::warn!("{}错误不可重试或已熔断，跳过剩余重试", engine.name());
+                    }
+                    break;
+                }
             }
         }
     }
 
-    tracing::warn!("⚠️ 豆包全部失败，等待 SenseVoice 最终结果...");
-    let _ = sensevoice_handle.await;
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("转录失败，未知错误")))
+}
 
-    if let Some(result) = sensevoice_result.lock().unwrap().take() {
-        match result {
-            Ok(text) => {
-                tracing::info!("✅使用 SenseVoice 备用结果: {}", text);
-                return Ok(text);
-            }
-            Err(sensevoice_error) => {
-                tracing::error!("❌两个 API 都失败了");
-                tracing::error!("   豆包错误: {:?}", doubao_last_error);
-                tracing::error!("   SenseVoice 错误: {:?}", sensevoice_error);
-                return Err(anyhow::anyhow!(
-                    "两个 API 都失败 - 豆包: {:?}, SenseVoice: {}",
-                    doubao_last_error,
-                    sensevoice_error
-                ));
-            }
+/// 第 `attempt` 次重试前的等待时间：retry_backoff_ms * 2^(attempt-1)
+fn retry_backoff(strategy: &AsrRaceStrategy, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(10);
+    Duration::from_millis(strategy.retry_backoff_ms.saturating_mul(factor))
+}
+
+fn combine_errors(mut errors: Vec<(String, anyhow::Error)>) -> anyhow::Error {
+    if errors.len() == 1 {
+        let (_, error) = errors.remove(0);
+        return error;
+    }
+
+    let details: Vec<String> = errors
+        .iter()
+        .map(|(name, e)| format!("{}: {}", name, e))
+        .collect();
+    tracing::error!("❌所有 ASR 引擎都失败了");
+    anyhow::anyhow!("所有 ASR 引擎都失败 - {}", details.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AsrProvider;
+    use futures_util::future::BoxFuture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct MockEngine {
+        name: &'static str,
+        delay: Duration,
+        result: std::result::Result<&'static str, &'static str>,
+        calls: AtomicUsize,
+        started_at: std::sync::Mutex<Option<tokio::time::Instant>>,
+    }
+
+    impl MockEngine {
+        fn new(
+            name: &'static str,
+            delay_ms: u64,
+            result: std::result::Result<&'static str, &'static str>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                delay: Duration::from_millis(delay_ms),
+                result,
+                calls: AtomicUsize::new(0),
+                started_at: std::sync::Mutex::new(None),
+            })
         }
     }
 
-    Err(anyhow::anyhow!("所有API都失败"))
+    impl AsrEngine for MockEngine {
+        fn provider(&self) -> AsrProvider {
+            AsrProvider::OpenAiCompatible(self.name.to_string())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn transcribe<'a>(&'a self, _audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                *self.started_at.lock().unwrap() = Some(tokio::time::Instant::now());
+                tokio::time::sleep(self.delay).await;
+                self.result
+                    .map(AsrTranscript::from_text)
+                    .map_err(|e| anyhow::anyhow!(e))
+            })
+        }
+    }
+
+    fn no_retry(policy: AsrRacePolicy) -> AsrRaceStrategy {
+        AsrRaceStrategy {
+            policy,
+            max_retries: 0,
+            retry_backoff_ms: 0,
+            attempt_timeout_ms: 0,
+            hedge_delay_ms: 50,
+        }
+    }
+
+    #[tokio::test]
+    async fn sequential_fails_over_in_order() {
+        let first = MockEngine::new("a", 0, Err("boom"));
+        let second = MockEngine::new("b", 0, Ok("second"));
+        let third = MockEngine::new("c", 0, Ok("third"));
+
+        let text = transcribe_with_strategy(
+            vec![first, second, third.clone()],
+            vec![],
+            &no_retry(AsrRacePolicy::Sequential),
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(text.text(), "second");
+        assert_eq!(third.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn race_returns_first_success() {
+        let slow = MockEngine::new("slow", 200, Ok("slow"));
+        let fast = MockEngine::new("fast", 10, Ok("fast"));
+
+        let text = transcribe_with_strategy(
+            vec![slow, fast],
+            vec![],
+            &no_retry(AsrRacePolicy::Race),
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(text.text(), "fast");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedged_only_starts_backup_after_delay() {
+        let primary = MockEngine::new("primary", 10, Ok("primary"));
+        let backup = MockEngine::new("backup", 0, Ok("backup"));
+
+        let text = transcribe_with_strategy(
+            vec![primary, backup.clone()],
+            vec![],
+            &no_retry(AsrRacePolicy::Hedged),
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(text.text(), "primary");
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 0);
+
+        let stalled = MockEngine::new("stalled", 500, Ok("stalled"));
+        let start = tokio::time::Instant::now();
+        let text = transcribe_with_strategy(
+            vec![stalled, backup.clone()],
+            vec![],
+            &no_retry(AsrRacePolicy::Hedged),
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(text.text(), "backup");
+        let started = backup.started_at.lock().unwrap().unwrap() - start;
+        assert_eq!(started, Duration::from_millis(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hedge_schedule_is_not_delayed_by_failures() {
+        // 首个对冲请求很快失败，不应推迟下一个对冲
+        let stalled = MockEngine::new("stalled", 1000, Ok("stalled"));
+        let failing = MockEngine::new("failing", 60, Err("boom"));
+        let backup = MockEngine::new("backup", 0, Ok("backup"));
+        let strategy = AsrRaceStrategy {
+            hedge_delay_ms: 100,
+            ..no_retry(AsrRacePolicy::Hedged)
+        };
+
+        let start = tokio::time::Instant::now();
+        let text = transcribe_with_strategy(
+            vec![stalled, failing, backup.clone()],
+            vec![],
+            &strategy,
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(text.text(), "backup");
+
+        // 第三个引擎在 2 × hedge_delay 时启动，而不是失败后再等一个 hedge_delay（260ms）
+        let started = backup.started_at.lock().unwrap().unwrap() - start;
+        assert_eq!(started, Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn best_of_n_picks_highest_score() {
+        let short = MockEngine::new("short", 0, Ok("你好"));
+        let long = MockEngine::new("long", 20, Ok("你好世界"));
+        let failed = MockEngine::new("failed", 0, Err("boom"));
+
+        let text = transcribe_with_strategy(
+            vec![short, long, failed],
+            vec![],
+            &no_retry(AsrRacePolicy::BestOfN),
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(text.text(), "你好世界");
+        assert_eq!(text.alternatives(), ["你好"]);
+    }
+
+    #[tokio::test]
+    async fn retries_after_attempt_timeout() {
+        let engine = MockEngine::new("slow", 100, Ok("late"));
+        let strategy = AsrRaceStrategy {
+            max_retries: 1,
+            attempt_timeout_ms: 10,
+            ..no_retry(AsrRacePolicy::Sequential)
+        };
+
+        let err = transcribe_with_strategy(
+            vec![engine.clone()],
+            vec![],
+            &strategy,
+            &AsrHealthTracker::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("超时"));
+        assert_eq!(engine.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_engine_and_stops_retries() {
+        let flaky = MockEngine::new("flaky", 0, Err("503 Service Unavailable"));
+        let backup = MockEngine::new("backup", 0, Ok("backup"));
+        let health = AsrHealthTracker::new(2, Duration::from_secs(60));
+        let strategy = AsrRaceStrategy {
+            max_retries: 5,
+            ..no_retry(AsrRacePolicy::Sequential)
+        };
+
+        // 第 2 次失败即熔断，剩余重试被跳过
+        let text = transcribe_with_strategy(
+            vec![flaky.clone(), backup.clone()],
+            vec![],
+            &strategy,
+            &health,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text.text(), "backup");
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+
+        // 熔断期间直接使用备用引擎
+        transcribe_with_strategy(vec![flaky.clone(), backup], vec![], &strategy, &health)
+            .await
+            .unwrap();
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn auth_errors_are_not_retried() {
+        let engine = MockEngine::new("expired", 0, Err("401 Unauthorized"));
+        let strategy = AsrRaceStrategy {
+            max_retries: 3,
+            ..no_retry(AsrRacePolicy::Sequential)
+        };
+
+        let result = transcribe_with_strategy(
+            vec![engine.clone()],
+            vec![],
+            &strategy,
+            &AsrHealthTracker::default(),
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(engine.calls.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,10 +1,14 @@
 // 豆包流式 ASR WebSocket 客户端（二进制协议）
-use crate::config::AsrLanguageMode;
-use crate::dictionary_utils::entries_to_words;
+use crate::asr::engine::{partial_transcript_channel, publish_partial};
+use crate::asr::language::doubao_context_data as build_context_data;
+use crate::asr::transcript::segments_from_utterances;
+use crate::asr::utils::doubao_hotwords;
+use crate::asr::{AsrTranscript, PartialTranscriptReceiver, RealtimeAsrSession, TranscriptSegment};
+use crate::config::{AsrLanguageMode, AsrProvider};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{future::BoxFuture, SinkExt, StreamExt};
 use std::io::{Read, Write};
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -16,21 +20,6 @@ const WEBSOCKET_URL: &str = "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel
 const RESOURCE_ID: &str = "volc.seedasr.sauc.duration";
 const TRANSCRIPTION_TIMEOUT_SECS: u64 = 6;
 
-fn build_context_data(language_mode: AsrLanguageMode) -> serde_json::Value {
-    match language_mode {
-        AsrLanguageMode::Auto => serde_json::json!([
-            {"text": "当前场景为技术听写，中英文混合"},
-            {"text": "保留英文专有名词和技术术语，如 Kubernetes, GPT-4o, Claude"},
-            {"text": "保留语气词，去除尾部句号"},
-        ]),
-        AsrLanguageMode::Zh => serde_json::json!([
-            {"text": "你好，请问有什么可以帮您的"},
-            {"text": "豆包语音识别真的不错呀"},
-            {"text": "当前聊天的场景是日常聊天，因此保留语气词，去除尾部句号"},
-        ]),
-    }
-}
-
 /// 生成随机的 Sec-WebSocket-Key
 fn generate_websocket_key() -> String {
     // 使用 UUID 生成 16 字节随机数据
@@ -40,7 +29,9 @@ fn generate_websocket_key() -> String {
 
 pub struct DoubaoRealtimeSession {
     sender: mpsc::Sender<SessionCommand>,
-    result_receiver: mpsc::Receiver<Result<String>>,
+    result_receiver: mpsc::Receiver<Result<AsrTranscript>>,
+    partial_receiver: PartialTranscriptReceiver,
+    finished: bool,
 }
 
 enum SessionCommand {
@@ -58,13 +49,22 @@ impl DoubaoRealtimeSession {
     }
 
     pub async fn finish_audio(&mut self) -> Result<()> {
+        self.finished = true;
         self.sender
             .send(SessionCommand::Finish)
             .await
             .map_err(|_| anyhow::anyhow!("发送结束标志失败"))
     }
 
-    pub async fn wait_for_result(&mut self) -> Result<String> {
+    /// 放弃会话：协议没有独立的关闭指令，尚未结束时补发结束标志让服务端断开
+    pub async fn close(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finish_audio().await
+    }
+
+    pub async fn wait_for_result(&mut self) -> Result<AsrTranscript> {
         match timeout(
             Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS),
             self.result_receiver.recv(),
@@ -127,6 +127,7 @@ impl DoubaoRealtimeClient {
          "enable_itn": true, //文本规范化
          "enable_punc": true, //启用标点
          "enable_ddc": true, //语义顺滑
+         "show_utterances": true, //分句与逐词时间戳
         //  "show_speech_rate":true //语速
         });
 
@@ -139,13 +140,9 @@ impl DoubaoRealtimeClient {
             });
 
             if !self.dictionary.is_empty() {
-                let purified_words = entries_to_words(&self.dictionary);
-                let hotwords: Vec<serde_json::Value> = purified_words
-                    .iter()
-                    .map(|w| serde_json::json!({"word": w}))
-                    .collect();
+                let hotwords = doubao_hotwords(&self.dictionary);
+                tracing::info!("豆包流式 ASR 词库: {} 个词（已提纯）", hotwords.len());
                 context_obj["hotwords"] = serde_json::json!(hotwords);
-                tracing::info!("豆包流式 ASR 词库: {} 个词（已提纯）", purified_words.len());
             } else {
                 tracing::info!("豆包流式 ASR 词库: 未配置");
             }
@@ -175,7 +172,7 @@ impl DoubaoRealtimeClient {
                     tracing::debug!("豆包 Full Client Request 响应: {} bytes", data.len());
                     // 解析响应检查是否成功（适配新的返回类型）
                     match parse_response(&data) {
-                        Ok((text, _is_last)) => {
+                        Ok((text, _segments, _is_last)) => {
                             if !text.is_empty() {
                                 tracing::debug!("豆包初始响应包含文本（意外）: {}", text);
                             }
@@ -195,7 +192,8 @@ impl DoubaoRealtimeClient {
         }
 
         let (cmd_tx, mut cmd_rx) = mpsc::channel::<SessionCommand>(100);
-        let (result_tx, result_rx) = mpsc::channel::<Result<String>>(1);
+        let (result_tx, result_rx) = mpsc::channel::<Result<AsrTranscript>>(1);
+        let (partial_tx, partial_rx) = partial_transcript_channel();
 
         let mut sequence = 1i32;
         tokio::spawn(async move {
@@ -229,6 +227,7 @@ impl DoubaoRealtimeClient {
 
         tokio::spawn(async move {
             let mut accumulated_text = String::new();
+            let mut accumulated_segments: Vec<TranscriptSegment> = Vec::new();
             let mut result_sent = false;
 
             while let Some(msg) = read.next().await {
@@ -236,9 +235,11 @@ impl DoubaoRealtimeClient {
                     Ok(Message::Binary(data)) => {
                         tracing::debug!("豆包 WebSocket 收到二进制消息: {} bytes", data.len());
                         match parse_response(&data) {
-                            Ok((text, is_final)) => {
+                            Ok((text, segments, is_final)) => {
                                 if !text.is_empty() {
                                     accumulated_text = text; // 更新为最新文本
+                                    accumulated_segments = segments;
+                                    publish_partial(&partial_tx, &accumulated_text);
                                     tracing::debug!("豆包累积文本: {}", accumulated_text);
                                 }
                                 if is_final {
@@ -248,7 +249,12 @@ impl DoubaoRealtimeClient {
                                         accumulated_text.clone()
                                     };
                                     tracing::info!("豆包流式转录结果（最终包）: {}", final_text);
-                                    let _ = result_tx.send(Ok(final_text)).await;
+                                    let _ = result_tx
+                                        .send(Ok(AsrTranscript::new(
+                                            final_text,
+                                            std::mem::take(&mut accumulated_segments),
+                                        )))
+                                        .await;
                                     result_sent = true;
                                     break;
                                 }
@@ -264,7 +270,12 @@ impl DoubaoRealtimeClient {
                         // 连接关闭时返回已累积的文本
                         if !accumulated_text.is_empty() {
                             tracing::info!("豆包连接关闭，返回累积文本: {}", accumulated_text);
-                            let _ = result_tx.send(Ok(accumulated_text.clone())).await;
+                            let _ = result_tx
+                                .send(Ok(AsrTranscript::new(
+                                    accumulated_text.clone(),
+                                    std::mem::take(&mut accumulated_segments),
+                                )))
+                                .await;
                             result_sent = true;
                         } else {
                             tracing::warn!("豆包连接关闭，无转录结果");
@@ -293,7 +304,12 @@ impl DoubaoRealtimeClient {
             if !result_sent {
                 if !accumulated_text.is_empty() {
                     tracing::info!("豆包连接结束，返回累积文本: {}", accumulated_text);
-                    let _ = result_tx.send(Ok(accumulated_text)).await;
+                    let _ = result_tx
+                        .send(Ok(AsrTranscript::new(
+                            accumulated_text,
+                            accumulated_segments,
+                        )))
+                        .await;
                 } else {
                     tracing::warn!("豆包连接结束，无转录结果");
                     let _ = result_tx
@@ -307,6 +323,8 @@ impl DoubaoRealtimeClient {
         Ok(DoubaoRealtimeSession {
             sender: cmd_tx,
             result_receiver: result_rx,
+            partial_receiver: partial_rx,
+            finished: false,
         })
     }
 }
@@ -342,7 +360,7 @@ fn build_message(
     Ok(msg)
 }
 
-fn parse_response(data: &[u8]) -> Result<(String, bool)> {
+fn parse_response(data: &[u8]) -> Result<(String, Vec<TranscriptSegment>, bool)> {
     if data.len() < 4 {
         return Err(anyhow::anyhow!("响应太短: {} bytes", data.len()));
     }
@@ -440,12 +458,39 @@ fn parse_response(data: &[u8]) -> Result<(String, bool)> {
 
     // 如果是最后一包或者有文本内容，返回结果
     if is_last || !text.is_empty() {
-        return Ok((text, is_last));
+        let segments = segments_from_utterances(&result["result"]["utterances"]);
+        return Ok((text, segments, is_last));
     }
 
     Err(anyhow::anyhow!("中间响应，等待更多数据"))
 }
 
+impl RealtimeAsrSession for DoubaoRealtimeSession {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::Doubao
+    }
+
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(DoubaoRealtimeSession::send_audio_chunk(self, pcm_data))
+    }
+
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.finish_audio())
+    }
+
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+        Box::pin(DoubaoRealtimeSession::wait_for_result(self))
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(DoubaoRealtimeSession::close(self))
+    }
+
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        Some(self.partial_receiver.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::build_context_data;
@@ -1,5 +1,9 @@
 mod doubao;
 mod qwen;
+mod rolling;
+mod websocket;
 
 pub use doubao::{DoubaoRealtimeClient, DoubaoRealtimeSession};
 pub use qwen::{QwenRealtimeClient, RealtimeSession};
+pub use rolling::{RealtimeSessionFactory, RollingRealtimeSession};
+pub use websocket::{WebSocketRealtimeClient, WebSocketRealtimeSession};
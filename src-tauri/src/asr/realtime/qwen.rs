@@ -1,11 +1,14 @@
 // qwen3-asr-flash-realtime WebSocket 客户端
 // 实时流式语音识别，边录音边发送
 
-use crate::config::AsrLanguageMode;
-use crate::dictionary_utils::entries_to_words;
+use crate::asr::engine::{partial_transcript_channel, publish_partial};
+use crate::asr::language::qwen_language_code as asr_language_code;
+use crate::asr::utils::qwen_corpus_text;
+use crate::asr::{AsrTranscript, PartialTranscriptReceiver, RealtimeAsrSession};
+use crate::config::{AsrLanguageMode, AsrProvider};
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
-use futures_util::{stream::SplitSink, SinkExt, StreamExt};
+use futures_util::{future::BoxFuture, stream::SplitSink, SinkExt, StreamExt};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
@@ -23,13 +26,6 @@ const MODEL: &str = "qwen3-asr-flash-realtime";
 const IDLE_TIMEOUT_SECS: u64 = 180; // 3 分钟空闲超时
 const TRANSCRIPTION_TIMEOUT_SECS: u64 = 10; // 转录结果等待超时（秒）
 
-fn asr_language_code(language_mode: AsrLanguageMode) -> &'static str {
-    match language_mode {
-        AsrLanguageMode::Auto => "auto",
-        AsrLanguageMode::Zh => "zh",
-    }
-}
-
 fn build_input_audio_transcription(
     language_mode: AsrLanguageMode,
     dictionary: &[String],
@@ -38,13 +34,12 @@ fn build_input_audio_transcription(
         "language": asr_language_code(language_mode)
     });
 
-    let purified_words = entries_to_words(dictionary);
-    let corpus_text = purified_words.join("、");
+    let (word_count, corpus_text) = qwen_corpus_text(dictionary);
 
     if !corpus_text.is_empty() {
         tracing::info!(
             "Qwen 流式 ASR 词库: {} 个词（已提纯）, corpus={}",
-            purified_words.len(),
+            word_count,
             corpus_text
         );
         input_audio_transcription["corpus"] = serde_json::json!({"text": corpus_text});
@@ -59,6 +54,7 @@ fn build_input_audio_transcription(
 pub struct RealtimeSession {
     sender: mpsc::Sender<SessionCommand>,
     result_receiver: mpsc::Receiver<Result<String>>,
+    partial_receiver: PartialTranscriptReceiver,
 }
 
 enum SessionCommand {
@@ -187,11 +183,12 @@ impl ConnectionPool {
         let (cmd_tx, mut cmd_rx) = mpsc::channel::<SessionCommand>(100);
         // 创建结果通道
         let (result_tx, result_rx) = mpsc::channel::<Result<String>>(1);
+        let (partial_tx, partial_rx) = partial_transcript_channel();
 
         // 发送 session.update 配置会话
         let input_audio_transcription =
             build_input_audio_transcription(self.language_mode, &self.dictionary);
-        let corpus_for_check = entries_to_words(&self.dictionary).join("、");
+        let (_, corpus_for_check) = qwen_corpus_text(&self.dictionary);
 
         let session_update = serde_json::json!({
             "event_id": format!("event_{}", std::time::SystemTime::now()
@@ -283,6 +280,15 @@ impl ConnectionPool {
                                     "input_audio_buffer.committed" => {
                                         tracing::info!("音频缓冲区已提交");
                                     }
+                                    "conversation.item.input_audio_transcription.text" => {
+                                        // 中间结果：text 为已确认部分，stash 为仍可能变化的部分
+                                        let confirmed = data["text"].as_str().unwrap_or("");
+                                        let stash = data["stash"].as_str().unwrap_or("");
+                                        publish_partial(
+                                            &partial_tx,
+                                            &format!("{}{}", confirmed, stash),
+                                        );
+                                    }
                                     "conversation.item.input_audio_transcription.completed" => {
                                         // 转录完成
                                         if let Some(transcript) = data["transcript"].as_str() {
@@ -295,6 +301,7 @@ impl ConnectionPool {
                                         // 增量转录结果
                                         if let Some(delta) = data["delta"].as_str() {
                                             final_text.push_str(delta);
+                                            publish_partial(&partial_tx, &final_text);
                                             tracing::debug!("增量转录: {}", delta);
                                         }
                                     }
@@ -380,6 +387,7 @@ impl ConnectionPool {
         Ok(RealtimeSession {
             sender: cmd_tx,
             result_receiver: result_rx,
+            partial_receiver: partial_rx,
         })
     }
 }
@@ -402,6 +410,37 @@ impl QwenRealtimeClient {
     }
 }
 
+impl RealtimeAsrSession for RealtimeSession {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::Qwen
+    }
+
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(RealtimeSession::send_audio_chunk(self, pcm_data))
+    }
+
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.commit_audio())
+    }
+
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+        // 千问实时接口只返回整段文本
+        Box::pin(async move {
+            RealtimeSession::wait_for_result(self)
+                .await
+                .map(AsrTranscript::from_text)
+        })
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(RealtimeSession::close(self))
+    }
+
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        Some(self.partial_receiver.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::build_input_audio_transcription;
@@ -0,0 +1,377 @@
+// 长录音的实时会话轮换
+//
+// 实时接口对单个会话有时长限制（千问空闲 180 秒即断开，整段识别也有超时），
+// 长时间口述时在接近上限前换一个新会话继续发送：
+// - 到达软上限后，等到一个停顿的音频块再切换，两段之间没有重叠
+// - 一直没有停顿时在硬上限处切换，并把最近约 1 秒的音频重发给新会话，拼接时去掉重复文字
+// 旧会话在后台完成 finish / 等待结果，最终结果按顺序拼接；中间结果带上已完成部分的文字
+
+use crate::asr::engine::{partial_transcript_channel, publish_partial};
+use crate::asr::long_form::{
+    append_transcript_text, stitch_transcripts, SegmentTranscript, OVERLAP_MS,
+};
+use crate::asr::{AsrTranscript, PartialTranscriptReceiver, RealtimeAsrSession};
+use crate::audio_utils::is_voice_active;
+use crate::config::AsrProvider;
+use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 实时会话的采样率（见 RealtimeAsrSession::send_audio_chunk）
+const SAMPLE_RATE: usize = 16000;
+
+/// 创建同一提供商的新会话，用于轮换
+pub type RealtimeSessionFactory =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<Box<dyn RealtimeAsrSession>>> + Send + Sync>;
+
+/// 单个会话的（软上限, 硬上限），单位秒
+fn session_limits(provider: &AsrProvider) -> (usize, usize) {
+    match provider {
+        // 千问实时会话 180 秒空闲超时，留出提交与等待结果的余量
+        AsrProvider::Qwen => (120, 170),
+        _ => (240, 290),
+    }
+}
+
+/// 已轮换出去、正在后台等待结果的会话
+struct RetiredSession {
+    result: JoinHandle<Result<AsrTranscript>>,
+    offset_ms: u64,
+    overlaps_previous: bool,
+}
+
+/// 中间结果的转发状态
+struct PartialRelay {
+    sender: Arc<watch::Sender<String>>,
+    receiver: PartialTranscriptReceiver,
+    /// 已轮换出去的会话的文字
+    committed: String,
+    forwarder: Option<JoinHandle<()>>,
+}
+
+/// 在多个实时会话之间自动轮换的会话，对调用方表现为单个会话
+pub struct RollingRealtimeSession {
+    provider: AsrProvider,
+    factory: RealtimeSessionFactory,
+    current: Option<Box<dyn RealtimeAsrSession>>,
+    /// 当前会话已发送的采样数
+    session_samples: usize,
+    /// 当前会话在整段录音中的起点（采样）
+    session_offset: usize,
+    current_overlaps: bool,
+    total_samples: usize,
+    /// 最近约 OVERLAP_MS 的音频块，硬切时重发
+    recent_chunks: VecDeque<Vec<i16>>,
+    recent_samples: usize,
+    retired: Vec<RetiredSession>,
+    partials: Option<PartialRelay>,
+}
+
+impl RollingRealtimeSession {
+    pub fn new(first: Box<dyn RealtimeAsrSession>, factory: RealtimeSessionFactory) -> Self {
+        let partials = first.subscribe_partials().map(|_| {
+            let (sender, receiver) = partial_transcript_channel();
+            PartialRelay {
+                sender: Arc::new(sender),
+                receiver,
+                committed: String::new(),
+                forwarder: None,
+            }
+        });
+        let mut session = Self {
+            provider: first.provider(),
+            factory,
+            current: None,
+            session_samples: 0,
+            session_offset: 0,
+            current_overlaps: false,
+            total_samples: 0,
+            recent_chunks: VecDeque::new(),
+            recent_samples: 0,
+            retired: Vec::new(),
+            partials,
+        };
+        session.attach(first, false);
+        session
+    }
+
+    /// 切换到新会话，并把它的中间结果接到已完成部分之后
+    fn attach(&mut self, session: Box<dyn RealtimeAsrSession>, overlaps_previous: bool) {
+        if let Some(relay) = self.partials.as_mut() {
+            if let Some(forwarder) = relay.forwarder.take() {
+                forwarder.abort();
+            }
+            if let Some(mut partials) = session.subscribe_partials() {
+                let sender = Arc::clone(&relay.sender);
+                let committed = relay.committed.clone();
+                relay.forwarder = Some(tokio::spawn(async move {
+                    while partials.changed().await.is_ok() {
+                        let mut text = committed.clone();
+                        append_transcript_text(
+                            &mut text,
+                            &partials.borrow_and_update(),
+                            overlaps_previous,
+                        );
+                        publish_partial(&sender, &text);
+                    }
+                }));
+            }
+        }
+        self.current = Some(session);
+        self.current_overlaps = overlaps_previous;
+    }
+
+    /// 结束当前会话（后台等待结果）并开启新会话
+    async fn roll_over(&mut self, resend_recent: bool) -> Result<()> {
+        let Some(mut retired) = self.current.take() else {
+            return Ok(());
+        };
+        if let Some(relay) = self.partials.as_mut() {
+            let last = retired
+                .subscribe_partials()
+                .map(|rx| rx.borrow().clone())
+                .unwrap_or_default();
+            append_transcript_text(&mut relay.committed, &last, self.current_overlaps);
+        }
+
+        tracing::info!(
+            "实时会话已持续 {}s，切换到新会话（{}）",
+            self.session_samples / SAMPLE_RATE,
+            if resend_recent {
+                "未检测到停顿，重叠 1 秒"
+            } else {
+                "在停顿处切分"
+            }
+        );
+        self.retired.push(RetiredSession {
+            result: tokio::spawn(async move {
+                let result = async {
+                    retired.finish().await?;
+                    retired.wait_for_result().await
+                }
+                .await;
+                let _ = retired.close().await;
+                result
+            }),
+            offset_ms: (self.session_offset * 1000 / SAMPLE_RATE) as u64,
+            overlaps_previous: self.current_overlaps,
+        });
+
+        let mut next = (self.factory)().await.context("轮换实时会话失败")?;
+        let mut sent = 0;
+        if resend_recent {
+            for chunk in &self.recent_chunks {
+                next.send_audio_chunk(chunk).await?;
+                sent += chunk.len();
+            }
+        }
+        self.session_offset = self.total_samples - sent;
+        self.session_samples = sent;
+        self.attach(next, resend_recent);
+        Ok(())
+    }
+
+    fn remember_chunk(&mut self, chunk: &[i16]) {
+        self.total_samples += chunk.len();
+        self.session_samples += chunk.len();
+        self.recent_chunks.push_back(chunk.to_vec());
+        self.recent_samples += chunk.len();
+        let keep = SAMPLE_RATE * OVERLAP_MS / 1000;
+        while let Some(front) = self.recent_chunks.front() {
+            if self.recent_samples - front.len() < keep {
+                break;
+            }
+            self.recent_samples -= front.len();
+            self.recent_chunks.pop_front();
+        }
+    }
+
+    async fn send_chunk(&mut self, chunk: &[i16]) -> Result<()> {
+        let (soft_limit, hard_limit) = session_limits(&self.provider);
+        let elapsed = self.session_samples / SAMPLE_RATE;
+        if elapsed >= soft_limit {
+            let samples: Vec<f32> = chunk.iter().map(|&s| s as f32 / 32768.0).collect();
+            let is_pause = !is_voice_active(&samples);
+            if is_pause || elapsed >= hard_limit {
+                self.roll_over(!is_pause).await?;
+            }
+        }
+
+        let session = self
+            .current
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("实时会话已中断"))?;
+        session.send_audio_chunk(chunk).await?;
+        self.remember_chunk(chunk);
+        Ok(())
+    }
+
+    async fn collect_result(&mut self) -> Result<AsrTranscript> {
+        let session = self
+            .current
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("实时会话已中断"))?;
+        let last = session.wait_for_result().await?;
+        if self.retired.is_empty() {
+            return Ok(last);
+        }
+
+        let mut parts = Vec::with_capacity(self.retired.len() + 1);
+        for retired in self.retired.drain(..) {
+            let transcript = retired.result.await.context("实时会话结果任务异常")??;
+            parts.push(SegmentTranscript {
+                transcript,
+                offset_ms: retired.offset_ms,
+                overlaps_previous: retired.overlaps_previous,
+            });
+        }
+        parts.push(SegmentTranscript {
+            transcript: last,
+            offset_ms: (self.session_offset * 1000 / SAMPLE_RATE) as u64,
+            overlaps_previous: self.current_overlaps,
+        });
+        tracing::info!("拼接 {} 个实时会话的识别结果", parts.len());
+        Ok(stitch_transcripts(parts))
+    }
+}
+
+impl RealtimeAsrSession for RollingRealtimeSession {
+    fn provider(&self) -> AsrProvider {
+        self.provider.clone()
+    }
+
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.send_chunk(pcm_data))
+    }
+
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            match self.current.as_mut() {
+                Some(session) => session.finish().await,
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+        Box::pin(self.collect_result())
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            for retired in self.retired.drain(..) {
+                retired.result.abort();
+            }
+            match self.current.as_mut() {
+                Some(session) => session.close().await,
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        self.partials.as_ref().map(|relay| relay.receiver.clone())
+    }
+}
+
+impl Drop for RollingRealtimeSession {
+    fn drop(&mut self) {
+        if let Some(forwarder) = self.partials.as_mut().and_then(|r| r.forwarder.take()) {
+            forwarder.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 记录收到的采样数，结果为「第 n 段」
+    struct FakeSession {
+        index: usize,
+        received: Arc<Vec<AtomicUsize>>,
+    }
+
+    impl RealtimeAsrSession for FakeSession {
+        fn provider(&self) -> AsrProvider {
+            AsrProvider::Qwen
+        }
+
+        fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+            self.received[self.index].fetch_add(pcm_data.len(), Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+            let text = format!("第{}段", self.index + 1);
+            Box::pin(async move { Ok(AsrTranscript::from_text(text)) })
+        }
+
+        fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn rolling_session(received: &Arc<Vec<AtomicUsize>>) -> RollingRealtimeSession {
+        let created = Arc::new(AtomicUsize::new(1));
+        let received_for_factory = Arc::clone(received);
+        let factory: RealtimeSessionFactory = Arc::new(move || {
+            let index = created.fetch_add(1, Ordering::SeqCst);
+            let received = Arc::clone(&received_for_factory);
+            Box::pin(async move {
+                let session: Box<dyn RealtimeAsrSession> =
+                    Box::new(FakeSession { index, received });
+                Ok(session)
+            })
+        });
+        let first = Box::new(FakeSession {
+            index: 0,
+            received: Arc::clone(received),
+        });
+        RollingRealtimeSession::new(first, factory)
+    }
+
+    #[tokio::test]
+    async fn rolls_over_at_pause_after_soft_limit() {
+        let received = Arc::new((0..3).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>());
+        let mut session = rolling_session(&received);
+        let speech = vec![4000i16; 3200];
+        let pause = vec![0i16; 3200];
+
+        // 千问软上限 120 秒：之前的停顿不触发切换
+        for _ in 0..5 * 120 {
+            session.send_audio_chunk(&speech).await.unwrap();
+        }
+        session.send_audio_chunk(&pause).await.unwrap();
+        session.send_audio_chunk(&speech).await.unwrap();
+        session.finish().await.unwrap();
+
+        assert_eq!(received[0].load(Ordering::SeqCst), 120 * 16000);
+        assert_eq!(received[1].load(Ordering::SeqCst), 2 * 3200);
+        let transcript = session.wait_for_result().await.unwrap();
+        assert_eq!(transcript.text(), "第1段，第2段");
+    }
+
+    #[tokio::test]
+    async fn resends_recent_audio_at_hard_limit() {
+        let received = Arc::new((0..3).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>());
+        let mut session = rolling_session(&received);
+        let speech = vec![4000i16; 3200];
+
+        for _ in 0..5 * 170 + 1 {
+            session.send_audio_chunk(&speech).await.unwrap();
+        }
+
+        assert_eq!(received[0].load(Ordering::SeqCst), 170 * 16000);
+        // 重发最近 1 秒再加上新的一块
+        assert_eq!(received[1].load(Ordering::SeqCst), 16000 + 3200);
+    }
+}
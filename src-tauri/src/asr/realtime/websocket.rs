@@ -0,0 +1,602 @@
+// 自建 WebSocket 流式 ASR 客户端（FunASR runtime / sherpa-onnx / Vosk）
+//
+// 这些开源服务都使用「JSON 控制消息 + 二进制音频」的简单协议，差异集中在四处：
+// - 开始：FunASR 首包为识别参数（模式、分块、热词），Vosk 首包为 {"config": {...}}，sherpa-onnx 无需首包
+// - 音频：FunASR / Vosk 为 16-bit PCM，sherpa-onnx 为 32-bit float
+// - 结束：FunASR 发送 {"is_speaking": false}，Vosk 发送 {"eof": 1}，sherpa-onnx 发送文本 "Done"
+// - 结果：FunASR 2pass 的 online 结果为增量片段、2pass-offline 为整句纠正；
+//   sherpa-onnx 按 segment 给出累积文本；Vosk 为 partial / 整句 text
+// 会话流程（发送音频块 / 结束 / 等待结果）与千问、豆包一致；批量模式把整段 WAV 按块推给同一会话
+
+use crate::asr::engine::{partial_transcript_channel, publish_partial};
+use crate::asr::long_form::{append_transcript_text, is_cjk};
+use crate::asr::{
+    AsrEngine, AsrTranscript, PartialTranscriptReceiver, RealtimeAsrSession, TranscriptSegment,
+    TranscriptWord,
+};
+use crate::config::{AsrProvider, WebSocketAsrProtocol, WebSocketAsrProvider};
+use crate::dictionary_utils::{asr_hotwords, HotwordLimit};
+use anyhow::{Context, Result};
+use futures_util::{future::BoxFuture, SinkExt, StreamExt};
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// 自建服务通常在本机或内网，连接超时取短一些，便于尽快回退
+const CONNECT_TIMEOUT_SECS: u64 = 5;
+const TRANSCRIPTION_TIMEOUT_SECS: u64 = 10;
+const SAMPLE_RATE: u32 = 16000;
+/// 批量模式下每次发送的采样数（0.2 秒，与流式录音块一致）
+const BATCH_CHUNK_SAMPLES: usize = 3200;
+/// 词库权重（1~10）到 FunASR 热词权重的倍数：默认权重 5 对应 FunASR 常用的 20
+const FUNASR_WEIGHT_SCALE: u32 = 4;
+
+enum SessionCommand {
+    SendAudio(Vec<i16>),
+    Finish,
+}
+
+pub struct WebSocketRealtimeSession {
+    provider: AsrProvider,
+    sender: mpsc::Sender<SessionCommand>,
+    result_receiver: mpsc::Receiver<Result<AsrTranscript>>,
+    partial_receiver: PartialTranscriptReceiver,
+    finished: bool,
+}
+
+impl WebSocketRealtimeSession {
+    pub async fn send_audio_chunk(&mut self, pcm_data: &[i16]) -> Result<()> {
+        self.sender
+            .send(SessionCommand::SendAudio(pcm_data.to_vec()))
+            .await
+            .map_err(|_| anyhow::anyhow!("发送音频块失败"))
+    }
+
+    pub async fn finish_audio(&mut self) -> Result<()> {
+        self.finished = true;
+        self.sender
+            .send(SessionCommand::Finish)
+            .await
+            .map_err(|_| anyhow::anyhow!("发送结束标志失败"))
+    }
+
+    /// 放弃会话：尚未结束时补发结束标志，会话释放后发送任务会关闭连接
+    pub async fn close(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finish_audio().await
+    }
+
+    pub async fn wait_for_result(&mut self) -> Result<AsrTranscript> {
+        match timeout(
+            Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS),
+            self.result_receiver.recv(),
+        )
+        .await
+        {
+            Ok(Some(result)) => result,
+            Ok(None) => Err(anyhow::anyhow!("通道已关闭")),
+            Err(_) => Err(anyhow::anyhow!("转录超时")),
+        }
+    }
+}
+
+/// 自建 WebSocket 流式服务客户端
+#[derive(Clone)]
+pub struct WebSocketRealtimeClient {
+    config: WebSocketAsrProvider,
+    dictionary: Vec<String>,
+}
+
+impl WebSocketRealtimeClient {
+    pub fn new(config: WebSocketAsrProvider, dictionary: Vec<String>) -> Self {
+        Self { config, dictionary }
+    }
+
+    pub fn name(&self) -> &str {
+        if self.config.name.trim().is_empty() {
+            &self.config.id
+        } else {
+            &self.config.name
+        }
+    }
+
+    pub async fn start_session(&self) -> Result<WebSocketRealtimeSession> {
+        let protocol = self.config.protocol;
+        let endpoint = self.config.endpoint.trim();
+        let (ws_stream, _) = timeout(
+            Duration::from_secs(CONNECT_TIMEOUT_SECS),
+            connect_async(endpoint),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("连接 {} 超时", endpoint))?
+        .with_context(|| format!("连接 {} 失败", endpoint))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        if let Some(start) = start_message(protocol, &self.dictionary) {
+            tracing::debug!("{} 开始消息: {}", self.name(), start);
+            write.send(Message::Text(start)).await?;
+        }
+        tracing::info!(
+            "{}（{}）WebSocket 连接已建立: {}",
+            self.name(),
+            protocol.display_name(),
+            endpoint
+        );
+
+        let (cmd_tx, mut cmd_rx) = mpsc::channel::<SessionCommand>(100);
+        let (result_tx, result_rx) = mpsc::channel::<Result<AsrTranscript>>(1);
+        let (partial_tx, partial_rx) = partial_transcript_channel();
+
+        let name = self.name().to_string();
+        tokio::spawn(async move {
+            while let Some(cmd) = cmd_rx.recv().await {
+                let message = match cmd {
+                    SessionCommand::SendAudio(pcm) => Message::Binary(encode_audio(protocol, &pcm)),
+                    SessionCommand::Finish => end_message(protocol),
+                };
+                if let Err(e) = write.send(message).await {
+                    tracing::error!("{} 发送失败: {}", name, e);
+                    break;
+                }
+            }
+            // 会话释放后主动断开（FunASR 在返回最终结果后不会自行关闭连接）
+            let _ = write.close().await;
+        });
+
+        let name = self.name().to_string();
+        tokio::spawn(async move {
+            let mut accumulator = TranscriptAccumulator::new(protocol);
+            let result = loop {
+                match read.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        tracing::debug!("{} 响应: {}", name, text);
+                        let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) else {
+                            continue;
+                        };
+                        let is_final = accumulator.handle(&json);
+                        publish_partial(&partial_tx, &accumulator.text());
+                        if is_final {
+                            break Ok(accumulator.into_transcript());
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        // sherpa-onnx / Vosk 在结束标志后返回最终结果并关闭连接
+                        break if accumulator.is_empty() {
+                            Err(anyhow::anyhow!("WebSocket 连接结束，无转录结果"))
+                        } else {
+                            Ok(accumulator.into_transcript())
+                        };
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => break Err(anyhow::anyhow!("WebSocket 错误: {}", e)),
+                }
+            };
+            if let Ok(ref transcript) = result {
+                tracing::info!("{} 流式转录结果: {}", name, transcript.text());
+            }
+            let _ = result_tx.send(result).await;
+        });
+
+        Ok(WebSocketRealtimeSession {
+            provider: AsrProvider::WebSocket(self.config.id.clone()),
+            sender: cmd_tx,
+            result_receiver: result_rx,
+            partial_receiver: partial_rx,
+            finished: false,
+        })
+    }
+
+    /// 批量转录：把整段 WAV 按块推给一个新会话
+    pub async fn transcribe_bytes(&self, audio_data: &[u8]) -> Result<AsrTranscript> {
+        let reader = hound::WavReader::new(Cursor::new(audio_data))?;
+        let spec = reader.spec();
+        if spec.channels != 1 || spec.sample_rate != SAMPLE_RATE || spec.bits_per_sample != 16 {
+            anyhow::bail!(
+                "仅支持 16kHz 单声道 16-bit 音频（当前 {}Hz {} 声道 {} bit）",
+                spec.sample_rate,
+                spec.channels,
+                spec.bits_per_sample
+            );
+        }
+        let samples = reader
+            .into_samples::<i16>()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut session = self.start_session().await?;
+        for chunk in samples.chunks(BATCH_CHUNK_SAMPLES) {
+            session.send_audio_chunk(chunk).await?;
+        }
+        session.finish_audio().await?;
+        session.wait_for_result().await
+    }
+}
+
+fn start_message(protocol: WebSocketAsrProtocol, dictionary: &[String]) -> Option<String> {
+    match protocol {
+        WebSocketAsrProtocol::FunAsr2Pass | WebSocketAsrProtocol::FunAsrOnline => {
+            let mode = if protocol == WebSocketAsrProtocol::FunAsr2Pass {
+                "2pass"
+            } else {
+                "online"
+            };
+            let mut message = serde_json::json!({
+                "mode": mode,
+                // 600ms 一块，向前看 300ms
+                "chunk_size": [5, 10, 5],
+                "chunk_interval": 10,
+                "encoder_chunk_look_back": 4,
+                "decoder_chunk_look_back": 0,
+                "wav_name": "push-2-talk",
+                "wav_format": "pcm",
+                "audio_fs": SAMPLE_RATE,
+                "is_speaking": true,
+                "itn": true,
+            });
+            let hotwords: serde_json::Map<String, serde_json::Value> =
+                asr_hotwords(dictionary, HotwordLimit::default())
+                    .into_iter()
+                    .map(|h| (h.word, (h.weight as u32 * FUNASR_WEIGHT_SCALE).into()))
+                    .collect();
+            if !hotwords.is_empty() {
+                // 热词为 JSON 字符串：{"词": 权重}
+                message["hotwords"] = serde_json::Value::Object(hotwords).to_string().into();
+            }
+            Some(message.to_string())
+        }
+        WebSocketAsrProtocol::Vosk => Some(
+            serde_json::json!({"config": {"sample_rate": SAMPLE_RATE, "words": 1}}).to_string(),
+        ),
+        WebSocketAsrProtocol::SherpaOnnx => None,
+    }
+}
+
+fn encode_audio(protocol: WebSocketAsrProtocol, pcm: &[i16]) -> Vec<u8> {
+    match protocol {
+        WebSocketAsrProtocol::SherpaOnnx => pcm
+            .iter()
+            .flat_map(|&s| (s as f32 / 32768.0).to_le_bytes())
+            .collect(),
+        _ => pcm.iter().flat_map(|&s| s.to_le_bytes()).collect(),
+    }
+}
+
+fn end_message(protocol: WebSocketAsrProtocol) -> Message {
+    match protocol {
+        WebSocketAsrProtocol::FunAsr2Pass | WebSocketAsrProtocol::FunAsrOnline => {
+            Message::Text(serde_json::json!({"is_speaking": false}).to_string())
+        }
+        WebSocketAsrProtocol::SherpaOnnx => Message::Text("Done".to_string()),
+        WebSocketAsrProtocol::Vosk => Message::Text(serde_json::json!({"eof": 1}).to_string()),
+    }
+}
+
+/// 按协议累积服务端返回的结果：已确定的句子 + 当前句的临时文本
+struct TranscriptAccumulator {
+    protocol: WebSocketAsrProtocol,
+    committed: String,
+    segments: Vec<TranscriptSegment>,
+    pending: String,
+    /// sherpa-onnx 当前的 segment 序号
+    segment_index: Option<u64>,
+}
+
+impl TranscriptAccumulator {
+    fn new(protocol: WebSocketAsrProtocol) -> Self {
+        Self {
+            protocol,
+            committed: String::new(),
+            segments: Vec::new(),
+            pending: String::new(),
+            segment_index: None,
+        }
+    }
+
+    /// 处理一条结果消息，返回是否为最终结果
+    fn handle(&mut self, message: &serde_json::Value) -> bool {
+        match self.protocol {
+            WebSocketAsrProtocol::FunAsr2Pass | WebSocketAsrProtocol::FunAsrOnline => {
+                let text = message["text"].as_str().unwrap_or_default();
+                match message["mode"].as_str().unwrap_or_default() {
+                    // 流式结果为增量片段
+                    "2pass-online" => self.pending.push_str(text),
+                    "online" => self.committed.push_str(text),
+                    // 整句的离线结果取代该句的流式片段
+                    _ => {
+                        self.pending.clear();
+                        self.commit(funasr_segment(message), false);
+                    }
+                }
+                message["is_final"].as_bool().unwrap_or(false)
+            }
+            WebSocketAsrProtocol::SherpaOnnx => {
+                let text = message["text"].as_str().unwrap_or_default().trim();
+                let index = message["segment"].as_u64().unwrap_or(0);
+                if self.segment_index.is_some_and(|current| current != index) {
+                    let previous = std::mem::take(&mut self.pending);
+                    self.commit(segment_from_text(&previous), true);
+                }
+                self.segment_index = Some(index);
+                self.pending = text.to_string();
+                false
+            }
+            WebSocketAsrProtocol::Vosk => {
+                if let Some(partial) = message["partial"].as_str() {
+                    self.pending = collapse_cjk_spaces(partial);
+                } else if message.get("text").is_some() {
+                    self.pending.clear();
+                    self.commit(vosk_segment(message), true);
+                }
+                false
+            }
+        }
+    }
+
+    /// 追加一句已确定的结果；separate 为 true 时句间补逗号或空格
+    fn commit(&mut self, segment: TranscriptSegment, separate: bool) {
+        if segment.text.is_empty() {
+            return;
+        }
+        if separate {
+            append_transcript_text(&mut self.committed, &segment.text, false);
+        } else {
+            self.committed.push_str(&segment.text);
+        }
+        self.segments.push(segment);
+    }
+
+    fn text(&self) -> String {
+        let mut text = self.committed.clone();
+        match self.protocol {
+            WebSocketAsrProtocol::FunAsr2Pass | WebSocketAsrProtocol::FunAsrOnline => {
+                text.push_str(&self.pending)
+            }
+            _ => append_transcript_text(&mut text, &self.pending, false),
+        }
+        text
+    }
+
+    fn is_empty(&self) -> bool {
+        self.committed.is_empty() && self.pending.is_empty()
+    }
+
+    fn into_transcript(self) -> AsrTranscript {
+        let text = self.text();
+        let segments = if self.pending.is_empty() {
+            self.segments
+        } else {
+            // 没有等到句末确认的临时文本也作为一段保留
+            let mut segments = self.segments;
+            segments.push(segment_from_text(&self.pending));
+            segments
+        };
+        AsrTranscript::new(text.trim(), segments)
+    }
+}
+
+fn segment_from_text(text: &str) -> TranscriptSegment {
+    TranscriptSegment {
+        text: text.to_string(),
+        ..Default::default()
+    }
+}
+
+/// FunASR 离线结果：timestamp 为逐字 [[开始, 结束], ...]（毫秒）的 JSON 字符串
+fn funasr_segment(message: &serde_json::Value) -> TranscriptSegment {
+    let text = message["text"].as_str().unwrap_or_default();
+    let stamps: Vec<[u64; 2]> = message["timestamp"]
+        .as_str()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    TranscriptSegment {
+        text: text.to_string(),
+        start_ms: stamps.first().map(|s| s[0]),
+        end_ms: stamps.last().map(|s| s[1]),
+        ..Default::default()
+    }
+}
+
+/// Vosk 整句结果：result 为逐词 {word, start, end, conf}（秒）
+fn vosk_segment(message: &serde_json::Value) -> TranscriptSegment {
+    let seconds = |value: &serde_json::Value| value.as_f64().map(|s| (s * 1000.0).round() as u64);
+    let words: Vec<TranscriptWord> = message["result"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(TranscriptWord {
+                        text: item["word"].as_str()?.to_string(),
+                        start_ms: seconds(&item["start"]),
+                        end_ms: seconds(&item["end"]),
+                        confidence: item["conf"].as_f64().map(|c| c as f32),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    TranscriptSegment {
+        text: collapse_cjk_spaces(message["text"].as_str().unwrap_or_default()),
+        start_ms: words.first().and_then(|w| w.start_ms),
+        end_ms: words.last().and_then(|w| w.end_ms),
+        words,
+        ..Default::default()
+    }
+}
+
+/// Vosk 中文模型按词输出并以空格分隔，去掉汉字之间的空格
+fn collapse_cjk_spaces(text: &str) -> String {
+    let chars: Vec<char> = text.trim().chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            !(c == ' '
+                && i > 0
+                && chars.get(i + 1).is_some_and(|&next| is_cjk(next))
+                && is_cjk(chars[i - 1]))
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+impl RealtimeAsrSession for WebSocketRealtimeSession {
+    fn provider(&self) -> AsrProvider {
+        self.provider.clone()
+    }
+
+    fn send_audio_chunk<'a>(&'a mut self, pcm_data: &'a [i16]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(WebSocketRealtimeSession::send_audio_chunk(self, pcm_data))
+    }
+
+    fn finish(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.finish_audio())
+    }
+
+    fn wait_for_result(&mut self) -> BoxFuture<'_, Result<AsrTranscript>> {
+        Box::pin(WebSocketRealtimeSession::wait_for_result(self))
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(WebSocketRealtimeSession::close(self))
+    }
+
+    fn subscribe_partials(&self) -> Option<PartialTranscriptReceiver> {
+        Some(self.partial_receiver.clone())
+    }
+}
+
+impl AsrEngine for WebSocketRealtimeClient {
+    fn provider(&self) -> AsrProvider {
+        AsrProvider::WebSocket(self.config.id.clone())
+    }
+
+    fn name(&self) -> &str {
+        WebSocketRealtimeClient::name(self)
+    }
+
+    fn transcribe<'a>(&'a self, audio_data: &'a [u8]) -> BoxFuture<'a, Result<AsrTranscript>> {
+        Box::pin(self.transcribe_bytes(audio_data))
+    }
+
+    fn with_dictionary(&self, dictionary: &[String]) -> Option<Arc<dyn AsrEngine>> {
+        Some(Arc::new(Self::new(
+            self.config.clone(),
+            dictionary.to_vec(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn feed(protocol: WebSocketAsrProtocol, messages: &[serde_json::Value]) -> Vec<(String, bool)> {
+        let mut accumulator = TranscriptAccumulator::new(protocol);
+        messages
+            .iter()
+            .map(|message| {
+                let is_final = accumulator.handle(message);
+                (accumulator.text(), is_final)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn funasr_2pass_replaces_online_pieces_with_offline_sentence() {
+        let steps = feed(
+            WebSocketAsrProtocol::FunAsr2Pass,
+            &[
+                json!({"mode": "2pass-online", "text": "今天", "is_final": false}),
+                json!({"mode": "2pass-online", "text": "天汽", "is_final": false}),
+                json!({"mode": "2pass-offline", "text": "今天天气，", "timestamp": "[[100,200],[200,400]]", "is_final": false}),
+                json!({"mode": "2pass-online", "text": "不错", "is_final": false}),
+                json!({"mode": "2pass-offline", "text": "不错。", "is_final": true}),
+            ],
+        );
+
+        assert_eq!(steps[1].0, "今天天汽");
+        assert_eq!(steps[2].0, "今天天气，");
+        assert_eq!(steps[3].0, "今天天气，不错");
+        assert_eq!(steps[4], ("今天天气，不错。".to_string(), true));
+    }
+
+    #[test]
+    fn funasr_offline_sentence_keeps_timestamps() {
+        let mut accumulator = TranscriptAccumulator::new(WebSocketAsrProtocol::FunAsr2Pass);
+        accumulator.handle(&json!({
+            "mode": "2pass-offline",
+            "text": "你好",
+            "timestamp": "[[380,560],[560,800]]",
+            "is_final": true
+        }));
+        let transcript = accumulator.into_transcript();
+
+        assert_eq!(transcript.text(), "你好");
+        assert_eq!(transcript.segments[0].start_ms, Some(380));
+        assert_eq!(transcript.segments[0].end_ms, Some(800));
+    }
+
+    #[test]
+    fn sherpa_onnx_accumulates_segments() {
+        let steps = feed(
+            WebSocketAsrProtocol::SherpaOnnx,
+            &[
+                json!({"text": "今天", "segment": 0}),
+                json!({"text": "今天天气", "segment": 0}),
+                json!({"text": "不错", "segment": 1}),
+            ],
+        );
+
+        assert_eq!(steps[1].0, "今天天气");
+        assert_eq!(steps[2].0, "今天天气，不错");
+    }
+
+    #[test]
+    fn vosk_joins_utterances_and_collapses_cjk_spaces() {
+        let mut accumulator = TranscriptAccumulator::new(WebSocketAsrProtocol::Vosk);
+        accumulator.handle(&json!({"partial": "今天 天气"}));
+        assert_eq!(accumulator.text(), "今天天气");
+        accumulator.handle(&json!({
+            "text": "今天 天气 不错",
+            "result": [
+                {"word": "今天", "start": 0.3, "end": 0.6, "conf": 1.0},
+                {"word": "天气", "start": 0.6, "end": 0.9, "conf": 0.8},
+                {"word": "不错", "start": 0.9, "end": 1.2, "conf": 1.0}
+            ]
+        }));
+        accumulator.handle(&json!({"text": "我们 出发"}));
+        let transcript = accumulator.into_transcript();
+
+        assert_eq!(transcript.text(), "今天天气不错，我们出发");
+        assert_eq!(transcript.segments[0].start_ms, Some(300));
+        assert_eq!(transcript.segments[0].words[1].end_ms, Some(900));
+    }
+
+    #[test]
+    fn funasr_start_message_carries_mode_and_hotwords() {
+        let dictionary = vec![
+            "Kubernetes".to_string(),
+            "通义|auto".to_string(),
+            "Tauri|w=10|asr".to_string(),
+            "仅润色|llm".to_string(),
+        ];
+        let start = start_message(WebSocketAsrProtocol::FunAsr2Pass, &dictionary).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&start).unwrap();
+
+        assert_eq!(json["mode"], "2pass");
+        let hotwords: serde_json::Value =
+            serde_json::from_str(json["hotwords"].as_str().unwrap()).unwrap();
+        assert_eq!(hotwords["Kubernetes"], 20);
+        assert_eq!(hotwords["通义"], 12);
+        assert_eq!(hotwords["Tauri"], 40);
+        assert!(hotwords.get("仅润色").is_none());
+
+        assert!(start_message(WebSocketAsrProtocol::SherpaOnnx, &dictionary).is_none());
+    }
+}
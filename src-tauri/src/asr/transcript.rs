@@ -0,0 +1,241 @@
+// ASR 转录结果
+//
+// 除最终文本外，保留提供商返回的分段、逐词时间戳、置信度与候选结果（n-best），
+// 供后续阶段（TNL、LLM 润色、自学习）判断识别器不确定的位置。
+// 提供商不返回这些信息时 segments / alternatives 为空，行为与纯文本一致
+
+use serde::{Deserialize, Serialize};
+
+/// 一次转录的完整结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AsrTranscript {
+    /// 最终文本（已做提供商侧的标点清理），与原先返回的字符串完全一致
+    text: String,
+    /// 分段信息（时间单位为毫秒，相对音频开头）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptSegment>,
+    /// 其他候选结果（n-best，按可信度降序，不含 text 本身）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    alternatives: Vec<String>,
+}
+
+/// 分段（句子 / utterance）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u64>,
+    /// 0.0 ~ 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+}
+
+/// 逐词信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u64>,
+    /// 0.0 ~ 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+impl AsrTranscript {
+    pub fn new(text: impl Into<String>, segments: Vec<TranscriptSegment>) -> Self {
+        Self {
+            text: text.into(),
+            segments,
+            alternatives: Vec::new(),
+        }
+    }
+
+    /// 追加候选结果：忽略空白、与最终文本相同及重复的候选
+    pub fn with_alternatives<I, S>(mut self, alternatives: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for alternative in alternatives {
+            let alternative = alternative.into();
+            let trimmed = alternative.trim();
+            if trimmed.is_empty()
+                || trimmed == self.text
+                || self.alternatives.iter().any(|a| a == trimmed)
+            {
+                continue;
+            }
+            self.alternatives.push(trimmed.to_string());
+        }
+        self
+    }
+
+    /// 仅有文本、没有分段信息的结果
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self::new(text, Vec::new())
+    }
+
+    /// 最终文本（兼容原先返回 String 的调用方）
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// 改写最终文本与候选结果（如去掉开头的唤醒词），分段信息保持原样
+    pub fn map_text(mut self, f: impl Fn(&str) -> String) -> Self {
+        self.text = f(&self.text);
+        self.alternatives = self.alternatives.iter().map(|a| f(a)).collect();
+        self
+    }
+
+    /// 其他候选结果（仅返回单一结果的提供商为空）
+    pub fn alternatives(&self) -> &[String] {
+        &self.alternatives
+    }
+
+    /// 所有分段中的逐词信息
+    pub fn words(&self) -> impl Iterator<Item = &TranscriptWord> {
+        self.segments.iter().flat_map(|s| s.words.iter())
+    }
+
+    /// 置信度低于阈值的词（提供商未返回置信度时为空）
+    pub fn uncertain_words(&self, threshold: f32) -> Vec<&TranscriptWord> {
+        self.words()
+            .filter(|w| w.confidence.is_some_and(|c| c < threshold))
+            .collect()
+    }
+}
+
+/// 解析 utterance 风格的分段数组（豆包 `result.utterances`）
+///
+/// 字段：text、start_time / begin_time、end_time（毫秒）、可选 confidence、可选 words
+pub(crate) fn segments_from_utterances(utterances: &serde_json::Value) -> Vec<TranscriptSegment> {
+    let Some(items) = utterances.as_array() else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let text = item["text"].as_str()?.to_string();
+            Some(TranscriptSegment {
+                text,
+                start_ms: time_ms(item),
+                end_ms: item["end_time"].as_u64(),
+                confidence: confidence(item),
+                words: words_from_json(&item["words"]),
+            })
+        })
+        .collect()
+}
+
+/// 解析逐词数组：text、start_time / begin_time、end_time（毫秒）、可选 confidence
+pub(crate) fn words_from_json(words: &serde_json::Value) -> Vec<TranscriptWord> {
+    let Some(items) = words.as_array() else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let text = item["text"].as_str()?;
+            if text.is_empty() {
+                return None;
+            }
+            Some(TranscriptWord {
+                text: text.to_string(),
+                start_ms: time_ms(item),
+                end_ms: item["end_time"].as_u64(),
+                confidence: confidence(item),
+            })
+        })
+        .collect()
+}
+
+fn time_ms(item: &serde_json::Value) -> Option<u64> {
+    item["start_time"]
+        .as_u64()
+        .or_else(|| item["begin_time"].as_u64())
+}
+
+fn confidence(item: &serde_json::Value) -> Option<f32> {
+    item["confidence"]
+        .as_f64()
+        .map(|c| c.clamp(0.0, 1.0) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utterances_with_words() {
+        let utterances = serde_json::json!([
+            {
+                "text": "你好世界",
+                "start_time": 120,
+                "end_time": 980,
+                "words": [
+                    {"text": "你好", "start_time": 120, "end_time": 500, "confidence": 0.93},
+                    {"text": "世界", "begin_time": 500, "end_time": 980, "confidence": 0.41},
+                    {"text": "", "start_time": 980, "end_time": 980}
+                ]
+            },
+            {"start_time": 1000}
+        ]);
+
+        let segments = segments_from_utterances(&utterances);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].start_ms, Some(120));
+        assert_eq!(segments[0].words.len(), 2);
+        assert_eq!(segments[0].words[1].start_ms, Some(500));
+
+        let transcript = AsrTranscript::new("你好世界", segments);
+        let uncertain: Vec<&str> = transcript
+            .uncertain_words(0.5)
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect();
+        assert_eq!(uncertain, vec!["世界"]);
+    }
+
+    #[test]
+    fn plain_text_transcript_round_trips() {
+        let transcript = AsrTranscript::from_text("hello");
+        assert_eq!(transcript.text(), "hello");
+        assert_eq!(
+            serde_json::to_value(&transcript).unwrap(),
+            serde_json::json!({"text": "hello"})
+        );
+        assert!(segments_from_utterances(&serde_json::Value::Null).is_empty());
+    }
+
+    #[test]
+    fn alternatives_skip_duplicates_and_top_hypothesis() {
+        let transcript = AsrTranscript::from_text("我用回了").with_alternatives([
+            "我又回了",
+            " 我用回了 ",
+            "",
+            "我又回了",
+            "我有回了",
+        ]);
+        assert_eq!(transcript.alternatives(), ["我又回了", "我有回了"]);
+        assert_eq!(
+            serde_json::to_value(&transcript).unwrap()["alternatives"],
+            serde_json::json!(["我又回了", "我有回了"])
+        );
+    }
+}
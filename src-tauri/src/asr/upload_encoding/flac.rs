@@ -0,0 +1,555 @@
+// FLAC 编码器（只覆盖上传所需的子集）
+//
+// 固定块大小，每个子帧在常量 / 0~4 阶固定预测 / 原样存储之间取最小者，残差使用分区 Rice 编码。
+// 不做 LPC 与声道去相关：压缩率比 libFLAC 略低几个百分点，但无需额外依赖且足够快。
+// 解码器只用于读回本模块写出的文件（录音归档），遇到子集以外的特性直接报错
+
+use super::PcmAudio;
+use anyhow::Result;
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 6;
+/// 4-bit Rice 参数中 15 为转义码，可用的最大参数为 14
+const MAX_RICE_PARAM: u32 = 14;
+
+pub(super) fn encode(pcm: &PcmAudio) -> Result<Vec<u8>> {
+    let channels = pcm.channels as usize;
+    if !(1..=8).contains(&channels) {
+        anyhow::bail!("FLAC 不支持 {} 声道", channels);
+    }
+    if pcm.sample_rate == 0 || pcm.sample_rate >= 1 << 20 {
+        anyhow::bail!("FLAC 不支持采样率 {}", pcm.sample_rate);
+    }
+
+    let total_samples = pcm.samples.len() / channels;
+    let mut out = Vec::with_capacity(pcm.samples.len());
+    out.extend_from_slice(b"fLaC");
+    write_stream_info(&mut out, pcm, total_samples as u64);
+
+    let mut channel_samples = vec![0i32; BLOCK_SIZE];
+    for (frame_number, start) in (0..total_samples).step_by(BLOCK_SIZE).enumerate() {
+        let block_size = BLOCK_SIZE.min(total_samples - start);
+        let mut w = BitWriter::default();
+
+        // 帧头：同步码 + 固定块大小策略
+        w.write(0b11_1111_1111_1110, 14);
+        w.write(0, 1);
+        w.write(0, 1);
+        // 块大小取自帧头末尾的 16-bit (blocksize - 1)
+        w.write(0b0111, 4);
+        w.write(sample_rate_code(pcm.sample_rate), 4);
+        // 各声道独立编码
+        w.write(channels as u64 - 1, 4);
+        // 16 bit 采样
+        w.write(0b100, 3);
+        w.write(0, 1);
+        write_utf8_number(&mut w, frame_number as u64);
+        w.write(block_size as u64 - 1, 16);
+        let crc = crc8(w.bytes());
+        w.write(crc as u64, 8);
+
+        for channel in 0..channels {
+            let block = &mut channel_samples[..block_size];
+            for (i, sample) in block.iter_mut().enumerate() {
+                *sample = pcm.samples[(start + i) * channels + channel] as i32;
+            }
+            write_subframe(&mut w, block);
+        }
+
+        w.align();
+        let crc = crc16(w.bytes());
+        w.write(crc as u64, 16);
+        out.extend_from_slice(w.bytes());
+    }
+
+    Ok(out)
+}
+
+pub(super) fn decode(data: &[u8]) -> Result<PcmAudio> {
+    if data.len() < 42 || &data[..4] != b"fLaC" {
+        anyhow::bail!("不是 FLAC 数据");
+    }
+
+    let mut r = BitReader::new(&data[4..]);
+    let mut last_metadata = r.read(1)? == 1;
+    if r.read(7)? != 0 || r.read(24)? != 34 {
+        anyhow::bail!("FLAC 缺少 STREAMINFO");
+    }
+    // 块大小上下限与帧大小上下限
+    r.skip(16 + 16 + 24 + 24)?;
+    let sample_rate = r.read(20)? as u32;
+    let channels = r.read(3)? as usize + 1;
+    if r.read(5)? + 1 != BITS_PER_SAMPLE as u64 {
+        anyhow::bail!("FLAC 仅支持 16 bit 采样");
+    }
+    let total_samples = ((r.read(4)? << 32) | r.read(32)?) as usize;
+
+    // 跳过其余元数据块
+    let mut offset = 4 + 4 + 34;
+    while !last_metadata {
+        let header = data
+            .get(offset..offset + 4)
+            .ok_or_else(|| anyhow::anyhow!("FLAC 元数据不完整"))?;
+        last_metadata = header[0] & 0x80 != 0;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        offset += 4 + len;
+    }
+
+    let mut r = BitReader::new(data.get(offset..).unwrap_or_default());
+    let mut samples = Vec::with_capacity(total_samples * channels);
+    let mut blocks = vec![Vec::new(); channels];
+    while samples.len() < total_samples * channels {
+        if r.read(14)? != 0b11_1111_1111_1110 {
+            anyhow::bail!("FLAC 帧同步码错误");
+        }
+        r.skip(2)?;
+        let block_size_code = r.read(4)?;
+        // 采样率取自 STREAMINFO；帧头末尾附带采样率的写法不在子集内
+        if r.read(4)? >= 0b1100 {
+            anyhow::bail!("FLAC 帧头采样率编码不受支持");
+        }
+        if r.read(4)? as usize + 1 != channels {
+            anyhow::bail!("FLAC 声道编排不受支持");
+        }
+        r.skip(4)?;
+        // 帧号：首字节高位连续 1 的个数即后续字节数 + 1
+        let first = r.read(8)? as u8;
+        r.skip(first.leading_ones().saturating_sub(1) * 8)?;
+        let block_size = match block_size_code {
+            0b0110 => r.read(8)? as usize + 1,
+            0b0111 => r.read(16)? as usize + 1,
+            _ => anyhow::bail!("FLAC 块大小编码不受支持"),
+        };
+        // CRC-8
+        r.skip(8)?;
+
+        for block in &mut blocks {
+            read_subframe(&mut r, block_size, block)?;
+        }
+        r.align();
+        // CRC-16
+        r.skip(16)?;
+
+        for i in 0..block_size {
+            samples.extend(blocks.iter().map(|block| block[i] as i16));
+        }
+    }
+    samples.truncate(total_samples * channels);
+
+    Ok(PcmAudio {
+        sample_rate,
+        channels: channels as u16,
+        samples,
+    })
+}
+
+fn read_subframe(r: &mut BitReader, block_size: usize, out: &mut Vec<i32>) -> Result<()> {
+    out.clear();
+    r.skip(1)?;
+    let kind = r.read(6)?;
+    if r.read(1)? != 0 {
+        anyhow::bail!("FLAC wasted bits 不受支持");
+    }
+
+    match kind {
+        0 => {
+            let value = r.read_signed(BITS_PER_SAMPLE)?;
+            out.resize(block_size, value);
+        }
+        1 => {
+            for _ in 0..block_size {
+                out.push(r.read_signed(BITS_PER_SAMPLE)?);
+            }
+        }
+        8..=12 => {
+            let order = (kind - 8) as usize;
+            if order > block_size {
+                anyhow::bail!("FLAC 预测阶数超过块大小");
+            }
+            for _ in 0..order {
+                out.push(r.read_signed(BITS_PER_SAMPLE)?);
+            }
+            read_residual(r, block_size, order, out)?;
+            restore_fixed(out, order);
+        }
+        _ => anyhow::bail!("FLAC 子帧类型 {} 不受支持", kind),
+    }
+    Ok(())
+}
+
+/// 读出残差并追加到预热采样之后
+fn read_residual(
+    r: &mut BitReader,
+    block_size: usize,
+    order: usize,
+    out: &mut Vec<i32>,
+) -> Result<()> {
+    if r.read(2)? != 0b00 {
+        anyhow::bail!("FLAC 残差编码方式不受支持");
+    }
+    let partition_order = r.read(4)? as u32;
+    let partition_len = block_size >> partition_order;
+    if partition_len << partition_order != block_size || partition_len < order {
+        anyhow::bail!("FLAC 残差分区不合法");
+    }
+
+    for index in 0..1usize << partition_order {
+        let param = r.read(4)? as u32;
+        if param > MAX_RICE_PARAM {
+            anyhow::bail!("FLAC Rice 转义码不受支持");
+        }
+        let len = if index == 0 {
+            partition_len - order
+        } else {
+            partition_len
+        };
+        for _ in 0..len {
+            let value = (r.read_unary()? << param) | r.read(param)? as u32;
+            out.push(unzigzag(value));
+        }
+    }
+    Ok(())
+}
+
+/// 按固定预测系数逐个还原采样（原地，前 order 个为预热采样）
+fn restore_fixed(samples: &mut [i32], order: usize) {
+    for i in order..samples.len() {
+        let s = |k: usize| samples[i - k] as i64;
+        let prediction = match order {
+            0 => 0,
+            1 => s(1),
+            2 => 2 * s(1) - s(2),
+            3 => 3 * s(1) - 3 * s(2) + s(3),
+            _ => 4 * s(1) - 6 * s(2) + 4 * s(3) - s(4),
+        };
+        samples[i] = (samples[i] as i64 + prediction) as i32;
+    }
+}
+
+fn write_stream_info(out: &mut Vec<u8>, pcm: &PcmAudio, total_samples: u64) {
+    let mut w = BitWriter::default();
+    // 最后一个元数据块，类型 STREAMINFO，长度 34 字节
+    w.write(1, 1);
+    w.write(0, 7);
+    w.write(34, 24);
+    w.write(BLOCK_SIZE as u64, 16);
+    w.write(BLOCK_SIZE as u64, 16);
+    // 帧大小未知
+    w.write(0, 24);
+    w.write(0, 24);
+    w.write(pcm.sample_rate as u64, 20);
+    w.write(pcm.channels as u64 - 1, 3);
+    w.write(BITS_PER_SAMPLE as u64 - 1, 5);
+    w.write(total_samples >> 32, 4);
+    w.write(total_samples & 0xFFFF_FFFF, 32);
+    out.extend_from_slice(w.bytes());
+
+    // 解码后采样（小端交错）的 MD5，16-bit 时即 WAV 的 PCM 数据
+    let pcm_bytes: Vec<u8> = pcm.samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    out.extend_from_slice(&md5::compute(&pcm_bytes).0);
+}
+
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        8000 => 0b0100,
+        16000 => 0b0101,
+        22050 => 0b0110,
+        24000 => 0b0111,
+        32000 => 0b1000,
+        44100 => 0b1001,
+        48000 => 0b1010,
+        // 从 STREAMINFO 读取
+        _ => 0b0000,
+    }
+}
+
+/// 帧号使用类 UTF-8 的变长编码（最多 36 bit）
+fn write_utf8_number(w: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        w.write(value, 8);
+        return;
+    }
+    // n 字节可容纳 5n + 1 bit
+    let mut len = 2;
+    while value >= 1 << (5 * len + 1) {
+        len += 1;
+    }
+    let prefix = (0xFF00u64 >> len) & 0xFF;
+    w.write(prefix | (value >> (6 * (len - 1))), 8);
+    for i in (0..len - 1).rev() {
+        w.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+fn write_subframe(w: &mut BitWriter, samples: &[i32]) {
+    if samples.iter().all(|&s| s == samples[0]) {
+        // CONSTANT
+        w.write(0b0000_0000, 8);
+        w.write_signed(samples[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * BITS_PER_SAMPLE as u64;
+    let mut best: Option<(RicePlan, Vec<i32>)> = None;
+    let mut best_bits = verbatim_bits;
+
+    // 第 k 阶固定预测的残差即第 k 阶差分
+    let mut residual = samples.to_vec();
+    for order in 0..=MAX_FIXED_ORDER.min(samples.len() - 1) {
+        if order > 0 {
+            residual = residual.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        }
+        let Some(plan) = plan_rice(&residual, samples.len(), order) else {
+            continue;
+        };
+        let bits = (order as u64 * BITS_PER_SAMPLE as u64) + plan.bits;
+        if bits < best_bits {
+            best_bits = bits;
+            best = Some((plan, residual.clone()));
+        }
+    }
+
+    match best {
+        Some((plan, residual)) => {
+            // FIXED，阶数在类型字段低 3 位
+            w.write(0b0001_0000 | ((plan.order as u64) << 1), 8);
+            for &sample in &samples[..plan.order] {
+                w.write_signed(sample, BITS_PER_SAMPLE);
+            }
+            write_residual(w, &residual, &plan);
+        }
+        None => {
+            // VERBATIM
+            w.write(0b0000_0010, 8);
+            for &sample in samples {
+                w.write_signed(sample, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+struct RicePlan {
+    /// 预测阶数，首个分区少这么多个残差
+    order: usize,
+    partition_order: u32,
+    params: Vec<u32>,
+    /// 残差部分的估算位数（含编码方式与分区头）
+    bits: u64,
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn unzigzag(value: u32) -> i32 {
+    (value >> 1) as i32 ^ -((value & 1) as i32)
+}
+
+/// 为每个分区挑选 Rice 参数，并选出总位数最少的分区阶数
+fn plan_rice(residual: &[i32], block_size: usize, order: usize) -> Option<RicePlan> {
+    let mut best: Option<RicePlan> = None;
+    for partition_order in 0..=MAX_PARTITION_ORDER {
+        let partitions = 1usize << partition_order;
+        // 分区须整除块大小，且首个分区要容纳预测器的预热采样
+        if !block_size.is_multiple_of(partitions) || block_size / partitions <= order {
+            break;
+        }
+        let partition_len = block_size / partitions;
+
+        let mut params = Vec::with_capacity(partitions);
+        let mut bits = 2 + 4;
+        let mut offset = 0;
+        for index in 0..partitions {
+            let len = if index == 0 {
+                partition_len - order
+            } else {
+                partition_len
+            };
+            let sum: u64 = residual[offset..offset + len]
+                .iter()
+                .map(|&r| zigzag(r) as u64)
+                .sum();
+            offset += len;
+
+            // 以 sum >> k 近似商的总长度（误差不超过分区长度），挑估算最小的参数
+            let (param, partition_bits) = (0..=MAX_RICE_PARAM)
+                .map(|k| (k, len as u64 * (k as u64 + 1) + (sum >> k)))
+                .min_by_key(|&(_, cost)| cost)
+                .unwrap_or((0, 0));
+            params.push(param);
+            bits += 4 + partition_bits;
+        }
+
+        if best.as_ref().is_none_or(|b| bits < b.bits) {
+            best = Some(RicePlan {
+                order,
+                partition_order,
+                params,
+                bits,
+            });
+        }
+    }
+    best
+}
+
+fn write_residual(w: &mut BitWriter, residual: &[i32], plan: &RicePlan) {
+    // 编码方式 0：4-bit Rice 参数
+    w.write(0b00, 2);
+    w.write(plan.partition_order as u64, 4);
+
+    let partition_len = (residual.len() + plan.order) >> plan.partition_order;
+    let mut offset = 0;
+    for (index, &param) in plan.params.iter().enumerate() {
+        let len = if index == 0 {
+            partition_len - plan.order
+        } else {
+            partition_len
+        };
+        w.write(param as u64, 4);
+        for &r in &residual[offset..offset + len] {
+            let value = zigzag(r);
+            w.write_zeros(value >> param);
+            w.write(1, 1);
+            w.write(value as u64, param);
+        }
+        offset += len;
+    }
+}
+
+/// 按位写入（高位在前）
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    /// 写入 value 的低 n 位（n <= 32）
+    fn write(&mut self, value: u64, n: u32) {
+        if n == 0 {
+            return;
+        }
+        self.acc = (self.acc << n) | (value & ((1u64 << n) - 1));
+        self.bits += n;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+    }
+
+    fn write_signed(&mut self, value: i32, n: u32) {
+        self.write(value as u32 as u64, n);
+    }
+
+    fn write_zeros(&mut self, mut n: u32) {
+        while n > 0 {
+            let chunk = n.min(32);
+            self.write(0, chunk);
+            n -= chunk;
+        }
+    }
+
+    /// 补零到字节边界
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    /// 已写满的字节（对齐后即全部内容）
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// 按位读取（高位在前）
+struct BitReader<'a> {
+    data: &'a [u8],
+    /// 已读的位数
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bit(&mut self) -> Result<u32> {
+        let byte = self
+            .data
+            .get(self.pos / 8)
+            .ok_or_else(|| anyhow::anyhow!("FLAC 数据不完整"))?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1;
+        self.pos += 1;
+        Ok(bit as u32)
+    }
+
+    /// 读取 n 位无符号数（n <= 32）
+    fn read(&mut self, n: u32) -> Result<u64> {
+        let mut value = 0u64;
+        for _ in 0..n {
+            value = (value << 1) | self.bit()? as u64;
+        }
+        Ok(value)
+    }
+
+    fn read_signed(&mut self, n: u32) -> Result<i32> {
+        let value = self.read(n)?;
+        Ok(((value << (64 - n)) as i64 >> (64 - n)) as i32)
+    }
+
+    /// Rice 编码的商：连续 0 的个数，以 1 结束
+    fn read_unary(&mut self) -> Result<u32> {
+        let mut zeros = 0;
+        while self.bit()? == 0 {
+            zeros += 1;
+        }
+        Ok(zeros)
+    }
+
+    fn skip(&mut self, n: u32) -> Result<()> {
+        self.pos += n as usize;
+        if self.pos > self.data.len() * 8 {
+            anyhow::bail!("FLAC 数据不完整");
+        }
+        Ok(())
+    }
+
+    /// 跳到下一个字节边界
+    fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
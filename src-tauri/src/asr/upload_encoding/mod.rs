@@ -0,0 +1,322 @@
+// 上传音频编码
+//
+// 录音默认以 16-bit PCM WAV 上传（千问还要再做一次 base64），长句在慢速网络下明显拖慢出字。
+// 这里按提供商接口接受的格式在上传前重新编码：
+// - FLAC：无损，语音通常压缩到原始大小的 50%~60%
+// - Ogg Opus：有损（32 kbps），约为原始大小的 1/8，依赖 `doubao-ime` 特性链接的 libopus
+// 编码失败时回退为原始 WAV；每次请求记录编码前后的体积与耗时，便于对比。
+// 录音归档同样以 FLAC 保存，重新转写前再解回 WAV
+
+mod flac;
+#[cfg(feature = "doubao-ime")]
+mod ogg_opus;
+
+use crate::config::{AsrProvider, AudioUploadFormat};
+use anyhow::Result;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use AudioUploadFormat::{Flac, OggOpus, Wav};
+
+/// 提供商上传接口接受的音频格式
+pub fn supported_upload_formats(provider: &AsrProvider) -> &'static [AudioUploadFormat] {
+    match provider {
+        // qwen3-asr-flash 的 data URI 接受 wav / flac / ogg 等常见格式
+        AsrProvider::Qwen => &[Wav, Flac, OggOpus],
+        // 录音文件识别极速版接受 wav / mp3 / ogg opus
+        AsrProvider::Doubao => &[Wav, OggOpus],
+        // Whisper 系服务（OpenAI、Groq、faster-whisper 等）经 ffmpeg 解码，flac / ogg 均可
+        AsrProvider::OpenAiCompatible(_) => &[Wav, Flac, OggOpus],
+        // SiliconFlow 文档未声明支持 FLAC / Ogg；本地 Whisper、豆包输入法与自建流式服务不经过 HTTP 上传
+        AsrProvider::SiliconFlow
+        | AsrProvider::LocalWhisper
+        | AsrProvider::DoubaoIme
+        | AsrProvider::WebSocket(_) => &[Wav],
+    }
+}
+
+/// Ogg Opus 需要 libopus，未启用 `doubao-ime` 特性时不可用
+fn is_format_available(format: AudioUploadFormat) -> bool {
+    format != OggOpus || cfg!(feature = "doubao-ime")
+}
+
+/// 将请求的上传格式解析为提供商实际使用的格式：有损不可用时退到 FLAC，无损不可用时退到 WAV
+pub fn resolve_upload_format(
+    provider: &AsrProvider,
+    requested: AudioUploadFormat,
+) -> AudioUploadFormat {
+    let candidates: &[AudioUploadFormat] = match requested {
+        OggOpus => &[OggOpus, Flac, Wav],
+        Flac => &[Flac, Wav],
+        Wav => &[Wav],
+    };
+    let supported = supported_upload_formats(provider);
+    let resolved = candidates
+        .iter()
+        .copied()
+        .find(|format| supported.contains(format) && is_format_available(*format))
+        .unwrap_or(Wav);
+    if resolved != requested {
+        tracing::info!(
+            "{} 不支持上传格式「{}」，改用「{}」",
+            provider.config_key(),
+            requested.display_name(),
+            resolved.display_name()
+        );
+    }
+    resolved
+}
+
+/// 从 WAV 解出的 16-bit 交错采样
+struct PcmAudio {
+    sample_rate: u32,
+    channels: u16,
+    samples: Vec<i16>,
+}
+
+fn read_wav(wav: &[u8]) -> Result<PcmAudio> {
+    let reader = hound::WavReader::new(Cursor::new(wav))?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        anyhow::bail!(
+            "仅支持 16-bit PCM（当前 {} bit {:?}）",
+            spec.bits_per_sample,
+            spec.sample_format
+        );
+    }
+    let samples = reader
+        .into_samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(PcmAudio {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        samples,
+    })
+}
+
+fn write_wav(pcm: &PcmAudio) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: pcm.channels,
+        sample_rate: pcm.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(44 + pcm.samples.len() * 2));
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for &sample in &pcm.samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// 将本模块编码的 FLAC 解回 16-bit PCM WAV
+pub fn decode_flac_to_wav(flac_data: &[u8]) -> Result<Vec<u8>> {
+    write_wav(&flac::decode(flac_data)?)
+}
+
+/// 编码后待上传的音频
+pub struct EncodedAudio<'a> {
+    pub format: AudioUploadFormat,
+    pub data: Cow<'a, [u8]>,
+    original_len: usize,
+    encode_time: Duration,
+}
+
+impl EncodedAudio<'_> {
+    pub fn mime_type(&self) -> &'static str {
+        match self.format {
+            Wav => "audio/wav",
+            Flac => "audio/flac",
+            OggOpus => "audio/ogg",
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self.format {
+            Wav => "audio.wav",
+            Flac => "audio.flac",
+            OggOpus => "audio.ogg",
+        }
+    }
+
+    /// 请求完成后记录体积与耗时对比
+    pub fn log_request(&self, name: &str, request_time: Duration) {
+        tracing::info!(
+            "{} 上传 {} {:.1} KB（WAV {:.1} KB，{:.0}%），编码 {}ms，请求 {}ms",
+            name,
+            self.format.display_name(),
+            self.data.len() as f64 / 1024.0,
+            self.original_len as f64 / 1024.0,
+            self.data.len() as f64 * 100.0 / self.original_len.max(1) as f64,
+            self.encode_time.as_millis(),
+            request_time.as_millis()
+        );
+    }
+}
+
+/// 将录音 WAV 编码为上传格式，失败时原样上传 WAV
+pub fn encode_for_upload(wav: &[u8], format: AudioUploadFormat) -> EncodedAudio<'_> {
+    let start = Instant::now();
+    let encoded = match format {
+        Wav => None,
+        Flac => Some(read_wav(wav).and_then(|pcm| flac::encode(&pcm))),
+        #[cfg(feature = "doubao-ime")]
+        OggOpus => Some(read_wav(wav).and_then(|pcm| ogg_opus::encode(&pcm))),
+        #[cfg(not(feature = "doubao-ime"))]
+        OggOpus => Some(Err(anyhow::anyhow!(
+            "未启用 doubao-ime 特性，缺少 Opus 编码器"
+        ))),
+    };
+
+    let (format, data) = match encoded {
+        Some(Ok(data)) => (format, Cow::Owned(data)),
+        Some(Err(e)) => {
+            tracing::warn!(
+                "音频编码为 {} 失败，改为上传 WAV: {}",
+                format.display_name(),
+                e
+            );
+            (Wav, Cow::Borrowed(wav))
+        }
+        None => (Wav, Cow::Borrowed(wav)),
+    };
+
+    EncodedAudio {
+        format,
+        data,
+        original_len: wav.len(),
+        encode_time: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(samples: &[i16]) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    /// 带少量噪声的 440Hz 正弦，近似一段有声语音
+    fn tone(len: usize) -> Vec<i16> {
+        let mut seed = 1u32;
+        (0..len)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let noise = ((seed >> 16) % 64) as f32 - 32.0;
+                let t = i as f32 / 16000.0;
+                ((t * 440.0 * std::f32::consts::TAU).sin() * 8000.0 + noise) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unsupported_formats_degrade_towards_wav() {
+        assert_eq!(resolve_upload_format(&AsrProvider::Qwen, Flac), Flac);
+        assert_eq!(resolve_upload_format(&AsrProvider::Doubao, Flac), Wav);
+        assert_eq!(
+            resolve_upload_format(&AsrProvider::SiliconFlow, OggOpus),
+            Wav
+        );
+        assert_eq!(resolve_upload_format(&AsrProvider::Qwen, Wav), Wav);
+
+        let expected = if cfg!(feature = "doubao-ime") {
+            OggOpus
+        } else {
+            Flac
+        };
+        assert_eq!(resolve_upload_format(&AsrProvider::Qwen, OggOpus), expected);
+    }
+
+    #[test]
+    fn flac_upload_keeps_stream_info_and_shrinks_audio() {
+        let samples = tone(16000 * 3 + 123);
+        let wav = wav_bytes(&samples);
+        let encoded = encode_for_upload(&wav, Flac);
+
+        assert_eq!(encoded.format, Flac);
+        assert_eq!(encoded.mime_type(), "audio/flac");
+        let data = &encoded.data;
+        assert_eq!(&data[..4], b"fLaC");
+        // STREAMINFO：采样率 20 bit、声道数 - 1 占 3 bit、位深 - 1 占 5 bit、总采样数 36 bit
+        let info = &data[8..42];
+        let packed = u64::from_be_bytes(info[10..18].try_into().unwrap());
+        assert_eq!(packed >> 44, 16000);
+        assert_eq!((packed >> 41) & 0x7, 0);
+        assert_eq!((packed >> 36) & 0x1F, 15);
+        assert_eq!(packed & 0xF_FFFF_FFFF, samples.len() as u64);
+        let pcm: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(info[18..34], md5::compute(&pcm).0);
+
+        assert!(data.len() < wav.len() * 2 / 3);
+    }
+
+    #[test]
+    fn flac_upload_decodes_with_reference_decoder() {
+        // 有声、静音与白噪声混合，覆盖 FIXED、CONSTANT 与 VERBATIM 子帧
+        let mut samples = tone(16000 + 77);
+        samples.extend(std::iter::repeat_n(0, 5000));
+        let mut seed = 7u32;
+        samples.extend((0..5000).map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as i16
+        }));
+        let wav = wav_bytes(&samples);
+        let encoded = encode_for_upload(&wav, Flac);
+        assert_eq!(encoded.format, Flac);
+
+        let mut reader = claxon::FlacReader::new(Cursor::new(&encoded.data)).unwrap();
+        let info = reader.streaminfo();
+        assert_eq!(info.sample_rate, 16000);
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.samples, Some(samples.len() as u64));
+        let decoded: Vec<i16> = reader.samples().map(|s| s.unwrap() as i16).collect();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn silence_compresses_to_constant_subframes() {
+        let wav = wav_bytes(&vec![0; 16000 * 2]);
+        let encoded = encode_for_upload(&wav, Flac);
+        // 8 个块，每块仅帧头 + 常量子帧 + CRC
+        assert!(encoded.data.len() < 200);
+    }
+
+    #[test]
+    fn flac_decodes_back_to_identical_wav() {
+        let mut samples = tone(16000 * 2 + 77);
+        samples.extend(std::iter::repeat_n(0, 5000));
+        samples.extend([i16::MIN, i16::MAX, -1, 1, 0]);
+        let wav = wav_bytes(&samples);
+        let encoded = encode_for_upload(&wav, Flac);
+        assert_eq!(encoded.format, Flac);
+
+        assert_eq!(decode_flac_to_wav(&encoded.data).unwrap(), wav);
+        assert!(decode_flac_to_wav(&encoded.data[..encoded.data.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn invalid_wav_is_uploaded_unchanged() {
+        let wav = b"not a wav file".to_vec();
+        let encoded = encode_for_upload(&wav, Flac);
+
+        assert_eq!(encoded.format, Wav);
+        assert_eq!(encoded.file_name(), "audio.wav");
+        assert!(matches!(encoded.data, Cow::Borrowed(_)));
+    }
+}
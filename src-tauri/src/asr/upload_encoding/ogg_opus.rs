@@ -0,0 +1,212 @@
+// Ogg Opus 编码（RFC 7845）
+//
+// 复用 `doubao-ime` 特性链接的 libopus，20ms 一帧编码后写入 Ogg 页：
+// 第一页 OpusHead、第二页 OpusTags，之后每页约 1 秒音频，末页以 granule 标出真实结尾
+
+use super::PcmAudio;
+use anyhow::Result;
+
+/// 语音识别用途下 32 kbps 宽带语音已无可闻损伤
+const OPUS_BITRATE: i32 = 32_000;
+/// libopus 编码延迟（6.5ms，48kHz 下 312 个采样），解码端需跳过
+const OPUS_PRE_SKIP: u16 = 312;
+/// 每帧 20ms，granule 始终以 48kHz 计
+const FRAME_GRANULE: u64 = 960;
+const FRAMES_PER_PAGE: usize = 50;
+/// 单流文件，序列号取任意值即可
+const STREAM_SERIAL: u32 = 0x5054_414B;
+
+pub(super) fn encode(pcm: &PcmAudio) -> Result<Vec<u8>> {
+    let channels = match pcm.channels {
+        1 => opus::Channels::Mono,
+        2 => opus::Channels::Stereo,
+        n => anyhow::bail!("Opus 不支持 {} 声道", n),
+    };
+    if !matches!(pcm.sample_rate, 8000 | 12000 | 16000 | 24000 | 48000) {
+        anyhow::bail!("Opus 不支持采样率 {}", pcm.sample_rate);
+    }
+    let mut encoder = opus::Encoder::new(pcm.sample_rate, channels, opus::Application::Voip)?;
+    encoder.set_bitrate(opus::Bitrate::Bits(OPUS_BITRATE))?;
+
+    let channel_count = pcm.channels as usize;
+    let frame_len = pcm.sample_rate as usize / 50 * channel_count;
+    let total_samples = (pcm.samples.len() / channel_count) as u64;
+    // 末尾补静音帧，保证编码延迟之后的最后一个采样也被输出
+    let end_granule = OPUS_PRE_SKIP as u64 + total_samples * (48_000 / pcm.sample_rate as u64);
+    let frame_count = end_granule.div_ceil(FRAME_GRANULE) as usize;
+
+    let mut ogg = OggWriter::new(STREAM_SERIAL);
+    ogg.push_packet(&opus_head(pcm), 0);
+    ogg.flush(false);
+    ogg.push_packet(&opus_tags(), 0);
+    ogg.flush(false);
+
+    let mut frame = vec![0i16; frame_len];
+    let mut packet = vec![0u8; 4000];
+    for index in 0..frame_count {
+        let start = (index * frame_len).min(pcm.samples.len());
+        let end = (start + frame_len).min(pcm.samples.len());
+        frame.fill(0);
+        frame[..end - start].copy_from_slice(&pcm.samples[start..end]);
+
+        let len = encoder.encode(&frame, &mut packet)?;
+        let last = index + 1 == frame_count;
+        let granule = if last {
+            end_granule
+        } else {
+            (index as u64 + 1) * FRAME_GRANULE
+        };
+        ogg.push_packet(&packet[..len], granule);
+        if last || (index + 1) % FRAMES_PER_PAGE == 0 {
+            ogg.flush(last);
+        }
+    }
+
+    Ok(ogg.finish())
+}
+
+fn opus_head(pcm: &PcmAudio) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1);
+    head.push(pcm.channels as u8);
+    head.extend_from_slice(&OPUS_PRE_SKIP.to_le_bytes());
+    head.extend_from_slice(&pcm.sample_rate.to_le_bytes());
+    // 输出增益 0，声道映射族 0（单声道 / 立体声）
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let vendor = b"push-2-talk";
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+/// 最小化的 Ogg 页写入器：包不跨页，满 255 个分段时提前换页
+struct OggWriter {
+    out: Vec<u8>,
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    body: Vec<u8>,
+    granule: u64,
+}
+
+impl OggWriter {
+    fn new(serial: u32) -> Self {
+        Self {
+            out: Vec::new(),
+            serial,
+            sequence: 0,
+            segments: Vec::new(),
+            body: Vec::new(),
+            granule: 0,
+        }
+    }
+
+    fn push_packet(&mut self, packet: &[u8], granule: u64) {
+        let lacing = packet.len() / 255 + 1;
+        if self.segments.len() + lacing > 255 {
+            self.flush(false);
+        }
+        self.segments
+            .extend(std::iter::repeat_n(255, packet.len() / 255));
+        self.segments.push((packet.len() % 255) as u8);
+        self.body.extend_from_slice(packet);
+        self.granule = granule;
+    }
+
+    fn flush(&mut self, end_of_stream: bool) {
+        if self.segments.is_empty() {
+            return;
+        }
+        let mut header_type = 0u8;
+        if self.sequence == 0 {
+            header_type |= 0x02;
+        }
+        if end_of_stream {
+            header_type |= 0x04;
+        }
+
+        let page_start = self.out.len();
+        self.out.extend_from_slice(b"OggS");
+        self.out.push(0);
+        self.out.push(header_type);
+        self.out.extend_from_slice(&self.granule.to_le_bytes());
+        self.out.extend_from_slice(&self.serial.to_le_bytes());
+        self.out.extend_from_slice(&self.sequence.to_le_bytes());
+        // CRC 计算时该字段置零
+        self.out.extend_from_slice(&[0; 4]);
+        self.out.push(self.segments.len() as u8);
+        self.out.append(&mut self.segments);
+        self.out.append(&mut self.body);
+
+        let crc = ogg_crc(&self.out[page_start..]);
+        self.out[page_start + 22..page_start + 26].copy_from_slice(&crc.to_le_bytes());
+        self.sequence += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.flush(true);
+        self.out
+    }
+}
+
+/// Ogg 页校验：多项式 0x04C11DB7，初值 0，不反射
+fn ogg_crc(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ogg_crc_matches_reference_check_value() {
+        // CRC-32/CKSUM 的校验值 0x765E7680 去掉最终取反
+        assert_eq!(ogg_crc(b"123456789"), 0x765E_7680 ^ 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn pages_carry_headers_and_end_of_stream() {
+        let mut ogg = OggWriter::new(7);
+        ogg.push_packet(b"OpusHead", 0);
+        ogg.flush(false);
+        ogg.push_packet(&[1u8; 600], 960);
+        let data = ogg.finish();
+
+        // 第一页：BOS，单个 8 字节包
+        assert_eq!(&data[..4], b"OggS");
+        assert_eq!(data[5], 0x02);
+        assert_eq!(data[26], 1);
+        assert_eq!(data[27], 8);
+
+        // 第二页：EOS，600 字节包拆为 255 + 255 + 90 三个分段
+        let second = 28 + 8;
+        assert_eq!(&data[second..second + 4], b"OggS");
+        assert_eq!(data[second + 5], 0x04);
+        assert_eq!(
+            u64::from_le_bytes(data[second + 6..second + 14].try_into().unwrap()),
+            960
+        );
+        assert_eq!(&data[second + 26..second + 30], &[3, 255, 255, 90]);
+        assert_eq!(data.len(), second + 30 + 600);
+    }
+}
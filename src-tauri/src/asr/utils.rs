@@ -1,16 +1,53 @@
+use crate::dictionary_utils::{asr_hotwords, HotwordLimit};
+use anyhow::Result;
 use reqwest::Client;
+use std::sync::OnceLock;
 use std::time::Duration;
 
-/// 创建标准配置的 HTTP 客户端（30s 超时，禁用代理）
-pub fn create_http_client() -> Client {
-    Client::builder()
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10))
-        .pool_idle_timeout(Duration::from_secs(30))
-        .pool_max_idle_per_host(10)
-        .no_proxy()
-        .build()
-        .unwrap_or_else(|_| Client::new())
+/// 空闲连接保留时长：覆盖两次听写之间的间隔，避免每次重新握手
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+/// 预热请求超时（只需完成 DNS / TCP / TLS 握手）
+const PREWARM_TIMEOUT_SECS: u64 = 5;
+
+/// 千问 corpus 文本上限：服务端限制约 10000 token，按字符保守截断
+pub const QWEN_CORPUS_LIMIT: HotwordLimit = HotwordLimit {
+    max_words: None,
+    max_chars: Some(8000),
+};
+/// 豆包 context 热词直传上限（个）
+pub const DOUBAO_HOTWORD_LIMIT: HotwordLimit = HotwordLimit {
+    max_words: Some(100),
+    max_chars: None,
+};
+
+static SHARED_HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// 获取标准配置的 HTTP 客户端（30s 超时，禁用代理）
+///
+/// 进程内共享同一个连接池，服务重启、引擎重建后仍可复用已建立的连接
+pub fn shared_http_client() -> Client {
+    SHARED_HTTP_CLIENT
+        .get_or_init(|| {
+            Client::builder()
+                .timeout(Duration::from_secs(30))
+                .connect_timeout(Duration::from_secs(10))
+                .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+                .pool_max_idle_per_host(10)
+                .no_proxy()
+                .build()
+                .unwrap_or_else(|_| Client::new())
+        })
+        .clone()
+}
+
+/// 预热 HTTP 连接：发送 HEAD 请求建立连接并放回连接池，响应状态码无关紧要
+pub async fn prewarm_http(client: &Client, url: &str) -> Result<()> {
+    client
+        .head(url)
+        .timeout(Duration::from_secs(PREWARM_TIMEOUT_SECS))
+        .send()
+        .await?;
+    Ok(())
 }
 
 /// 去除转录结果末尾的标点符号
@@ -28,3 +65,37 @@ pub fn strip_trailing_punctuation(text: &mut String) {
         }
     }
 }
+
+/// 由词库生成千问 corpus 文本：按权重从高到低以顿号分隔，返回（词数，文本）
+pub fn qwen_corpus_text(dictionary: &[String]) -> (usize, String) {
+    let words: Vec<String> = asr_hotwords(dictionary, QWEN_CORPUS_LIMIT)
+        .into_iter()
+        .map(|h| h.word)
+        .collect();
+    (words.len(), words.join("、"))
+}
+
+/// 由词库生成豆包 context 热词列表（按权重从高到低，超出上限截断低权重词条）
+///
+/// 每个热词携带词库权重，供服务端按权重增强
+pub fn doubao_hotwords(dictionary: &[String]) -> Vec<serde_json::Value> {
+    asr_hotwords(dictionary, DOUBAO_HOTWORD_LIMIT)
+        .into_iter()
+        .map(|h| serde_json::json!({"word": h.word, "weight": h.weight}))
+        .collect()
+}
+
+/// 由词库生成提示词：`prefix` 之后按权重从高到低追加词条（以"，"分隔），总字符数不超过 `max_chars`
+///
+/// Whisper 系模型只保留提示词末尾约 224 个 token，超出预算的低权重词条直接丢弃
+pub fn build_dictionary_prompt(prefix: &str, dictionary: &[String], max_chars: usize) -> String {
+    let limit = HotwordLimit {
+        max_words: None,
+        max_chars: Some(max_chars.saturating_sub(prefix.chars().count())),
+    };
+    let words: Vec<String> = asr_hotwords(dictionary, limit)
+        .into_iter()
+        .map(|h| h.word)
+        .collect();
+    format!("{}{}", prefix, words.join("，"))
+}
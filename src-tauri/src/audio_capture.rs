@@ -0,0 +1,693 @@
+// 音频采集引擎模块
+// 统一打开 cpal 录音流，将设备音频归一化为 16kHz 单声道帧（可选降噪）后分发给各个输出端（sink）：
+// WAV 缓冲、实时音频块通道、音量电平、磁盘归档。
+// 批量（HTTP）与实时模式的区别仅在于挂载的 sink 不同。
+// 后台监听（开放麦克风）时录音流常开，会话开始时直接挂载会话 sink 并补上预录音频。
+
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::Stream;
+use crossbeam_channel::Sender;
+use hound::{WavSpec, WavWriter};
+use std::collections::VecDeque;
+use std::io::{BufWriter, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::AppHandle;
+
+use crate::audio_device::{open_input_device, CaptureWatchdog};
+use crate::audio_utils::{apply_agc, calculate_audio_level, emit_audio_level, Resampler};
+use crate::config::{NoiseSuppressionConfig, VadConfig};
+use crate::denoise::NoiseSuppressor;
+use crate::vad::VoiceDetector;
+
+/// 采集引擎输出的统一采样率（API 要求）
+pub const TARGET_SAMPLE_RATE: u32 = 16000;
+/// 实时音频块的样本数（0.2秒 @ 16kHz = 3200 样本）
+pub const CHUNK_SAMPLES: usize = 3200;
+
+/// 采集输出端：接收归一化后的 16kHz 单声道 f32 帧
+///
+/// 在录音回调线程中调用，实现中不应阻塞
+pub trait CaptureSink: Send {
+    /// 处理一帧音频
+    fn write(&mut self, frame: &[f32]);
+
+    /// 录音结束（重采样器尾部已冲刷），用于收尾落盘等
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 录音回调与引擎共享的状态
+struct CaptureState {
+    /// 会话录音中
+    recording: bool,
+    /// 后台监听中（录音流在会话之间保持打开）
+    monitoring: bool,
+    channels: u16,
+    /// 录音设备采样率
+    input_rate: u32,
+    resampler: Resampler,
+    /// 降噪（在各 sink 的 AGC 之前）
+    denoiser: Option<NoiseSuppressor>,
+    /// 会话 sink
+    sinks: Vec<Box<dyn CaptureSink>>,
+    /// 后台监听 sink：仅在会话之间接收音频
+    monitor_sinks: Vec<Box<dyn CaptureSink>>,
+    /// 预录缓冲：会话之间保留最近的音频，会话开始时先交给会话 sink
+    preroll: VecDeque<f32>,
+    preroll_capacity: usize,
+}
+
+impl CaptureState {
+    /// 转单声道 + 重采样 + 降噪后分发给所有 sink
+    fn push(&mut self, interleaved: &[f32]) {
+        let mono = to_mono(interleaved, self.channels);
+        let mut frame = self.resampler.process(&mono);
+        if let Some(denoiser) = self.denoiser.as_mut() {
+            frame = denoiser.process(&frame);
+        }
+        self.dispatch(&frame);
+    }
+
+    /// 冲刷重采样器与降噪器的尾部并分发
+    fn flush(&mut self) {
+        let mut tail = self.resampler.flush();
+        if let Some(denoiser) = self.denoiser.as_mut() {
+            tail = denoiser.process(&tail);
+            tail.extend(denoiser.flush());
+        }
+        self.dispatch(&tail);
+    }
+
+    fn dispatch(&mut self, frame: &[f32]) {
+        if frame.is_empty() {
+            return;
+        }
+        if self.recording {
+            for sink in self.sinks.iter_mut() {
+                sink.write(frame);
+            }
+            return;
+        }
+
+        for sink in self.monitor_sinks.iter_mut() {
+            sink.write(frame);
+        }
+        if self.preroll_capacity > 0 {
+            let keep = frame.len().min(self.preroll_capacity);
+            let overflow = (self.preroll.len() + keep).saturating_sub(self.preroll_capacity);
+            self.preroll.drain(..overflow);
+            self.preroll.extend(&frame[frame.len() - keep..]);
+        }
+    }
+
+    /// 开始会话：挂载会话 sink，并先写入预录音频
+    fn begin_session(&mut self, mut sinks: Vec<Box<dyn CaptureSink>>) {
+        if !self.preroll.is_empty() {
+            let preroll: Vec<f32> = self.preroll.drain(..).collect();
+            tracing::info!(
+                "补上预录音频: {}ms",
+                preroll.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64
+            );
+            for sink in sinks.iter_mut() {
+                sink.write(&preroll);
+            }
+        }
+        self.sinks = sinks;
+        self.recording = true;
+    }
+
+    /// 结束会话但录音流继续用于后台监听：先把重采样器与降噪器的尾部冲刷给会话 sink，
+    /// 再换上新的重采样器与降噪器，之后的监听音频不受冲刷时补入的静音影响
+    fn end_session(&mut self, denoiser: Option<NoiseSuppressor>) -> Vec<Box<dyn CaptureSink>> {
+        self.flush();
+        self.resampler = Resampler::new(self.input_rate, TARGET_SAMPLE_RATE);
+        self.denoiser = denoiser;
+        self.recording = false;
+        std::mem::take(&mut self.sinks)
+    }
+}
+
+/// 音频采集引擎
+///
+/// 每次录音打开一个 cpal 录音流，挂载的 sink 可在录音中追加；
+/// 后台监听期间录音流常开，会话之间的音频交给监听 sink 并保留在预录缓冲中
+pub struct CaptureEngine {
+    preferred_devices: Arc<Mutex<Vec<String>>>,
+    noise_suppression: Arc<Mutex<NoiseSuppressionConfig>>,
+    state: Arc<Mutex<CaptureState>>,
+    stream: Option<Stream>,
+    watchdog: Option<CaptureWatchdog>,
+}
+
+impl CaptureEngine {
+    pub fn new(
+        preferred_devices: Arc<Mutex<Vec<String>>>,
+        noise_suppression: Arc<Mutex<NoiseSuppressionConfig>>,
+    ) -> Self {
+        Self {
+            preferred_devices,
+            noise_suppression,
+            state: Arc::new(Mutex::new(CaptureState {
+                recording: false,
+                monitoring: false,
+                channels: 1,
+                input_rate: TARGET_SAMPLE_RATE,
+                resampler: Resampler::new(TARGET_SAMPLE_RATE, TARGET_SAMPLE_RATE),
+                denoiser: None,
+                sinks: Vec::new(),
+                monitor_sinks: Vec::new(),
+                preroll: VecDeque::new(),
+                preroll_capacity: 0,
+            })),
+            stream: None,
+            watchdog: None,
+        }
+    }
+
+    /// 打开录音设备并开始采集，音频分发给给定的 sink
+    ///
+    /// 后台监听中时复用已打开的录音流，并先写入预录音频
+    pub fn start(
+        &mut self,
+        app_handle: Option<AppHandle>,
+        sinks: Vec<Box<dyn CaptureSink>>,
+    ) -> Result<()> {
+        if self.stream.is_some() && self.is_monitoring() {
+            self.state.lock().unwrap().begin_session(sinks);
+            return Ok(());
+        }
+
+        self.open_stream(app_handle)?;
+        self.state.lock().unwrap().begin_session(sinks);
+        Ok(())
+    }
+
+    /// 开始后台监听：录音流保持打开，会话之间的音频交给监听 sink，并保留最近 `preroll_ms` 毫秒
+    pub fn start_monitor(
+        &mut self,
+        app_handle: Option<AppHandle>,
+        sinks: Vec<Box<dyn CaptureSink>>,
+        preroll_ms: u32,
+    ) -> Result<()> {
+        if self.stream.is_none() || !self.is_monitoring() {
+            if self.is_recording() {
+                return Err(anyhow::anyhow!("录音中，无法开始后台监听"));
+            }
+            self.open_stream(app_handle)?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.monitoring = true;
+        state.monitor_sinks = sinks;
+        state.preroll_capacity = preroll_ms as usize * TARGET_SAMPLE_RATE as usize / 1000;
+        state.preroll.clear();
+        Ok(())
+    }
+
+    /// 结束后台监听；不在会话中时关闭录音流，释放麦克风
+    pub fn stop_monitor(&mut self) {
+        let recording = {
+            let mut state = self.state.lock().unwrap();
+            state.monitoring = false;
+            state.monitor_sinks.clear();
+            state.preroll.clear();
+            state.preroll_capacity = 0;
+            state.recording
+        };
+        if !recording {
+            self.stream = None;
+            self.watchdog = None;
+        }
+    }
+
+    /// 是否在后台监听（录音设备断开后视为已停止）
+    pub fn is_monitoring(&self) -> bool {
+        let device_ok = self
+            .watchdog
+            .as_ref()
+            .is_none_or(|watchdog| watchdog.check().is_ok());
+        device_ok && self.state.lock().unwrap().monitoring
+    }
+
+    /// 打开录音设备并创建录音流
+    fn open_stream(&mut self, app_handle: Option<AppHandle>) -> Result<()> {
+        // 上一次录音未正常结束时先丢弃旧的流
+        self.stream = None;
+        self.watchdog = None;
+
+        let preferred = self.preferred_devices.lock().unwrap().clone();
+        let (device, device_name) = open_input_device(&preferred)?;
+
+        let supported_config = device
+            .default_input_config()
+            .map_err(|e| anyhow::anyhow!("无法获取默认音频配置: {}", e))?;
+        tracing::info!("设备支持的配置: {:?}", supported_config);
+
+        let config = supported_config.config();
+        tracing::info!(
+            "采集配置: 采样率={}Hz, 声道={}, 目标采样率={}Hz",
+            config.sample_rate.0,
+            config.channels,
+            TARGET_SAMPLE_RATE
+        );
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.channels = config.channels;
+            state.input_rate = config.sample_rate.0;
+            state.resampler = Resampler::new(config.sample_rate.0, TARGET_SAMPLE_RATE);
+            state.denoiser = NoiseSuppressor::from_config(
+                &self.noise_suppression.lock().unwrap(),
+                TARGET_SAMPLE_RATE,
+            );
+            state.preroll.clear();
+        }
+
+        let watchdog = CaptureWatchdog::start(device_name, app_handle);
+        let state = Arc::clone(&self.state);
+
+        let stream = match supported_config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                build_stream::<f32>(&device, &config, state, &watchdog, |s| s)
+            }
+            cpal::SampleFormat::I16 => {
+                build_stream::<i16>(&device, &config, state, &watchdog, |s| {
+                    s as f32 / i16::MAX as f32
+                })
+            }
+            cpal::SampleFormat::U16 => {
+                build_stream::<u16>(&device, &config, state, &watchdog, |s| {
+                    (s as f32 - 32768.0) / 32768.0
+                })
+            }
+            _ => Err(anyhow::anyhow!("不支持的采样格式")),
+        };
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.reset_state();
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = stream.play() {
+            self.reset_state();
+            return Err(e.into());
+        }
+
+        self.stream = Some(stream);
+        self.watchdog = Some(watchdog);
+        Ok(())
+    }
+
+    /// 录音中追加 sink
+    pub fn attach(&self, sink: Box<dyn CaptureSink>) {
+        self.state.lock().unwrap().sinks.push(sink);
+    }
+
+    /// 停止采集：关闭录音流，冲刷重采样器与降噪器尾部并结束所有 sink
+    ///
+    /// 后台监听中只结束会话 sink，录音流保持打开。
+    /// 录音期间设备断开时返回 [`crate::audio_device::DeviceLostError`]，而不是把静音交给后续流程
+    pub fn stop(&mut self) -> Result<()> {
+        let mut sinks = if self.is_monitoring() {
+            let denoiser = NoiseSuppressor::from_config(
+                &self.noise_suppression.lock().unwrap(),
+                TARGET_SAMPLE_RATE,
+            );
+            self.state.lock().unwrap().end_session(denoiser)
+        } else {
+            self.state.lock().unwrap().recording = false;
+
+            // Drop stream 会等待正在执行的回调返回，之后不会再有新数据写入
+            self.stream = None;
+
+            let mut state = self.state.lock().unwrap();
+            state.monitoring = false;
+            state.monitor_sinks.clear();
+            state.flush();
+            std::mem::take(&mut state.sinks)
+        };
+
+        let mut result = Ok(());
+        for sink in sinks.iter_mut() {
+            if let Err(e) = sink.finish() {
+                tracing::error!("采集输出端收尾失败: {}", e);
+                result = Err(e);
+            }
+        }
+        drop(sinks);
+
+        if self.stream.is_none() {
+            if let Some(watchdog) = self.watchdog.take() {
+                watchdog.check()?;
+            }
+        }
+        result
+    }
+
+    /// 检查是否正在录音
+    pub fn is_recording(&self) -> bool {
+        self.state.lock().unwrap().recording
+    }
+
+    fn reset_state(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.recording = false;
+        state.monitoring = false;
+        state.sinks.clear();
+        state.monitor_sinks.clear();
+    }
+}
+
+/// 按设备采样格式创建录音流，样本统一转换为 f32 后交给共享状态处理
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    state: Arc<Mutex<CaptureState>>,
+    watchdog: &CaptureWatchdog,
+    convert: fn(T) -> f32,
+) -> Result<Stream>
+where
+    T: cpal::SizedSample,
+{
+    let heartbeat = watchdog.heartbeat();
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            heartbeat();
+            let mut state = state.lock().unwrap();
+            if !state.recording && !state.monitoring {
+                return;
+            }
+            let samples: Vec<f32> = data.iter().map(|&s| convert(s)).collect();
+            state.push(&samples);
+        },
+        watchdog.error_callback(),
+        None,
+    )?;
+    Ok(stream)
+}
+
+/// 将多声道音频转换为单声道
+fn to_mono(input: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return input.to_vec();
+    }
+
+    input
+        .chunks_exact(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// 将 f32 样本转换为 i16
+pub fn f32_to_i16(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&s| (s * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+        .collect()
+}
+
+fn wav_spec() -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+/// 将 16kHz 单声道样本编码为内存中的 WAV
+pub fn encode_wav(samples: &[f32]) -> Result<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut cursor, wav_spec())?;
+        for sample in f32_to_i16(samples) {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize()?;
+    }
+    Ok(cursor.into_inner())
+}
+
+// ============================================================================
+// 内置 sink
+// ============================================================================
+
+/// WAV 缓冲：累积完整录音，停止后由持有句柄的一方编码
+pub struct WavBufferSink {
+    samples: Arc<Mutex<Vec<f32>>>,
+}
+
+impl WavBufferSink {
+    pub fn new(samples: Arc<Mutex<Vec<f32>>>) -> Self {
+        samples.lock().unwrap().clear();
+        Self { samples }
+    }
+}
+
+impl CaptureSink for WavBufferSink {
+    fn write(&mut self, frame: &[f32]) {
+        self.samples.lock().unwrap().extend_from_slice(frame);
+    }
+}
+
+/// 实时音频块通道：按 0.2 秒分块，经 VAD（按配置选择引擎）过滤静音、AGC 后发送 i16 PCM
+///
+/// sink 被丢弃时发送端随之关闭，接收方据此得知录音结束
+pub struct ChunkChannelSink {
+    sender: Sender<Vec<i16>>,
+    pending: Vec<f32>,
+    vad: VoiceDetector,
+    hangover: usize,
+    agc_gain: f32,
+}
+
+impl ChunkChannelSink {
+    /// VAD 拖尾块数：检测到静音后继续发送几个块，防止句尾吞字
+    /// 3块 * 0.2s = 0.6秒拖尾，平衡防吞字和响应速度
+    const HANGOVER_CHUNKS: usize = 3;
+
+    pub fn new(sender: Sender<Vec<i16>>, vad: &VadConfig) -> Self {
+        Self {
+            sender,
+            pending: Vec::with_capacity(CHUNK_SAMPLES * 2),
+            vad: VoiceDetector::new(vad, TARGET_SAMPLE_RATE),
+            hangover: 0,
+            agc_gain: 1.0,
+        }
+    }
+}
+
+impl CaptureSink for ChunkChannelSink {
+    fn write(&mut self, frame: &[f32]) {
+        self.pending.extend_from_slice(frame);
+
+        while self.pending.len() >= CHUNK_SAMPLES {
+            let mut chunk: Vec<f32> = self.pending.drain(..CHUNK_SAMPLES).collect();
+
+            // VAD 判断
+            let is_active = self.vad.is_voice(&chunk);
+            if is_active {
+                self.hangover = Self::HANGOVER_CHUNKS;
+            } else if self.hangover > 0 {
+                self.hangover -= 1;
+            }
+
+            // 静音且拖尾结束，丢弃前先衰减增益
+            if !is_active && self.hangover == 0 {
+                self.agc_gain = self.agc_gain * 0.5 + 0.5;
+                continue;
+            }
+
+            // AGC（带平滑处理）
+            apply_agc(&mut chunk, &mut self.agc_gain);
+
+            if self.sender.try_send(f32_to_i16(&chunk)).is_err() {
+                tracing::warn!("音频块通道已满，丢弃块");
+            }
+        }
+    }
+}
+
+/// 音量电平：基于时间节流发送到前端（目标 ~30Hz，每 33ms 发送一次）
+pub struct LevelMeterSink {
+    app: AppHandle,
+    last_emit: Instant,
+    emit_counter: u32,
+}
+
+impl LevelMeterSink {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            last_emit: Instant::now(),
+            emit_counter: 0,
+        }
+    }
+}
+
+impl CaptureSink for LevelMeterSink {
+    fn write(&mut self, frame: &[f32]) {
+        if self.last_emit.elapsed().as_millis() < 33 {
+            return;
+        }
+        let level = calculate_audio_level(frame);
+        emit_audio_level(&self.app, level);
+        self.last_emit = Instant::now();
+
+        // 调试日志：每30次打印一次（约每秒）
+        self.emit_counter += 1;
+        if self.emit_counter.is_multiple_of(30) {
+            tracing::info!("[AudioLevel] 发送音频级别: {:.4} (30Hz)", level);
+        }
+    }
+}
+
+/// 磁盘归档：边录边写 16kHz 单声道 WAV 文件
+#[allow(dead_code)]
+pub struct ArchiveSink {
+    path: PathBuf,
+    writer: Option<WavWriter<BufWriter<std::fs::File>>>,
+}
+
+#[allow(dead_code)]
+impl ArchiveSink {
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = WavWriter::create(path, wav_spec())?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Some(writer),
+        })
+    }
+}
+
+impl CaptureSink for ArchiveSink {
+    fn write(&mut self, frame: &[f32]) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let result = f32_to_i16(frame)
+            .into_iter()
+            .try_for_each(|sample| writer.write_sample(sample));
+        if let Err(e) = result {
+            tracing::error!("写入录音归档失败 {:?}: {}", self.path, e);
+            // 写入失败后不再继续写，避免刷屏
+            self.writer = None;
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+            tracing::info!("录音已归档: {:?}", self.path);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_sink_drops_silence_and_sends_voice() {
+        let (tx, rx) = crossbeam_channel::bounded(16);
+        let mut sink = ChunkChannelSink::new(tx, &VadConfig::default());
+
+        // 静音块被丢弃
+        sink.write(&vec![0.0; CHUNK_SAMPLES]);
+        assert!(rx.try_recv().is_err());
+
+        // 有声块按 CHUNK_SAMPLES 分块发送，不足一块的部分留待下一帧
+        let voice: Vec<f32> = (0..CHUNK_SAMPLES * 2 + 100)
+            .map(|i| (i as f32 * 0.05).sin() * 0.2)
+            .collect();
+        sink.write(&voice);
+        assert_eq!(rx.try_recv().unwrap().len(), CHUNK_SAMPLES);
+        assert_eq!(rx.try_recv().unwrap().len(), CHUNK_SAMPLES);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(sink.pending.len(), 100);
+
+        // sink 丢弃后通道关闭
+        drop(sink);
+        assert!(matches!(
+            rx.try_recv(),
+            Err(crossbeam_channel::TryRecvError::Disconnected)
+        ));
+    }
+
+    fn monitoring_state(input_rate: u32, preroll_capacity: usize) -> CaptureState {
+        CaptureState {
+            recording: false,
+            monitoring: true,
+            channels: 1,
+            input_rate,
+            resampler: Resampler::new(input_rate, TARGET_SAMPLE_RATE),
+            denoiser: None,
+            sinks: Vec::new(),
+            monitor_sinks: Vec::new(),
+            preroll: VecDeque::new(),
+            preroll_capacity,
+        }
+    }
+
+    #[test]
+    fn preroll_without_monitor_sinks_is_prepended_to_session() {
+        // 只开预录缓冲：没有监听 sink，会话之间只保留最近的音频
+        let mut state = monitoring_state(TARGET_SAMPLE_RATE, 4);
+        state.dispatch(&[0.1, 0.2, 0.3]);
+        state.dispatch(&[0.4, 0.5, 0.6]);
+        assert_eq!(state.preroll, [0.3, 0.4, 0.5, 0.6]);
+
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        state.begin_session(vec![Box::new(WavBufferSink::new(Arc::clone(&samples)))]);
+        state.dispatch(&[0.7]);
+        assert_eq!(*samples.lock().unwrap(), [0.3, 0.4, 0.5, 0.6, 0.7]);
+        assert!(state.preroll.is_empty());
+    }
+
+    #[test]
+    fn ending_session_while_monitoring_flushes_tail_to_session_sinks() {
+        let mut state = monitoring_state(48000, 0);
+        let monitor = Arc::new(Mutex::new(Vec::new()));
+        state.monitor_sinks = vec![Box::new(WavBufferSink::new(Arc::clone(&monitor)))];
+
+        let session = Arc::new(Mutex::new(Vec::new()));
+        state.begin_session(vec![Box::new(WavBufferSink::new(Arc::clone(&session)))]);
+        state.push(&vec![0.1; 4800]);
+        let sinks = state.end_session(None);
+        assert_eq!(sinks.len(), 1);
+        // 48kHz 的 100ms 重采样后完整交给会话 sink，尾部不丢
+        assert_eq!(session.lock().unwrap().len(), 1600);
+        assert!(monitor.lock().unwrap().is_empty());
+
+        // 会话结束后监听从新的重采样器开始
+        state.push(&vec![0.1; 4800]);
+        assert_eq!(session.lock().unwrap().len(), 1600);
+        assert!(!monitor.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn archive_sink_writes_wav_file() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let path = temp.path().join("archive.wav");
+        let mut sink = ArchiveSink::create(&path).unwrap();
+        sink.write(&[0.0, 0.5, -0.5]);
+        sink.write(&[1.0]);
+        sink.finish().unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, TARGET_SAMPLE_RATE);
+        let samples: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(samples, f32_to_i16(&[0.0, 0.5, -0.5, 1.0]));
+    }
+}
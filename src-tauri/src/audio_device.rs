@@ -0,0 +1,291 @@
+// 音频输入设备模块
+// 提供输入设备枚举、按偏好选择设备、录音中断开检测和热插拔监听
+
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// 录音回调停止超过该时长视为设备已断开（蓝牙耳机切换到通话模式需要 1~2 秒，留出余量）
+const STALL_TIMEOUT: Duration = Duration::from_secs(3);
+/// 录音中断开检测的轮询间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(250);
+/// 热插拔监听的轮询间隔
+const HOTPLUG_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 输入设备信息（返回给前端）
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// 录音设备断开事件 payload
+#[derive(Clone, serde::Serialize)]
+struct InputDeviceLostPayload {
+    device: String,
+}
+
+/// 录音过程中设备断开
+#[derive(Debug)]
+pub struct DeviceLostError {
+    pub device: String,
+}
+
+impl std::fmt::Display for DeviceLostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "录音设备已断开（{}），请检查麦克风连接", self.device)
+    }
+}
+
+impl std::error::Error for DeviceLostError {}
+
+/// 判断错误是否为录音设备断开
+pub fn is_device_lost_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<DeviceLostError>().is_some()
+}
+
+/// 枚举当前可用的输入设备
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host
+        .input_devices()
+        .map_err(|e| anyhow::anyhow!("枚举音频输入设备失败: {}", e))?;
+
+    Ok(devices
+        .filter_map(|d| d.name().ok())
+        .map(|name| InputDeviceInfo {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+/// 按偏好顺序选择设备：返回第一个当前可用的首选设备名称（先精确匹配，再忽略大小写），
+/// 均不可用时返回 None（使用系统默认设备）
+pub fn select_device_name<'a>(preferred: &[String], available: &'a [String]) -> Option<&'a String> {
+    preferred
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .find_map(|name| {
+            available
+                .iter()
+                .find(|candidate| candidate.as_str() == name)
+                .or_else(|| {
+                    available
+                        .iter()
+                        .find(|candidate| candidate.eq_ignore_ascii_case(name))
+                })
+        })
+}
+
+/// 打开录音设备：优先使用首选设备（按顺序回退），否则使用系统默认设备
+///
+/// 每次开始录音时重新解析，首选设备重新插入后下一次录音即自动切换回来
+pub fn open_input_device(preferred: &[String]) -> Result<(cpal::Device, String)> {
+    let host = cpal::default_host();
+
+    if preferred.iter().any(|name| !name.trim().is_empty()) {
+        let devices: Vec<(String, cpal::Device)> = host
+            .input_devices()
+            .map_err(|e| anyhow::anyhow!("枚举音频输入设备失败: {}", e))?
+            .filter_map(|d| d.name().ok().map(|name| (name, d)))
+            .collect();
+        let names: Vec<String> = devices.iter().map(|(name, _)| name.clone()).collect();
+
+        if let Some(selected) = select_device_name(preferred, &names) {
+            if let Some((name, device)) = devices.into_iter().find(|(name, _)| name == selected) {
+                tracing::info!("使用首选输入设备: {}", name);
+                return Ok((device, name));
+            }
+        }
+        tracing::warn!("首选输入设备均不可用 ({:?})，使用系统默认设备", preferred);
+    }
+
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| anyhow::anyhow!("没有找到默认音频输入设备"))?;
+    let name = device.name().unwrap_or_else(|_| "系统默认设备".to_string());
+    tracing::info!("使用系统默认输入设备: {}", name);
+    Ok((device, name))
+}
+
+/// 录音设备断开检测
+///
+/// 设备拔出时部分平台会通过错误回调报告 `DeviceNotAvailable`，部分平台只是不再回调；
+/// 两种情况都会标记为断开并通知前端，停止录音时返回 [`DeviceLostError`] 而不是静音
+pub struct CaptureWatchdog {
+    device_name: String,
+    lost: Arc<AtomicBool>,
+    last_data: Arc<Mutex<Instant>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl CaptureWatchdog {
+    pub fn start(device_name: String, app_handle: Option<AppHandle>) -> Self {
+        let watchdog = Self {
+            device_name,
+            lost: Arc::new(AtomicBool::new(false)),
+            last_data: Arc::new(Mutex::new(Instant::now())),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+
+        let device_name = watchdog.device_name.clone();
+        let lost = Arc::clone(&watchdog.lost);
+        let last_data = Arc::clone(&watchdog.last_data);
+        let stopped = Arc::clone(&watchdog.stopped);
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                std::thread::sleep(WATCHDOG_INTERVAL);
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let stalled = last_data.lock().unwrap().elapsed() > STALL_TIMEOUT;
+                if lost.load(Ordering::SeqCst) || stalled {
+                    lost.store(true, Ordering::SeqCst);
+                    tracing::error!(
+                        "录音设备已断开: {} ({})",
+                        device_name,
+                        if stalled {
+                            "音频回调停止"
+                        } else {
+                            "设备不可用"
+                        }
+                    );
+                    if let Some(app) = app_handle {
+                        let _ = app.emit(
+                            "input_device_lost",
+                            InputDeviceLostPayload {
+                                device: device_name.clone(),
+                            },
+                        );
+                    }
+                    break;
+                }
+            }
+        });
+
+        watchdog
+    }
+
+    /// 数据回调中调用，记录最近一次收到音频的时间
+    pub fn heartbeat(&self) -> impl Fn() + Send + 'static {
+        let last_data = Arc::clone(&self.last_data);
+        move || *last_data.lock().unwrap() = Instant::now()
+    }
+
+    /// 录音流错误回调：设备不可用时立即标记断开
+    pub fn error_callback(&self) -> impl FnMut(cpal::StreamError) + Send + 'static {
+        let lost = Arc::clone(&self.lost);
+        move |err| {
+            tracing::error!("录音流错误: {}", err);
+            if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                lost.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// 检查录音期间设备是否断开
+    pub fn check(&self) -> Result<()> {
+        if self.lost.load(Ordering::SeqCst) {
+            return Err(DeviceLostError {
+                device: self.device_name.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CaptureWatchdog {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+/// 启动热插拔监听：设备列表变化时通知前端，首选设备出现 / 消失导致选择变化时记录并通知
+///
+/// 录音中不切换设备（避免截断当前语句），下一次录音按最新偏好重新选择
+pub fn spawn_device_watcher(app: AppHandle, preferred: Arc<Mutex<Vec<String>>>) {
+    std::thread::spawn(move || {
+        let mut last_names: Option<BTreeSet<String>> = None;
+        let mut last_selected: Option<Option<String>> = None;
+
+        loop {
+            match list_input_devices() {
+                Ok(devices) => {
+                    let names: BTreeSet<String> = devices.iter().map(|d| d.name.clone()).collect();
+                    if last_names.as_ref() != Some(&names) {
+                        if last_names.is_some() {
+                            tracing::info!("输入设备列表变化: {:?}", names);
+                            let _ = app.emit("input_devices_changed", &devices);
+                        }
+                        last_names = Some(names);
+                    }
+
+                    let available: Vec<String> = devices.into_iter().map(|d| d.name).collect();
+                    let selected =
+                        select_device_name(&preferred.lock().unwrap(), &available).cloned();
+                    if last_selected.as_ref() != Some(&selected) {
+                        if last_selected.is_some() {
+                            match &selected {
+                                Some(name) => {
+                                    tracing::info!("首选输入设备可用，后续录音将使用: {}", name)
+                                }
+                                None => tracing::info!(
+                                    "首选输入设备均不可用，后续录音将使用系统默认设备"
+                                ),
+                            }
+                            let _ = app.emit("input_device_switched", &selected);
+                        }
+                        last_selected = Some(selected);
+                    }
+                }
+                Err(e) => tracing::warn!("热插拔监听: {}", e),
+            }
+            std::thread::sleep(HOTPLUG_POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_first_available_preferred_device() {
+        let available = vec![
+            "Microphone (Realtek Audio)".to_string(),
+            "Headset (Jabra Evolve2)".to_string(),
+        ];
+
+        let preferred = vec![
+            "USB Mic (Blue Yeti)".to_string(),
+            "headset (jabra evolve2)".to_string(),
+            "Microphone (Realtek Audio)".to_string(),
+        ];
+        assert_eq!(
+            select_device_name(&preferred, &available).map(String::as_str),
+            Some("Headset (Jabra Evolve2)")
+        );
+
+        let missing = vec!["USB Mic (Blue Yeti)".to_string(), " ".to_string()];
+        assert_eq!(select_device_name(&missing, &available), None);
+        assert_eq!(select_device_name(&[], &available), None);
+    }
+
+    #[test]
+    fn device_lost_error_is_detectable() {
+        let error: anyhow::Error = DeviceLostError {
+            device: "Headset".to_string(),
+        }
+        .into();
+        assert!(is_device_lost_error(&error));
+        assert!(!is_device_lost_error(&anyhow::anyhow!("录音失败")));
+    }
+}
@@ -4,6 +4,9 @@
 use anyhow::Result;
 use tauri::{AppHandle, Emitter};
 
+use crate::config::{VadConfig, VadEngine};
+use crate::vad::contains_speech;
+
 /// 音频级别事件 payload
 #[derive(Clone, serde::Serialize)]
 pub struct AudioLevelPayload {
@@ -74,10 +77,202 @@ pub fn apply_agc(samples: &mut [f32], current_gain: &mut f32) {
     }
 }
 
+/// VAD 的 RMS 阈值，与 AGC 的 NOISE_FLOOR 对齐，平衡灵敏度和抗噪能力
+pub const VOICE_RMS_THRESHOLD: f32 = 0.003;
+
 /// VAD：基于 RMS 阈值判断是否有语音
 pub fn is_voice_active(samples: &[f32]) -> bool {
-    const THRESHOLD: f32 = 0.003; // 与 NOISE_FLOOR 对齐，平衡灵敏度和抗噪能力
-    calculate_rms(samples) > THRESHOLD
+    calculate_rms(samples) > VOICE_RMS_THRESHOLD
+}
+
+// ============================================================================
+// 重采样
+// ============================================================================
+
+/// 重采样滤波器单侧的过零点数量，越大过渡带越窄
+const RESAMPLE_ZERO_CROSSINGS: f64 = 24.0;
+/// 截止频率相对较低一侧奈奎斯特频率的比例，留出过渡带防止混叠
+const RESAMPLE_ROLLOFF: f64 = 0.85;
+/// 预计算多相滤波器组的最大相位数，超出时按需计算系数
+const RESAMPLE_MAX_PHASES: usize = 1024;
+
+/// 带限重采样器（多相加窗 sinc 滤波）
+///
+/// 采样率比例约分为 L/M，按 L 个相位预计算 Blackman 窗 sinc 滤波器组。
+/// 降采样时截止频率取目标奈奎斯特频率的 85%，先抗混叠再抽取，
+/// 避免 44.1/48kHz 降到 16kHz 时高频（齿音）折叠到语音频段。
+///
+/// 有状态：录音回调中逐块调用 [`Resampler::process`]，块边界处与一次性处理结果一致
+pub struct Resampler {
+    up: usize,
+    down: usize,
+    half_taps: usize,
+    cutoff: f64,
+    /// 多相滤波器组（相位数过大时为空，按需计算）
+    phases: Vec<Vec<f32>>,
+    /// 待处理输入（含滤波器所需的历史样本）
+    buffer: Vec<f32>,
+    /// 下一个输出样本对应的输入位置（buffer 下标）与相位
+    center: usize,
+    phase: usize,
+    input_len: u64,
+    output_len: u64,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let g = gcd(from_rate.max(1) as u64, to_rate.max(1) as u64);
+        let up = (to_rate.max(1) as u64 / g) as usize;
+        let down = (from_rate.max(1) as u64 / g) as usize;
+
+        // 截止频率（单位：周期 / 输入样本）
+        let cutoff = 0.5 * RESAMPLE_ROLLOFF * (up as f64 / down as f64).min(1.0);
+        let half_taps = (RESAMPLE_ZERO_CROSSINGS / (2.0 * cutoff)).ceil() as usize;
+
+        let phases = if up != down && up <= RESAMPLE_MAX_PHASES {
+            (0..up)
+                .map(|phase| phase_taps(phase, up, half_taps, cutoff))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            up,
+            down,
+            half_taps,
+            cutoff,
+            phases,
+            buffer: vec![0.0; half_taps.saturating_sub(1)],
+            center: half_taps.saturating_sub(1),
+            phase: 0,
+            input_len: 0,
+            output_len: 0,
+        }
+    }
+
+    fn is_passthrough(&self) -> bool {
+        self.up == self.down
+    }
+
+    /// 输入一块样本，返回当前可以输出的样本（滤波器前瞻的尾部样本留到下一块或 flush）
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.is_passthrough() {
+            return input.to_vec();
+        }
+        self.input_len += input.len() as u64;
+        self.buffer.extend_from_slice(input);
+        self.drain(u64::MAX)
+    }
+
+    /// 输入结束，补零输出剩余样本（总输出长度为 ceil(输入长度 × L / M)）
+    pub fn flush(&mut self) -> Vec<f32> {
+        if self.is_passthrough() {
+            return Vec::new();
+        }
+        let total = (self.input_len * self.up as u64).div_ceil(self.down as u64);
+        self.buffer
+            .extend(std::iter::repeat_n(0.0, self.half_taps + 1));
+        self.drain(total)
+    }
+
+    fn drain(&mut self, max_output_len: u64) -> Vec<f32> {
+        let taps = 2 * self.half_taps;
+        let mut output = Vec::with_capacity(
+            (self.buffer.len().saturating_sub(self.center) * self.up / self.down) + 1,
+        );
+
+        while self.center + self.half_taps < self.buffer.len() && self.output_len < max_output_len {
+            let computed;
+            let coefficients: &[f32] = if self.phases.is_empty() {
+                computed = phase_taps(self.phase, self.up, self.half_taps, self.cutoff);
+                &computed
+            } else {
+                &self.phases[self.phase]
+            };
+            let start = self.center + 1 - self.half_taps;
+            let sample: f32 = self.buffer[start..start + taps]
+                .iter()
+                .zip(coefficients)
+                .map(|(x, h)| x * h)
+                .sum();
+            output.push(sample);
+            self.output_len += 1;
+
+            self.phase += self.down;
+            self.center += self.phase / self.up;
+            self.phase %= self.up;
+        }
+
+        // 丢弃后续输出不再需要的历史样本
+        let consumed = (self.center + 1)
+            .saturating_sub(self.half_taps)
+            .min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.center -= consumed;
+
+        output
+    }
+}
+
+/// 计算某一相位的滤波器系数：输出位于两个输入样本之间 phase/up 处，
+/// 抽头 t 对应输入偏移 j = t + 1 - half_taps，系数为 h(phase/up - j)，并归一化为单位直流增益
+fn phase_taps(phase: usize, up: usize, half_taps: usize, cutoff: f64) -> Vec<f32> {
+    let frac = phase as f64 / up as f64;
+    let half_width = RESAMPLE_ZERO_CROSSINGS / (2.0 * cutoff);
+
+    let raw: Vec<f64> = (0..2 * half_taps)
+        .map(|t| {
+            let x = frac - (t as f64 + 1.0 - half_taps as f64);
+            if x.abs() >= half_width {
+                return 0.0;
+            }
+            let arg = 2.0 * cutoff * x;
+            let sinc = if arg.abs() < 1e-12 {
+                1.0
+            } else {
+                (std::f64::consts::PI * arg).sin() / (std::f64::consts::PI * arg)
+            };
+            let w = std::f64::consts::PI * x / half_width;
+            let window = 0.42 + 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+            sinc * window
+        })
+        .collect();
+
+    let sum: f64 = raw.iter().sum();
+    raw.iter().map(|&h| (h / sum) as f32).collect()
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// 一次性重采样（f32 样本）
+pub fn resample(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let mut resampler = Resampler::new(from_rate, to_rate);
+    let mut output = resampler.process(input);
+    output.extend(resampler.flush());
+    output
+}
+
+/// 一次性重采样（16-bit PCM 样本）
+#[cfg_attr(not(feature = "doubao-ime"), allow(dead_code))]
+pub fn resample_i16(input: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
+    if from_rate == to_rate {
+        return input.to_vec();
+    }
+    let samples: Vec<f32> = input.iter().map(|&s| s as f32 / 32768.0).collect();
+    resample(&samples, from_rate, to_rate)
+        .into_iter()
+        .map(|s| {
+            (s * 32768.0)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+        })
+        .collect()
 }
 
 // ============================================================================
@@ -88,15 +283,15 @@ pub fn is_voice_active(samples: &[f32]) -> bool {
 const MIN_AUDIO_DURATION_SAMPLES: usize = 8000; // 0.5秒 @ 16kHz
 const MIN_AUDIO_RMS: f32 = 0.02; // 静音阈值（需高于麦克风底噪）
 
-/// 验证音频数据是否有效（WAV 格式）
+/// 验证音频数据是否有效（16kHz WAV 格式）
 ///
 /// 检测条件：
 /// - 时长 >= 0.5 秒：直接通过
-/// - 时长 < 0.5 秒 且 RMS < 0.02（静音）：跳过（用户误触）
-/// - 时长 < 0.5 秒 但 RMS >= 0.02（有声音）：继续转写
+/// - 时长 < 0.5 秒 且没有检测到语音（RMS 引擎：RMS < 0.02）：跳过（用户误触）
+/// - 时长 < 0.5 秒 但检测到语音：继续转写
 ///
 /// 返回 Ok(()) 表示有效，Err 表示无效（包含原因）
-pub fn validate_audio(audio_data: &[u8]) -> Result<()> {
+pub fn validate_audio(audio_data: &[u8], vad: &VadConfig) -> Result<()> {
     // 检查1：非空
     if audio_data.is_empty() {
         return Err(anyhow::anyhow!("音频数据为空"));
@@ -127,7 +322,15 @@ pub fn validate_audio(audio_data: &[u8]) -> Result<()> {
     let sum_squares: f64 = samples.iter().map(|&s| (s as f64 / 32768.0).powi(2)).sum();
     let rms = (sum_squares / samples.len() as f64).sqrt() as f32;
 
-    if rms < MIN_AUDIO_RMS {
+    let has_speech = match vad.engine {
+        VadEngine::Rms => rms >= MIN_AUDIO_RMS,
+        VadEngine::Gmm => {
+            let pcm: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+            contains_speech(&pcm, 16000, vad)
+        }
+    };
+
+    if !has_speech {
         tracing::info!(
             "音频过短且静音 ({} 采样点, RMS={:.4})，跳过转写",
             samples.len(),
@@ -144,3 +347,71 @@ pub fn validate_audio(audio_data: &[u8]) -> Result<()> {
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f64, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                (2.0 * std::f64::consts::PI * freq * i as f64 / rate as f64).sin() as f32 * 0.5
+            })
+            .collect()
+    }
+
+    /// 去掉首尾滤波器过渡区后的 RMS
+    fn steady_rms(samples: &[f32]) -> f32 {
+        let margin = samples.len() / 10;
+        calculate_rms(&samples[margin..samples.len() - margin])
+    }
+
+    #[test]
+    fn resample_keeps_speech_band_and_length() {
+        for from_rate in [48000, 44100, 22050, 8000] {
+            let input = sine(1000.0, from_rate, from_rate as usize);
+            let output = resample(&input, from_rate, 16000);
+            assert_eq!(output.len(), 16000, "{}Hz", from_rate);
+            let ratio = steady_rms(&output) / steady_rms(&input);
+            assert!(
+                (ratio - 1.0).abs() < 0.02,
+                "{}Hz: gain {}",
+                from_rate,
+                ratio
+            );
+        }
+
+        let input = sine(1000.0, 16000, 1600);
+        assert_eq!(resample(&input, 16000, 16000), input);
+    }
+
+    #[test]
+    fn resample_rejects_content_above_target_nyquist() {
+        // 12kHz 在 16kHz 采样下会折叠到 4kHz，必须被抗混叠滤波器滤除
+        for from_rate in [48000, 44100] {
+            let input = sine(12000.0, from_rate, from_rate as usize);
+            let output = resample(&input, from_rate, 16000);
+            let ratio = steady_rms(&output) / steady_rms(&input);
+            assert!(ratio < 0.001, "{}Hz: leakage {}", from_rate, ratio);
+        }
+    }
+
+    #[test]
+    fn chunked_resampling_matches_one_shot() {
+        let input = sine(440.0, 44100, 44100);
+        let expected = resample(&input, 44100, 16000);
+
+        let mut resampler = Resampler::new(44100, 16000);
+        let mut output = Vec::new();
+        for chunk in input.chunks(441) {
+            output.extend(resampler.process(chunk));
+        }
+        output.extend(resampler.flush());
+
+        assert_eq!(output.len(), expected.len());
+        assert!(output
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+}
@@ -0,0 +1,259 @@
+// 免提自动结束模块
+// 切换模式 / 松手锁定模式下无需再次按键：检测到语音后持续静音超过设定时长，
+// 或录音达到最长时长时自动结束录音，之后走与手动结束相同的转写流程。
+// 静音累积期间通过 auto_stop_countdown 事件通知悬浮窗显示倒计时。
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_capture::{CaptureSink, TARGET_SAMPLE_RATE};
+use crate::config::{AutoStopConfig, VadConfig};
+use crate::vad::VoiceDetector;
+
+/// 语音检测的分析块长度（0.1秒 @ 16kHz）
+const ANALYSIS_SAMPLES: usize = 1600;
+/// 静音超过该时长才开始显示倒计时，避免词间停顿引起闪烁
+const COUNTDOWN_DELAY_MS: u64 = 500;
+/// 距离最长录音时长不足该值时显示倒计时
+const MAX_DURATION_WARNING_MS: u64 = 5000;
+/// 监听任务的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 自动结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// 说话后持续静音
+    Silence,
+    /// 达到最长录音时长
+    MaxDuration,
+}
+
+/// 自动结束判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoStopStatus {
+    Listening,
+    /// 即将自动结束，剩余毫秒数
+    Countdown(u64),
+    Stop(StopReason),
+}
+
+/// 自动结束判定：按已采集的音频时长计时，不受回调调度抖动影响
+pub struct SilenceTracker {
+    silence_ms: u64,
+    max_duration_ms: u64,
+    total_samples: u64,
+    trailing_silence_samples: u64,
+    speech_detected: bool,
+    /// 录音已结束（采集 sink 已收尾或被丢弃）
+    closed: bool,
+}
+
+impl SilenceTracker {
+    pub fn new(config: &AutoStopConfig) -> Self {
+        Self {
+            silence_ms: config.silence_ms as u64,
+            max_duration_ms: config.max_duration_secs as u64 * 1000,
+            total_samples: 0,
+            trailing_silence_samples: 0,
+            speech_detected: false,
+            closed: false,
+        }
+    }
+
+    /// 记录一段音频的语音判决
+    pub fn push(&mut self, samples: usize, is_voice: bool) {
+        self.total_samples += samples as u64;
+        if is_voice {
+            self.speech_detected = true;
+            self.trailing_silence_samples = 0;
+        } else {
+            self.trailing_silence_samples += samples as u64;
+        }
+    }
+
+    pub fn status(&self) -> AutoStopStatus {
+        let elapsed_ms = samples_to_ms(self.total_samples);
+        if self.max_duration_ms > 0 && elapsed_ms >= self.max_duration_ms {
+            return AutoStopStatus::Stop(StopReason::MaxDuration);
+        }
+
+        // 尚未说话时不因静音结束（用户可能还在组织语言）
+        let mut remaining: Option<u64> = None;
+        if self.speech_detected {
+            let silence = samples_to_ms(self.trailing_silence_samples);
+            if silence >= self.silence_ms {
+                return AutoStopStatus::Stop(StopReason::Silence);
+            }
+            if silence >= COUNTDOWN_DELAY_MS.min(self.silence_ms / 2) {
+                remaining = Some(self.silence_ms - silence);
+            }
+        }
+        if self.max_duration_ms > 0 {
+            let left = self.max_duration_ms - elapsed_ms;
+            if left <= MAX_DURATION_WARNING_MS {
+                remaining = Some(remaining.map_or(left, |r| r.min(left)));
+            }
+        }
+
+        remaining.map_or(AutoStopStatus::Listening, AutoStopStatus::Countdown)
+    }
+}
+
+fn samples_to_ms(samples: u64) -> u64 {
+    samples * 1000 / TARGET_SAMPLE_RATE as u64
+}
+
+/// 自动结束采集 sink：逐块做语音检测并更新判定状态
+pub struct AutoStopSink {
+    tracker: Arc<Mutex<SilenceTracker>>,
+    vad: VoiceDetector,
+    pending: Vec<f32>,
+}
+
+impl AutoStopSink {
+    pub fn new(tracker: Arc<Mutex<SilenceTracker>>, vad: &VadConfig) -> Self {
+        Self {
+            tracker,
+            vad: VoiceDetector::new(vad, TARGET_SAMPLE_RATE),
+            pending: Vec::with_capacity(ANALYSIS_SAMPLES * 2),
+        }
+    }
+}
+
+impl CaptureSink for AutoStopSink {
+    fn write(&mut self, frame: &[f32]) {
+        self.pending.extend_from_slice(frame);
+        let blocks = self.pending.len() / ANALYSIS_SAMPLES;
+        if blocks == 0 {
+            return;
+        }
+
+        let mut tracker = self.tracker.lock().unwrap();
+        for block in self.pending[..blocks * ANALYSIS_SAMPLES].chunks_exact(ANALYSIS_SAMPLES) {
+            let is_voice = self.vad.is_voice(block);
+            tracker.push(block.len(), is_voice);
+        }
+        drop(tracker);
+        self.pending.drain(..blocks * ANALYSIS_SAMPLES);
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        self.tracker.lock().unwrap().closed = true;
+        Ok(())
+    }
+}
+
+impl Drop for AutoStopSink {
+    fn drop(&mut self) {
+        // 录音被取消或重新开始时 sink 直接被丢弃，同样通知监听任务退出
+        self.tracker.lock().unwrap().closed = true;
+    }
+}
+
+/// 启动自动结束监听任务
+///
+/// 静音累积时推送 `auto_stop_countdown`（剩余毫秒数，恢复说话时推送 null）；
+/// 满足结束条件时调用 `on_stop`。录音以其他方式结束后任务自动退出
+pub fn spawn_auto_stop_watcher<F>(app: AppHandle, tracker: Arc<Mutex<SilenceTracker>>, on_stop: F)
+where
+    F: FnOnce(StopReason) + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut countdown_shown = false;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let status = {
+                let tracker = tracker.lock().unwrap();
+                if tracker.closed {
+                    None
+                } else {
+                    Some(tracker.status())
+                }
+            };
+
+            match status {
+                Some(AutoStopStatus::Countdown(remaining_ms)) => {
+                    countdown_shown = true;
+                    let _ = app.emit("auto_stop_countdown", Some(remaining_ms));
+                }
+                Some(AutoStopStatus::Listening) => {
+                    if countdown_shown {
+                        countdown_shown = false;
+                        let _ = app.emit("auto_stop_countdown", None::<u64>);
+                    }
+                }
+                Some(AutoStopStatus::Stop(reason)) => {
+                    let _ = app.emit("auto_stop_countdown", None::<u64>);
+                    tracing::info!("自动结束录音: {:?}", reason);
+                    on_stop(reason);
+                    return;
+                }
+                None => {
+                    if countdown_shown {
+                        let _ = app.emit("auto_stop_countdown", None::<u64>);
+                    }
+                    return;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(silence_ms: u32, max_duration_secs: u32) -> AutoStopConfig {
+        AutoStopConfig {
+            enabled: true,
+            silence_ms,
+            max_duration_secs,
+        }
+    }
+
+    /// 以 0.1 秒为单位推送语音 / 静音
+    fn push(tracker: &mut SilenceTracker, blocks: usize, is_voice: bool) {
+        for _ in 0..blocks {
+            tracker.push(ANALYSIS_SAMPLES, is_voice);
+        }
+    }
+
+    #[test]
+    fn stops_after_trailing_silence_once_speech_was_heard() {
+        let mut tracker = SilenceTracker::new(&config(2000, 0));
+
+        // 开口前的长时间静音不触发结束
+        push(&mut tracker, 50, false);
+        assert_eq!(tracker.status(), AutoStopStatus::Listening);
+
+        push(&mut tracker, 10, true);
+        push(&mut tracker, 3, false);
+        assert_eq!(tracker.status(), AutoStopStatus::Listening);
+
+        // 静音累积后开始倒计时，恢复说话则重置
+        push(&mut tracker, 5, false);
+        assert_eq!(tracker.status(), AutoStopStatus::Countdown(1200));
+        push(&mut tracker, 1, true);
+        assert_eq!(tracker.status(), AutoStopStatus::Listening);
+
+        push(&mut tracker, 20, false);
+        assert_eq!(tracker.status(), AutoStopStatus::Stop(StopReason::Silence));
+    }
+
+    #[test]
+    fn stops_at_max_duration_even_while_speaking() {
+        let mut tracker = SilenceTracker::new(&config(2000, 10));
+
+        push(&mut tracker, 40, true);
+        assert_eq!(tracker.status(), AutoStopStatus::Listening);
+        push(&mut tracker, 20, true);
+        assert_eq!(tracker.status(), AutoStopStatus::Countdown(4000));
+        push(&mut tracker, 40, true);
+        assert_eq!(
+            tracker.status(),
+            AutoStopStatus::Stop(StopReason::MaxDuration)
+        );
+    }
+}
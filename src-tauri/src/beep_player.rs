@@ -1,5 +1,8 @@
 use rodio::{Decoder, OutputStream, Sink, Source};
 use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 // 在编译时嵌入音效文件
 const NOTIFICATION_SOUND: &[u8] = include_bytes!("../resources/notification.ogg");
@@ -7,13 +10,35 @@ const NOTIFICATION_SOUND: &[u8] = include_bytes!("../resources/notification.ogg"
 // 音量系数 (0.0 - 1.0)，调小这个值可以降低音量
 const VOLUME: f32 = 0.2;
 
+/// 提示音结束后仍视为可听见的时长（扬声器余响、音频缓冲）
+const AUDIBLE_TAIL: Duration = Duration::from_millis(300);
+
+/// 正在播放的提示音数量
+static PLAYING: AtomicUsize = AtomicUsize::new(0);
+/// 最近一次提示音播放结束的时间
+static LAST_FINISHED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 提示音是否正在播放或刚刚播放完（开放麦克风据此忽略麦克风拾取到的提示音）
+pub fn is_beep_audible() -> bool {
+    if PLAYING.load(Ordering::SeqCst) > 0 {
+        return true;
+    }
+    LAST_FINISHED
+        .lock()
+        .unwrap()
+        .is_some_and(|finished| finished.elapsed() < AUDIBLE_TAIL)
+}
+
 /// 播放提示音（非阻塞）
 pub fn play_notification() {
     // 在新线程中播放，避免阻塞主线程
+    PLAYING.fetch_add(1, Ordering::SeqCst);
     std::thread::spawn(|| {
         if let Err(e) = play_notification_blocking() {
             tracing::error!("播放提示音失败: {}", e);
         }
+        *LAST_FINISHED.lock().unwrap() = Some(Instant::now());
+        PLAYING.fetch_sub(1, Ordering::SeqCst);
     });
 }
 
@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
@@ -332,6 +332,9 @@ pub struct DualHotkeyConfig {
     /// AI助手模式快捷键（默认 Alt+Space）
     #[serde(default = "default_assistant_hotkey")]
     pub assistant: HotkeyConfig,
+    /// 切换识别语言的快捷键（可选，未录音时按下循环切换）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_switch_keys: Option<Vec<HotkeyKey>>,
 }
 
 fn default_dictation_hotkey() -> HotkeyConfig {
@@ -357,6 +360,7 @@ impl Default for DualHotkeyConfig {
         Self {
             dictation: default_dictation_hotkey(),
             assistant: default_assistant_hotkey(),
+            language_switch_keys: None,
         }
     }
 }
@@ -396,6 +400,30 @@ impl DualHotkeyConfig {
             );
         }
 
+        // 语言切换快捷键（如果设置）：规则同主快捷键，且不能与录音快捷键冲突
+        if let Some(ref language_keys) = self.language_switch_keys {
+            HotkeyConfig {
+                keys: language_keys.clone(),
+                ..Default::default()
+            }
+            .validate()
+            .map_err(|e| anyhow::anyhow!("语言切换快捷键配置无效: {}", e))?;
+
+            let language_set: HashSet<_> = language_keys.iter().collect();
+            for (name, set) in [("听写模式", &dictation_set), ("AI助手模式", &assistant_set)]
+            {
+                if language_set.is_subset(set) || set.is_subset(&language_set) {
+                    anyhow::bail!("语言切换快捷键与{}快捷键冲突", name);
+                }
+            }
+            if let Some(ref release_keys) = self.dictation.release_mode_keys {
+                let release_set: HashSet<_> = release_keys.iter().collect();
+                if language_set == release_set {
+                    anyhow::bail!("语言切换快捷键不能与松手模式快捷键相同");
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -471,15 +499,27 @@ impl HotkeyConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+/// OpenAI 兼容转写实例在配置中的前缀：`openai:<id>`
+const OPENAI_COMPATIBLE_PREFIX: &str = "openai:";
+/// 自建 WebSocket 流式服务实例在配置中的前缀：`ws:<id>`
+const WEBSOCKET_PREFIX: &str = "ws:";
+
+/// ASR 提供商
+///
+/// 序列化为字符串：内置提供商使用固定名称，OpenAI 兼容实例使用 `openai:<id>`，
+/// 自建 WebSocket 服务使用 `ws:<id>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AsrProvider {
     Qwen,
     Doubao,
-    #[serde(rename = "doubao_ime")]
     DoubaoIme,
-    #[serde(rename = "siliconflow")]
     SiliconFlow,
+    /// 本地离线 Whisper（whisper.cpp）
+    LocalWhisper,
+    /// OpenAI 兼容的 `/v1/audio/transcriptions` 服务（对应 `AsrConfig.openai_providers` 中的 id）
+    OpenAiCompatible(String),
+    /// 自建的 WebSocket 流式识别服务（对应 `AsrConfig.websocket_providers` 中的 id）
+    WebSocket(String),
 }
 
 impl Default for AsrProvider {
@@ -488,6 +528,73 @@ impl Default for AsrProvider {
     }
 }
 
+impl AsrProvider {
+    /// 配置文件与前端使用的字符串标识
+    pub fn config_key(&self) -> String {
+        match self {
+            AsrProvider::Qwen => "qwen".to_string(),
+            AsrProvider::Doubao => "doubao".to_string(),
+            AsrProvider::DoubaoIme => "doubao_ime".to_string(),
+            AsrProvider::SiliconFlow => "siliconflow".to_string(),
+            AsrProvider::LocalWhisper => "local_whisper".to_string(),
+            AsrProvider::OpenAiCompatible(id) => format!("{}{}", OPENAI_COMPATIBLE_PREFIX, id),
+            AsrProvider::WebSocket(id) => format!("{}{}", WEBSOCKET_PREFIX, id),
+        }
+    }
+
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        match key {
+            "qwen" => Some(AsrProvider::Qwen),
+            "doubao" => Some(AsrProvider::Doubao),
+            "doubao_ime" => Some(AsrProvider::DoubaoIme),
+            "siliconflow" => Some(AsrProvider::SiliconFlow),
+            "local_whisper" => Some(AsrProvider::LocalWhisper),
+            _ => {
+                if let Some(id) = key.strip_prefix(WEBSOCKET_PREFIX) {
+                    return (!id.is_empty()).then(|| AsrProvider::WebSocket(id.to_string()));
+                }
+                key.strip_prefix(OPENAI_COMPATIBLE_PREFIX)
+                    .filter(|id| !id.is_empty())
+                    .map(|id| AsrProvider::OpenAiCompatible(id.to_string()))
+            }
+        }
+    }
+
+    /// 只支持一次性转录（没有实时流式接口）的提供商
+    pub fn is_batch_only(&self) -> bool {
+        matches!(
+            self,
+            AsrProvider::LocalWhisper | AsrProvider::OpenAiCompatible(_)
+        )
+    }
+}
+
+impl Serialize for AsrProvider {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.config_key())
+    }
+}
+
+impl<'de> Deserialize<'de> for AsrProvider {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        AsrProvider::from_config_key(&key).ok_or_else(|| {
+            serde::de::Error::unknown_variant(
+                &key,
+                &[
+                    "qwen",
+                    "doubao",
+                    "doubao_ime",
+                    "siliconflow",
+                    "local_whisper",
+                    "openai:<id>",
+                    "ws:<id>",
+                ],
+            )
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AsrCredentials {
     #[serde(default)]
@@ -498,7 +605,7 @@ pub struct AsrCredentials {
     pub doubao_app_id: String,
     #[serde(default)]
     pub doubao_access_token: String,
-    // 豆包输入法 ASR 凭据 (自动注册获取)
+    // 豆包输入法 ASR 凭据：旧版保存位置，启动时迁移到独立的凭据存储（doubao_ime_credentials.json）后清空
     #[serde(default)]
     pub doubao_ime_device_id: String,
     #[serde(default)]
@@ -507,6 +614,48 @@ pub struct AsrCredentials {
     pub doubao_ime_cdid: String,
 }
 
+/// 多个 ASR 提供商之间的调度策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AsrRacePolicy {
+    /// 顺序故障转移：按顺序尝试，前一个彻底失败后才启动下一个
+    Sequential,
+    /// 全并行竞速：同时启动所有提供商，第一个成功的结果胜出
+    #[default]
+    Race,
+    /// 对冲请求：前一个在 hedge_delay_ms 内没有结果（或已失败）才启动下一个
+    Hedged,
+    /// 择优：等待所有提供商完成，按评分选出最佳结果
+    BestOfN,
+}
+
+/// ASR 调度策略配置（重试、退避、超时均属于策略的一部分）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AsrRaceStrategy {
+    pub policy: AsrRacePolicy,
+    /// 单个提供商的最大重试次数（不含首次尝试）
+    pub max_retries: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub retry_backoff_ms: u64,
+    /// 单次尝试的超时时间，0 表示不限制
+    pub attempt_timeout_ms: u64,
+    /// 对冲策略下启动下一个提供商前的等待时间
+    pub hedge_delay_ms: u64,
+}
+
+impl Default for AsrRaceStrategy {
+    fn default() -> Self {
+        Self {
+            policy: AsrRacePolicy::Race,
+            max_retries: 2,
+            retry_backoff_ms: 500,
+            attempt_timeout_ms: 30_000,
+            hedge_delay_ms: 1_500,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AsrSelection {
     #[serde(default)]
@@ -515,6 +664,11 @@ pub struct AsrSelection {
     pub enable_fallback: bool,
     #[serde(default)]
     pub fallback_provider: Option<AsrProvider>,
+    /// 备用提供商的有序列表（为空时沿用 fallback_provider）
+    #[serde(default)]
+    pub providers: Vec<AsrProvider>,
+    #[serde(default)]
+    pub strategy: AsrRaceStrategy,
 }
 
 impl Default for AsrSelection {
@@ -523,24 +677,230 @@ impl Default for AsrSelection {
             active_provider: AsrProvider::DoubaoIme,
             enable_fallback: false,
             fallback_provider: None,
+            providers: Vec::new(),
+            strategy: AsrRaceStrategy::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+impl AsrSelection {
+    /// 批量转录时参与调度的提供商顺序：主提供商在前，未开启备用时只有主提供商
+    pub fn provider_order(&self) -> Vec<AsrProvider> {
+        let mut order = vec![self.active_provider.clone()];
+        if !self.enable_fallback {
+            return order;
+        }
+
+        let backups: Vec<AsrProvider> = if self.providers.is_empty() {
+            self.fallback_provider.iter().cloned().collect()
+        } else {
+            self.providers.clone()
+        };
+        for provider in backups {
+            if !order.contains(&provider) {
+                order.push(provider);
+            }
+        }
+        order
+    }
+}
+
+/// 识别语言模式
+///
+/// 各提供商的支持情况见 `asr::supported_language_modes`，不支持时降级为自动识别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum AsrLanguageMode {
+    /// 中文（普通话）
     Zh,
+    /// 中英混说（code-switch）
+    ZhEn,
+    /// 英文
+    En,
+    /// 粤语
+    Yue,
+    /// 日语
+    Ja,
+    /// 韩语
+    Ko,
     #[default]
     Auto,
 }
 
+impl AsrLanguageMode {
+    /// 全部模式（托盘菜单与快捷键切换的顺序）
+    pub const ALL: [AsrLanguageMode; 7] = [
+        AsrLanguageMode::Auto,
+        AsrLanguageMode::Zh,
+        AsrLanguageMode::ZhEn,
+        AsrLanguageMode::En,
+        AsrLanguageMode::Yue,
+        AsrLanguageMode::Ja,
+        AsrLanguageMode::Ko,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AsrLanguageMode::Zh => "中文",
+            AsrLanguageMode::ZhEn => "中英混说",
+            AsrLanguageMode::En => "英语",
+            AsrLanguageMode::Yue => "粤语",
+            AsrLanguageMode::Ja => "日语",
+            AsrLanguageMode::Ko => "韩语",
+            AsrLanguageMode::Auto => "自动识别",
+        }
+    }
+
+    /// ISO 639 语言代码；自动识别为 None，中英混说以中文为主语言
+    pub fn iso_code(&self) -> Option<&'static str> {
+        match self {
+            AsrLanguageMode::Zh | AsrLanguageMode::ZhEn => Some("zh"),
+            AsrLanguageMode::En => Some("en"),
+            AsrLanguageMode::Yue => Some("yue"),
+            AsrLanguageMode::Ja => Some("ja"),
+            AsrLanguageMode::Ko => Some("ko"),
+            AsrLanguageMode::Auto => None,
+        }
+    }
+
+    /// 与 serde 一致的字符串标识（托盘菜单 ID 等使用）
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            AsrLanguageMode::Zh => "zh",
+            AsrLanguageMode::ZhEn => "zh_en",
+            AsrLanguageMode::En => "en",
+            AsrLanguageMode::Yue => "yue",
+            AsrLanguageMode::Ja => "ja",
+            AsrLanguageMode::Ko => "ko",
+            AsrLanguageMode::Auto => "auto",
+        }
+    }
+
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.config_key() == key)
+    }
+}
+
+/// HTTP 提供商的上传音频格式
+///
+/// 默认上传 WAV，压缩格式需按提供商单独开启（`AsrConfig.upload_formats` /
+/// `OpenAiAsrProvider.upload_format`）。各提供商接受的格式见 `asr::supported_upload_formats`，
+/// 不支持时依次降级为 FLAC、WAV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioUploadFormat {
+    /// 原始 16-bit PCM WAV
+    #[default]
+    Wav,
+    /// FLAC 无损压缩
+    Flac,
+    /// Ogg 封装的 Opus（有损，体积最小）
+    OggOpus,
+}
+
+impl AudioUploadFormat {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AudioUploadFormat::Wav => "WAV",
+            AudioUploadFormat::Flac => "FLAC",
+            AudioUploadFormat::OggOpus => "Ogg Opus",
+        }
+    }
+}
+
+/// 本地 Whisper 配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct LocalWhisperConfig {
+    /// ggml 模型文件路径（如 ggml-small.bin）
+    #[serde(default)]
+    pub model_path: String,
+    /// 推理线程数，0 表示自动
+    #[serde(default)]
+    pub threads: u32,
+}
+
+/// OpenAI 兼容转写服务实例（faster-whisper / LocalAI / Groq / OpenAI 等）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct OpenAiAsrProvider {
+    pub id: String,
+    pub name: String,
+    /// 基础 URL 或完整的 `/audio/transcriptions` 地址
+    pub endpoint: String,
+    #[serde(default)]
+    pub api_key: String,
+    pub model: String,
+    /// 语言代码（如 zh、en），留空则跟随 language_mode
+    #[serde(default)]
+    pub language: String,
+    /// 基础提示词，词库词条会追加在其后
+    #[serde(default)]
+    pub prompt: String,
+    /// json / verbose_json / text
+    #[serde(default = "default_transcription_response_format")]
+    pub response_format: String,
+    /// 上传音频格式，默认 WAV
+    #[serde(default)]
+    pub upload_format: AudioUploadFormat,
+}
+
+fn default_transcription_response_format() -> String {
+    "json".to_string()
+}
+
+/// 自建 WebSocket 流式服务的协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WebSocketAsrProtocol {
+    /// FunASR runtime 2pass：流式出字，每句结束后用离线模型（带标点）纠正
+    #[default]
+    #[serde(rename = "funasr_2pass")]
+    FunAsr2Pass,
+    /// FunASR runtime 纯流式（online）
+    #[serde(rename = "funasr_online")]
+    FunAsrOnline,
+    /// sherpa-onnx online websocket server
+    SherpaOnnx,
+    /// Vosk server
+    Vosk,
+}
+
+impl WebSocketAsrProtocol {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WebSocketAsrProtocol::FunAsr2Pass => "FunASR 2pass",
+            WebSocketAsrProtocol::FunAsrOnline => "FunASR online",
+            WebSocketAsrProtocol::SherpaOnnx => "sherpa-onnx",
+            WebSocketAsrProtocol::Vosk => "Vosk",
+        }
+    }
+}
+
+/// 自建 WebSocket 流式识别服务实例（FunASR runtime / sherpa-onnx / Vosk）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WebSocketAsrProvider {
+    pub id: String,
+    pub name: String,
+    /// 服务地址，如 `ws://127.0.0.1:10095`
+    pub endpoint: String,
+    #[serde(default)]
+    pub protocol: WebSocketAsrProtocol,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AsrConfig {
     pub credentials: AsrCredentials,
     pub selection: AsrSelection,
     #[serde(default)]
     pub language_mode: AsrLanguageMode,
+    #[serde(default)]
+    pub local_whisper: LocalWhisperConfig,
+    #[serde(default)]
+    pub openai_providers: Vec<OpenAiAsrProvider>,
+    /// 内置 HTTP 提供商单独开启的上传格式，未列出的提供商上传 WAV
+    #[serde(default)]
+    pub upload_formats: HashMap<AsrProvider, AudioUploadFormat>,
+    #[serde(default)]
+    pub websocket_providers: Vec<WebSocketAsrProvider>,
 }
 
 impl Default for AsrConfig {
@@ -549,6 +909,268 @@ impl Default for AsrConfig {
             credentials: AsrCredentials::default(),
             selection: AsrSelection::default(),
             language_mode: AsrLanguageMode::Auto,
+            local_whisper: LocalWhisperConfig::default(),
+            openai_providers: Vec::new(),
+            upload_formats: HashMap::new(),
+            websocket_providers: Vec::new(),
+        }
+    }
+}
+
+impl AsrConfig {
+    /// 按 id 查找 OpenAI 兼容转写实例
+    pub fn openai_provider(&self, id: &str) -> Option<&OpenAiAsrProvider> {
+        self.openai_providers.iter().find(|p| p.id == id)
+    }
+
+    /// 按 id 查找自建 WebSocket 流式服务实例
+    pub fn websocket_provider(&self, id: &str) -> Option<&WebSocketAsrProvider> {
+        self.websocket_providers.iter().find(|p| p.id == id)
+    }
+
+    /// 提供商选择的上传格式（未单独开启时为 WAV），实际格式由 `asr::resolve_upload_format` 按能力降级
+    pub fn upload_format(&self, provider: &AsrProvider) -> AudioUploadFormat {
+        match provider {
+            AsrProvider::OpenAiCompatible(id) => self
+                .openai_provider(id)
+                .map(|p| p.upload_format)
+                .unwrap_or_default(),
+            _ => self
+                .upload_formats
+                .get(provider)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 语音活动检测（VAD）引擎
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VadEngine {
+    /// 固定 RMS 能量阈值（默认）
+    #[default]
+    Rms,
+    /// WebRTC 风格的子带高斯混合模型，自适应底噪（需手动开启）
+    Gmm,
+}
+
+/// 语音活动检测配置：用于实时模式丢弃静音块、短录音误触检测和长录音切分
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VadConfig {
+    #[serde(default)]
+    pub engine: VadEngine,
+    /// 激进程度 0~3，越高越不容易把噪声判为语音（仅 GMM）
+    #[serde(default = "default_vad_aggressiveness")]
+    pub aggressiveness: u8,
+}
+
+fn default_vad_aggressiveness() -> u8 {
+    1
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            engine: VadEngine::default(),
+            aggressiveness: default_vad_aggressiveness(),
+        }
+    }
+}
+
+/// 免提自动结束配置（切换模式与松手锁定模式）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutoStopConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 说话后持续静音多久自动结束（毫秒）
+    #[serde(default = "default_auto_stop_silence_ms")]
+    pub silence_ms: u32,
+    /// 单次录音最长时长（秒），0 表示不限制
+    #[serde(default = "default_auto_stop_max_duration_secs")]
+    pub max_duration_secs: u32,
+}
+
+fn default_auto_stop_silence_ms() -> u32 {
+    2000
+}
+
+fn default_auto_stop_max_duration_secs() -> u32 {
+    300
+}
+
+impl Default for AutoStopConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            silence_ms: default_auto_stop_silence_ms(),
+            max_duration_secs: default_auto_stop_max_duration_secs(),
+        }
+    }
+}
+
+/// 开放麦克风配置：无需热键，检测到持续语音自动开始听写，静音后自动结束
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpenMicConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 连续语音多久后开始听写（毫秒）
+    #[serde(default = "default_open_mic_trigger_ms")]
+    pub trigger_ms: u32,
+    /// 说话后持续静音多久结束听写（毫秒）
+    #[serde(default = "default_open_mic_silence_ms")]
+    pub silence_ms: u32,
+    /// 单次听写最长时长（秒）
+    #[serde(default = "default_open_mic_max_duration_secs")]
+    pub max_duration_secs: u32,
+}
+
+fn default_open_mic_trigger_ms() -> u32 {
+    400
+}
+
+fn default_open_mic_silence_ms() -> u32 {
+    1500
+}
+
+fn default_open_mic_max_duration_secs() -> u32 {
+    60
+}
+
+impl Default for OpenMicConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trigger_ms: default_open_mic_trigger_ms(),
+            silence_ms: default_open_mic_silence_ms(),
+            max_duration_secs: default_open_mic_max_duration_secs(),
+        }
+    }
+}
+
+impl OpenMicConfig {
+    /// 开放麦克风会话的自动结束条件（不受免提自动结束开关影响）
+    pub fn auto_stop(&self) -> AutoStopConfig {
+        AutoStopConfig {
+            enabled: true,
+            silence_ms: self.silence_ms,
+            max_duration_secs: self.max_duration_secs,
+        }
+    }
+}
+
+/// 唤醒词：检测到后开始对应模式的会话
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WakeWord {
+    pub id: String,
+    /// 唤醒词文本（用于从转写结果开头去掉唤醒词）
+    pub phrase: String,
+    pub trigger_mode: TriggerMode,
+    /// 录制的样本文件名（位于配置目录的 wake_words 子目录）
+    #[serde(default)]
+    pub samples: Vec<String>,
+}
+
+/// 唤醒词配置：本地关键词检测，与热键并存
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WakeWordConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 灵敏度（0~1，越高越容易唤醒，也越容易误唤醒）
+    #[serde(default = "default_wake_word_sensitivity")]
+    pub sensitivity: f32,
+    #[serde(default)]
+    pub words: Vec<WakeWord>,
+}
+
+fn default_wake_word_sensitivity() -> f32 {
+    0.5
+}
+
+impl Default for WakeWordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitivity: default_wake_word_sensitivity(),
+            words: Vec::new(),
+        }
+    }
+}
+
+/// 预录缓冲配置：快捷键服务运行时录音流常开，保留最近一段音频补在每次录音开头，
+/// 避免蓝牙耳机等设备打开录音流较慢时丢掉第一个字
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PrerollConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 预录时长（毫秒）
+    #[serde(default = "default_preroll_duration_ms")]
+    pub duration_ms: u32,
+}
+
+fn default_preroll_duration_ms() -> u32 {
+    300
+}
+
+impl Default for PrerollConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_ms: default_preroll_duration_ms(),
+        }
+    }
+}
+
+/// 录音归档配置：每次录音以 FLAC 保存到本地，可稍后换提供商 / 预设重新转写
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordingArchiveConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 保留天数（0 表示不按时间清理）
+    #[serde(default = "default_archive_max_age_days")]
+    pub max_age_days: u32,
+    /// 归档总大小上限（MB，0 表示不限），超出时从最旧的录音开始删除
+    #[serde(default = "default_archive_max_total_mb")]
+    pub max_total_mb: u32,
+}
+
+fn default_archive_max_age_days() -> u32 {
+    30
+}
+
+fn default_archive_max_total_mb() -> u32 {
+    500
+}
+
+impl Default for RecordingArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_days: default_archive_max_age_days(),
+            max_total_mb: default_archive_max_total_mb(),
+        }
+    }
+}
+
+/// 降噪配置：在 AGC 之前用 RNNoise 抑制背景噪声
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoiseSuppressionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 降噪强度 0~1：1 为完全使用降噪输出，较低时混入原始信号以保留语音细节
+    #[serde(default = "default_noise_suppression_strength")]
+    pub strength: f32,
+}
+
+fn default_noise_suppression_strength() -> f32 {
+    1.0
+}
+
+impl Default for NoiseSuppressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: default_noise_suppression_strength(),
         }
     }
 }
@@ -597,6 +1219,30 @@ pub struct AppConfig {
     /// 录音时自动静音其他应用
     #[serde(default)]
     pub enable_mute_other_apps: bool,
+    /// 首选录音输入设备（按名称，依次回退；为空或均不可用时使用系统默认设备）
+    #[serde(default)]
+    pub preferred_input_devices: Vec<String>,
+    /// 语音活动检测配置
+    #[serde(default)]
+    pub vad_config: VadConfig,
+    /// 降噪
+    #[serde(default)]
+    pub noise_suppression: NoiseSuppressionConfig,
+    /// 免提自动结束
+    #[serde(default)]
+    pub auto_stop: AutoStopConfig,
+    /// 开放麦克风
+    #[serde(default)]
+    pub open_mic: OpenMicConfig,
+    /// 唤醒词
+    #[serde(default)]
+    pub wake_word: WakeWordConfig,
+    /// 录音归档
+    #[serde(default)]
+    pub recording_archive: RecordingArchiveConfig,
+    /// 预录缓冲
+    #[serde(default)]
+    pub preroll: PrerollConfig,
     /// 个人词典（热词列表）- 简化格式："word" 或 "word|auto"
     #[serde(default)]
     pub dictionary: Vec<String>,
@@ -1017,6 +1663,27 @@ pub fn normalize_chat_completions_endpoint(endpoint: &str) -> String {
     format!("{}/chat/completions", e)
 }
 
+/// Normalize OpenAI-compatible audio transcriptions endpoint.
+///
+/// Accepts either a base URL (e.g. http://localhost:8000/v1) or the full
+/// `/audio/transcriptions` endpoint, mirroring `normalize_chat_completions_endpoint`.
+pub fn normalize_transcriptions_endpoint(endpoint: &str) -> String {
+    let mut e = endpoint.trim().to_string();
+    if e.is_empty() {
+        return e;
+    }
+
+    while e.ends_with('/') {
+        e.pop();
+    }
+
+    if e.ends_with("/audio/transcriptions") {
+        return e;
+    }
+
+    format!("{}/audio/transcriptions", e)
+}
+
 fn default_llm_model() -> String {
     "glm-4-flash-250414".to_string()
 }
@@ -1224,6 +1891,14 @@ impl AppConfig {
             dual_hotkey_config: DualHotkeyConfig::default(),
             transcription_mode: TranscriptionMode::default(),
             enable_mute_other_apps: false,
+            preferred_input_devices: Vec::new(),
+            vad_config: VadConfig::default(),
+            noise_suppression: NoiseSuppressionConfig::default(),
+            auto_stop: AutoStopConfig::default(),
+            open_mic: OpenMicConfig::default(),
+            wake_word: WakeWordConfig::default(),
+            recording_archive: RecordingArchiveConfig::default(),
+            preroll: PrerollConfig::default(),
             dictionary: Vec::new(),
             builtin_dictionary_domains: Vec::new(),
             theme: default_theme(),
@@ -1775,10 +2450,175 @@ impl AppConfig {
 
 #[cfg(test)]
 mod tests {
-    use super::{AsrConfig, AsrLanguageMode};
+    use super::{
+        normalize_transcriptions_endpoint, AsrConfig, AsrLanguageMode, AsrProvider, AsrRacePolicy,
+        AsrSelection, AudioUploadFormat, DualHotkeyConfig, HotkeyKey, OpenAiAsrProvider, VadConfig,
+        VadEngine,
+    };
 
     #[test]
     fn asr_config_defaults_to_auto_language_mode() {
         assert_eq!(AsrConfig::default().language_mode, AsrLanguageMode::Auto);
     }
+
+    #[test]
+    fn vad_defaults_to_rms_and_gmm_is_opt_in() {
+        assert_eq!(VadConfig::default().engine, VadEngine::Rms);
+        let config: VadConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.engine, VadEngine::Rms);
+        let config: VadConfig = serde_json::from_str(r#"{"engine":"gmm"}"#).unwrap();
+        assert_eq!(config.engine, VadEngine::Gmm);
+    }
+
+    #[test]
+    fn upload_format_is_opt_in_per_provider() {
+        let config: AsrConfig = serde_json::from_str(
+            r#"{
+                "credentials": {},
+                "selection": {"active_provider": "qwen", "enable_fallback": false, "fallback_provider": null},
+                "upload_formats": {"qwen": "flac"},
+                "openai_providers": [
+                    {"id": "groq", "name": "Groq", "endpoint": "https://api.groq.com/openai/v1", "model": "whisper-large-v3", "upload_format": "ogg_opus"},
+                    {"id": "local", "name": "Local", "endpoint": "http://127.0.0.1:8000/v1", "model": "large-v3"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.upload_format(&AsrProvider::Qwen),
+            AudioUploadFormat::Flac
+        );
+        assert_eq!(
+            config.upload_format(&AsrProvider::Doubao),
+            AudioUploadFormat::Wav
+        );
+        assert_eq!(
+            config.upload_format(&AsrProvider::OpenAiCompatible("groq".to_string())),
+            AudioUploadFormat::OggOpus
+        );
+        assert_eq!(
+            config.upload_format(&AsrProvider::OpenAiCompatible("local".to_string())),
+            AudioUploadFormat::Wav
+        );
+        assert_eq!(
+            OpenAiAsrProvider::default().upload_format,
+            AudioUploadFormat::Wav
+        );
+    }
+
+    #[test]
+    fn language_mode_keys_match_serde() {
+        for mode in AsrLanguageMode::ALL {
+            let json = serde_json::to_string(&mode).unwrap();
+            assert_eq!(json, format!("\"{}\"", mode.config_key()));
+            assert_eq!(
+                AsrLanguageMode::from_config_key(mode.config_key()),
+                Some(mode)
+            );
+        }
+        let mode: AsrLanguageMode = serde_json::from_str(r#""zh_en""#).unwrap();
+        assert_eq!(mode, AsrLanguageMode::ZhEn);
+    }
+
+    #[test]
+    fn language_switch_keys_must_not_overlap_recording_hotkeys() {
+        let mut config = DualHotkeyConfig {
+            language_switch_keys: Some(vec![HotkeyKey::F4]),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        // 默认听写快捷键为 Ctrl+Win，Ctrl+Win+Shift 是其超集
+        config.language_switch_keys = Some(vec![
+            HotkeyKey::ControlLeft,
+            HotkeyKey::MetaLeft,
+            HotkeyKey::ShiftLeft,
+        ]);
+        assert!(config.validate().is_err());
+
+        config.language_switch_keys = Some(vec![HotkeyKey::F2]);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn legacy_selection_deserializes_with_default_strategy() {
+        let selection: AsrSelection = serde_json::from_str(
+            r#"{"active_provider":"qwen","enable_fallback":true,"fallback_provider":"siliconflow"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(selection.strategy.policy, AsrRacePolicy::Race);
+        assert_eq!(
+            selection.provider_order(),
+            vec![AsrProvider::Qwen, AsrProvider::SiliconFlow]
+        );
+    }
+
+    #[test]
+    fn provider_order_puts_active_first_and_dedups() {
+        let selection = AsrSelection {
+            active_provider: AsrProvider::Doubao,
+            enable_fallback: true,
+            fallback_provider: Some(AsrProvider::SiliconFlow),
+            providers: vec![
+                AsrProvider::Qwen,
+                AsrProvider::Doubao,
+                AsrProvider::SiliconFlow,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            selection.provider_order(),
+            vec![
+                AsrProvider::Doubao,
+                AsrProvider::Qwen,
+                AsrProvider::SiliconFlow
+            ]
+        );
+
+        let disabled = AsrSelection {
+            enable_fallback: false,
+            ..selection
+        };
+        assert_eq!(disabled.provider_order(), vec![AsrProvider::Doubao]);
+    }
+
+    #[test]
+    fn asr_provider_serializes_builtin_names_and_custom_instances() {
+        let providers = vec![
+            AsrProvider::DoubaoIme,
+            AsrProvider::SiliconFlow,
+            AsrProvider::OpenAiCompatible("groq".to_string()),
+            AsrProvider::WebSocket("funasr".to_string()),
+        ];
+        let json = serde_json::to_string(&providers).unwrap();
+        assert_eq!(
+            json,
+            r#"["doubao_ime","siliconflow","openai:groq","ws:funasr"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<AsrProvider>>(&json).unwrap(),
+            providers
+        );
+
+        assert!(serde_json::from_str::<AsrProvider>(r#""openai:""#).is_err());
+        assert!(serde_json::from_str::<AsrProvider>(r#""ws:""#).is_err());
+        assert!(serde_json::from_str::<AsrProvider>(r#""unknown""#).is_err());
+    }
+
+    #[test]
+    fn transcriptions_endpoint_accepts_base_or_full_url() {
+        assert_eq!(
+            normalize_transcriptions_endpoint(" http://localhost:8000/v1/ "),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            normalize_transcriptions_endpoint(
+                "https://api.groq.com/openai/v1/audio/transcriptions"
+            ),
+            "https://api.groq.com/openai/v1/audio/transcriptions"
+        );
+        assert_eq!(normalize_transcriptions_endpoint("  "), "");
+    }
 }
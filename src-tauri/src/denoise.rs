@@ -0,0 +1,204 @@
+// 降噪模块
+// 基于 RNNoise（nnnoiseless，纯 Rust 实现）抑制空调、风扇、键盘与远处人声等背景噪声。
+// 位于采集链路的重采样之后、AGC 之前，避免 AGC 把噪声一起放大；
+// 实时音频块与备用 WAV 都经过同一处理。
+// 需启用 `noise-suppression` feature；未启用时降噪设置不生效，音频原样通过。
+
+#[cfg(feature = "noise-suppression")]
+use nnnoiseless::DenoiseState;
+
+#[cfg(feature = "noise-suppression")]
+use crate::audio_utils::Resampler;
+use crate::config::NoiseSuppressionConfig;
+
+/// RNNoise 模型的工作采样率
+#[cfg(feature = "noise-suppression")]
+const DENOISE_SAMPLE_RATE: u32 = 48000;
+/// RNNoise 每帧样本数（10ms @ 48kHz），输出相对输入延迟一帧
+#[cfg(feature = "noise-suppression")]
+const FRAME_SIZE: usize = DenoiseState::FRAME_SIZE;
+/// RNNoise 以 i16 幅度范围处理 f32 样本
+#[cfg(feature = "noise-suppression")]
+const PCM_SCALE: f32 = i16::MAX as f32;
+
+/// 流式降噪器
+///
+/// 输入输出均为采集引擎的统一采样率；内部升采样到 48kHz 逐帧降噪后再降采样回来。
+/// 强度小于 1 时按比例混入（与降噪输出对齐的）原始信号，保留更多语音细节。
+/// 冲刷后总输出长度与总输入长度一致
+#[cfg(feature = "noise-suppression")]
+pub struct NoiseSuppressor {
+    state: Box<DenoiseState<'static>>,
+    strength: f32,
+    upsampler: Resampler,
+    downsampler: Resampler,
+    /// 待凑满一帧的 48kHz 样本
+    pending: Vec<f32>,
+    /// 上一帧原始信号，用于与延迟一帧的降噪输出对齐混合
+    previous_dry: Vec<f32>,
+    output: Vec<f32>,
+    input_len: usize,
+    output_len: usize,
+    /// 降噪输出的首帧是延迟填充，需要丢弃的 48kHz 样本数
+    skip: usize,
+}
+
+#[cfg(feature = "noise-suppression")]
+impl NoiseSuppressor {
+    /// 按配置创建降噪器，未启用时返回 None
+    pub fn from_config(config: &NoiseSuppressionConfig, sample_rate: u32) -> Option<Self> {
+        if !config.enabled || config.strength <= 0.0 {
+            return None;
+        }
+        tracing::info!("启用降噪: 强度 {:.0}%", config.strength.min(1.0) * 100.0);
+        Some(Self::new(config.strength, sample_rate))
+    }
+
+    pub fn new(strength: f32, sample_rate: u32) -> Self {
+        Self {
+            state: DenoiseState::new(),
+            strength: strength.clamp(0.0, 1.0),
+            upsampler: Resampler::new(sample_rate, DENOISE_SAMPLE_RATE),
+            downsampler: Resampler::new(DENOISE_SAMPLE_RATE, sample_rate),
+            pending: Vec::with_capacity(FRAME_SIZE * 2),
+            previous_dry: vec![0.0; FRAME_SIZE],
+            output: vec![0.0; FRAME_SIZE],
+            input_len: 0,
+            output_len: 0,
+            skip: FRAME_SIZE,
+        }
+    }
+
+    /// 处理一块音频，返回已完成降噪的部分（相对输入约有 10ms 延迟）
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.input_len += input.len();
+        let upsampled = self.upsampler.process(input);
+        let denoised = self.denoise(&upsampled);
+        let output = self.downsampler.process(&denoised);
+        self.output_len += output.len();
+        output
+    }
+
+    /// 录音结束：补零推出延迟中的样本，返回剩余输出
+    pub fn flush(&mut self) -> Vec<f32> {
+        let mut tail = self.upsampler.flush();
+        // 补齐最后一帧，再多送一帧把延迟中的样本推出来
+        let padding = (FRAME_SIZE - (self.pending.len() + tail.len()) % FRAME_SIZE) % FRAME_SIZE;
+        tail.extend(std::iter::repeat_n(0.0, padding + FRAME_SIZE));
+
+        let denoised = self.denoise(&tail);
+        let mut output = self.downsampler.process(&denoised);
+        output.extend(self.downsampler.flush());
+
+        output.truncate(self.input_len.saturating_sub(self.output_len));
+        self.output_len += output.len();
+        output
+    }
+
+    /// 逐帧降噪 48kHz 样本，不足一帧的部分留到下次
+    fn denoise(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.pending.extend_from_slice(samples);
+        let frames = self.pending.len() / FRAME_SIZE;
+        let mut denoised = Vec::with_capacity(frames * FRAME_SIZE);
+
+        for frame in self.pending[..frames * FRAME_SIZE].chunks_exact(FRAME_SIZE) {
+            let input: Vec<f32> = frame.iter().map(|s| s * PCM_SCALE).collect();
+            self.state.process_frame(&mut self.output, &input);
+
+            let start = self.skip.min(FRAME_SIZE);
+            self.skip -= start;
+            denoised.extend(
+                self.output[start..]
+                    .iter()
+                    .zip(&self.previous_dry[start..])
+                    .map(|(wet, dry)| {
+                        (self.strength * wet + (1.0 - self.strength) * dry) / PCM_SCALE
+                    }),
+            );
+            self.previous_dry.copy_from_slice(&input);
+        }
+
+        self.pending.drain(..frames * FRAME_SIZE);
+        denoised
+    }
+}
+
+// ==================== 功能未启用时的存根实现 ====================
+
+#[cfg(not(feature = "noise-suppression"))]
+pub struct NoiseSuppressor;
+
+#[cfg(not(feature = "noise-suppression"))]
+impl NoiseSuppressor {
+    /// 降噪功能未编译，始终返回 None
+    pub fn from_config(config: &NoiseSuppressionConfig, _sample_rate: u32) -> Option<Self> {
+        if config.enabled && config.strength > 0.0 {
+            tracing::warn!("降噪功能未启用，请使用 `--features noise-suppression` 编译");
+        }
+        None
+    }
+
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        input.to_vec()
+    }
+
+    pub fn flush(&mut self) -> Vec<f32> {
+        Vec::new()
+    }
+}
+
+#[cfg(all(test, feature = "noise-suppression"))]
+mod tests {
+    use super::*;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt()
+    }
+
+    /// 确定性的伪随机白噪声（模拟空调、风扇声）
+    fn white_noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut seed: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn run(suppressor: &mut NoiseSuppressor, input: &[f32]) -> Vec<f32> {
+        let mut output = Vec::new();
+        for chunk in input.chunks(441) {
+            output.extend(suppressor.process(chunk));
+        }
+        output.extend(suppressor.flush());
+        output
+    }
+
+    #[test]
+    fn attenuates_steady_noise_and_keeps_length() {
+        let noise = white_noise(16000 * 3, 0.05);
+        let output = run(&mut NoiseSuppressor::new(1.0, 16000), &noise);
+
+        assert_eq!(output.len(), noise.len());
+        // 跳过模型收敛阶段后比较
+        let settled = 16000..noise.len();
+        assert!(
+            rms(&output[settled.clone()]) < rms(&noise[settled]) * 0.5,
+            "稳态噪声应被明显抑制"
+        );
+    }
+
+    #[test]
+    fn zero_strength_passes_signal_through() {
+        let tone: Vec<f32> = (0..16000)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let output = run(&mut NoiseSuppressor::new(0.0, 16000), &tone);
+
+        assert_eq!(output.len(), tone.len());
+        // 与原始信号对齐（仅有重采样误差），而不是错位一帧
+        let error: Vec<f32> = output.iter().zip(&tone).map(|(a, b)| a - b).collect();
+        assert!(rms(&error[800..15200]) < 0.01);
+    }
+}
@@ -5,6 +5,125 @@
 
 use std::collections::HashSet;
 
+/// 词条权重范围（1~10），权重越高越优先进入提供商的热词上限
+pub const MIN_WEIGHT: u8 = 1;
+pub const MAX_WEIGHT: u8 = 10;
+/// 未显式设置权重时的默认值：手动添加的词条高于自动学习的词条
+pub const DEFAULT_MANUAL_WEIGHT: u8 = 5;
+pub const DEFAULT_AUTO_WEIGHT: u8 = 3;
+
+/// 词条作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DictionaryScope {
+    /// ASR 热词与 LLM 词库增强都使用
+    #[default]
+    All,
+    /// 仅作为 ASR 热词
+    Asr,
+    /// 仅用于 LLM 词库增强
+    Llm,
+}
+
+impl DictionaryScope {
+    /// 前端传入的作用范围名称（"all" / "asr" / "llm"），无法识别时视为 All
+    pub fn from_name(name: &str) -> Self {
+        match name.trim() {
+            "asr" => Self::Asr,
+            "llm" => Self::Llm,
+            _ => Self::All,
+        }
+    }
+}
+
+/// 解析后的词条
+///
+/// 存储格式为 `word[|auto][|w=N][|asr|llm]`，附加字段均可省略，
+/// 与旧格式 "word" / "word|auto" 兼容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryEntry {
+    pub word: String,
+    pub auto: bool,
+    /// 显式设置的权重（None 时按来源取默认值）
+    pub weight: Option<u8>,
+    pub scope: DictionaryScope,
+}
+
+impl DictionaryEntry {
+    pub fn parse(entry: &str) -> Self {
+        let mut fields = entry.split('|');
+        let word = normalize_word(fields.next().unwrap_or_default());
+        let mut parsed = Self {
+            word,
+            auto: false,
+            weight: None,
+            scope: DictionaryScope::All,
+        };
+        for field in fields.map(str::trim) {
+            match field {
+                "auto" => parsed.auto = true,
+                "asr" => parsed.scope = DictionaryScope::Asr,
+                "llm" => parsed.scope = DictionaryScope::Llm,
+                _ => {
+                    if let Some(weight) = field
+                        .strip_prefix("w=")
+                        .and_then(|w| w.trim().parse::<u8>().ok())
+                    {
+                        parsed.weight = Some(weight.clamp(MIN_WEIGHT, MAX_WEIGHT));
+                    }
+                }
+            }
+        }
+        parsed
+    }
+
+    pub fn to_entry_string(&self) -> String {
+        let mut entry = self.word.clone();
+        if self.auto {
+            entry.push_str("|auto");
+        }
+        if let Some(weight) = self.weight {
+            entry.push_str(&format!("|w={}", weight));
+        }
+        match self.scope {
+            DictionaryScope::All => {}
+            DictionaryScope::Asr => entry.push_str("|asr"),
+            DictionaryScope::Llm => entry.push_str("|llm"),
+        }
+        entry
+    }
+
+    pub fn effective_weight(&self) -> u8 {
+        self.weight.unwrap_or(if self.auto {
+            DEFAULT_AUTO_WEIGHT
+        } else {
+            DEFAULT_MANUAL_WEIGHT
+        })
+    }
+
+    pub fn applies_to_asr(&self) -> bool {
+        self.scope != DictionaryScope::Llm
+    }
+
+    pub fn applies_to_llm(&self) -> bool {
+        self.scope != DictionaryScope::Asr
+    }
+}
+
+/// 带权重的 ASR 热词
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotword {
+    pub word: String,
+    pub weight: u8,
+}
+
+/// 提供商的热词容量上限（None 表示不限制）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HotwordLimit {
+    pub max_words: Option<usize>,
+    /// 词条字符数之和（每个词额外计 1 个分隔符）
+    pub max_chars: Option<usize>,
+}
+
 /// 标准化词汇（去除首尾空格）
 pub fn normalize_word(word: &str) -> String {
     word.trim().to_string()
@@ -31,7 +150,7 @@ pub fn extract_word(entry: &str) -> &str {
 /// 插入或更新词条（去重）
 ///
 /// 如果词汇已存在：
-/// - 如果新来源是 manual，则更新为 manual（优先级更高）
+/// - 如果新来源是 manual，则更新为 manual（优先级更高），保留权重与作用范围
 /// - 如果新来源是 auto，保持原来源不变
 pub fn upsert_entry(entries: &mut Vec<String>, word: &str, source: &str) {
     let normalized = normalize_word(word);
@@ -41,9 +160,11 @@ pub fn upsert_entry(entries: &mut Vec<String>, word: &str, source: &str) {
 
     // 检查是否已存在
     if let Some(existing) = entries.iter_mut().find(|e| extract_word(e) == normalized) {
-        // 已存在，如果是手动添加，更新为 manual（去除 |auto 后缀）
+        // 已存在，如果是手动添加，更新为 manual（去除 |auto 标记）
         if source == "manual" {
-            *existing = normalized.clone();
+            let mut entry = DictionaryEntry::parse(existing);
+            entry.auto = false;
+            *existing = entry.to_entry_string();
         }
         return;
     }
@@ -52,6 +173,26 @@ pub fn upsert_entry(entries: &mut Vec<String>, word: &str, source: &str) {
     entries.push(format_entry(&normalized, source));
 }
 
+/// 更新词条的权重与作用范围（按 word 匹配），词条不存在时返回 false
+///
+/// `weight` 为 None 表示恢复按来源取默认权重
+pub fn update_entry_attributes(
+    entries: &mut [String],
+    word: &str,
+    weight: Option<u8>,
+    scope: DictionaryScope,
+) -> bool {
+    let normalized = normalize_word(word);
+    let Some(existing) = entries.iter_mut().find(|e| extract_word(e) == normalized) else {
+        return false;
+    };
+    let mut entry = DictionaryEntry::parse(existing);
+    entry.weight = weight.map(|w| w.clamp(MIN_WEIGHT, MAX_WEIGHT));
+    entry.scope = scope;
+    *existing = entry.to_entry_string();
+    true
+}
+
 /// 删除指定词汇（按 word 匹配，不区分来源）
 pub fn remove_entries(entries: &mut Vec<String>, words: &[String]) {
     let words_set: HashSet<&str> = words.iter().map(|s| s.as_str()).collect();
@@ -71,6 +212,52 @@ pub fn entries_to_words(entries: &[String]) -> Vec<String> {
         .collect()
 }
 
+/// 按权重排序的词条（去重，同权重保持原顺序）
+fn ranked_entries(
+    entries: &[String],
+    applies: impl Fn(&DictionaryEntry) -> bool,
+) -> Vec<DictionaryEntry> {
+    let mut seen = HashSet::new();
+    let mut ranked: Vec<DictionaryEntry> = entries
+        .iter()
+        .map(|e| DictionaryEntry::parse(e))
+        .filter(|e| !e.word.is_empty() && applies(e))
+        .filter(|e| seen.insert(e.word.clone()))
+        .collect();
+    ranked.sort_by_key(|e| std::cmp::Reverse(e.effective_weight()));
+    ranked
+}
+
+/// 提取 ASR 热词：过滤仅用于 LLM 的词条，按权重从高到低排列，
+/// 超出提供商上限时从低权重一侧截断
+pub fn asr_hotwords(entries: &[String], limit: HotwordLimit) -> Vec<Hotword> {
+    let mut hotwords = Vec::new();
+    let mut used_chars = 0usize;
+    for entry in ranked_entries(entries, DictionaryEntry::applies_to_asr) {
+        if limit.max_words.is_some_and(|max| hotwords.len() >= max) {
+            break;
+        }
+        let chars = entry.word.chars().count() + 1;
+        if limit.max_chars.is_some_and(|max| used_chars + chars > max) {
+            break;
+        }
+        used_chars += chars;
+        hotwords.push(Hotword {
+            weight: entry.effective_weight(),
+            word: entry.word,
+        });
+    }
+    hotwords
+}
+
+/// 提取 LLM 词库增强使用的纯词汇（按权重从高到低排列）
+pub fn llm_words(entries: &[String]) -> Vec<String> {
+    ranked_entries(entries, DictionaryEntry::applies_to_llm)
+        .into_iter()
+        .map(|e| e.word)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +322,109 @@ mod tests {
         let words = entries_to_words(&entries);
         assert_eq!(words, vec!["claude", "rust", "python"]);
     }
+
+    #[test]
+    fn test_dictionary_entry_round_trip() {
+        let entry = DictionaryEntry::parse("Kubernetes|auto|w=8|asr");
+        assert_eq!(entry.word, "Kubernetes");
+        assert!(entry.auto);
+        assert_eq!(entry.weight, Some(8));
+        assert_eq!(entry.scope, DictionaryScope::Asr);
+        assert_eq!(entry.to_entry_string(), "Kubernetes|auto|w=8|asr");
+
+        // 旧格式保持不变
+        assert_eq!(
+            DictionaryEntry::parse("rust|auto").to_entry_string(),
+            "rust|auto"
+        );
+        assert_eq!(
+            DictionaryEntry::parse("rust").effective_weight(),
+            DEFAULT_MANUAL_WEIGHT
+        );
+        assert_eq!(
+            DictionaryEntry::parse("rust|auto").effective_weight(),
+            DEFAULT_AUTO_WEIGHT
+        );
+        // 超出范围的权重被夹紧，无法识别的字段忽略
+        assert_eq!(
+            DictionaryEntry::parse("x|w=99|foo").weight,
+            Some(MAX_WEIGHT)
+        );
+    }
+
+    #[test]
+    fn test_upsert_entry_keeps_weight_and_scope() {
+        let mut entries = vec!["rust|auto|w=9|llm".to_string()];
+        upsert_entry(&mut entries, "rust", "manual");
+        assert_eq!(entries, vec!["rust|w=9|llm"]);
+
+        assert!(update_entry_attributes(
+            &mut entries,
+            "rust",
+            Some(0),
+            DictionaryScope::All
+        ));
+        assert_eq!(entries, vec!["rust|w=1"]);
+        assert!(update_entry_attributes(
+            &mut entries,
+            "rust",
+            None,
+            DictionaryScope::Asr
+        ));
+        assert_eq!(entries, vec!["rust|asr"]);
+        assert!(!update_entry_attributes(
+            &mut entries,
+            "go",
+            None,
+            DictionaryScope::All
+        ));
+    }
+
+    #[test]
+    fn test_asr_hotwords_ranked_and_truncated() {
+        let entries = vec![
+            "low|auto".to_string(),
+            "mid".to_string(),
+            "top|w=9".to_string(),
+            "prompt only|llm".to_string(),
+            "mid|auto".to_string(),
+            "also mid".to_string(),
+        ];
+
+        let words: Vec<String> = asr_hotwords(&entries, HotwordLimit::default())
+            .into_iter()
+            .map(|h| h.word)
+            .collect();
+        assert_eq!(words, vec!["top", "mid", "also mid", "low"]);
+
+        let capped = asr_hotwords(
+            &entries,
+            HotwordLimit {
+                max_words: Some(2),
+                max_chars: None,
+            },
+        );
+        assert_eq!(capped.len(), 2);
+        assert_eq!(
+            capped[0],
+            Hotword {
+                word: "top".into(),
+                weight: 9
+            }
+        );
+
+        let by_chars = asr_hotwords(
+            &entries,
+            HotwordLimit {
+                max_words: None,
+                max_chars: Some(8),
+            },
+        );
+        assert_eq!(by_chars.len(), 2);
+
+        assert_eq!(
+            llm_words(&entries),
+            vec!["top", "mid", "prompt only", "also mid", "low"]
+        );
+    }
 }
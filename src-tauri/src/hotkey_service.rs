@@ -183,6 +183,9 @@ struct HotkeyState {
 /// 第二个参数：bool - 是否为松手模式（true=松手模式，false=普通模式）
 type Callback = Arc<dyn Fn(TriggerMode, bool) + Send + Sync>;
 
+/// 无参数回调（语言切换等一次性动作）
+type ActionCallback = Arc<dyn Fn() + Send + Sync>;
+
 /// 单例热键服务（支持双模式）
 pub struct HotkeyService {
     /// 服务是否激活（控制是否响应热键事件）
@@ -198,6 +201,9 @@ pub struct HotkeyService {
     /// 回调函数（现在接收 TriggerMode 参数）
     on_start: Arc<RwLock<Option<Callback>>>,
     on_stop: Arc<RwLock<Option<Callback>>>,
+    /// 切换识别语言快捷键（未录音时按下触发）
+    language_switch_keys: Arc<RwLock<Option<Vec<HotkeyKey>>>>,
+    on_language_switch: Arc<RwLock<Option<ActionCallback>>>,
 }
 
 impl HotkeyService {
@@ -215,6 +221,8 @@ impl HotkeyService {
             listener_started: Arc::new(AtomicBool::new(false)),
             on_start: Arc::new(RwLock::new(None)),
             on_stop: Arc::new(RwLock::new(None)),
+            language_switch_keys: Arc::new(RwLock::new(None)),
+            on_language_switch: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -323,6 +331,8 @@ impl HotkeyService {
         let state = Arc::clone(&self.state);
         let on_start = Arc::clone(&self.on_start);
         let on_stop = Arc::clone(&self.on_stop);
+        let language_switch_keys = Arc::clone(&self.language_switch_keys);
+        let on_language_switch = Arc::clone(&self.on_language_switch);
 
         thread::spawn(move || {
             tracing::info!("快捷键监听线程已启动");
@@ -340,6 +350,7 @@ impl HotkeyService {
                 let mut prev_dictation_down = false;
                 let mut prev_assistant_down = false;
                 let mut prev_release_down = false;
+                let mut prev_language_down = false;
 
                 loop {
                     thread::sleep(Duration::from_millis(HOTKEY_POLL_INTERVAL_MS));
@@ -354,12 +365,18 @@ impl HotkeyService {
                         .as_deref()
                         .map(is_hotkey_pressed_strict)
                         .unwrap_or(false);
+                    let language_keys = language_switch_keys.read().unwrap().clone();
+                    let language_down = language_keys
+                        .as_deref()
+                        .map(is_hotkey_pressed_strict)
+                        .unwrap_or(false);
 
                     // 未激活时：同步边沿状态，避免激活瞬间误触发
                     if !is_active.load(Ordering::Relaxed) {
                         prev_dictation_down = dictation_down;
                         prev_assistant_down = assistant_down;
                         prev_release_down = release_down;
+                        prev_language_down = language_down;
                         continue;
                     }
 
@@ -368,6 +385,7 @@ impl HotkeyService {
                     let assistant_rise = assistant_down && !prev_assistant_down;
                     let assistant_fall = !assistant_down && prev_assistant_down;
                     let release_rise = release_down && !prev_release_down;
+                    let language_rise = language_down && !prev_language_down;
 
                     // 更新 pressed_keys（仅用于调试信息）
                     {
@@ -387,6 +405,11 @@ impl HotkeyService {
                                 keys_to_check.insert(key.clone());
                             }
                         }
+                        if let Some(ref keys) = language_keys {
+                            for key in keys.iter() {
+                                keys_to_check.insert(key.clone());
+                            }
+                        }
 
                         for key in keys_to_check.into_iter() {
                             if is_key_physically_down(&key) {
@@ -397,6 +420,7 @@ impl HotkeyService {
 
                     let mut start_action: Option<(TriggerMode, bool)> = None;
                     let mut stop_action: Option<(TriggerMode, bool)> = None;
+                    let mut language_action = false;
 
                     {
                         let mut s = state.lock().unwrap();
@@ -440,6 +464,9 @@ impl HotkeyService {
                                 s.is_release_mode_triggered = false;
                                 s.watchdog_running = false;
                                 start_action = Some((TriggerMode::AiAssistant, false));
+                            } else if language_rise {
+                                tracing::info!("检测到快捷键按下: 切换识别语言");
+                                language_action = true;
                             }
                         } else if !s.is_release_mode_triggered {
                             // 录音中：根据当前触发模式处理停止逻辑（Press=松手停止；Toggle=再次按下停止）
@@ -503,10 +530,16 @@ impl HotkeyService {
                             cb(mode, is_release_mode);
                         }
                     }
+                    if language_action {
+                        if let Some(cb) = on_language_switch.read().unwrap().as_ref() {
+                            cb();
+                        }
+                    }
 
                     prev_dictation_down = dictation_down;
                     prev_assistant_down = assistant_down;
                     prev_release_down = release_down;
+                    prev_language_down = language_down;
                 }
             }
 
@@ -522,6 +555,8 @@ impl HotkeyService {
                 let state_inner = Arc::clone(&state);
                 let on_start_inner = Arc::clone(&on_start);
                 let on_stop_inner = Arc::clone(&on_stop);
+                let language_switch_keys_inner = Arc::clone(&language_switch_keys);
+                let on_language_switch_inner = Arc::clone(&on_language_switch);
 
                 let callback = move |event: Event| {
                     // 检查服务是否激活
@@ -613,6 +648,26 @@ impl HotkeyService {
                                 }
 
                                 if !s.is_recording {
+                                    // 语言切换快捷键：严格匹配，与录音快捷键互斥（配置校验保证）
+                                    let matches_language = language_switch_keys_inner
+                                        .read()
+                                        .unwrap()
+                                        .as_ref()
+                                        .is_some_and(|keys| {
+                                            keys.iter().all(|k| s.pressed_keys.contains(k))
+                                                && s.pressed_keys.len() == keys.len()
+                                        });
+                                    if matches_language {
+                                        tracing::info!("检测到快捷键按下: 切换识别语言");
+                                        drop(s);
+                                        if let Some(cb) =
+                                            on_language_switch_inner.read().unwrap().as_ref()
+                                        {
+                                            cb();
+                                        }
+                                        return;
+                                    }
+
                                     // 确定触发模式（优先级：松手模式 > 普通听写 > AI助手）
                                     let (trigger_mode, is_release_mode) = if matches_release_mode {
                                         (Some(TriggerMode::Dictation), true)
@@ -909,6 +964,7 @@ impl HotkeyService {
         // 更新配置
         *self.dictation_config.write().unwrap() = config.dictation;
         *self.assistant_config.write().unwrap() = config.assistant;
+        *self.language_switch_keys.write().unwrap() = config.language_switch_keys;
 
         // 更新回调
         *self.on_start.write().unwrap() = Some(Arc::new(on_start));
@@ -932,6 +988,14 @@ impl HotkeyService {
         Ok(())
     }
 
+    /// 设置语言切换快捷键回调（未配置语言切换快捷键时不会触发）
+    pub fn set_language_switch_callback<F>(&self, callback: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self.on_language_switch.write().unwrap() = Some(Arc::new(callback));
+    }
+
     /// 停用服务（不终止线程）
     pub fn deactivate(&self) {
         tracing::info!("停用快捷键服务");
@@ -959,6 +1023,58 @@ impl HotkeyService {
         s.current_trigger_mode = None;
     }
 
+    /// 以代码方式开始录音（如开放麦克风），等同于用户按下开始快捷键（非松手模式）
+    ///
+    /// 服务未激活或已在录音时返回 false
+    pub fn request_start(&self, mode: TriggerMode) -> bool {
+        if !self.is_active.load(Ordering::SeqCst) {
+            return false;
+        }
+        {
+            let mut s = self.state.lock().unwrap();
+            if s.is_recording {
+                return false;
+            }
+            s.is_recording = true;
+            s.current_trigger_mode = Some(mode);
+            s.is_release_mode_triggered = false;
+            s.watchdog_running = false;
+        }
+
+        tracing::info!("请求开始录音: {:?}", mode);
+        if let Some(cb) = self.on_start.read().unwrap().as_ref() {
+            cb(mode, false);
+        }
+        true
+    }
+
+    /// 以代码方式结束当前录音（如免提自动结束），等同于用户按下结束快捷键
+    ///
+    /// 当前未在录音时返回 false
+    pub fn request_stop(&self) -> bool {
+        let stop_action = {
+            let mut s = self.state.lock().unwrap();
+            if !s.is_recording {
+                return false;
+            }
+            let mode = s
+                .current_trigger_mode
+                .take()
+                .unwrap_or(TriggerMode::Dictation);
+            let is_release_mode = s.is_release_mode_triggered;
+            s.is_recording = false;
+            s.watchdog_running = false;
+            s.is_release_mode_triggered = false;
+            (mode, is_release_mode)
+        };
+
+        tracing::info!("请求结束录音: {:?}", stop_action.0);
+        if let Some(cb) = self.on_stop.read().unwrap().as_ref() {
+            cb(stop_action.0, stop_action.1);
+        }
+        true
+    }
+
     /// 获取当前状态信息（用于调试）
     pub fn get_debug_info(&self) -> String {
         let s = self.state.lock().unwrap();
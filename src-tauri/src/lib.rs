@@ -3,32 +3,40 @@
 
 pub mod asr;
 mod assistant_processor;
+mod audio_capture;
+mod audio_device;
 mod audio_mute_manager;
 mod audio_recorder;
 mod audio_utils;
+mod auto_stop;
 mod beep_player;
 mod builtin_dictionary_updater;
 mod clipboard_manager;
 mod config;
+mod denoise;
 mod dictionary_utils;
 mod hotkey_service;
 mod learning;
 mod llm_post_processor;
+mod open_mic;
 mod openai_client;
 mod pipeline;
+mod recording_archive;
 mod streaming_recorder;
 mod text_inserter;
 mod tnl;
 mod uia_text_reader;
 mod usage_stats;
+mod vad;
+mod wake_word;
 mod win32_input;
 
 use asr::{
-    DoubaoASRClient, DoubaoImeCredentials, DoubaoImeRealtimeClient, DoubaoImeRealtimeSession,
-    DoubaoRealtimeClient, DoubaoRealtimeSession, QwenASRClient, QwenRealtimeClient,
-    RealtimeSession, SenseVoiceClient,
+    AsrEngineRegistry, AsrHealthTracker, ConnectionPrewarmer, DoubaoImeCredentialStore,
+    DoubaoImeRealtimeClient, DoubaoRealtimeClient, QwenRealtimeClient, RealtimeAsrSession,
 };
 use assistant_processor::AssistantProcessor;
+use audio_capture::CaptureSink;
 use audio_mute_manager::AudioMuteManager;
 use audio_recorder::AudioRecorder;
 use config::{AppConfig, CONFIG_LOCK};
@@ -44,7 +52,7 @@ use usage_stats::UsageStats;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tauri::{
-    menu::{CheckMenuItem, Menu, MenuItem, Submenu},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, WindowEvent,
 };
@@ -93,6 +101,9 @@ fn find_monitor_at_cursor(window: &tauri::WebviewWindow) -> Option<tauri::Monito
     window.primary_monitor().ok().flatten()
 }
 
+/// 当前活跃的实时转录会话（各提供商共用同一个槽位）
+type RealtimeSessionSlot = Arc<tokio::sync::Mutex<Option<Box<dyn RealtimeAsrSession>>>>;
+
 // 全局应用状态
 struct AppState {
     audio_recorder: Arc<Mutex<Option<AudioRecorder>>>,
@@ -106,16 +117,19 @@ struct AppState {
     enable_post_process: Arc<Mutex<bool>>,
     /// 语句润色：是否启用“词库增强”（将个人词库注入提示词）
     enable_dictionary_enhancement: Arc<Mutex<bool>>,
-    enable_fallback: Arc<Mutex<bool>>,
-    qwen_client: Arc<Mutex<Option<QwenASRClient>>>,
-    sensevoice_client: Arc<Mutex<Option<SenseVoiceClient>>>,
-    doubao_client: Arc<Mutex<Option<DoubaoASRClient>>>,
+    /// 批量转录的提供商顺序与调度策略
+    asr_selection: Arc<Mutex<config::AsrSelection>>,
+    /// 批量 ASR 引擎注册表（按提供商索引）
+    asr_engines: Arc<Mutex<AsrEngineRegistry>>,
+    /// 当前识别语言（托盘 / 快捷键可热切换）
+    asr_language_mode: Arc<Mutex<config::AsrLanguageMode>>,
+    /// 各 ASR 提供商的健康状态与熔断器
+    asr_health: Arc<AsrHealthTracker>,
+    /// 按下快捷键时预热 ASR 连接
+    asr_prewarmer: Arc<ConnectionPrewarmer>,
     // 活跃的实时转录会话（用于真正的流式传输）
-    active_session: Arc<tokio::sync::Mutex<Option<RealtimeSession>>>,
-    doubao_session: Arc<tokio::sync::Mutex<Option<DoubaoRealtimeSession>>>,
-    doubao_ime_session: Arc<tokio::sync::Mutex<Option<DoubaoImeRealtimeSession>>>,
+    realtime_session: RealtimeSessionSlot,
     realtime_provider: Arc<Mutex<Option<config::AsrProvider>>>,
-    fallback_provider: Arc<Mutex<Option<config::AsrProvider>>>,
     // 音频发送任务句柄
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     // 单例热键服务
@@ -132,12 +146,32 @@ struct AppState {
     is_processing_stop: Arc<AtomicBool>,
     /// 录音时静音其他应用的管理器
     audio_mute_manager: Arc<Mutex<Option<AudioMuteManager>>>,
+    /// 首选录音输入设备（录音器每次开始录音时按此顺序选择设备）
+    preferred_input_devices: Arc<Mutex<Vec<String>>>,
+    // 语音活动检测配置（录音器与长录音分段共享）
+    vad_config: Arc<Mutex<config::VadConfig>>,
+    // 降噪配置（下一次录音生效）
+    noise_suppression: Arc<Mutex<config::NoiseSuppressionConfig>>,
+    // 免提自动结束配置（切换模式与松手锁定模式）
+    auto_stop: Arc<Mutex<config::AutoStopConfig>>,
+    // 开放麦克风配置（语音触发听写，无需快捷键）
+    open_mic_config: Arc<Mutex<config::OpenMicConfig>>,
+    /// 开放麦克风静音开关与会话触发信号（静音同时暂停唤醒词监听）
+    open_mic: Arc<open_mic::OpenMicController>,
+    // 唤醒词配置
+    wake_word_config: Arc<Mutex<config::WakeWordConfig>>,
+    /// 唤醒词触发信号与会话唤醒词
+    wake_word: Arc<wake_word::WakeWordController>,
+    // 录音归档配置
+    recording_archive: Arc<Mutex<config::RecordingArchiveConfig>>,
+    // 预录缓冲配置（快捷键服务运行时录音流常开）
+    preroll: Arc<Mutex<config::PrerollConfig>>,
     /// 目标窗口句柄（热键按下时保存，用于焦点恢复）
     target_window: Arc<Mutex<Option<isize>>>,
     /// 词库（用于 Realtime 模式热更新）
     dictionary: Arc<Mutex<Vec<String>>>,
-    /// 豆包输入法凭据（自动注册获取，跨会话复用）
-    doubao_ime_credentials: Arc<Mutex<Option<DoubaoImeCredentials>>>,
+    /// 豆包输入法凭据存储（自动注册获取，独立于主配置文件保存）
+    doubao_ime_credentials: Arc<DoubaoImeCredentialStore>,
     /// 使用统计数据
     usage_stats: Arc<Mutex<UsageStats>>,
     /// 录音开始时间（用于计算录音时长）
@@ -163,8 +197,13 @@ struct TrayMenuState {
     asr_qwen_item: CheckMenuItem<tauri::Wry>,
     asr_doubao_item: CheckMenuItem<tauri::Wry>,
     asr_doubao_ime_item: CheckMenuItem<tauri::Wry>,
+    language_items: Vec<(config::AsrLanguageMode, CheckMenuItem<tauri::Wry>)>,
+    open_mic_mute_item: CheckMenuItem<tauri::Wry>,
 }
 
+const TRAY_ID: &str = "main";
+const TRAY_TOOLTIP: &str = "PushToTalk - AI 语音转写助手";
+
 const TRAY_MENU_ID_SHOW: &str = "show";
 const TRAY_MENU_ID_QUIT: &str = "quit";
 const TRAY_MENU_ID_TOGGLE_POST_PROCESS: &str = "tray_toggle_post_process";
@@ -172,6 +211,8 @@ const TRAY_MENU_ID_TOGGLE_DICTIONARY_ENHANCEMENT: &str = "tray_toggle_dictionary
 const TRAY_MENU_ID_ASR_QWEN: &str = "tray_asr_qwen";
 const TRAY_MENU_ID_ASR_DOUBAO: &str = "tray_asr_doubao";
 const TRAY_MENU_ID_ASR_DOUBAO_IME: &str = "tray_asr_doubao_ime";
+const TRAY_MENU_ID_LANGUAGE_PREFIX: &str = "tray_lang_";
+const TRAY_MENU_ID_OPEN_MIC_MUTE: &str = "tray_open_mic_mute";
 
 /// 全局互斥标志：防止并发 ASR 引擎切换导致多个 restart 并行执行
 static TRAY_ASR_SWITCHING: AtomicBool = AtomicBool::new(false);
@@ -200,6 +241,7 @@ fn sync_tray_menu_from_config(app_handle: &AppHandle, config: &AppConfig) {
         &tray_state.asr_doubao_ime_item,
         &config.asr_config.selection.active_provider,
     );
+    sync_language_checks(&tray_state.language_items, config.asr_config.language_mode);
 }
 
 fn load_persisted_config() -> Result<AppConfig, String> {
@@ -368,6 +410,9 @@ fn asr_provider_name(provider: &config::AsrProvider) -> &'static str {
         config::AsrProvider::Doubao => "豆包",
         config::AsrProvider::DoubaoIme => "豆包输入法",
         config::AsrProvider::SiliconFlow => "硅基流动",
+        config::AsrProvider::LocalWhisper => "本地 Whisper",
+        config::AsrProvider::OpenAiCompatible(_) => "OpenAI 兼容",
+        config::AsrProvider::WebSocket(_) => "自建流式服务",
     }
 }
 
@@ -396,6 +441,19 @@ fn is_asr_provider_configured(config: &AppConfig, provider: &config::AsrProvider
             .sensevoice_api_key
             .trim()
             .is_empty(),
+        // 本地 Whisper 需要启用 `local-whisper` 特性，并有可用的模型文件
+        config::AsrProvider::LocalWhisper => {
+            cfg!(feature = "local-whisper")
+                && std::path::Path::new(config.asr_config.local_whisper.model_path.trim()).is_file()
+        }
+        config::AsrProvider::OpenAiCompatible(id) => config
+            .asr_config
+            .openai_provider(id)
+            .is_some_and(|p| !p.endpoint.trim().is_empty()),
+        config::AsrProvider::WebSocket(id) => config
+            .asr_config
+            .websocket_provider(id)
+            .is_some_and(|p| !p.endpoint.trim().is_empty()),
     }
 }
 
@@ -420,6 +478,41 @@ fn sync_asr_provider_checks(
     }
 }
 
+/// 托盘引擎菜单项显示熔断状态
+///
+/// 托盘只列出千问、豆包与豆包输入法三个引擎；硅基流动、本地 Whisper、OpenAI 兼容与自建流式服务
+/// 没有对应菜单项，熔断状态只在 ASR 设置页的健康面板中显示（同样由 `asr_health_changed` 事件推送）
+fn sync_tray_asr_health(app_handle: &AppHandle, health: &asr::ProviderHealth) {
+    let Some(tray_state) = app_handle.try_state::<TrayMenuState>() else {
+        return;
+    };
+    let (item, label) = match health.provider {
+        config::AsrProvider::Qwen => (&tray_state.asr_qwen_item, "千问"),
+        config::AsrProvider::Doubao => (&tray_state.asr_doubao_item, "豆包"),
+        config::AsrProvider::DoubaoIme => (&tray_state.asr_doubao_ime_item, "豆包输入法(免费)"),
+        _ => return,
+    };
+    let text = match health.circuit {
+        asr::CircuitState::Closed => label.to_string(),
+        asr::CircuitState::Open => format!("{}（熔断中）", label),
+        asr::CircuitState::HalfOpen => format!("{}（恢复探测中）", label),
+    };
+    if let Err(e) = item.set_text(text) {
+        tracing::warn!("更新托盘引擎健康状态失败: {}", e);
+    }
+}
+
+fn sync_language_checks(
+    items: &[(config::AsrLanguageMode, CheckMenuItem<tauri::Wry>)],
+    language_mode: config::AsrLanguageMode,
+) {
+    for (mode, item) in items {
+        if let Err(e) = item.set_checked(*mode == language_mode) {
+            tracing::warn!("更新托盘识别语言勾选状态失败: {}", e);
+        }
+    }
+}
+
 async fn restart_service_with_config(
     app_handle: AppHandle,
     config: AppConfig,
@@ -428,7 +521,8 @@ async fn restart_service_with_config(
         tracing::warn!("切换 ASR 引擎时停止服务失败: {}", e);
     }
 
-    let dictionary_words = learning::store::entries_to_words(&config.dictionary);
+    // 保留词条的权重与作用范围标记，由各提供商自行转换
+    let dictionary_words = config.dictionary.clone();
 
     start_app(
         app_handle,
@@ -625,6 +719,72 @@ async fn switch_asr_provider_from_tray_inner(
     Ok(())
 }
 
+#[derive(Clone, serde::Serialize)]
+struct AsrLanguageChangedPayload {
+    /// 用户选择的识别语言
+    mode: config::AsrLanguageMode,
+    /// 当前 ASR 引擎实际使用的识别语言（不支持时降级）
+    effective: config::AsrLanguageMode,
+}
+
+/// 切换识别语言：持久化配置并热更新引擎，无需重启服务
+///
+/// 返回当前提供商实际使用的识别语言
+fn switch_asr_language_mode(
+    app_handle: &AppHandle,
+    language_mode: config::AsrLanguageMode,
+) -> Result<config::AsrLanguageMode, String> {
+    let (updated_config, active_provider) = mutate_persisted_config_with_result(|config| {
+        config.asr_config.language_mode = language_mode;
+        Ok(config.asr_config.selection.active_provider.clone())
+    })?;
+
+    emit_config_updated(app_handle, &updated_config);
+
+    {
+        let state = app_handle.state::<AppState>();
+        *state.asr_language_mode.lock().unwrap() = language_mode;
+        state
+            .asr_engines
+            .lock()
+            .unwrap()
+            .update_language_mode(language_mode);
+    }
+
+    let effective = asr::resolve_language_mode(&active_provider, language_mode);
+    let _ = app_handle.emit(
+        "asr_language_changed",
+        AsrLanguageChangedPayload {
+            mode: language_mode,
+            effective,
+        },
+    );
+
+    tracing::info!(
+        "识别语言切换为: {}（{} 实际使用: {}）",
+        language_mode.display_name(),
+        asr_provider_name(&active_provider),
+        effective.display_name()
+    );
+    Ok(effective)
+}
+
+/// 语言切换快捷键：在当前提供商支持的语言之间循环
+fn cycle_asr_language_mode(app_handle: &AppHandle) -> Result<config::AsrLanguageMode, String> {
+    let (current, provider) = {
+        let state = app_handle.state::<AppState>();
+        let current = *state.asr_language_mode.lock().unwrap();
+        let provider = state
+            .realtime_provider
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_default();
+        (current, provider)
+    };
+    switch_asr_language_mode(app_handle, asr::next_language_mode(&provider, current))
+}
+
 fn merge_asr_config_for_save(
     asr_config: Option<config::AsrConfig>,
     existing_asr_config: &config::AsrConfig,
@@ -763,6 +923,14 @@ async fn save_config(
             transcription_mode: existing.transcription_mode,
             enable_mute_other_apps: enable_mute_other_apps
                 .unwrap_or(existing.enable_mute_other_apps),
+            preferred_input_devices: existing.preferred_input_devices.clone(),
+            vad_config: existing.vad_config.clone(),
+            noise_suppression: existing.noise_suppression.clone(),
+            auto_stop: existing.auto_stop.clone(),
+            open_mic: existing.open_mic.clone(),
+            wake_word: existing.wake_word.clone(),
+            recording_archive: existing.recording_archive.clone(),
+            preroll: existing.preroll.clone(),
             dictionary: final_dictionary,
             builtin_dictionary_domains: builtin_dictionary_domains
                 .unwrap_or_else(|| existing.builtin_dictionary_domains.clone()),
@@ -799,8 +967,10 @@ mod save_config_merge_tests {
                 active_provider: config::AsrProvider::Doubao,
                 enable_fallback: true,
                 fallback_provider: Some(config::AsrProvider::Qwen),
+                ..Default::default()
             },
             language_mode: config::AsrLanguageMode::Zh,
+            ..Default::default()
         }
     }
 
@@ -915,16 +1085,15 @@ async fn handle_recording_start(
     app: AppHandle,
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    active_session: Arc<tokio::sync::Mutex<Option<RealtimeSession>>>,
-    doubao_session: Arc<tokio::sync::Mutex<Option<DoubaoRealtimeSession>>>,
-    doubao_ime_session: Arc<tokio::sync::Mutex<Option<DoubaoImeRealtimeSession>>>,
-    doubao_ime_credentials: Arc<Mutex<Option<DoubaoImeCredentials>>>,
+    realtime_session: RealtimeSessionSlot,
+    doubao_ime_credentials: Arc<DoubaoImeCredentialStore>,
     realtime_provider: Arc<Mutex<Option<config::AsrProvider>>>,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     use_realtime: bool,
     api_key: String,
     doubao_app_id: Option<String>,
     doubao_access_token: Option<String>,
+    websocket_provider: Option<config::WebSocketAsrProvider>,
     audio_mute_manager: Arc<Mutex<Option<AudioMuteManager>>>,
     dictionary: Vec<String>,
     language_mode: config::AsrLanguageMode,
@@ -962,19 +1131,33 @@ async fn handle_recording_start(
         let _ = overlay.show();
     }
 
+    prewarm_asr_connections(&app, use_realtime);
+
     if use_realtime {
         let provider = realtime_provider.lock().unwrap().clone();
+        if let Some(ref p) = provider {
+            if !app.state::<AppState>().asr_health.allow(p) {
+                // 熔断期间不再尝试建立连接，只录音，停止时直接走备用方案
+                tracing::warn!(
+                    "{}熔断中，跳过实时连接，本次使用备用方案",
+                    asr_provider_name(p)
+                );
+                close_stale_realtime_session(&realtime_session, &audio_sender_handle).await;
+                start_streaming_without_session(&app, &streaming_recorder);
+                return;
+            }
+        }
         match provider {
             Some(config::AsrProvider::Doubao) => {
                 handle_doubao_realtime_start(
                     app,
                     streaming_recorder,
-                    doubao_session,
+                    realtime_session,
                     audio_sender_handle,
                     doubao_app_id,
                     doubao_access_token,
                     dictionary,
-                    language_mode,
+                    asr::resolve_language_mode(&config::AsrProvider::Doubao, language_mode),
                 )
                 .await;
             }
@@ -982,22 +1165,33 @@ async fn handle_recording_start(
                 handle_doubao_ime_realtime_start(
                     app,
                     streaming_recorder,
-                    doubao_ime_session,
+                    realtime_session,
                     audio_sender_handle,
                     doubao_ime_credentials,
                     dictionary,
                 )
                 .await;
             }
+            Some(config::AsrProvider::WebSocket(_)) => {
+                handle_websocket_realtime_start(
+                    app,
+                    streaming_recorder,
+                    realtime_session,
+                    audio_sender_handle,
+                    websocket_provider,
+                    dictionary,
+                )
+                .await;
+            }
             _ => {
                 handle_qwen_realtime_start(
                     app,
                     streaming_recorder,
-                    active_session,
+                    realtime_session,
                     audio_sender_handle,
                     api_key,
                     dictionary,
-                    language_mode,
+                    asr::resolve_language_mode(&config::AsrProvider::Qwen, language_mode),
                 )
                 .await;
             }
@@ -1019,11 +1213,408 @@ async fn handle_recording_start(
     }
 }
 
+/// 为免提录音（切换模式 / 松手锁定模式 / 开放麦克风）挂载自动结束检测
+///
+/// 满足结束条件时通过热键服务结束录音，与用户再次按下快捷键走同一流程
+fn start_auto_stop(app: &AppHandle, use_realtime: bool, auto_stop_config: &config::AutoStopConfig) {
+    let state = app.state::<AppState>();
+    let tracker = Arc::new(Mutex::new(auto_stop::SilenceTracker::new(auto_stop_config)));
+    let sink = Box::new(auto_stop::AutoStopSink::new(
+        Arc::clone(&tracker),
+        &state.vad_config.lock().unwrap(),
+    ));
+
+    // 录音可能已失败或已被用户结束，此时不再挂载
+    let attached = if use_realtime {
+        match state.streaming_recorder.lock().unwrap().as_ref() {
+            Some(rec) if rec.is_recording() => {
+                rec.attach_sink(sink);
+                true
+            }
+            _ => false,
+        }
+    } else {
+        match state.audio_recorder.lock().unwrap().as_ref() {
+            Some(rec) if rec.is_recording() => {
+                rec.attach_sink(sink);
+                true
+            }
+            _ => false,
+        }
+    };
+    if !attached {
+        return;
+    }
+
+    tracing::info!(
+        "已启用免提自动结束: 静音 {}ms, 最长 {}s",
+        auto_stop_config.silence_ms,
+        auto_stop_config.max_duration_secs
+    );
+    let app_stop = app.clone();
+    auto_stop::spawn_auto_stop_watcher(app.clone(), tracker, move |_| {
+        if !app_stop.state::<AppState>().hotkey_service.request_stop() {
+            tracing::info!("录音已结束，跳过自动结束");
+        }
+    });
+}
+
+/// 是否应在后台监听（开放麦克风、唤醒词或预录缓冲已启用、未静音且服务运行中）
+fn background_listening_wanted(state: &AppState) -> bool {
+    let wake_word_ready = {
+        let config = state.wake_word_config.lock().unwrap();
+        config.enabled
+            && config
+                .words
+                .iter()
+                .any(|word| word.samples.len() >= wake_word::MIN_SAMPLES)
+    };
+    (state.open_mic_config.lock().unwrap().enabled
+        || wake_word_ready
+        || state.preroll.lock().unwrap().enabled)
+        && !state.open_mic.is_muted()
+        && *state.is_running.lock().unwrap()
+}
+
+/// 当前模式使用的录音器是否在后台监听 / 录音中
+fn monitor_recorder_status(state: &AppState) -> (bool, bool) {
+    if *state.use_realtime_asr.lock().unwrap() {
+        match state.streaming_recorder.lock().unwrap().as_ref() {
+            Some(rec) => (rec.is_monitoring(), rec.is_recording()),
+            None => (false, false),
+        }
+    } else {
+        match state.audio_recorder.lock().unwrap().as_ref() {
+            Some(rec) => (rec.is_monitoring(), rec.is_recording()),
+            None => (false, false),
+        }
+    }
+}
+
+/// 按开放麦克风、唤醒词与预录缓冲配置开始或结束后台监听，并刷新隐私指示
+///
+/// 监听挂在听写所用的同一个录音器上，触发的会话直接复用已打开的录音流和预录音频；
+/// 只开启预录缓冲时不挂监听 sink，录音流保持打开，仅保留最近的音频补在每次录音开头
+fn sync_background_listening(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let mut sinks: Vec<Box<dyn CaptureSink>> = Vec::new();
+    let mut preroll_ms = 0;
+    if background_listening_wanted(&state) {
+        let vad_config = state.vad_config.lock().unwrap().clone();
+
+        let preroll_config = state.preroll.lock().unwrap().clone();
+        if preroll_config.enabled {
+            preroll_ms = preroll_config.duration_ms;
+        }
+
+        let open_mic_config = state.open_mic_config.lock().unwrap().clone();
+        if open_mic_config.enabled {
+            preroll_ms = preroll_ms.max(open_mic_config.trigger_ms + open_mic::PREROLL_MARGIN_MS);
+            sinks.push(Box::new(open_mic::OpenMicSink::new(
+                &open_mic_config,
+                &vad_config,
+                state.open_mic.trigger_sender(),
+            )));
+        }
+
+        let wake_word_config = state.wake_word_config.lock().unwrap().clone();
+        if wake_word_config.enabled {
+            match wake_word::KeywordSpotter::from_config(&wake_word_config) {
+                Some(spotter) => {
+                    // 预录缓冲需覆盖整个唤醒词，转写后再从文本中去掉
+                    preroll_ms =
+                        preroll_ms.max(spotter.max_template_ms() + wake_word::PREROLL_MARGIN_MS);
+                    sinks.push(Box::new(wake_word::WakeWordSink::new(
+                        spotter,
+                        &vad_config,
+                        state.wake_word.trigger_sender(),
+                    )));
+                }
+                None => tracing::warn!("没有录好样本的唤醒词，唤醒词监听未开启"),
+            }
+        }
+    }
+    let should_listen = !sinks.is_empty() || preroll_ms > 0;
+
+    let result = if *state.use_realtime_asr.lock().unwrap() {
+        match state.streaming_recorder.lock().unwrap().as_mut() {
+            Some(rec) if should_listen => rec.start_monitor(Some(app.clone()), sinks, preroll_ms),
+            Some(rec) => {
+                rec.stop_monitor();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    } else {
+        match state.audio_recorder.lock().unwrap().as_mut() {
+            Some(rec) if should_listen => rec.start_monitor(Some(app.clone()), sinks, preroll_ms),
+            Some(rec) => {
+                rec.stop_monitor();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    };
+    if let Err(e) = result {
+        tracing::warn!("开始后台监听失败: {}", e);
+    }
+
+    let (listening, _) = monitor_recorder_status(&state);
+    emit_open_mic_state(app, listening);
+}
+
+/// 后台监听中重新打开录音流，让输入设备、降噪等在打开录音流时读取的设置立即生效
+///
+/// 录音中只结束监听，会话结束后由调度任务重新开始监听
+fn reopen_background_listening(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let (listening, recording) = monitor_recorder_status(&state);
+    if !listening {
+        return;
+    }
+    if *state.use_realtime_asr.lock().unwrap() {
+        if let Some(rec) = state.streaming_recorder.lock().unwrap().as_mut() {
+            rec.stop_monitor();
+        }
+    } else if let Some(rec) = state.audio_recorder.lock().unwrap().as_mut() {
+        rec.stop_monitor();
+    }
+    if !recording {
+        sync_background_listening(app);
+    }
+}
+
+/// 推送开放麦克风状态：托盘提示与静音勾选、前端隐私指示
+fn emit_open_mic_state(app: &AppHandle, listening: bool) {
+    let state = app.state::<AppState>();
+    let open_mic_state = open_mic::OpenMicState {
+        enabled: state.open_mic_config.lock().unwrap().enabled,
+        muted: state.open_mic.is_muted(),
+        listening,
+    };
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let tooltip = if listening {
+            format!("{}（麦克风监听中）", TRAY_TOOLTIP)
+        } else {
+            TRAY_TOOLTIP.to_string()
+        };
+        if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+            tracing::warn!("更新托盘提示失败: {}", e);
+        }
+        // 监听中托盘图标带红点，作为常驻的隐私指示
+        if let Some(icon) = app.default_window_icon() {
+            let icon = if listening {
+                tauri::image::Image::new_owned(
+                    open_mic::with_listening_badge(icon.rgba(), icon.width(), icon.height()),
+                    icon.width(),
+                    icon.height(),
+                )
+            } else {
+                icon.clone()
+            };
+            if let Err(e) = tray.set_icon(Some(icon)) {
+                tracing::warn!("更新托盘图标失败: {}", e);
+            }
+        }
+    }
+    if let Some(tray_state) = app.try_state::<TrayMenuState>() {
+        if let Err(e) = tray_state
+            .open_mic_mute_item
+            .set_checked(open_mic_state.muted)
+        {
+            tracing::warn!("同步托盘开放麦克风静音状态失败: {}", e);
+        }
+    }
+    let _ = app.emit("open_mic_state", &open_mic_state);
+}
+
+/// 后台监听会话调度（进程级，随应用启动）
+///
+/// 开放麦克风检测到持续语音后开始听写、唤醒词命中后开始对应模式的会话，均通过热键服务发起，
+/// 与按下快捷键走同一流程。触发在独立线程上阻塞等待，不轮询；
+/// 另有定时任务检查监听是否意外中断（如录音设备断开），必要时重新打开
+fn spawn_background_listening_scheduler(
+    app: AppHandle,
+    open_mic_triggers: crossbeam_channel::Receiver<()>,
+    wake_word_triggers: crossbeam_channel::Receiver<wake_word::WakeWordHit>,
+) {
+    const RESYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+    let trigger_app = app.clone();
+    let spawn_result = std::thread::Builder::new()
+        .name("background-listening".to_string())
+        .spawn(move || loop {
+            crossbeam_channel::select! {
+                recv(wake_word_triggers) -> hit => {
+                    let Ok(hit) = hit else { break };
+                    let state = trigger_app.state::<AppState>();
+                    if background_listening_wanted(&state) {
+                        state.wake_word.request_session(hit.phrase);
+                        if !state.hotkey_service.request_start(hit.mode) {
+                            state.wake_word.cancel_request();
+                            tracing::debug!("录音进行中或快捷键服务未激活，忽略唤醒词");
+                        }
+                    }
+                }
+                recv(open_mic_triggers) -> trigger => {
+                    if trigger.is_err() {
+                        break;
+                    }
+                    let state = trigger_app.state::<AppState>();
+                    if background_listening_wanted(&state) {
+                        state.open_mic.request_session();
+                        if !state
+                            .hotkey_service
+                            .request_start(config::TriggerMode::Dictation)
+                        {
+                            state.open_mic.take_session_request();
+                            tracing::debug!("录音进行中或快捷键服务未激活，忽略开放麦克风触发");
+                        }
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawn_result {
+        tracing::error!("启动后台监听调度线程失败: {}", e);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(RESYNC_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            let (listening, recording) = monitor_recorder_status(&state);
+            if background_listening_wanted(&state) && !listening && !recording {
+                tracing::info!("后台监听已中断，重新开始监听");
+                sync_background_listening(&app);
+            }
+        }
+    });
+}
+
+/// 与录音并行预热本次可能用到的批量引擎（主引擎与备用引擎）
+///
+/// 实时模式下主引擎的 WebSocket 会话由各 start 函数建立，这里只预热备用引擎
+fn prewarm_asr_connections(app: &AppHandle, use_realtime: bool) {
+    let state = app.state::<AppState>();
+    let Some(active_provider) = state.realtime_provider.lock().unwrap().clone() else {
+        return;
+    };
+    let mut selection = state.asr_selection.lock().unwrap().clone();
+    selection.active_provider = active_provider.clone();
+    let engines = state.asr_engines.lock().unwrap().clone();
+
+    let targets: Vec<Arc<dyn asr::AsrEngine>> = selection
+        .provider_order()
+        .into_iter()
+        .filter(|p| !(use_realtime && *p == active_provider))
+        .filter(|p| !state.asr_health.is_open(p))
+        .filter_map(|p| engines.get(&p))
+        .collect();
+    state.asr_prewarmer.prewarm(targets);
+}
+
+/// 清理旧的实时会话和音频发送任务（防止资源泄漏）
+async fn close_stale_realtime_session(
+    realtime_session: &RealtimeSessionSlot,
+    audio_sender_handle: &Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+) {
+    {
+        let mut session_guard = realtime_session.lock().await;
+        if let Some(mut old_session) = session_guard.take() {
+            tracing::warn!(
+                "发现旧的{}会话，先关闭它",
+                asr_provider_name(&old_session.provider())
+            );
+            let _ = old_session.close().await;
+        }
+    }
+    {
+        if let Some(old_handle) = audio_sender_handle.lock().unwrap().take() {
+            tracing::warn!("发现旧的音频发送任务，先取消它");
+            old_handle.abort();
+        }
+    }
+}
+
+/// 启动音频发送任务：把流式录音块持续推送给当前实时会话
+fn spawn_realtime_audio_sender(
+    realtime_session: &RealtimeSessionSlot,
+    chunk_rx: crossbeam_channel::Receiver<Vec<i16>>,
+    provider_name: &'static str,
+) -> tokio::task::JoinHandle<()> {
+    let session_for_sender = Arc::clone(realtime_session);
+    tokio::spawn(async move {
+        tracing::info!("{}音频发送任务启动", provider_name);
+        let mut chunk_count = 0;
+
+        while let Ok(chunk) = chunk_rx.recv() {
+            let mut session_guard = session_for_sender.lock().await;
+            if let Some(ref mut session) = *session_guard {
+                if let Err(e) = session.send_audio_chunk(&chunk).await {
+                    tracing::error!("发送音频块失败: {}", e);
+                    break;
+                }
+                chunk_count += 1;
+                if chunk_count % 10 == 0 {
+                    tracing::debug!("已发送 {} 个音频块", chunk_count);
+                }
+            } else {
+                break;
+            }
+            drop(session_guard);
+        }
+
+        tracing::info!(
+            "{}音频发送任务结束，共发送 {} 个块",
+            provider_name,
+            chunk_count
+        );
+    })
+}
+
+/// 将实时会话的中间识别结果转发为 `transcription_partial` 事件，会话结束后自动退出
+fn spawn_partial_transcript_forwarder(app: &AppHandle, session: &dyn RealtimeAsrSession) {
+    let Some(mut partials) = session.subscribe_partials() else {
+        return;
+    };
+    let app = app.clone();
+    tokio::spawn(async move {
+        while partials.changed().await.is_ok() {
+            let text = partials.borrow_and_update().clone();
+            if !text.is_empty() {
+                let _ = app.emit("transcription_partial", text);
+            }
+        }
+    });
+}
+
+/// 安装新建立的实时会话：包装为可轮换的会话（长录音接近时长上限时自动换新会话），
+/// 转发中间结果并启动音频发送任务
+async fn install_realtime_session(
+    app: &AppHandle,
+    realtime_session: &RealtimeSessionSlot,
+    audio_sender_handle: &Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    session: Box<dyn RealtimeAsrSession>,
+    factory: asr::RealtimeSessionFactory,
+    chunk_rx: crossbeam_channel::Receiver<Vec<i16>>,
+    provider_name: &'static str,
+) {
+    let session = asr::RollingRealtimeSession::new(session, factory);
+    spawn_partial_transcript_forwarder(app, &session);
+    *realtime_session.lock().await = Some(Box::new(session));
+
+    let sender_handle = spawn_realtime_audio_sender(realtime_session, chunk_rx, provider_name);
+    *audio_sender_handle.lock().unwrap() = Some(sender_handle);
+}
+
 /// 处理豆包实时模式启动
 async fn handle_doubao_realtime_start(
     app: AppHandle,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    doubao_session: Arc<tokio::sync::Mutex<Option<DoubaoRealtimeSession>>>,
+    realtime_session: RealtimeSessionSlot,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     doubao_app_id: Option<String>,
     doubao_access_token: Option<String>,
@@ -1057,64 +1648,50 @@ async fn handle_doubao_realtime_start(
         if let (Some(app_id), Some(access_token)) =
             (doubao_app_id.as_ref(), doubao_access_token.as_ref())
         {
-            let realtime_client = DoubaoRealtimeClient::new(
+            let realtime_client = Arc::new(DoubaoRealtimeClient::new(
                 app_id.clone(),
                 access_token.clone(),
                 dictionary,
                 language_mode,
-            );
-            // 清理旧的会话和任务（防止资源泄漏）
-            {
-                let mut session_guard = doubao_session.lock().await;
-                if let Some(mut old_session) = session_guard.take() {
-                    tracing::warn!("发现旧的豆包会话，先关闭它");
-                    let _ = old_session.finish_audio().await;
-                }
-            }
-            {
-                if let Some(old_handle) = audio_sender_handle.lock().unwrap().take() {
-                    tracing::warn!("发现旧的音频发送任务，先取消它");
-                    old_handle.abort();
-                }
-            }
+            ));
+            close_stale_realtime_session(&realtime_session, &audio_sender_handle).await;
 
+            let connect_start = std::time::Instant::now();
             match realtime_client.start_session().await {
                 Ok(session) => {
                     tracing::info!("豆包 WebSocket 连接已建立");
-                    *doubao_session.lock().await = Some(session);
-
-                    let session_for_sender = Arc::clone(&doubao_session);
-                    let sender_handle = tokio::spawn(async move {
-                        tracing::info!("豆包音频发送任务启动");
-                        let mut chunk_count = 0;
-
-                        while let Ok(chunk) = chunk_rx.recv() {
-                            let mut session_guard = session_for_sender.lock().await;
-                            if let Some(ref mut session) = *session_guard {
-                                if let Err(e) = session.send_audio_chunk(&chunk).await {
-                                    tracing::error!("发送音频块失败: {}", e);
-                                    break;
-                                }
-                                chunk_count += 1;
-                                if chunk_count % 10 == 0 {
-                                    tracing::debug!("已发送 {} 个音频块", chunk_count);
-                                }
-                            } else {
-                                break;
-                            }
-                            drop(session_guard);
-                        }
-
-                        tracing::info!("豆包音频发送任务结束，共发送 {} 个块", chunk_count);
+                    app.state::<AppState>()
+                        .asr_health
+                        .record_connected(&config::AsrProvider::Doubao);
+                    let factory: asr::RealtimeSessionFactory = Arc::new(move || {
+                        let client = Arc::clone(&realtime_client);
+                        Box::pin(async move {
+                            let session: Box<dyn RealtimeAsrSession> =
+                                Box::new(client.start_session().await?);
+                            Ok(session)
+                        })
                     });
-
-                    *audio_sender_handle.lock().unwrap() = Some(sender_handle);
+                    install_realtime_session(
+                        &app,
+                        &realtime_session,
+                        &audio_sender_handle,
+                        Box::new(session),
+                        factory,
+                        chunk_rx,
+                        "豆包",
+                    )
+                    .await;
                 }
                 Err(e) => {
                     tracing::error!(
                         "建立豆包 WebSocket 连接失败: {}，录音已启动，将使用备用方案",
                         e
                     );
+                    app.state::<AppState>().asr_health.record_failure(
+                        &config::AsrProvider::Doubao,
+                        &e,
+                        connect_start.elapsed(),
+                    );
                 }
             }
         } else {
@@ -1123,15 +1700,25 @@ async fn handle_doubao_realtime_start(
     }
 }
 
+/// 豆包输入法实时转录
+///
+/// 输入法协议的 StartSession 只有 audio_info / extra 字段，没有热词或上下文入口，
+/// 词库无法随会话下发，这里仅记录日志提示
 async fn handle_doubao_ime_realtime_start(
     app: AppHandle,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    doubao_ime_session: Arc<tokio::sync::Mutex<Option<DoubaoImeRealtimeSession>>>,
+    realtime_session: RealtimeSessionSlot,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
-    doubao_ime_credentials: Arc<Mutex<Option<DoubaoImeCredentials>>>,
-    _dictionary: Vec<String>,
+    doubao_ime_credentials: Arc<DoubaoImeCredentialStore>,
+    dictionary: Vec<String>,
 ) {
     tracing::info!("启动豆包输入法实时流式转录...");
+    if !dictionary.is_empty() {
+        tracing::info!(
+            "豆包输入法协议不支持热词，词库 ({} 条) 不参与识别",
+            dictionary.len()
+        );
+    }
 
     let chunk_rx = {
         let mut streaming_guard = streaming_recorder.lock().unwrap();
@@ -1153,162 +1740,90 @@ async fn handle_doubao_ime_realtime_start(
         }
     };
 
-    if let Some(chunk_rx) = chunk_rx {
-        // 检查是否有已保存的凭据
-        let saved_credentials = doubao_ime_credentials.lock().unwrap().clone();
-        let had_credentials = saved_credentials.is_some();
-
-        let mut realtime_client = if let Some(creds) = saved_credentials {
-            tracing::info!(
-                "豆包输入法 ASR: 使用已保存的凭据 (device_id={})",
-                creds.device_id
-            );
-            DoubaoImeRealtimeClient::with_credentials(
-                reqwest::Client::new(),
-                asr::DoubaoImeClientConfig::default(),
-                creds,
-            )
-        } else {
-            tracing::info!("豆包输入法 ASR: 无已保存凭据，将自动注册");
-            DoubaoImeRealtimeClient::new(
-                reqwest::Client::new(),
-                asr::DoubaoImeClientConfig::default(),
-            )
-        };
+    let Some(chunk_rx) = chunk_rx else {
+        return;
+    };
 
-        {
-            let mut session_guard = doubao_ime_session.lock().await;
-            if let Some(mut old_session) = session_guard.take() {
-                tracing::warn!("发现旧的豆包输入法会话，先关闭它");
-                let _ = old_session.finish_audio().await;
-            }
-        }
-        {
-            if let Some(old_handle) = audio_sender_handle.lock().unwrap().take() {
-                tracing::warn!("发现旧的音频发送任务，先取消它");
-                old_handle.abort();
-            }
+    close_stale_realtime_session(&realtime_session, &audio_sender_handle).await;
+
+    let connect_start = std::time::Instant::now();
+    let http_client = asr::shared_http_client();
+    let mut session_result = start_doubao_ime_session(&doubao_ime_credentials, &http_client).await;
+    if let Err(ref e) = session_result {
+        if asr::is_credential_error(e) {
+            // 先作废凭据（旧 Token 只刷新 Token，新 Token 仍被拒绝则重新注册），再重试一次
+            tracing::warn!("豆包输入法 ASR: 凭据被拒绝，刷新后重试。原始错误: {}", e);
+            doubao_ime_credentials.report_rejected();
+            session_result = start_doubao_ime_session(&doubao_ime_credentials, &http_client).await;
         }
+    }
 
-        let mut session_result = realtime_client.start_session().await;
-        if session_result.is_err() && had_credentials {
-            if let Some(err_text) = session_result.as_ref().err().map(|e| e.to_string()) {
-                let normalized = err_text.to_lowercase();
-                let should_refresh_credentials = normalized.contains("taskfailed")
-                    || normalized.contains("sessionfailed")
-                    || normalized.contains("token")
-                    || normalized.contains("auth")
-                    || normalized.contains("401")
-                    || normalized.contains("403");
-
-                if should_refresh_credentials {
-                    tracing::warn!(
-                        "豆包输入法 ASR: 现有凭据可能失效，清除后重试。原始错误: {}",
-                        err_text
-                    );
-                    *doubao_ime_credentials.lock().unwrap() = None;
-                    if let Err(e) = clear_doubao_ime_credentials_from_config(&app).await {
-                        tracing::error!("清除豆包输入法配置凭据失败: {}", e);
-                    }
-
-                    realtime_client = DoubaoImeRealtimeClient::new(
-                        reqwest::Client::new(),
-                        asr::DoubaoImeClientConfig::default(),
+    match session_result {
+        Ok(session) => {
+            tracing::info!("豆包输入法 WebSocket 连接已建立");
+            doubao_ime_credentials.report_accepted();
+            app.state::<AppState>()
+                .asr_health
+                .record_connected(&config::AsrProvider::DoubaoIme);
+
+            let store = Arc::clone(&doubao_ime_credentials);
+            let factory: asr::RealtimeSessionFactory = Arc::new(move || {
+                let store = Arc::clone(&store);
+                Box::pin(async move {
+                    let session: Box<dyn RealtimeAsrSession> = Box::new(
+                        start_doubao_ime_session(&store, &asr::shared_http_client()).await?,
                     );
-                    session_result = realtime_client.start_session().await;
-                }
+                    Ok(session)
+                })
+            });
+            install_realtime_session(
+                &app,
+                &realtime_session,
+                &audio_sender_handle,
+                Box::new(session),
+                factory,
+                chunk_rx,
+                "豆包输入法",
+            )
+            .await;
+        }
+        Err(e) => {
+            if asr::is_credential_error(&e) {
+                doubao_ime_credentials.report_rejected();
             }
+            tracing::error!(
+                "建立豆包输入法 WebSocket 连接失败: {}，录音已启动，将使用备用方案",
+                e
+            );
+            app.state::<AppState>().asr_health.record_failure(
+                &config::AsrProvider::DoubaoIme,
+                &e,
+                connect_start.elapsed(),
+            );
         }
+    }
+}
 
-        match session_result {
-            Ok(session) => {
-                tracing::info!("豆包输入法 WebSocket 连接已建立");
-
-                if let Some(new_creds) = realtime_client.credentials() {
-                    let should_save = !had_credentials
-                        || doubao_ime_credentials
-                            .lock()
-                            .unwrap()
-                            .as_ref()
-                            .map(|old| {
-                                old.device_id != new_creds.device_id
-                                    || old.token != new_creds.token
-                                    || old.cdid != new_creds.cdid
-                            })
-                            .unwrap_or(true);
-
-                    if should_save {
-                        tracing::info!(
-                            "豆包输入法 ASR: 更新凭据缓存 (device_id={})",
-                            new_creds.device_id
-                        );
-                        *doubao_ime_credentials.lock().unwrap() = Some(new_creds.clone());
-                        if let Err(e) =
-                            save_doubao_ime_credentials_to_config(&app, new_creds.clone()).await
-                        {
-                            tracing::error!("保存豆包输入法凭据到配置文件失败: {}", e);
-                        }
-                    }
-                }
-
-                *doubao_ime_session.lock().await = Some(session);
-
-                let session_for_sender = Arc::clone(&doubao_ime_session);
-                let sender_handle = tokio::spawn(async move {
-                    tracing::info!("豆包输入法音频发送任务启动");
-                    let mut chunk_count = 0;
-
-                    while let Ok(chunk) = chunk_rx.recv() {
-                        let mut session_guard = session_for_sender.lock().await;
-                        if let Some(ref mut session) = *session_guard {
-                            if let Err(e) = session.send_audio_chunk(&chunk).await {
-                                tracing::error!("发送音频块失败: {}", e);
-                                break;
-                            }
-                            chunk_count += 1;
-                            if chunk_count % 10 == 0 {
-                                tracing::debug!("已发送 {} 个音频块", chunk_count);
-                            }
-                        } else {
-                            break;
-                        }
-                        drop(session_guard);
-                    }
-
-                    tracing::info!("豆包输入法音频发送任务结束，共发送 {} 个块", chunk_count);
-                });
-
-                *audio_sender_handle.lock().unwrap() = Some(sender_handle);
-            }
-            Err(e) => {
-                tracing::error!(
-                    "建立豆包输入法 WebSocket 连接失败: {}，录音已启动，将使用备用方案",
-                    e
-                );
-            }
-        }
-    }
-}
-
-/// 保存豆包输入法凭据到配置文件
-async fn save_doubao_ime_credentials_to_config(
-    app: &AppHandle,
-    creds: DoubaoImeCredentials,
-) -> anyhow::Result<()> {
-    let updated_config = mutate_persisted_config(|config| {
-        config.asr_config.credentials.doubao_ime_device_id = creds.device_id;
-        config.asr_config.credentials.doubao_ime_token = creds.token;
-        config.asr_config.credentials.doubao_ime_cdid = creds.cdid;
-        Ok(())
-    })
-    .map_err(anyhow::Error::msg)?;
-
-    emit_config_updated(app, &updated_config);
-    tracing::info!("豆包输入法凭据已保存到配置文件");
-    Ok(())
+/// 按凭据生命周期取得可用凭据后建立豆包输入法实时会话
+async fn start_doubao_ime_session(
+    store: &DoubaoImeCredentialStore,
+    http_client: &reqwest::Client,
+) -> anyhow::Result<asr::DoubaoImeRealtimeSession> {
+    let creds = asr::ensure_doubao_ime_credentials(store, http_client).await?;
+    tracing::info!(
+        "豆包输入法 ASR: 使用已保存的凭据 (device_id={})",
+        creds.device_id
+    );
+    let mut realtime_client = DoubaoImeRealtimeClient::with_credentials(
+        http_client.clone(),
+        asr::DoubaoImeClientConfig::default(),
+        creds,
+    );
+    realtime_client.start_session().await
 }
 
-async fn clear_doubao_ime_credentials_from_config(app: &AppHandle) -> anyhow::Result<()> {
+/// 移除旧版保存在主配置文件中的豆包输入法凭据（已迁移到独立存储）
+fn clear_legacy_doubao_ime_credentials(app: &AppHandle) -> anyhow::Result<()> {
     let updated_config = mutate_persisted_config(|config| {
         config.asr_config.credentials.doubao_ime_device_id.clear();
         config.asr_config.credentials.doubao_ime_token.clear();
@@ -1318,15 +1833,17 @@ async fn clear_doubao_ime_credentials_from_config(app: &AppHandle) -> anyhow::Re
     .map_err(anyhow::Error::msg)?;
 
     emit_config_updated(app, &updated_config);
-    tracing::info!("已清除豆包输入法凭据缓存");
+    tracing::info!("已从主配置移除豆包输入法凭据");
     Ok(())
 }
 
 /// 处理千问实时模式启动
+///
+/// 先启动录音再建立连接，握手期间的音频缓存在通道中，连接建立后补发
 async fn handle_qwen_realtime_start(
     app: AppHandle,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    active_session: Arc<tokio::sync::Mutex<Option<RealtimeSession>>>,
+    realtime_session: RealtimeSessionSlot,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     api_key: String,
     dictionary: Vec<String>,
@@ -1334,95 +1851,182 @@ async fn handle_qwen_realtime_start(
 ) {
     tracing::info!("启动千问实时流式转录...");
 
-    // 清理旧的会话和任务（防止资源泄漏）
-    {
-        let mut session_guard = active_session.lock().await;
-        if let Some(old_session) = session_guard.take() {
-            tracing::warn!("发现旧的千问会话，先关闭它");
-            let _ = old_session.close().await;
-        }
-    }
-    {
-        if let Some(old_handle) = audio_sender_handle.lock().unwrap().take() {
-            tracing::warn!("发现旧的音频发送任务，先取消它");
-            old_handle.abort();
-        }
-    }
+    close_stale_realtime_session(&realtime_session, &audio_sender_handle).await;
 
-    let realtime_client = QwenRealtimeClient::new(api_key, dictionary, language_mode);
-    match realtime_client.start_session().await {
-        Ok(session) => {
-            tracing::info!("千问 WebSocket 连接已建立");
-
-            let chunk_rx = {
-                let mut streaming_guard = streaming_recorder.lock().unwrap();
-                if let Some(ref mut rec) = *streaming_guard {
-                    // 检查是否已在录音，如果是则先停止
-                    if rec.is_recording() {
-                        tracing::warn!("发现正在进行的流式录音，先停止它");
-                        let _ = rec.stop_streaming();
-                    }
-                    match rec.start_streaming(Some(app.clone())) {
-                        Ok(rx) => Some(rx),
-                        Err(e) => {
-                            emit_error_and_hide_overlay(&app, format!("录音失败: {}", e));
-                            None
-                        }
-                    }
-                } else {
-                    emit_error_and_hide_overlay(&app, "流式录音器未初始化".to_string());
+    let chunk_rx = {
+        let mut streaming_guard = streaming_recorder.lock().unwrap();
+        if let Some(ref mut rec) = *streaming_guard {
+            // 检查是否已在录音，如果是则先停止
+            if rec.is_recording() {
+                tracing::warn!("发现正在进行的流式录音，先停止它");
+                let _ = rec.stop_streaming();
+            }
+            match rec.start_streaming(Some(app.clone())) {
+                Ok(rx) => Some(rx),
+                Err(e) => {
+                    emit_error_and_hide_overlay(&app, format!("录音失败: {}", e));
                     None
                 }
-            };
+            }
+        } else {
+            emit_error_and_hide_overlay(&app, "流式录音器未初始化".to_string());
+            None
+        }
+    };
+
+    let Some(chunk_rx) = chunk_rx else {
+        return;
+    };
 
-            if let Some(chunk_rx) = chunk_rx {
-                *active_session.lock().await = Some(session);
+    let realtime_client = Arc::new(QwenRealtimeClient::new(api_key, dictionary, language_mode));
+    let connect_start = std::time::Instant::now();
+    match realtime_client.start_session().await {
+        Ok(session) => {
+            tracing::info!(
+                "千问 WebSocket 连接已建立 ({}ms)",
+                connect_start.elapsed().as_millis()
+            );
+            app.state::<AppState>()
+                .asr_health
+                .record_connected(&config::AsrProvider::Qwen);
+            let factory: asr::RealtimeSessionFactory = Arc::new(move || {
+                let client = Arc::clone(&realtime_client);
+                Box::pin(async move {
+                    let session: Box<dyn RealtimeAsrSession> =
+                        Box::new(client.start_session().await?);
+                    Ok(session)
+                })
+            });
+            install_realtime_session(
+                &app,
+                &realtime_session,
+                &audio_sender_handle,
+                Box::new(session),
+                factory,
+                chunk_rx,
+                "千问",
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::error!(
+                "建立千问 WebSocket 连接失败: {}，录音已启动，将使用备用方案",
+                e
+            );
+            app.state::<AppState>().asr_health.record_failure(
+                &config::AsrProvider::Qwen,
+                &e,
+                connect_start.elapsed(),
+            );
+        }
+    }
+}
 
-                let session_for_sender = Arc::clone(&active_session);
-                let sender_handle = tokio::spawn(async move {
-                    tracing::info!("千问音频发送任务启动");
-                    let mut chunk_count = 0;
+/// 处理自建 WebSocket 流式服务（FunASR / sherpa-onnx / Vosk）启动，流程与千问一致
+async fn handle_websocket_realtime_start(
+    app: AppHandle,
+    streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
+    realtime_session: RealtimeSessionSlot,
+    audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    provider_config: Option<config::WebSocketAsrProvider>,
+    dictionary: Vec<String>,
+) {
+    tracing::info!("启动自建流式服务实时转录...");
 
-                    while let Ok(chunk) = chunk_rx.recv() {
-                        let session_guard = session_for_sender.lock().await;
-                        if let Some(ref session) = *session_guard {
-                            if let Err(e) = session.send_audio_chunk(&chunk).await {
-                                tracing::error!("发送音频块失败: {}", e);
-                                break;
-                            }
-                            chunk_count += 1;
-                            if chunk_count % 10 == 0 {
-                                tracing::debug!("已发送 {} 个音频块", chunk_count);
-                            }
-                        } else {
-                            break;
-                        }
-                        drop(session_guard);
-                    }
+    close_stale_realtime_session(&realtime_session, &audio_sender_handle).await;
 
-                    tracing::info!("千问音频发送任务结束，共发送 {} 个块", chunk_count);
-                });
+    let Some(provider_config) = provider_config else {
+        tracing::error!("自建流式服务配置缺失，录音已启动，将使用备用方案");
+        start_streaming_without_session(&app, &streaming_recorder);
+        return;
+    };
 
-                *audio_sender_handle.lock().unwrap() = Some(sender_handle);
+    let chunk_rx = {
+        let mut streaming_guard = streaming_recorder.lock().unwrap();
+        if let Some(ref mut rec) = *streaming_guard {
+            if rec.is_recording() {
+                tracing::warn!("发现正在进行的流式录音，先停止它");
+                let _ = rec.stop_streaming();
             }
-        }
-        Err(e) => {
-            tracing::error!("建立千问 WebSocket 连接失败: {}，回退到普通录音", e);
-
-            let mut streaming_guard = streaming_recorder.lock().unwrap();
-            if let Some(ref mut rec) = *streaming_guard {
-                // 检查是否已在录音，如果是则先停止
-                if rec.is_recording() {
-                    tracing::warn!("发现正在进行的流式录音，先停止它");
-                    let _ = rec.stop_streaming();
-                }
-                if let Err(e) = rec.start_streaming(Some(app.clone())) {
+            match rec.start_streaming(Some(app.clone())) {
+                Ok(rx) => Some(rx),
+                Err(e) => {
                     emit_error_and_hide_overlay(&app, format!("录音失败: {}", e));
+                    None
                 }
-            } else {
-                emit_error_and_hide_overlay(&app, "录音器未初始化".to_string());
             }
+        } else {
+            emit_error_and_hide_overlay(&app, "流式录音器未初始化".to_string());
+            None
+        }
+    };
+
+    let Some(chunk_rx) = chunk_rx else {
+        return;
+    };
+
+    let provider = config::AsrProvider::WebSocket(provider_config.id.clone());
+    let realtime_client = Arc::new(asr::WebSocketRealtimeClient::new(
+        provider_config,
+        dictionary,
+    ));
+    let connect_start = std::time::Instant::now();
+    match realtime_client.start_session().await {
+        Ok(session) => {
+            app.state::<AppState>()
+                .asr_health
+                .record_connected(&provider);
+            let factory: asr::RealtimeSessionFactory = Arc::new(move || {
+                let client = Arc::clone(&realtime_client);
+                Box::pin(async move {
+                    let session: Box<dyn RealtimeAsrSession> =
+                        Box::new(client.start_session().await?);
+                    Ok(session)
+                })
+            });
+            install_realtime_session(
+                &app,
+                &realtime_session,
+                &audio_sender_handle,
+                Box::new(session),
+                factory,
+                chunk_rx,
+                "自建流式服务",
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::error!(
+                "连接{}失败: {:#}，录音已启动，将使用备用方案",
+                realtime_client.name(),
+                e
+            );
+            app.state::<AppState>().asr_health.record_failure(
+                &provider,
+                &e,
+                connect_start.elapsed(),
+            );
+        }
+    }
+}
+
+/// 仅启动流式录音（不建立实时会话），停止时由备用方案转录
+fn start_streaming_without_session(
+    app: &AppHandle,
+    streaming_recorder: &Arc<Mutex<Option<StreamingRecorder>>>,
+) {
+    let mut streaming_guard = streaming_recorder.lock().unwrap();
+    if let Some(ref mut rec) = *streaming_guard {
+        // 检查是否已在录音，如果是则先停止
+        if rec.is_recording() {
+            tracing::warn!("发现正在进行的流式录音，先停止它");
+            let _ = rec.stop_streaming();
         }
+        if let Err(e) = rec.start_streaming(Some(app.clone())) {
+            emit_error_and_hide_overlay(app, format!("录音失败: {}", e));
+        }
+    } else {
+        emit_error_and_hide_overlay(app, "录音器未初始化".to_string());
     }
 }
 
@@ -1445,6 +2049,18 @@ async fn start_app(
 ) -> Result<String, String> {
     tracing::info!("启动应用...");
 
+    // 未编译本地 Whisper 时拒绝启动，避免选中后每次转录才报错
+    if !cfg!(feature = "local-whisper")
+        && asr_config
+            .as_ref()
+            .is_some_and(|cfg| cfg.selection.active_provider == config::AsrProvider::LocalWhisper)
+    {
+        return Err(
+            "本地 Whisper 功能未启用。请使用 `--features local-whisper` 编译，或切换其他 ASR 提供商"
+                .to_string(),
+        );
+    }
+
     // 获取应用状态
     tracing::info!("[DEBUG] 获取应用状态...");
     let state = app_handle.state::<AppState>();
@@ -1472,16 +2088,25 @@ async fn start_app(
     // 确定是否使用实时模式
     let mut use_realtime_mode = use_realtime.unwrap_or(true);
 
-    // 强制覆盖：DoubaoIme 只支持流式模式
+    // 强制覆盖：DoubaoIme 只支持流式模式，本地 Whisper / OpenAI 兼容服务只支持非流式模式
     if let Some(ref cfg) = asr_config {
-        if matches!(
-            cfg.selection.active_provider,
-            config::AsrProvider::DoubaoIme
-        ) {
-            if !use_realtime_mode {
-                tracing::info!("豆包输入法只支持流式模式，已自动切换");
+        match cfg.selection.active_provider {
+            config::AsrProvider::DoubaoIme => {
+                if !use_realtime_mode {
+                    tracing::info!("豆包输入法只支持流式模式，已自动切换");
+                }
+                use_realtime_mode = true;
+            }
+            ref provider if provider.is_batch_only() => {
+                if use_realtime_mode {
+                    tracing::info!(
+                        "{} 只支持非流式模式，已自动切换",
+                        asr_provider_name(provider)
+                    );
+                }
+                use_realtime_mode = false;
             }
-            use_realtime_mode = true;
+            _ => {}
         }
     }
 
@@ -1526,64 +2151,51 @@ async fn start_app(
     // 保存词库到 state（用于 Realtime 模式热更新）
     *state.dictionary.lock().unwrap() = dict.clone();
 
-    // 根据 asr_config 初始化 ASR 客户端
+    // 根据 asr_config 初始化 ASR 引擎注册表
     {
-        *state.qwen_client.lock().unwrap() = None;
-        *state.sensevoice_client.lock().unwrap() = None;
-        *state.doubao_client.lock().unwrap() = None;
-
-        if let Some(ref cfg) = asr_config {
-            // 初始化所有有凭证的客户端
-            if !cfg.credentials.qwen_api_key.is_empty() {
-                *state.qwen_client.lock().unwrap() = Some(QwenASRClient::new(
-                    cfg.credentials.qwen_api_key.clone(),
-                    dict.clone(),
-                    cfg.language_mode,
-                ));
-            }
-            if !cfg.credentials.sensevoice_api_key.is_empty() {
-                *state.sensevoice_client.lock().unwrap() = Some(SenseVoiceClient::new(
-                    cfg.credentials.sensevoice_api_key.clone(),
-                ));
-            }
-            if !cfg.credentials.doubao_app_id.is_empty()
-                && !cfg.credentials.doubao_access_token.is_empty()
-            {
-                *state.doubao_client.lock().unwrap() = Some(DoubaoASRClient::new(
-                    cfg.credentials.doubao_app_id.clone(),
-                    cfg.credentials.doubao_access_token.clone(),
-                    dict.clone(),
-                    cfg.language_mode,
-                ));
-            }
-
+        let asr_cfg = if let Some(ref cfg) = asr_config {
             // 设置实时转录提供商
             *state.realtime_provider.lock().unwrap() = Some(cfg.selection.active_provider.clone());
-            *state.fallback_provider.lock().unwrap() = cfg.selection.fallback_provider.clone();
+            cfg.clone()
         } else {
             // 旧逻辑回退（基本不会走到这里）
-            if !api_key.is_empty() {
-                *state.qwen_client.lock().unwrap() = Some(QwenASRClient::new(
-                    api_key.clone(),
-                    dict.clone(),
-                    config::AsrLanguageMode::Auto,
-                ));
-            }
-            if !fallback_api_key.is_empty() {
-                *state.sensevoice_client.lock().unwrap() =
-                    Some(SenseVoiceClient::new(fallback_api_key.clone()));
+            let mut cfg = config::AsrConfig::default();
+            cfg.credentials.qwen_api_key = api_key.clone();
+            cfg.credentials.sensevoice_api_key = fallback_api_key.clone();
+            cfg
+        };
+        *state.asr_engines.lock().unwrap() = AsrEngineRegistry::from_config(&asr_cfg, &dict);
+        // 引擎已重建（凭据、模型缓存随之重置），下次按键重新预热
+        state.asr_prewarmer.invalidate();
+        *state.asr_language_mode.lock().unwrap() = asr_cfg.language_mode;
+
+        // 旧版豆包输入法凭据保存在主配置中：迁移到独立存储后从主配置移除
+        if !asr_cfg.credentials.doubao_ime_device_id.is_empty() {
+            state
+                .doubao_ime_credentials
+                .import_legacy(&asr_cfg.credentials);
+            if let Err(e) = clear_legacy_doubao_ime_credentials(&app_handle) {
+                tracing::error!("移除主配置中的豆包输入法凭据失败: {}", e);
             }
         }
     }
 
     // 存储 fallback 配置
     {
-        let enable_fb = asr_config
+        let selection = asr_config
             .as_ref()
-            .map(|c| c.selection.enable_fallback)
-            .unwrap_or(false);
-        *state.enable_fallback.lock().unwrap() = enable_fb;
-        tracing::info!("并行 fallback: {}", if enable_fb { "启用" } else { "禁用" });
+            .map(|c| c.selection.clone())
+            .unwrap_or_default();
+        if selection.enable_fallback {
+            tracing::info!(
+                "备用 ASR: 启用 (策略: {:?}, 顺序: {:?})",
+                selection.strategy.policy,
+                selection.provider_order()
+            );
+        } else {
+            tracing::info!("备用 ASR: 禁用");
+        }
+        *state.asr_selection.lock().unwrap() = selection;
     }
 
     // 初始化 LLM 后处理器（复用连接）
@@ -1661,12 +2273,20 @@ async fn start_app(
     *state.streaming_recorder.lock().unwrap() = None;
 
     if use_realtime_mode {
-        let streaming_recorder =
-            StreamingRecorder::new().map_err(|e| format!("初始化流式录音器失败: {}", e))?;
+        let streaming_recorder = StreamingRecorder::new(
+            Arc::clone(&state.preferred_input_devices),
+            Arc::clone(&state.vad_config),
+            Arc::clone(&state.noise_suppression),
+        )
+        .map_err(|e| format!("初始化流式录音器失败: {}", e))?;
         *state.streaming_recorder.lock().unwrap() = Some(streaming_recorder);
     } else {
-        let audio_recorder =
-            AudioRecorder::new().map_err(|e| format!("初始化音频录制器失败: {}", e))?;
+        let audio_recorder = AudioRecorder::new(
+            Arc::clone(&state.preferred_input_devices),
+            Arc::clone(&state.vad_config),
+            Arc::clone(&state.noise_suppression),
+        )
+        .map_err(|e| format!("初始化音频录制器失败: {}", e))?;
         *state.audio_recorder.lock().unwrap() = Some(audio_recorder);
     }
 
@@ -1693,9 +2313,7 @@ async fn start_app(
     let app_handle_start = app_handle.clone();
     let audio_recorder_start = Arc::clone(&state.audio_recorder);
     let streaming_recorder_start = Arc::clone(&state.streaming_recorder);
-    let active_session_start = Arc::clone(&state.active_session);
-    let doubao_session_start = Arc::clone(&state.doubao_session);
-    let doubao_ime_session_start = Arc::clone(&state.doubao_ime_session);
+    let realtime_session_start = Arc::clone(&state.realtime_session);
     let doubao_ime_credentials_start = Arc::clone(&state.doubao_ime_credentials);
     let realtime_provider_start = Arc::clone(&state.realtime_provider);
     let audio_sender_handle_start = Arc::clone(&state.audio_sender_handle);
@@ -1718,60 +2336,55 @@ async fn start_app(
                 Some(cfg.credentials.doubao_app_id.clone()),
                 Some(cfg.credentials.doubao_access_token.clone()),
             ),
-            config::AsrProvider::DoubaoIme => {
-                // 豆包输入法模式：加载已保存的凭据（如果有的话）
-                if !cfg.credentials.doubao_ime_device_id.is_empty()
-                    && !cfg.credentials.doubao_ime_token.is_empty()
-                {
-                    let saved_creds = DoubaoImeCredentials {
-                        device_id: cfg.credentials.doubao_ime_device_id.clone(),
-                        token: cfg.credentials.doubao_ime_token.clone(),
-                        cdid: cfg.credentials.doubao_ime_cdid.clone(),
-                        ..Default::default()
-                    };
-                    *state.doubao_ime_credentials.lock().unwrap() = Some(saved_creds);
-                    tracing::info!("已加载保存的豆包输入法凭据");
-                }
-                (String::new(), None, None)
-            }
+            config::AsrProvider::DoubaoIme => (String::new(), None, None),
             config::AsrProvider::SiliconFlow => {
                 (cfg.credentials.sensevoice_api_key.clone(), None, None)
             }
+            config::AsrProvider::LocalWhisper
+            | config::AsrProvider::OpenAiCompatible(_)
+            | config::AsrProvider::WebSocket(_) => (String::new(), None, None),
         }
     } else {
         (String::new(), None, None)
     };
+    // 自建 WebSocket 流式服务的连接配置
+    let websocket_provider_start =
+        asr_config
+            .as_ref()
+            .and_then(|cfg| match &cfg.selection.active_provider {
+                config::AsrProvider::WebSocket(id) => cfg.websocket_provider(id).cloned(),
+                _ => None,
+            });
     let api_key_start = asr_api_key.clone();
     let doubao_app_id_start = doubao_app_id;
     let doubao_access_token_start = doubao_access_token;
-    let asr_language_mode_start = asr_config
-        .as_ref()
-        .map(|cfg| cfg.language_mode)
-        .unwrap_or(config::AsrLanguageMode::Auto);
+    let asr_language_mode_start = Arc::clone(&state.asr_language_mode);
 
     let app_handle_stop = app_handle.clone();
     let audio_recorder_stop = Arc::clone(&state.audio_recorder);
     let streaming_recorder_stop = Arc::clone(&state.streaming_recorder);
-    let active_session_stop = Arc::clone(&state.active_session);
+    let realtime_session_stop = Arc::clone(&state.realtime_session);
     let audio_sender_handle_stop = Arc::clone(&state.audio_sender_handle);
     let post_processor_stop = Arc::clone(&state.post_processor);
     let assistant_processor_stop = Arc::clone(&state.assistant_processor);
     let text_inserter_stop = Arc::clone(&state.text_inserter);
-    let qwen_client_stop = Arc::clone(&state.qwen_client);
-    let sensevoice_client_stop = Arc::clone(&state.sensevoice_client);
-    let doubao_client_stop = Arc::clone(&state.doubao_client);
-    let doubao_session_stop = Arc::clone(&state.doubao_session);
-    let doubao_ime_session_stop = Arc::clone(&state.doubao_ime_session);
+    let asr_engines_stop = Arc::clone(&state.asr_engines);
     let realtime_provider_stop = Arc::clone(&state.realtime_provider);
     let use_realtime_stop = use_realtime_mode;
     let is_running_stop = Arc::clone(&state.is_running);
-    let enable_fallback_stop = Arc::clone(&state.enable_fallback);
+    let asr_selection_stop = Arc::clone(&state.asr_selection);
 
     // 松手模式相关变量（用于 on_start）
     let is_recording_locked_start = Arc::clone(&state.is_recording_locked);
     let _lock_timer_handle_start = Arc::clone(&state.lock_timer_handle);
     let _recording_start_time_start = Arc::clone(&state.recording_start_time);
-    let _dual_hotkey_cfg_start = dual_hotkey_cfg.clone();
+    let dual_hotkey_cfg_start = dual_hotkey_cfg.clone();
+
+    // 免提自动结束与开放麦克风（用于 on_start）
+    let auto_stop_start = Arc::clone(&state.auto_stop);
+    let open_mic_config_start = Arc::clone(&state.open_mic_config);
+    let open_mic_start = Arc::clone(&state.open_mic);
+    let wake_word_start = Arc::clone(&state.wake_word);
 
     // 松手模式相关变量（用于 on_stop）
     let is_recording_locked_stop = Arc::clone(&state.is_recording_locked);
@@ -1793,6 +2406,10 @@ async fn start_app(
 
     // 按键按下回调（支持双模式 + 松手模式）
     let on_start = move |trigger_mode: config::TriggerMode, is_release_mode: bool| {
+        // 本次录音是否由开放麦克风发起（先取出标记，避免被忽略的触发残留到下一次按键）
+        let open_mic_session = open_mic_start.take_session_request();
+        let wake_word_session = wake_word_start.begin_session();
+
         // === 防重入检查必须在保存窗口句柄之前 ===
         // 避免松手模式下误触热键覆盖正确的目标窗口句柄
         if is_recording_locked_start.load(Ordering::SeqCst) {
@@ -1824,17 +2441,25 @@ async fn start_app(
         };
         tracing::info!("触发模式: {:?} ({})", trigger_mode, mode_desc);
 
+        // 切换模式与松手锁定模式无需按住快捷键，可免提自动结束
+        let trigger_hotkey_mode = match trigger_mode {
+            config::TriggerMode::Dictation => dual_hotkey_cfg_start.dictation.mode,
+            config::TriggerMode::AiAssistant => dual_hotkey_cfg_start.assistant.mode,
+        };
+        let hands_free = is_release_mode || trigger_hotkey_mode == config::HotkeyMode::Toggle;
+
         // 注意：剪贴板捕获已移至 on_stop 回调
         // 原因：在 on_start 时物理按键仍被按住，模拟 Ctrl+C 会与 Alt/Meta 等修饰键冲突
 
-        beep_player::play_start_beep();
+        // 开放麦克风会话不播放开始提示音，避免提示音被录进会话开头
+        if !open_mic_session {
+            beep_player::play_start_beep();
+        }
 
         let app = app_handle_start.clone();
         let recorder = Arc::clone(&audio_recorder_start);
         let streaming_recorder = Arc::clone(&streaming_recorder_start);
-        let active_session = Arc::clone(&active_session_start);
-        let doubao_session = Arc::clone(&doubao_session_start);
-        let doubao_ime_session = Arc::clone(&doubao_ime_session_start);
+        let realtime_session = Arc::clone(&realtime_session_start);
         let doubao_ime_credentials = Arc::clone(&doubao_ime_credentials_start);
         let realtime_provider = Arc::clone(&realtime_provider_start);
         let audio_sender_handle = Arc::clone(&audio_sender_handle_start);
@@ -1842,7 +2467,8 @@ async fn start_app(
         let api_key = api_key_start.clone();
         let doubao_app_id = doubao_app_id_start.clone();
         let doubao_access_token = doubao_access_token_start.clone();
-        let language_mode = asr_language_mode_start;
+        let websocket_provider = websocket_provider_start.clone();
+        let language_mode_state = Arc::clone(&asr_language_mode_start);
         let is_recording_locked_spawn = Arc::clone(&is_recording_locked_start);
         let audio_mute_manager = Arc::clone(&audio_mute_manager_start);
         let dictionary_state = Arc::clone(&dictionary_state_start);
@@ -1853,16 +2479,15 @@ async fn start_app(
             // 注意：这个时间略早于实际音频采集开始，但包含了用户感知到的准备时间
             *recording_start_instant_spawn.lock().unwrap() = Some(std::time::Instant::now());
 
-            // 从 state 获取最新词库（支持热更新）
+            // 从 state 获取最新词库与识别语言（支持热更新）
             let dictionary = dictionary_state.lock().unwrap().clone();
+            let language_mode = *language_mode_state.lock().unwrap();
             // 1. 先执行开始录音逻辑 (内部会发送 recording_started 事件)
             handle_recording_start(
                 app.clone(),
                 recorder,
                 streaming_recorder,
-                active_session,
-                doubao_session,
-                doubao_ime_session,
+                realtime_session,
                 doubao_ime_credentials,
                 realtime_provider,
                 audio_sender_handle,
@@ -1870,6 +2495,7 @@ async fn start_app(
                 api_key,
                 doubao_app_id,
                 doubao_access_token,
+                websocket_provider,
                 audio_mute_manager,
                 dictionary,
                 language_mode,
@@ -1883,6 +2509,23 @@ async fn start_app(
                 let _ = app.emit("recording_locked", ());
                 tracing::info!("通过松手模式快捷键启动，直接进入锁定状态");
             }
+
+            // 3. 免提自动结束（静音 / 最长时长）；开放麦克风与唤醒词会话始终在静音后结束
+            let auto_stop_config = if open_mic_session {
+                Some(open_mic_config_start.lock().unwrap().auto_stop())
+            } else if wake_word_session {
+                Some(config::AutoStopConfig {
+                    enabled: true,
+                    ..auto_stop_start.lock().unwrap().clone()
+                })
+            } else if hands_free {
+                Some(auto_stop_start.lock().unwrap().clone()).filter(|config| config.enabled)
+            } else {
+                None
+            };
+            if let Some(auto_stop_config) = auto_stop_config {
+                start_auto_stop(&app, use_realtime, &auto_stop_config);
+            }
         });
     };
 
@@ -1939,15 +2582,11 @@ async fn start_app(
         let app = app_handle_stop.clone();
         let recorder = Arc::clone(&audio_recorder_stop);
         let streaming_recorder = Arc::clone(&streaming_recorder_stop);
-        let active_session = Arc::clone(&active_session_stop);
+        let realtime_session = Arc::clone(&realtime_session_stop);
         let audio_sender_handle = Arc::clone(&audio_sender_handle_stop);
-        let qwen_client_state = Arc::clone(&qwen_client_stop);
-        let sensevoice_client_state = Arc::clone(&sensevoice_client_stop);
-        let doubao_client_state = Arc::clone(&doubao_client_stop);
-        let doubao_session_state = Arc::clone(&doubao_session_stop);
-        let doubao_ime_session_state = Arc::clone(&doubao_ime_session_stop);
+        let asr_engines_state = Arc::clone(&asr_engines_stop);
         let realtime_provider_state = Arc::clone(&realtime_provider_stop);
-        let enable_fallback_state = Arc::clone(&enable_fallback_stop);
+        let asr_selection_state = Arc::clone(&asr_selection_stop);
         let use_realtime = use_realtime_stop;
 
         // 根据触发模式选择处理器
@@ -1976,17 +2615,12 @@ async fn start_app(
                         handle_realtime_stop(
                             app,
                             streaming_recorder,
-                            active_session,
-                            doubao_session_state,
-                            doubao_ime_session_state,
-                            realtime_provider_state,
+                            realtime_session,
                             audio_sender_handle,
                             post_processor,
                             text_inserter,
-                            qwen_client_state,
-                            sensevoice_client_state,
-                            doubao_client_state,
-                            enable_fallback_state,
+                            asr_engines_state,
+                            asr_selection_state,
                             target_hwnd,
                             usage_stats.clone(),
                             recording_start_instant.clone(),
@@ -1998,10 +2632,8 @@ async fn start_app(
                             recorder,
                             post_processor,
                             text_inserter,
-                            qwen_client_state,
-                            sensevoice_client_state,
-                            doubao_client_state,
-                            enable_fallback_state,
+                            asr_engines_state,
+                            asr_selection_state,
                             target_hwnd,
                             usage_stats.clone(),
                             recording_start_instant.clone(),
@@ -2039,18 +2671,14 @@ async fn start_app(
                         app,
                         recorder,
                         streaming_recorder,
-                        active_session,
-                        doubao_session_state,
-                        doubao_ime_session_state,
+                        realtime_session,
                         realtime_provider_state,
                         audio_sender_handle,
                         assistant_processor,
                         clipboard_guard,
                         selected_text,
-                        qwen_client_state,
-                        sensevoice_client_state,
-                        doubao_client_state,
-                        enable_fallback_state,
+                        asr_engines_state,
+                        asr_selection_state,
                         use_realtime,
                         target_hwnd,
                         usage_stats.clone(),
@@ -2062,6 +2690,15 @@ async fn start_app(
         });
     };
 
+    // 语言切换快捷键回调（在热键线程中触发，切换逻辑只涉及配置与内存状态）
+    let app_handle_language = app_handle.clone();
+    hotkey_service.set_language_switch_callback(move || {
+        if let Err(e) = cycle_asr_language_mode(&app_handle_language) {
+            tracing::error!("快捷键切换识别语言失败: {}", e);
+            let _ = app_handle_language.emit("error", e);
+        }
+    });
+
     tracing::info!("[DEBUG] 准备激活热键服务...");
     hotkey_service
         .activate_dual(dual_hotkey_cfg.clone(), on_start, on_stop)
@@ -2070,6 +2707,7 @@ async fn start_app(
 
     // 标记为运行中（重新获取锁）
     *state.is_running.lock().unwrap() = true;
+    sync_background_listening(&app_handle);
     tracing::info!("[DEBUG] 启动完成!");
     let mode_str = if use_realtime_mode {
         "实时模式"
@@ -2091,18 +2729,14 @@ async fn handle_assistant_mode(
     app: AppHandle,
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    active_session: Arc<tokio::sync::Mutex<Option<RealtimeSession>>>,
-    doubao_session: Arc<tokio::sync::Mutex<Option<DoubaoRealtimeSession>>>,
-    doubao_ime_session: Arc<tokio::sync::Mutex<Option<DoubaoImeRealtimeSession>>>,
+    realtime_session: RealtimeSessionSlot,
     realtime_provider: Arc<Mutex<Option<config::AsrProvider>>>,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     assistant_processor: Arc<Mutex<Option<AssistantProcessor>>>,
     clipboard_guard: Option<clipboard_manager::ClipboardGuard>,
     selected_text: Option<String>,
-    qwen_client_state: Arc<Mutex<Option<QwenASRClient>>>,
-    sensevoice_client_state: Arc<Mutex<Option<SenseVoiceClient>>>,
-    doubao_client_state: Arc<Mutex<Option<DoubaoASRClient>>>,
-    enable_fallback_state: Arc<Mutex<bool>>,
+    asr_engines_state: Arc<Mutex<AsrEngineRegistry>>,
+    asr_selection_state: Arc<Mutex<config::AsrSelection>>,
     use_realtime: bool,
     target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
     usage_stats: Arc<Mutex<UsageStats>>,
@@ -2114,6 +2748,7 @@ async fn handle_assistant_mode(
     // 1. 停止录音并获取音频数据
     let (asr_result, audio_data) = if use_realtime {
         // 实时模式：先停止流式录音
+        let mut device_lost = None;
         let audio_data = {
             let mut recorder_guard = streaming_recorder.lock().unwrap();
             if let Some(ref mut rec) = *recorder_guard {
@@ -2121,6 +2756,9 @@ async fn handle_assistant_mode(
                     Ok(data) => Some(data),
                     Err(e) => {
                         tracing::error!("停止流式录音失败: {}", e);
+                        if audio_device::is_device_lost_error(&e) {
+                            device_lost = Some(e.to_string());
+                        }
                         None
                     }
                 }
@@ -2129,6 +2767,12 @@ async fn handle_assistant_mode(
             }
         };
 
+        if let Some(message) = device_lost {
+            abort_realtime_session(&realtime_session, &audio_sender_handle).await;
+            emit_error_and_hide_overlay(&app, message);
+            return;
+        }
+
         // 等待音频发送任务完成
         {
             let handle = audio_sender_handle.lock().unwrap().take();
@@ -2139,45 +2783,21 @@ async fn handle_assistant_mode(
         }
 
         // 获取实时转录结果
-        let provider = realtime_provider.lock().unwrap().clone();
-        let result = match provider {
-            Some(config::AsrProvider::Doubao) => {
-                let mut session_guard = doubao_session.lock().await;
-                if let Some(ref mut session) = *session_guard {
-                    let _ = session.finish_audio().await;
-                    let res = session.wait_for_result().await;
-                    drop(session_guard);
-                    *doubao_session.lock().await = None;
-                    res
-                } else {
-                    Err(anyhow::anyhow!("没有活跃的豆包会话"))
-                }
-            }
-            Some(config::AsrProvider::DoubaoIme) => {
-                let mut session_guard = doubao_ime_session.lock().await;
-                if let Some(ref mut session) = *session_guard {
-                    let _ = session.finish_audio().await;
-                    let res = session.wait_for_result().await;
-                    drop(session_guard);
-                    *doubao_ime_session.lock().await = None;
-                    res
-                } else {
-                    Err(anyhow::anyhow!("没有活跃的豆包输入法会话"))
-                }
-            }
-            _ => {
-                let mut session_guard = active_session.lock().await;
-                if let Some(ref mut session) = *session_guard {
-                    let _ = session.commit_audio().await;
-                    let res = session.wait_for_result().await;
-                    let _ = session.close().await;
-                    drop(session_guard);
-                    *active_session.lock().await = None;
-                    res
-                } else {
-                    Err(anyhow::anyhow!("没有活跃的千问会话"))
+        let session = realtime_session.lock().await.take();
+        let result = if let Some(mut session) = session {
+            let _ = session.finish().await;
+            let res = session.wait_for_result().await;
+            let _ = session.close().await;
+            let health = &app.state::<AppState>().asr_health;
+            match &res {
+                Ok(_) => health.record_success(&session.provider(), asr_start.elapsed()),
+                Err(e) => {
+                    health.record_failure(&session.provider(), e, asr_start.elapsed());
                 }
             }
+            res
+        } else {
+            Err(anyhow::anyhow!("没有活跃的实时转录会话"))
         };
 
         (result, audio_data)
@@ -2205,26 +2825,21 @@ async fn handle_assistant_mode(
 
         let result = if let Some(ref data) = audio_data {
             // 使用 HTTP ASR
-            let enable_fb = *enable_fallback_state.lock().unwrap();
-            let qwen = { qwen_client_state.lock().unwrap().clone() };
-            let doubao = { doubao_client_state.lock().unwrap().clone() };
-            let sensevoice = { sensevoice_client_state.lock().unwrap().clone() };
+            let selection = { asr_selection_state.lock().unwrap().clone() };
+            let engines = { asr_engines_state.lock().unwrap().clone() };
             let active_prov = realtime_provider.lock().unwrap().clone();
-            let fallback_prov = app
-                .state::<AppState>()
-                .fallback_provider
-                .lock()
-                .unwrap()
-                .clone();
 
+            let health = Arc::clone(&app.state::<AppState>().asr_health);
+
+            let vad = app.state::<AppState>().vad_config.lock().unwrap().clone();
             transcribe_with_available_clients(
-                qwen,
-                doubao,
-                sensevoice,
+                engines,
+                &health,
+                &vad,
                 data,
-                enable_fb,
+                selection,
                 active_prov,
-                fallback_prov,
+                false,
                 "(AI助手HTTP) ",
             )
             .await
@@ -2238,37 +2853,23 @@ async fn handle_assistant_mode(
     let asr_time_ms = asr_start.elapsed().as_millis() as u64;
 
     // 2. 如果实时模式失败且有音频数据，尝试 HTTP 备用
-    let final_result = if asr_result.is_err() && audio_data.is_some() {
+    let final_result = if let (Err(_), Some(data)) = (&asr_result, &audio_data) {
         tracing::warn!("实时 ASR 失败，尝试 HTTP 备用");
-        let data = audio_data.unwrap();
-        let enable_fb = *enable_fallback_state.lock().unwrap();
-        let qwen = { qwen_client_state.lock().unwrap().clone() };
-        let doubao = { doubao_client_state.lock().unwrap().clone() };
-        let sensevoice = { sensevoice_client_state.lock().unwrap().clone() };
+        let selection = { asr_selection_state.lock().unwrap().clone() };
+        let engines = { asr_engines_state.lock().unwrap().clone() };
         let active_prov = realtime_provider.lock().unwrap().clone();
-        let fallback_prov = app
-            .state::<AppState>()
-            .fallback_provider
-            .lock()
-            .unwrap()
-            .clone();
 
-        // DoubaoIme 不支持 HTTP 模式，直接使用 fallback_provider
-        let effective_active_prov = if matches!(active_prov, Some(config::AsrProvider::DoubaoIme)) {
-            tracing::info!("豆包输入法不支持 HTTP 备用模式，切换到 fallback provider");
-            fallback_prov.clone()
-        } else {
-            active_prov
-        };
+        let health = Arc::clone(&app.state::<AppState>().asr_health);
 
+        let vad = app.state::<AppState>().vad_config.lock().unwrap().clone();
         transcribe_with_available_clients(
-            qwen,
-            doubao,
-            sensevoice,
-            &data,
-            enable_fb,
-            effective_active_prov,
-            fallback_prov,
+            engines,
+            &health,
+            &vad,
+            data,
+            selection,
+            active_prov,
+            true,
             "(AI助手备用) ",
         )
         .await
@@ -2276,6 +2877,8 @@ async fn handle_assistant_mode(
         asr_result
     };
 
+    let final_result = strip_wake_word(&app, final_result);
+
     // 3. 使用 AssistantPipeline 处理
     let processor = { assistant_processor.lock().unwrap().clone() };
     let dictionary = {
@@ -2330,8 +2933,15 @@ async fn handle_assistant_mode(
                 total_time_ms: result.total_time_ms,
                 mode: Some(format!("{:?}", result.mode).to_lowercase()),
                 inserted: Some(result.inserted),
+                transcript: result.transcript,
             };
 
+            archive_recording(
+                &app,
+                audio_data,
+                config::TriggerMode::AiAssistant,
+                Some(&transcription_result),
+            );
             let _ = app.emit("transcription_complete", transcription_result);
         }
         Err(e) => {
@@ -2340,135 +2950,92 @@ async fn handle_assistant_mode(
             let _ = recording_start_instant.lock().unwrap().take();
             tracing::error!("AI 助手处理失败: {}", e);
             let _ = app.emit("error", format!("AI 助手处理失败: {}", e));
+            archive_recording(&app, audio_data, config::TriggerMode::AiAssistant, None);
         }
     }
 }
 
 /// 统一的 HTTP ASR 转录逻辑
 ///
-/// 根据配置的 active_provider 和 fallback_provider 选择合适的转录方式
+/// 按 AsrSelection 的提供商顺序从引擎注册表中取出引擎，再交给调度策略执行；长录音先在停顿处分段。
+/// `skip_doubao_ime` 用于实时会话失败后的回退：此时不再尝试豆包输入法
 async fn transcribe_with_available_clients(
-    qwen: Option<QwenASRClient>,
-    doubao: Option<DoubaoASRClient>,
-    sensevoice: Option<SenseVoiceClient>,
+    engines: AsrEngineRegistry,
+    health: &AsrHealthTracker,
+    vad: &config::VadConfig,
     audio_data: &[u8],
-    enable_fallback: bool,
+    mut selection: config::AsrSelection,
     active_provider: Option<config::AsrProvider>,
-    fallback_provider: Option<config::AsrProvider>,
+    skip_doubao_ime: bool,
     log_prefix: &str,
-) -> anyhow::Result<String> {
-    if enable_fallback {
-        // 根据配置的 active_provider 和 fallback_provider 选择客户端组合
-        match (active_provider.as_ref(), fallback_provider.as_ref()) {
-            (Some(config::AsrProvider::Qwen), Some(config::AsrProvider::SiliconFlow)) => {
-                if let (Some(q), Some(s)) = (&qwen, &sensevoice) {
-                    tracing::info!("{}使用千问+SenseVoice并行竞速", log_prefix);
-                    asr::transcribe_with_fallback_clients(q.clone(), s.clone(), audio_data.to_vec())
-                        .await
-                } else {
-                    Err(anyhow::anyhow!("千问或 SenseVoice 客户端未初始化"))
-                }
-            }
-            (Some(config::AsrProvider::Doubao), Some(config::AsrProvider::SiliconFlow)) => {
-                if let (Some(d), Some(s)) = (&doubao, &sensevoice) {
-                    tracing::info!("{}使用豆包+SenseVoice并行竞速", log_prefix);
-                    asr::transcribe_doubao_sensevoice_race(
-                        d.clone(),
-                        s.clone(),
-                        audio_data.to_vec(),
-                    )
-                    .await
-                } else {
-                    Err(anyhow::anyhow!("豆包或 SenseVoice 客户端未初始化"))
-                }
-            }
-            _ => {
-                // 其他组合或只有主客户端，使用主客户端
-                match active_provider {
-                    Some(config::AsrProvider::Qwen) => {
-                        if let Some(q) = qwen {
-                            tracing::info!("{}使用千问 ASR", log_prefix);
-                            q.transcribe_bytes(audio_data).await
-                        } else {
-                            Err(anyhow::anyhow!("千问客户端未初始化"))
-                        }
-                    }
-                    Some(config::AsrProvider::Doubao) => {
-                        if let Some(d) = doubao {
-                            tracing::info!("{}使用豆包 ASR", log_prefix);
-                            d.transcribe_bytes(audio_data).await
-                        } else {
-                            Err(anyhow::anyhow!("豆包客户端未初始化"))
-                        }
-                    }
-                    Some(config::AsrProvider::SiliconFlow) => {
-                        if let Some(s) = sensevoice {
-                            tracing::info!("{}使用 SenseVoice ASR", log_prefix);
-                            s.transcribe_bytes(audio_data).await
-                        } else {
-                            Err(anyhow::anyhow!("SenseVoice 客户端未初始化"))
-                        }
-                    }
-                    Some(config::AsrProvider::DoubaoIme) => {
-                        // 豆包输入法目前只支持实时流式模式，不支持 HTTP 模式
-                        Err(anyhow::anyhow!("豆包输入法 ASR 不支持 HTTP 模式"))
-                    }
-                    None => {
-                        tracing::error!("{}未配置 ASR 提供商", log_prefix);
-                        Err(anyhow::anyhow!("ASR 提供商未配置"))
-                    }
-                }
-            }
+) -> anyhow::Result<asr::AsrTranscript> {
+    let Some(active_provider) = active_provider else {
+        tracing::error!("{}未配置 ASR 提供商", log_prefix);
+        return Err(anyhow::anyhow!("ASR 提供商未配置"));
+    };
+    selection.active_provider = active_provider;
+
+    let mut providers = selection.provider_order();
+    if skip_doubao_ime && providers.first() == Some(&config::AsrProvider::DoubaoIme) {
+        // DoubaoIme 不支持 HTTP 模式，直接使用 fallback_provider
+        tracing::info!("豆包输入法不支持 HTTP 备用模式，切换到 fallback provider");
+        providers.retain(|p| *p != config::AsrProvider::DoubaoIme);
+        if providers.is_empty() {
+            providers.extend(selection.fallback_provider.clone());
         }
-    } else {
-        // 非 fallback 模式：只使用主客户端
-        match active_provider {
-            Some(config::AsrProvider::Qwen) => {
-                if let Some(q) = qwen {
-                    tracing::info!("{}使用千问 ASR", log_prefix);
-                    q.transcribe_bytes(audio_data).await
-                } else {
-                    Err(anyhow::anyhow!("千问客户端未初始化"))
-                }
-            }
-            Some(config::AsrProvider::Doubao) => {
-                if let Some(d) = doubao {
-                    tracing::info!("{}使用豆包 ASR", log_prefix);
-                    d.transcribe_bytes(audio_data).await
-                } else {
-                    Err(anyhow::anyhow!("豆包客户端未初始化"))
-                }
-            }
-            Some(config::AsrProvider::SiliconFlow) => {
-                if let Some(s) = sensevoice {
-                    tracing::info!("{}使用 SenseVoice ASR", log_prefix);
-                    s.transcribe_bytes(audio_data).await
-                } else {
-                    Err(anyhow::anyhow!("SenseVoice 客户端未初始化"))
-                }
-            }
-            Some(config::AsrProvider::DoubaoIme) => {
-                // 豆包输入法目前只支持实时流式模式，不支持 HTTP 模式
-                Err(anyhow::anyhow!("豆包输入法 ASR 不支持 HTTP 模式"))
-            }
-            None => {
-                tracing::error!("{}未配置 ASR 提供商", log_prefix);
-                Err(anyhow::anyhow!("ASR 提供商未配置"))
-            }
+    }
+    let Some(primary_provider) = providers.first().cloned() else {
+        tracing::error!("{}未配置 ASR 提供商", log_prefix);
+        return Err(anyhow::anyhow!("ASR 提供商未配置"));
+    };
+
+    let mut selected = Vec::with_capacity(providers.len());
+    for provider in &providers {
+        match engines.get(provider) {
+            Some(engine) => selected.push(engine),
+            None => tracing::warn!(
+                "{}{}客户端未初始化，跳过",
+                log_prefix,
+                asr_provider_name(provider)
+            ),
         }
     }
-}
+    if selected.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{}客户端未初始化",
+            asr_provider_name(&primary_provider)
+        ));
+    }
 
-/// HTTP 模式转录处理（听写模式专用）
-async fn handle_http_transcription(
-    app: AppHandle,
-    recorder: Arc<Mutex<Option<AudioRecorder>>>,
+    let names: Vec<&str> = selected.iter().map(|e| e.name()).collect();
+    if selected.len() > 1 {
+        tracing::info!(
+            "{}使用{}调度 ({:?})",
+            log_prefix,
+            names.join("+"),
+            selection.strategy.policy
+        );
+    } else {
+        tracing::info!("{}使用{} ASR", log_prefix, names[0]);
+    }
+    asr::transcribe_long_form(
+        selected,
+        audio_data.to_vec(),
+        &selection.strategy,
+        health,
+        vad,
+    )
+    .await
+}
+
+/// HTTP 模式转录处理（听写模式专用）
+async fn handle_http_transcription(
+    app: AppHandle,
+    recorder: Arc<Mutex<Option<AudioRecorder>>>,
     post_processor: Arc<Mutex<Option<LlmPostProcessor>>>,
     text_inserter: Arc<Mutex<Option<TextInserter>>>,
-    qwen_client_state: Arc<Mutex<Option<QwenASRClient>>>,
-    sensevoice_client_state: Arc<Mutex<Option<SenseVoiceClient>>>,
-    doubao_client_state: Arc<Mutex<Option<DoubaoASRClient>>>,
-    enable_fallback_state: Arc<Mutex<bool>>,
+    asr_engines_state: Arc<Mutex<AsrEngineRegistry>>,
+    asr_selection_state: Arc<Mutex<config::AsrSelection>>,
     target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
     usage_stats: Arc<Mutex<UsageStats>>,
     recording_start_instant: Arc<Mutex<Option<std::time::Instant>>>,
@@ -2497,32 +3064,27 @@ async fn handle_http_transcription(
     if let Some(audio_data) = audio_data {
         let _ = app.emit("transcribing", ());
 
-        let enable_fallback = *enable_fallback_state.lock().unwrap();
-        let qwen = { qwen_client_state.lock().unwrap().clone() };
-        let doubao = { doubao_client_state.lock().unwrap().clone() };
-        let sensevoice = { sensevoice_client_state.lock().unwrap().clone() };
+        let selection = { asr_selection_state.lock().unwrap().clone() };
+        let engines = { asr_engines_state.lock().unwrap().clone() };
         let active_prov = app
             .state::<AppState>()
             .realtime_provider
             .lock()
             .unwrap()
             .clone();
-        let fallback_prov = app
-            .state::<AppState>()
-            .fallback_provider
-            .lock()
-            .unwrap()
-            .clone();
 
+        let health = Arc::clone(&app.state::<AppState>().asr_health);
+
+        let vad = app.state::<AppState>().vad_config.lock().unwrap().clone();
         let asr_start = std::time::Instant::now();
         let result = transcribe_with_available_clients(
-            qwen,
-            doubao,
-            sensevoice,
+            engines,
+            &health,
+            &vad,
             &audio_data,
-            enable_fallback,
+            selection,
             active_prov,
-            fallback_prov,
+            false,
             "(HTTP) ",
         )
         .await;
@@ -2537,6 +3099,7 @@ async fn handle_http_transcription(
             target_hwnd,
             usage_stats,
             recording_start_instant,
+            Some(audio_data),
         )
         .await;
     }
@@ -2546,26 +3109,21 @@ async fn handle_http_transcription(
 async fn handle_realtime_stop(
     app: AppHandle,
     streaming_recorder: Arc<Mutex<Option<StreamingRecorder>>>,
-    active_session: Arc<tokio::sync::Mutex<Option<RealtimeSession>>>,
-    doubao_session: Arc<tokio::sync::Mutex<Option<DoubaoRealtimeSession>>>,
-    doubao_ime_session: Arc<tokio::sync::Mutex<Option<DoubaoImeRealtimeSession>>>,
-    realtime_provider: Arc<Mutex<Option<config::AsrProvider>>>,
+    realtime_session: RealtimeSessionSlot,
     audio_sender_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     post_processor: Arc<Mutex<Option<LlmPostProcessor>>>,
     text_inserter: Arc<Mutex<Option<TextInserter>>>,
-    qwen_client_state: Arc<Mutex<Option<QwenASRClient>>>,
-    sensevoice_client_state: Arc<Mutex<Option<SenseVoiceClient>>>,
-    doubao_client_state: Arc<Mutex<Option<DoubaoASRClient>>>,
-    enable_fallback_state: Arc<Mutex<bool>>,
+    asr_engines_state: Arc<Mutex<AsrEngineRegistry>>,
+    asr_selection_state: Arc<Mutex<config::AsrSelection>>,
     target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
     usage_stats: Arc<Mutex<UsageStats>>,
     recording_start_instant: Arc<Mutex<Option<std::time::Instant>>>,
 ) {
     let _ = app.emit("transcribing", ());
     let asr_start = std::time::Instant::now();
-    let enable_fb = *enable_fallback_state.lock().unwrap();
 
     // 1. 停止流式录音，获取完整音频数据（用于备用方案）
+    let mut device_lost = None;
     let audio_data = {
         let mut recorder_guard = streaming_recorder.lock().unwrap();
         if let Some(ref mut rec) = *recorder_guard {
@@ -2573,6 +3131,9 @@ async fn handle_realtime_stop(
                 Ok(data) => Some(data),
                 Err(e) => {
                     tracing::error!("停止流式录音失败: {}", e);
+                    if audio_device::is_device_lost_error(&e) {
+                        device_lost = Some(e.to_string());
+                    }
                     None
                 }
             }
@@ -2581,6 +3142,13 @@ async fn handle_realtime_stop(
         }
     };
 
+    // 录音设备中途断开：丢弃实时会话并提示，避免把断开后的静音当作结果
+    if let Some(message) = device_lost {
+        abort_realtime_session(&realtime_session, &audio_sender_handle).await;
+        emit_error_and_hide_overlay(&app, message);
+        return;
+    }
+
     // 2. 等待音频发送任务完成
     {
         let handle = audio_sender_handle.lock().unwrap().take();
@@ -2590,308 +3158,105 @@ async fn handle_realtime_stop(
         }
     }
 
-    // 3. 检查使用的是哪个 provider
-    let provider = realtime_provider.lock().unwrap().clone();
+    // 3. 取出实时会话（各提供商统一走 RealtimeAsrSession 流程）
+    let session = realtime_session.lock().await.take();
 
-    match provider {
-        Some(config::AsrProvider::Doubao) => {
-            // 处理豆包流式会话
-            let mut doubao_session_guard = doubao_session.lock().await;
-            if let Some(ref mut session) = *doubao_session_guard {
-                tracing::info!("豆包：发送 finish 并等待转录结果...");
-
-                // 发送 finish
-                if let Err(e) = session.finish_audio().await {
-                    tracing::error!("豆包发送 finish 失败: {}", e);
-                    drop(doubao_session_guard);
-                    // 回退到备用方案
-                    if let Some(audio_data) = audio_data {
-                        fallback_transcription(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Arc::clone(&qwen_client_state),
-                            Arc::clone(&sensevoice_client_state),
-                            Arc::clone(&doubao_client_state),
-                            audio_data,
-                            enable_fb,
-                            target_hwnd,
-                            Arc::clone(&usage_stats),
-                            Arc::clone(&recording_start_instant),
-                        )
-                        .await;
-                    }
-                    return;
-                }
-
-                // 等待转录结果
-                match session.wait_for_result().await {
-                    Ok(text) => {
-                        let asr_time_ms = asr_start.elapsed().as_millis() as u64;
-                        tracing::info!("豆包实时转录成功: {} (ASR 耗时: {}ms)", text, asr_time_ms);
-                        drop(doubao_session_guard);
-                        *doubao_session.lock().await = None;
-                        handle_transcription_result(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Ok(text),
-                            asr_time_ms,
-                            target_hwnd,
-                            usage_stats,
-                            recording_start_instant,
-                        )
-                        .await;
-                    }
-                    Err(e) => {
-                        tracing::warn!("豆包等待转录结果失败: {}，尝试备用方案", e);
-                        drop(doubao_session_guard);
-                        *doubao_session.lock().await = None;
-
-                        // 回退到备用方案
-                        if let Some(audio_data) = audio_data {
-                            fallback_transcription(
-                                app,
-                                post_processor,
-                                text_inserter,
-                                Arc::clone(&qwen_client_state),
-                                Arc::clone(&sensevoice_client_state),
-                                Arc::clone(&doubao_client_state),
-                                audio_data,
-                                enable_fb,
-                                target_hwnd,
-                                Arc::clone(&usage_stats),
-                                Arc::clone(&recording_start_instant),
-                            )
-                            .await;
-                        } else {
-                            emit_error_and_hide_overlay(&app, format!("转录失败: {}", e));
-                        }
-                    }
-                }
-            } else {
-                // 没有活跃的豆包会话，使用备用方案
-                tracing::warn!("没有活跃的豆包 WebSocket 会话，使用备用方案");
-                drop(doubao_session_guard);
-
-                if let Some(audio_data) = audio_data {
-                    fallback_transcription(
-                        app,
-                        post_processor,
-                        text_inserter,
-                        Arc::clone(&qwen_client_state),
-                        Arc::clone(&sensevoice_client_state),
-                        Arc::clone(&doubao_client_state),
-                        audio_data,
-                        enable_fb,
-                        target_hwnd,
-                        Arc::clone(&usage_stats),
-                        Arc::clone(&recording_start_instant),
-                    )
-                    .await;
-                } else {
-                    emit_error_and_hide_overlay(&app, "没有录制到音频数据".to_string());
-                }
-            }
+    let Some(mut session) = session else {
+        // 没有活跃会话，使用备用方案（可能是连接失败时的回退）
+        tracing::warn!("没有活跃的实时 WebSocket 会话，使用备用方案");
+        if let Some(audio_data) = audio_data {
+            fallback_transcription(
+                app,
+                post_processor,
+                text_inserter,
+                asr_engines_state,
+                asr_selection_state,
+                audio_data,
+                target_hwnd,
+                usage_stats,
+                recording_start_instant,
+            )
+            .await;
+        } else {
+            emit_error_and_hide_overlay(&app, "没有录制到音频数据".to_string());
         }
-        Some(config::AsrProvider::DoubaoIme) => {
-            let mut doubao_ime_session_guard = doubao_ime_session.lock().await;
-            if let Some(ref mut session) = *doubao_ime_session_guard {
-                tracing::info!("豆包输入法：发送 finish 并等待转录结果...");
-
-                if let Err(e) = session.finish_audio().await {
-                    tracing::error!("豆包输入法发送 finish 失败: {}", e);
-                    drop(doubao_ime_session_guard);
-                    if let Some(audio_data) = audio_data {
-                        fallback_transcription(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Arc::clone(&qwen_client_state),
-                            Arc::clone(&sensevoice_client_state),
-                            Arc::clone(&doubao_client_state),
-                            audio_data,
-                            enable_fb,
-                            target_hwnd,
-                            Arc::clone(&usage_stats),
-                            Arc::clone(&recording_start_instant),
-                        )
-                        .await;
-                    }
-                    return;
-                }
+        return;
+    };
 
-                match session.wait_for_result().await {
-                    Ok(text) => {
-                        let asr_time_ms = asr_start.elapsed().as_millis() as u64;
-                        tracing::info!(
-                            "豆包输入法实时转录成功: {} (ASR 耗时: {}ms)",
-                            text,
-                            asr_time_ms
-                        );
-                        drop(doubao_ime_session_guard);
-                        *doubao_ime_session.lock().await = None;
-                        handle_transcription_result(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Ok(text),
-                            asr_time_ms,
-                            target_hwnd,
-                            usage_stats,
-                            recording_start_instant,
-                        )
-                        .await;
-                    }
-                    Err(e) => {
-                        tracing::warn!("豆包输入法等待转录结果失败: {}，尝试备用方案", e);
-                        drop(doubao_ime_session_guard);
-                        *doubao_ime_session.lock().await = None;
-
-                        if let Some(audio_data) = audio_data {
-                            fallback_transcription(
-                                app,
-                                post_processor,
-                                text_inserter,
-                                Arc::clone(&qwen_client_state),
-                                Arc::clone(&sensevoice_client_state),
-                                Arc::clone(&doubao_client_state),
-                                audio_data,
-                                enable_fb,
-                                target_hwnd,
-                                Arc::clone(&usage_stats),
-                                Arc::clone(&recording_start_instant),
-                            )
-                            .await;
-                        } else {
-                            emit_error_and_hide_overlay(&app, format!("转录失败: {}", e));
-                        }
-                    }
-                }
-            } else {
-                tracing::warn!("没有活跃的豆包输入法 WebSocket 会话，使用备用方案");
-                drop(doubao_ime_session_guard);
+    let provider = session.provider();
+    let provider_name = asr_provider_name(&provider);
+    let health = Arc::clone(&app.state::<AppState>().asr_health);
+    tracing::info!("{}：发送结束信号并等待转录结果...", provider_name);
+
+    // 发送结束信号
+    if let Err(e) = session.finish().await {
+        tracing::error!("{}发送结束信号失败: {}", provider_name, e);
+        health.record_failure(&provider, &e, asr_start.elapsed());
+        let _ = session.close().await;
+        // 回退到备用方案
+        if let Some(audio_data) = audio_data {
+            fallback_transcription(
+                app,
+                post_processor,
+                text_inserter,
+                asr_engines_state,
+                asr_selection_state,
+                audio_data,
+                target_hwnd,
+                usage_stats,
+                recording_start_instant,
+            )
+            .await;
+        }
+        return;
+    }
 
-                if let Some(audio_data) = audio_data {
-                    fallback_transcription(
-                        app,
-                        post_processor,
-                        text_inserter,
-                        Arc::clone(&qwen_client_state),
-                        Arc::clone(&sensevoice_client_state),
-                        Arc::clone(&doubao_client_state),
-                        audio_data,
-                        enable_fb,
-                        target_hwnd,
-                        Arc::clone(&usage_stats),
-                        Arc::clone(&recording_start_instant),
-                    )
-                    .await;
-                } else {
-                    emit_error_and_hide_overlay(&app, "没有录制到音频数据".to_string());
-                }
-            }
+    // 等待转录结果
+    match session.wait_for_result().await {
+        Ok(transcript) => {
+            health.record_success(&provider, asr_start.elapsed());
+            let asr_time_ms = asr_start.elapsed().as_millis() as u64;
+            tracing::info!(
+                "{}实时转录成功: {} (ASR 耗时: {}ms)",
+                provider_name,
+                transcript.text(),
+                asr_time_ms
+            );
+            let _ = session.close().await;
+            handle_transcription_result(
+                app,
+                post_processor,
+                text_inserter,
+                Ok(transcript),
+                asr_time_ms,
+                target_hwnd,
+                usage_stats,
+                recording_start_instant,
+                audio_data,
+            )
+            .await;
         }
-        _ => {
-            // 处理千问流式会话
-            let mut session_guard = active_session.lock().await;
-            if let Some(ref mut session) = *session_guard {
-                tracing::info!("千问：发送 commit 并等待转录结果...");
-
-                // 发送 commit
-                if let Err(e) = session.commit_audio().await {
-                    tracing::error!("千问发送 commit 失败: {}", e);
-                    drop(session_guard);
-                    // 回退到备用方案
-                    if let Some(audio_data) = audio_data {
-                        fallback_transcription(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Arc::clone(&qwen_client_state),
-                            Arc::clone(&sensevoice_client_state),
-                            Arc::clone(&doubao_client_state),
-                            audio_data,
-                            enable_fb,
-                            target_hwnd,
-                            Arc::clone(&usage_stats),
-                            Arc::clone(&recording_start_instant),
-                        )
-                        .await;
-                    }
-                    return;
-                }
+        Err(e) => {
+            tracing::warn!("{}等待转录结果失败: {}，尝试备用方案", provider_name, e);
+            health.record_failure(&provider, &e, asr_start.elapsed());
+            let _ = session.close().await;
 
-                // 等待转录结果
-                match session.wait_for_result().await {
-                    Ok(text) => {
-                        let asr_time_ms = asr_start.elapsed().as_millis() as u64;
-                        tracing::info!("千问实时转录成功: {} (ASR 耗时: {}ms)", text, asr_time_ms);
-                        let _ = session.close().await;
-                        drop(session_guard);
-                        *active_session.lock().await = None;
-                        handle_transcription_result(
-                            app,
-                            post_processor,
-                            text_inserter,
-                            Ok(text),
-                            asr_time_ms,
-                            target_hwnd,
-                            usage_stats,
-                            recording_start_instant,
-                        )
-                        .await;
-                    }
-                    Err(e) => {
-                        tracing::warn!("千问等待转录结果失败: {}，尝试备用方案", e);
-                        let _ = session.close().await;
-                        drop(session_guard);
-                        *active_session.lock().await = None;
-
-                        // 回退到备用方案
-                        if let Some(audio_data) = audio_data {
-                            fallback_transcription(
-                                app,
-                                post_processor,
-                                text_inserter,
-                                Arc::clone(&qwen_client_state),
-                                Arc::clone(&sensevoice_client_state),
-                                Arc::clone(&doubao_client_state),
-                                audio_data,
-                                enable_fb,
-                                target_hwnd,
-                                Arc::clone(&usage_stats),
-                                Arc::clone(&recording_start_instant),
-                            )
-                            .await;
-                        } else {
-                            emit_error_and_hide_overlay(&app, format!("转录失败: {}", e));
-                        }
-                    }
-                }
+            // 回退到备用方案
+            if let Some(audio_data) = audio_data {
+                fallback_transcription(
+                    app,
+                    post_processor,
+                    text_inserter,
+                    asr_engines_state,
+                    asr_selection_state,
+                    audio_data,
+                    target_hwnd,
+                    usage_stats,
+                    recording_start_instant,
+                )
+                .await;
             } else {
-                // 没有活跃会话，使用备用方案（可能是连接失败时的回退）
-                tracing::warn!("没有活跃的千问 WebSocket 会话，使用备用方案");
-                drop(session_guard);
-
-                if let Some(audio_data) = audio_data {
-                    fallback_transcription(
-                        app,
-                        post_processor,
-                        text_inserter,
-                        Arc::clone(&qwen_client_state),
-                        Arc::clone(&sensevoice_client_state),
-                        Arc::clone(&doubao_client_state),
-                        audio_data,
-                        enable_fb,
-                        target_hwnd,
-                        Arc::clone(&usage_stats),
-                        Arc::clone(&recording_start_instant),
-                    )
-                    .await;
-                } else {
-                    emit_error_and_hide_overlay(&app, "没有录制到音频数据".to_string());
-                }
+                emit_error_and_hide_overlay(&app, format!("转录失败: {}", e));
             }
         }
     }
@@ -2902,48 +3267,34 @@ async fn fallback_transcription(
     app: AppHandle,
     post_processor: Arc<Mutex<Option<LlmPostProcessor>>>,
     text_inserter: Arc<Mutex<Option<TextInserter>>>,
-    qwen_client_state: Arc<Mutex<Option<QwenASRClient>>>,
-    sensevoice_client_state: Arc<Mutex<Option<SenseVoiceClient>>>,
-    doubao_client_state: Arc<Mutex<Option<DoubaoASRClient>>>,
+    asr_engines_state: Arc<Mutex<AsrEngineRegistry>>,
+    asr_selection_state: Arc<Mutex<config::AsrSelection>>,
     audio_data: Vec<u8>,
-    enable_fallback: bool,
     target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
     usage_stats: Arc<Mutex<UsageStats>>,
     recording_start_instant: Arc<Mutex<Option<std::time::Instant>>>,
 ) {
-    let qwen = { qwen_client_state.lock().unwrap().clone() };
-    let sensevoice = { sensevoice_client_state.lock().unwrap().clone() };
-    let doubao = { doubao_client_state.lock().unwrap().clone() };
+    let selection = { asr_selection_state.lock().unwrap().clone() };
+    let engines = { asr_engines_state.lock().unwrap().clone() };
     let active_prov = app
         .state::<AppState>()
         .realtime_provider
         .lock()
         .unwrap()
         .clone();
-    let fallback_prov = app
-        .state::<AppState>()
-        .fallback_provider
-        .lock()
-        .unwrap()
-        .clone();
 
-    // DoubaoIme 不支持 HTTP 模式，直接使用 fallback_provider
-    let effective_active_prov = if matches!(active_prov, Some(config::AsrProvider::DoubaoIme)) {
-        tracing::info!("豆包输入法不支持 HTTP 备用模式，切换到 fallback provider");
-        fallback_prov.clone()
-    } else {
-        active_prov
-    };
+    let health = Arc::clone(&app.state::<AppState>().asr_health);
 
+    let vad = app.state::<AppState>().vad_config.lock().unwrap().clone();
     let asr_start = std::time::Instant::now();
     let result = transcribe_with_available_clients(
-        qwen,
-        doubao,
-        sensevoice,
+        engines,
+        &health,
+        &vad,
         &audio_data,
-        enable_fallback,
-        effective_active_prov,
-        fallback_prov,
+        selection,
+        active_prov,
+        true,
         "(备用) ",
     )
     .await;
@@ -2958,6 +3309,7 @@ async fn fallback_transcription(
         target_hwnd,
         usage_stats,
         recording_start_instant,
+        Some(audio_data),
     )
     .await;
 }
@@ -2985,6 +3337,19 @@ fn hide_overlay_silently(app: &AppHandle) {
     }
 }
 
+/// 中止实时转写：取消音频发送任务并关闭会话（不等待结果）
+async fn abort_realtime_session(
+    realtime_session: &RealtimeSessionSlot,
+    audio_sender_handle: &Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+) {
+    if let Some(handle) = audio_sender_handle.lock().unwrap().take() {
+        handle.abort();
+    }
+    if let Some(mut session) = realtime_session.lock().await.take() {
+        let _ = session.close().await;
+    }
+}
+
 /// 检查错误是否为"音频跳过"类型（用户误触等正常情况）
 fn is_audio_skip_error(error: &anyhow::Error) -> bool {
     let msg = error.to_string();
@@ -3005,6 +3370,56 @@ struct TranscriptionResult {
     mode: Option<String>, // 新增：处理模式
     #[serde(skip_serializing_if = "Option::is_none")]
     inserted: Option<bool>, // 新增：是否已自动插入
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<asr::AsrTranscript>, // ASR 分段、时间戳与置信度（提供商支持时）
+}
+
+/// 唤醒词发起的会话：去掉转写文本开头的唤醒词
+fn strip_wake_word(
+    app: &AppHandle,
+    result: anyhow::Result<asr::AsrTranscript>,
+) -> anyhow::Result<asr::AsrTranscript> {
+    let Some(phrase) = app.state::<AppState>().wake_word.take_session_phrase() else {
+        return result;
+    };
+    result.map(|transcript| transcript.map_text(|text| wake_word::strip_wake_phrase(text, &phrase)))
+}
+
+/// 录音归档开启时保存本次录音及转写结果（转写失败时为空）
+///
+/// 在文本插入之后调用；FLAC 编码、写盘与保留策略清理都放到阻塞线程，不拖慢转写，失败也只记日志
+fn archive_recording(
+    app: &AppHandle,
+    audio_data: Option<Vec<u8>>,
+    mode: config::TriggerMode,
+    result: Option<&TranscriptionResult>,
+) {
+    let Some(audio_data) = audio_data else {
+        return;
+    };
+    let config = app
+        .state::<AppState>()
+        .recording_archive
+        .lock()
+        .unwrap()
+        .clone();
+    if !config.enabled {
+        return;
+    }
+    let transcription = result.map(|result| recording_archive::ArchivedTranscription {
+        created_at: recording_archive::now_ms(),
+        provider: None,
+        preset_id: None,
+        tnl: None,
+        text: result.text.clone(),
+        original_text: result.original_text.clone(),
+        transcript: result.transcript.clone(),
+    });
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = recording_archive::save(&audio_data, mode, transcription, &config) {
+            tracing::warn!("录音归档失败: {}", e);
+        }
+    });
 }
 
 /// 处理转录结果（听写模式专用，使用 NormalPipeline）
@@ -3015,12 +3430,15 @@ async fn handle_transcription_result(
     app: AppHandle,
     post_processor: Arc<Mutex<Option<LlmPostProcessor>>>,
     text_inserter: Arc<Mutex<Option<TextInserter>>>,
-    result: anyhow::Result<String>,
+    result: anyhow::Result<asr::AsrTranscript>,
     asr_time_ms: u64,
     target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
     usage_stats: Arc<Mutex<UsageStats>>,
     recording_start_instant: Arc<Mutex<Option<std::time::Instant>>>,
+    audio_data: Option<Vec<u8>>, // 本次录音（开启录音归档时，文本插入后归档）
 ) {
+    let result = strip_wake_word(&app, result);
+
     // 从锁中提取处理器（clone 后立即释放锁）
     let post_proc = { post_processor.lock().unwrap().clone() };
 
@@ -3079,8 +3497,15 @@ async fn handle_transcription_result(
                 total_time_ms: result.total_time_ms,
                 mode: Some(format!("{:?}", result.mode).to_lowercase()),
                 inserted: Some(result.inserted),
+                transcript: result.transcript,
             };
 
+            archive_recording(
+                &app,
+                audio_data,
+                config::TriggerMode::Dictation,
+                Some(&transcription_result),
+            );
             // 发送完成事件
             let _ = app.emit("transcription_complete", transcription_result);
         }
@@ -3094,6 +3519,7 @@ async fn handle_transcription_result(
             // 发送错误事件
             tracing::error!("转录处理失败: {}", e);
             let _ = app.emit("error", format!("转录失败: {}", e));
+            archive_recording(&app, audio_data, config::TriggerMode::Dictation, None);
         }
     }
 }
@@ -3130,24 +3556,13 @@ async fn stop_app(app_handle: AppHandle) -> Result<String, String> {
 
     // 显式关闭活跃的 WebSocket Session
     {
-        let mut session_guard = state.active_session.lock().await;
-        if let Some(session) = session_guard.take() {
+        let session = state.realtime_session.lock().await.take();
+        if let Some(mut session) = session {
             let _ = session.close().await;
-            tracing::info!("已关闭千问 WebSocket 会话");
-        }
-    }
-    {
-        let mut session_guard = state.doubao_session.lock().await;
-        if let Some(mut session) = session_guard.take() {
-            let _ = session.finish_audio().await;
-            tracing::info!("已关闭豆包 WebSocket 会话");
-        }
-    }
-    {
-        let mut session_guard = state.doubao_ime_session.lock().await;
-        if let Some(mut session) = session_guard.take() {
-            let _ = session.finish_audio().await;
-            tracing::info!("已关闭豆包输入法 WebSocket 会话");
+            tracing::info!(
+                "已关闭{} WebSocket 会话",
+                asr_provider_name(&session.provider())
+            );
         }
     }
 
@@ -3156,10 +3571,9 @@ async fn stop_app(app_handle: AppHandle) -> Result<String, String> {
     *state.text_inserter.lock().unwrap() = None;
     *state.post_processor.lock().unwrap() = None;
     *state.assistant_processor.lock().unwrap() = None;
-    *state.qwen_client.lock().unwrap() = None;
-    *state.sensevoice_client.lock().unwrap() = None;
-    *state.doubao_client.lock().unwrap() = None;
+    *state.asr_engines.lock().unwrap() = AsrEngineRegistry::new();
     *state.is_running.lock().unwrap() = false;
+    sync_background_listening(&app_handle);
 
     Ok("应用已停止".to_string())
 }
@@ -3185,9 +3599,7 @@ async fn quit_app(app_handle: AppHandle) -> Result<(), String> {
             *state.text_inserter.lock().unwrap() = None;
             *state.post_processor.lock().unwrap() = None;
             *state.assistant_processor.lock().unwrap() = None;
-            *state.qwen_client.lock().unwrap() = None;
-            *state.sensevoice_client.lock().unwrap() = None;
-            *state.doubao_client.lock().unwrap() = None;
+            *state.asr_engines.lock().unwrap() = AsrEngineRegistry::new();
             *is_running = false;
         }
     }
@@ -3228,25 +3640,13 @@ async fn cancel_transcription(app_handle: AppHandle) -> Result<String, String> {
 
     // 4. 关闭 WebSocket 会话
     {
-        let mut session_guard = state.active_session.lock().await;
-        if let Some(ref session) = *session_guard {
+        let session = state.realtime_session.lock().await.take();
+        if let Some(mut session) = session {
             let _ = session.close().await;
-            tracing::info!("已关闭 WebSocket 会话");
-        }
-        *session_guard = None;
-    }
-    {
-        let mut session_guard = state.doubao_session.lock().await;
-        if let Some(mut session) = session_guard.take() {
-            let _ = session.finish_audio().await;
-            tracing::info!("已关闭豆包 WebSocket 会话");
-        }
-    }
-    {
-        let mut session_guard = state.doubao_ime_session.lock().await;
-        if let Some(mut session) = session_guard.take() {
-            let _ = session.finish_audio().await;
-            tracing::info!("已关闭豆包输入法 WebSocket 会话");
+            tracing::info!(
+                "已关闭{} WebSocket 会话",
+                asr_provider_name(&session.provider())
+            );
         }
     }
 
@@ -3327,17 +3727,12 @@ async fn finish_locked_recording(app_handle: AppHandle) -> Result<String, String
     let use_realtime = *state.use_realtime_asr.lock().unwrap();
     let streaming_recorder = Arc::clone(&state.streaming_recorder);
     let audio_recorder = Arc::clone(&state.audio_recorder);
-    let active_session = Arc::clone(&state.active_session);
-    let doubao_session = Arc::clone(&state.doubao_session);
-    let doubao_ime_session = Arc::clone(&state.doubao_ime_session);
-    let realtime_provider = Arc::clone(&state.realtime_provider);
+    let realtime_session = Arc::clone(&state.realtime_session);
     let audio_sender_handle = Arc::clone(&state.audio_sender_handle);
     let post_processor = Arc::clone(&state.post_processor);
     let text_inserter = Arc::clone(&state.text_inserter);
-    let qwen_client = Arc::clone(&state.qwen_client);
-    let sensevoice_client = Arc::clone(&state.sensevoice_client);
-    let doubao_client = Arc::clone(&state.doubao_client);
-    let enable_fallback = Arc::clone(&state.enable_fallback);
+    let asr_engines = Arc::clone(&state.asr_engines);
+    let asr_selection = Arc::clone(&state.asr_selection);
     let target_hwnd = *state.target_window.lock().unwrap(); // 获取目标窗口句柄
     let usage_stats = Arc::clone(&state.usage_stats);
     let recording_start_instant = Arc::clone(&state.recording_start_instant);
@@ -3350,17 +3745,12 @@ async fn finish_locked_recording(app_handle: AppHandle) -> Result<String, String
                 handle_realtime_stop(
                     app,
                     streaming_recorder,
-                    active_session,
-                    doubao_session,
-                    doubao_ime_session,
-                    realtime_provider,
+                    realtime_session,
                     audio_sender_handle,
                     post_processor,
                     text_inserter,
-                    qwen_client,
-                    sensevoice_client,
-                    doubao_client,
-                    enable_fallback,
+                    asr_engines,
+                    asr_selection,
                     target_hwnd,
                     usage_stats,
                     recording_start_instant,
@@ -3372,10 +3762,8 @@ async fn finish_locked_recording(app_handle: AppHandle) -> Result<String, String
                     audio_recorder,
                     post_processor,
                     text_inserter,
-                    qwen_client,
-                    sensevoice_client,
-                    doubao_client,
-                    enable_fallback,
+                    asr_engines,
+                    asr_selection,
                     target_hwnd,
                     usage_stats,
                     recording_start_instant,
@@ -3511,6 +3899,35 @@ async fn reset_hotkey_state(app_handle: AppHandle) -> Result<String, String> {
     Ok("热键状态已重置".to_string())
 }
 
+/// 切换识别语言（热更新，无需重启服务），返回当前引擎实际使用的语言
+#[tauri::command]
+async fn set_asr_language_mode(
+    app_handle: AppHandle,
+    language_mode: config::AsrLanguageMode,
+) -> Result<config::AsrLanguageMode, String> {
+    switch_asr_language_mode(&app_handle, language_mode)
+}
+
+/// 获取各 ASR 提供商的健康状态（延迟、错误类型、熔断状态）
+#[tauri::command]
+async fn get_asr_health(app_handle: AppHandle) -> Result<Vec<asr::ProviderHealth>, String> {
+    Ok(app_handle.state::<AppState>().asr_health.snapshot())
+}
+
+/// 手动重置熔断器（不指定提供商时重置全部）
+#[tauri::command]
+async fn reset_asr_health(
+    app_handle: AppHandle,
+    provider: Option<config::AsrProvider>,
+) -> Result<(), String> {
+    app_handle
+        .state::<AppState>()
+        .asr_health
+        .reset(provider.as_ref());
+    tracing::info!("已重置 ASR 熔断状态");
+    Ok(())
+}
+
 /// 获取热键服务是否激活
 #[tauri::command]
 async fn get_hotkey_service_active(app_handle: AppHandle) -> Result<bool, String> {
@@ -3697,16 +4114,8 @@ async fn update_runtime_config(
         *state.dictionary.lock().unwrap() = dict.clone();
         tracing::info!("热更新: state.dictionary 已更新 ({} 词)", dict.len());
 
-        // 更新千问 HTTP 客户端
-        if let Some(ref mut client) = *state.qwen_client.lock().unwrap() {
-            client.update_dictionary(dict.clone());
-            tracing::info!("热更新: 千问 ASR HTTP 客户端词库已更新");
-        }
-        // 更新豆包 HTTP 客户端
-        if let Some(ref mut client) = *state.doubao_client.lock().unwrap() {
-            client.update_dictionary(dict.clone());
-            tracing::info!("热更新: 豆包 ASR HTTP 客户端词库已更新");
-        }
+        // 更新使用词库的 HTTP 引擎
+        state.asr_engines.lock().unwrap().update_dictionary(&dict);
         updated.push("词库");
     }
 
@@ -3717,6 +4126,477 @@ async fn update_runtime_config(
     }
 }
 
+// ============================================================================
+// 录音输入设备
+// ============================================================================
+
+/// 枚举当前可用的录音输入设备
+#[tauri::command]
+async fn list_input_devices() -> Result<Vec<audio_device::InputDeviceInfo>, String> {
+    audio_device::list_input_devices().map_err(|e| e.to_string())
+}
+
+/// 设置首选录音输入设备（按顺序回退），下一次录音生效；后台监听中立即切换
+#[tauri::command]
+async fn set_preferred_input_devices(
+    app_handle: AppHandle,
+    devices: Vec<String>,
+) -> Result<(), String> {
+    let devices: Vec<String> = devices
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    tracing::info!("设置首选输入设备: {:?}", devices);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.preferred_input_devices = devices.clone();
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.preferred_input_devices.lock().unwrap() = devices;
+
+    emit_config_updated(&app_handle, &updated_config);
+    reopen_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 设置语音活动检测引擎与灵敏度（0~3，越大越严格），下一次录音生效
+#[tauri::command]
+async fn set_vad_config(
+    app_handle: AppHandle,
+    engine: config::VadEngine,
+    aggressiveness: u8,
+) -> Result<(), String> {
+    let vad_config = config::VadConfig {
+        engine,
+        aggressiveness: aggressiveness.min(3),
+    };
+    tracing::info!("设置语音活动检测: {:?}", vad_config);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.vad_config = vad_config.clone();
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.vad_config.lock().unwrap() = vad_config;
+
+    emit_config_updated(&app_handle, &updated_config);
+    // 后台监听立即改用新的检测配置
+    if background_listening_wanted(&state) {
+        sync_background_listening(&app_handle);
+    }
+    Ok(())
+}
+
+/// 设置免提自动结束（切换模式与松手锁定模式），下一次录音生效
+#[tauri::command]
+async fn set_auto_stop_config(
+    app_handle: AppHandle,
+    enabled: bool,
+    silence_ms: u32,
+    max_duration_secs: u32,
+) -> Result<(), String> {
+    let auto_stop = config::AutoStopConfig {
+        enabled,
+        silence_ms: silence_ms.clamp(500, 10_000),
+        max_duration_secs,
+    };
+    tracing::info!("设置免提自动结束: {:?}", auto_stop);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.auto_stop = auto_stop.clone();
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.auto_stop.lock().unwrap() = auto_stop;
+
+    emit_config_updated(&app_handle, &updated_config);
+    Ok(())
+}
+
+/// 设置开放麦克风（语音触发听写），立即开始或结束后台监听
+#[tauri::command]
+async fn set_open_mic_config(
+    app_handle: AppHandle,
+    enabled: bool,
+    trigger_ms: u32,
+    silence_ms: u32,
+    max_duration_secs: u32,
+) -> Result<(), String> {
+    let open_mic = config::OpenMicConfig {
+        enabled,
+        trigger_ms: trigger_ms.clamp(200, 2000),
+        silence_ms: silence_ms.clamp(500, 10_000),
+        max_duration_secs,
+    };
+    tracing::info!("设置开放麦克风: {:?}", open_mic);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.open_mic = open_mic.clone();
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.open_mic_config.lock().unwrap() = open_mic;
+
+    emit_config_updated(&app_handle, &updated_config);
+    sync_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 开放麦克风静音（仅本次运行有效，释放麦克风但保留开放麦克风设置）
+#[tauri::command]
+async fn set_open_mic_muted(app_handle: AppHandle, muted: bool) -> Result<(), String> {
+    tracing::info!("开放麦克风{}", if muted { "静音" } else { "取消静音" });
+    app_handle.state::<AppState>().open_mic.set_muted(muted);
+    sync_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 获取开放麦克风当前状态（隐私指示初始化）
+#[tauri::command]
+async fn get_open_mic_state(app_handle: AppHandle) -> Result<open_mic::OpenMicState, String> {
+    let state = app_handle.state::<AppState>();
+    let (listening, _) = monitor_recorder_status(&state);
+    Ok(open_mic::OpenMicState {
+        enabled: state.open_mic_config.lock().unwrap().enabled,
+        muted: state.open_mic.is_muted(),
+        listening,
+    })
+}
+
+/// 设置唤醒词开关与灵敏度（0~1），立即开始或结束后台监听
+#[tauri::command]
+async fn set_wake_word_config(
+    app_handle: AppHandle,
+    enabled: bool,
+    sensitivity: f32,
+) -> Result<(), String> {
+    let sensitivity = sensitivity.clamp(0.0, 1.0);
+    tracing::info!("设置唤醒词: 启用={}, 灵敏度={:.2}", enabled, sensitivity);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.wake_word.enabled = enabled;
+        config.wake_word.sensitivity = sensitivity;
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.wake_word_config.lock().unwrap() = updated_config.wake_word.clone();
+
+    emit_config_updated(&app_handle, &updated_config);
+    sync_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 添加唤醒词（需再录制样本后才会生效）
+#[tauri::command]
+async fn add_wake_word(
+    app_handle: AppHandle,
+    phrase: String,
+    trigger_mode: config::TriggerMode,
+) -> Result<config::WakeWord, String> {
+    let phrase = phrase.trim().to_string();
+    if phrase.is_empty() {
+        return Err("唤醒词不能为空".to_string());
+    }
+    let word = config::WakeWord {
+        id: uuid::Uuid::new_v4().to_string(),
+        phrase,
+        trigger_mode,
+        samples: Vec::new(),
+    };
+    tracing::info!("添加唤醒词「{}」→ {:?}", word.phrase, word.trigger_mode);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.wake_word.words.push(word.clone());
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.wake_word_config.lock().unwrap() = updated_config.wake_word.clone();
+
+    emit_config_updated(&app_handle, &updated_config);
+    Ok(word)
+}
+
+/// 删除唤醒词及其样本
+#[tauri::command]
+async fn remove_wake_word(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let (updated_config, removed) = mutate_persisted_config_with_result(|config| {
+        let index = config
+            .wake_word
+            .words
+            .iter()
+            .position(|word| word.id == id)
+            .ok_or_else(|| "唤醒词不存在".to_string())?;
+        Ok(config.wake_word.words.remove(index))
+    })?;
+    tracing::info!("删除唤醒词「{}」", removed.phrase);
+    wake_word::remove_samples(&removed.samples);
+
+    let state = app_handle.state::<AppState>();
+    *state.wake_word_config.lock().unwrap() = updated_config.wake_word.clone();
+
+    emit_config_updated(&app_handle, &updated_config);
+    sync_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 为唤醒词录制一遍样本（录音约 2.5 秒，期间用户说出唤醒词）
+#[tauri::command]
+async fn record_wake_word_sample(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<config::WakeWord, String> {
+    const SAMPLE_RECORDING: std::time::Duration = std::time::Duration::from_millis(2500);
+
+    let state = app_handle.state::<AppState>();
+    if !state
+        .wake_word_config
+        .lock()
+        .unwrap()
+        .words
+        .iter()
+        .any(|word| word.id == id)
+    {
+        return Err("唤醒词不存在".to_string());
+    }
+
+    // 使用与录音相同的采集链路（设备偏好、降噪），样本与运行时特征一致
+    let mut recorder = AudioRecorder::new(
+        Arc::clone(&state.preferred_input_devices),
+        Arc::clone(&state.vad_config),
+        Arc::clone(&state.noise_suppression),
+    )
+    .map_err(|e| format!("初始化录音失败: {}", e))?;
+    recorder
+        .start_recording(None)
+        .map_err(|e| format!("开始录音失败: {}", e))?;
+    tokio::time::sleep(SAMPLE_RECORDING).await;
+    let wav = recorder
+        .stop_recording_to_memory()
+        .map_err(|e| format!("录音失败: {}", e))?;
+    let sample = wake_word::save_sample(&id, &wav).map_err(|e| e.to_string())?;
+
+    let (updated_config, word) = mutate_persisted_config_with_result(|config| {
+        let word = config
+            .wake_word
+            .words
+            .iter_mut()
+            .find(|word| word.id == id)
+            .ok_or_else(|| "唤醒词不存在".to_string())?;
+        word.samples.push(sample.clone());
+        Ok(word.clone())
+    })?;
+    tracing::info!(
+        "唤醒词「{}」已录制 {} 个样本",
+        word.phrase,
+        word.samples.len()
+    );
+
+    *state.wake_word_config.lock().unwrap() = updated_config.wake_word.clone();
+
+    emit_config_updated(&app_handle, &updated_config);
+    sync_background_listening(&app_handle);
+    Ok(word)
+}
+
+/// 设置预录缓冲开关与时长（100~1000 毫秒），立即打开或关闭常开的录音流
+#[tauri::command]
+async fn set_preroll_config(
+    app_handle: AppHandle,
+    enabled: bool,
+    duration_ms: u32,
+) -> Result<(), String> {
+    let duration_ms = duration_ms.clamp(100, 1000);
+    tracing::info!("设置预录缓冲: 启用={}, 时长={}ms", enabled, duration_ms);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.preroll.enabled = enabled;
+        config.preroll.duration_ms = duration_ms;
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.preroll.lock().unwrap() = updated_config.preroll.clone();
+
+    emit_config_updated(&app_handle, &updated_config);
+    sync_background_listening(&app_handle);
+    Ok(())
+}
+
+/// 设置录音归档开关与保留策略（保留天数、总大小上限，0 表示不限），立即按新策略清理
+#[tauri::command]
+async fn set_recording_archive_config(
+    app_handle: AppHandle,
+    enabled: bool,
+    max_age_days: u32,
+    max_total_mb: u32,
+) -> Result<(), String> {
+    let max_age_days = max_age_days.min(3650);
+    let max_total_mb = max_total_mb.min(100 * 1024);
+    tracing::info!(
+        "设置录音归档: 启用={}, 保留 {} 天, 上限 {} MB",
+        enabled,
+        max_age_days,
+        max_total_mb
+    );
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.recording_archive.enabled = enabled;
+        config.recording_archive.max_age_days = max_age_days;
+        config.recording_archive.max_total_mb = max_total_mb;
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.recording_archive.lock().unwrap() = updated_config.recording_archive.clone();
+
+    if let Err(e) = recording_archive::apply_retention(&updated_config.recording_archive) {
+        tracing::warn!("录音归档清理失败: {}", e);
+    }
+    emit_config_updated(&app_handle, &updated_config);
+    Ok(())
+}
+
+/// 所有归档录音（最新的在前）
+#[tauri::command]
+async fn list_archived_recordings() -> Result<Vec<recording_archive::ArchivedRecording>, String> {
+    recording_archive::list().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_archived_recording(id: String) -> Result<(), String> {
+    recording_archive::delete(&id).map_err(|e| e.to_string())
+}
+
+/// 用指定的提供商、润色预设与 TNL 设置重新转写一条归档录音（不插入文本），结果追加到该记录
+///
+/// `provider` 为空时使用当前提供商；`preset_id` 为空时不润色；`enable_tnl` 为空时沿用 TNL 配置
+#[tauri::command]
+async fn retranscribe_archived_recording(
+    app_handle: AppHandle,
+    id: String,
+    provider: Option<String>,
+    preset_id: Option<String>,
+    enable_tnl: Option<bool>,
+) -> Result<recording_archive::ArchivedRecording, String> {
+    let record = recording_archive::get(&id).map_err(|e| e.to_string())?;
+    let wav =
+        recording_archive::load_wav(&record).map_err(|e| format!("读取归档录音失败: {}", e))?;
+    let config = load_persisted_config()?;
+
+    let provider = match provider {
+        Some(key) => config::AsrProvider::from_config_key(&key)
+            .ok_or_else(|| format!("未知的 ASR 提供商: {}", key))?,
+        None => config.asr_config.selection.active_provider.clone(),
+    };
+    // 与启动服务时一致：保留词条的权重与作用范围标记，由各提供商自行转换
+    let dictionary = config.dictionary.clone();
+    let engine = AsrEngineRegistry::from_config(&config.asr_config, &dictionary)
+        .get(&provider)
+        .ok_or_else(|| format!("{}未配置或不支持转写录音文件", asr_provider_name(&provider)))?;
+    tracing::info!(
+        "重新转写归档录音 {}: 提供商={}, 预设={:?}, TNL={:?}",
+        id,
+        asr_provider_name(&provider),
+        preset_id,
+        enable_tnl
+    );
+
+    let state = app_handle.state::<AppState>();
+    let health = Arc::clone(&state.asr_health);
+    let vad = state.vad_config.lock().unwrap().clone();
+    let transcript = asr::transcribe_long_form(
+        vec![engine],
+        wav,
+        &config.asr_config.selection.strategy,
+        &health,
+        &vad,
+    )
+    .await
+    .map_err(|e| format!("重新转写失败: {}", e))?;
+    let asr_text = transcript.text().to_string();
+
+    let tnl = enable_tnl.unwrap_or(config.tnl_config.enabled);
+    let text = if tnl {
+        tnl::TnlEngine::new(dictionary_utils::entries_to_words(&dictionary))
+            .normalize(&asr_text)
+            .text
+    } else {
+        asr_text.clone()
+    };
+
+    let text = match &preset_id {
+        Some(preset_id) => {
+            let mut llm_config = config.llm_config.clone();
+            if !llm_config.presets.iter().any(|p| &p.id == preset_id) {
+                return Err(format!("润色预设不存在: {}", preset_id));
+            }
+            llm_config.active_preset_id = preset_id.clone();
+            if llm_config.resolve_polishing().api_key.trim().is_empty() {
+                return Err("语句润色未配置 API Key".to_string());
+            }
+            let enable_dictionary_enhancement =
+                *state.enable_dictionary_enhancement.lock().unwrap();
+            LlmPostProcessor::new(llm_config)
+                .polish_transcript(
+                    &text,
+                    transcript.alternatives(),
+                    &dictionary,
+                    true,
+                    enable_dictionary_enhancement,
+                )
+                .await
+                .map_err(|e| format!("润色失败: {}", e))?
+        }
+        None => text,
+    };
+
+    let transcription = recording_archive::ArchivedTranscription {
+        created_at: recording_archive::now_ms(),
+        provider: Some(provider.config_key()),
+        preset_id,
+        tnl: Some(tnl),
+        original_text: (text != asr_text).then_some(asr_text),
+        text,
+        transcript: Some(transcript),
+    };
+    recording_archive::update(&id, |record| record.retranscriptions.push(transcription))
+        .map_err(|e| e.to_string())
+}
+
+/// 设置降噪开关与强度（0~1），下一次录音生效；后台监听中立即生效
+#[tauri::command]
+async fn set_noise_suppression(
+    app_handle: AppHandle,
+    enabled: bool,
+    strength: f32,
+) -> Result<(), String> {
+    let noise_suppression = config::NoiseSuppressionConfig {
+        enabled,
+        strength: strength.clamp(0.0, 1.0),
+    };
+    tracing::info!("设置降噪: {:?}", noise_suppression);
+
+    let updated_config = mutate_persisted_config(|config| {
+        config.noise_suppression = noise_suppression.clone();
+        Ok(())
+    })?;
+
+    let state = app_handle.state::<AppState>();
+    *state.noise_suppression.lock().unwrap() = noise_suppression;
+
+    emit_config_updated(&app_handle, &updated_config);
+    reopen_background_listening(&app_handle);
+    Ok(())
+}
+
 // ============================================================================
 // 词典管理命令（自动词库学习功能）
 // ============================================================================
@@ -3728,26 +4608,21 @@ async fn add_learned_word(
     word: String,
     source: String,
 ) -> Result<(), String> {
-    use crate::dictionary_utils::{entries_to_words, upsert_entry};
+    use crate::dictionary_utils::upsert_entry;
 
     tracing::info!("添加学习词汇: {} (来源: {})", word, source);
     let (updated_config, words) = mutate_persisted_config_with_result(|config| {
         // 添加词条（source: "manual" 或 "auto"）
         upsert_entry(&mut config.dictionary, &word, &source);
-        Ok(entries_to_words(&config.dictionary))
+        Ok(config.dictionary.clone())
     })?;
 
     // 热更新运行时词库
     let state = app_handle.state::<AppState>();
     *state.dictionary.lock().unwrap() = words.clone();
 
-    // 更新 ASR 客户端词库
-    if let Some(ref mut client) = *state.qwen_client.lock().unwrap() {
-        client.update_dictionary(words.clone());
-    }
-    if let Some(ref mut client) = *state.doubao_client.lock().unwrap() {
-        client.update_dictionary(words.clone());
-    }
+    // 更新 ASR 引擎词库
+    state.asr_engines.lock().unwrap().update_dictionary(&words);
 
     // 发送事件通知前端刷新配置和词典
     emit_config_updated(&app_handle, &updated_config);
@@ -3757,6 +4632,48 @@ async fn add_learned_word(
     Ok(())
 }
 
+/// 设置词条的热词权重（1~10，None 恢复默认）与作用范围（"all" / "asr" / "llm"）
+#[tauri::command]
+async fn update_dictionary_entry(
+    app_handle: AppHandle,
+    word: String,
+    weight: Option<u8>,
+    scope: String,
+) -> Result<(), String> {
+    use crate::dictionary_utils::{update_entry_attributes, DictionaryScope};
+
+    tracing::info!(
+        "更新词条属性: {} (权重: {:?}, 范围: {})",
+        word,
+        weight,
+        scope
+    );
+    let (updated_config, words) = mutate_persisted_config_with_result(|config| {
+        if !update_entry_attributes(
+            &mut config.dictionary,
+            &word,
+            weight,
+            DictionaryScope::from_name(&scope),
+        ) {
+            return Err(format!("词条 '{}' 不存在", word));
+        }
+        Ok(config.dictionary.clone())
+    })?;
+
+    // 热更新运行时词库
+    let state = app_handle.state::<AppState>();
+    *state.dictionary.lock().unwrap() = words.clone();
+
+    // 更新 ASR 引擎词库
+    state.asr_engines.lock().unwrap().update_dictionary(&words);
+
+    // 发送事件通知前端刷新配置和词典
+    emit_config_updated(&app_handle, &updated_config);
+    app_handle.emit("dictionary_updated", ()).ok();
+
+    Ok(())
+}
+
 /// 获取所有词典条目
 #[tauri::command]
 async fn get_dictionary_entries() -> Result<Vec<String>, String> {
@@ -3777,26 +4694,25 @@ async fn delete_dictionary_entries(
     app_handle: AppHandle,
     words: Vec<String>,
 ) -> Result<(), String> {
-    use crate::dictionary_utils::{entries_to_words, remove_entries};
+    use crate::dictionary_utils::remove_entries;
 
     tracing::info!("删除词典条目: {:?}", words);
     let (updated_config, dict_words) = mutate_persisted_config_with_result(|config| {
         // 删除指定词汇（按 word 匹配，不区分来源）
         remove_entries(&mut config.dictionary, &words);
-        Ok(entries_to_words(&config.dictionary))
+        Ok(config.dictionary.clone())
     })?;
 
     // 热更新运行时词库
     let state = app_handle.state::<AppState>();
     *state.dictionary.lock().unwrap() = dict_words.clone();
 
-    // 更新 ASR 客户端词库
-    if let Some(ref mut client) = *state.qwen_client.lock().unwrap() {
-        client.update_dictionary(dict_words.clone());
-    }
-    if let Some(ref mut client) = *state.doubao_client.lock().unwrap() {
-        client.update_dictionary(dict_words.clone());
-    }
+    // 更新 ASR 引擎词库
+    state
+        .asr_engines
+        .lock()
+        .unwrap()
+        .update_dictionary(&dict_words);
 
     // 发送事件通知前端刷新配置和词典
     emit_config_updated(&app_handle, &updated_config);
@@ -3953,6 +4869,56 @@ pub fn run() {
             let initial_builtin_hotwords = builtin_dictionary_updater::load_builtin_hotwords();
             let builtin_hotwords_raw = Arc::new(Mutex::new(initial_builtin_hotwords));
             let builtin_dictionary_updater_started = Arc::new(AtomicBool::new(false));
+            let persisted_config = AppConfig::load().map(|(config, _)| config).ok();
+            let preferred_input_devices = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.preferred_input_devices.clone())
+                    .unwrap_or_default(),
+            ));
+            let vad_config = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.vad_config.clone())
+                    .unwrap_or_default(),
+            ));
+            let noise_suppression = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.noise_suppression.clone())
+                    .unwrap_or_default(),
+            ));
+            let auto_stop = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.auto_stop.clone())
+                    .unwrap_or_default(),
+            ));
+            let open_mic_config = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.open_mic.clone())
+                    .unwrap_or_default(),
+            ));
+            let (open_mic, open_mic_triggers) = open_mic::OpenMicController::new();
+            let wake_word_config = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.wake_word.clone())
+                    .unwrap_or_default(),
+            ));
+            let (wake_word, wake_word_triggers) = wake_word::WakeWordController::new();
+            let recording_archive = Arc::new(Mutex::new(
+                persisted_config
+                    .as_ref()
+                    .map(|config| config.recording_archive.clone())
+                    .unwrap_or_default(),
+            ));
+            let preroll = Arc::new(Mutex::new(
+                persisted_config
+                    .map(|config| config.preroll)
+                    .unwrap_or_default(),
+            ));
 
             let app_state = AppState {
                 audio_recorder: Arc::new(Mutex::new(None)),
@@ -3964,15 +4930,13 @@ pub fn run() {
                 use_realtime_asr: Arc::new(Mutex::new(true)),
                 enable_post_process: Arc::new(Mutex::new(false)),
                 enable_dictionary_enhancement: Arc::new(Mutex::new(true)),
-                enable_fallback: Arc::new(Mutex::new(false)),
-                qwen_client: Arc::new(Mutex::new(None)),
-                sensevoice_client: Arc::new(Mutex::new(None)),
-                doubao_client: Arc::new(Mutex::new(None)),
-                active_session: Arc::new(tokio::sync::Mutex::new(None)),
-                doubao_session: Arc::new(tokio::sync::Mutex::new(None)),
-                doubao_ime_session: Arc::new(tokio::sync::Mutex::new(None)),
+                asr_selection: Arc::new(Mutex::new(config::AsrSelection::default())),
+                asr_engines: Arc::new(Mutex::new(AsrEngineRegistry::new())),
+                asr_language_mode: Arc::new(Mutex::new(config::AsrLanguageMode::default())),
+                asr_health: Arc::new(AsrHealthTracker::default()),
+                asr_prewarmer: Arc::new(ConnectionPrewarmer::default()),
+                realtime_session: Arc::new(tokio::sync::Mutex::new(None)),
                 realtime_provider: Arc::new(Mutex::new(None)),
-                fallback_provider: Arc::new(Mutex::new(None)),
                 audio_sender_handle: Arc::new(Mutex::new(None)),
                 hotkey_service: Arc::new(HotkeyService::new()),
                 current_trigger_mode: Arc::new(Mutex::new(None)),
@@ -3981,9 +4945,19 @@ pub fn run() {
                 recording_start_time: Arc::new(Mutex::new(None)),
                 is_processing_stop: Arc::new(AtomicBool::new(false)),
                 audio_mute_manager: Arc::new(Mutex::new(None)),
+                preferred_input_devices: Arc::clone(&preferred_input_devices),
+                vad_config,
+                noise_suppression,
+                auto_stop,
+                open_mic_config,
+                open_mic: Arc::new(open_mic),
+                wake_word_config,
+                wake_word: Arc::new(wake_word),
+                recording_archive,
+                preroll,
                 target_window: Arc::new(Mutex::new(None)),
                 dictionary: Arc::new(Mutex::new(Vec::new())),
-                doubao_ime_credentials: Arc::new(Mutex::new(None)),
+                doubao_ime_credentials: DoubaoImeCredentialStore::shared(),
                 usage_stats: Arc::new(Mutex::new(usage_stats)),
                 recording_start_instant: Arc::new(Mutex::new(None)),
                 builtin_hotwords_raw: Arc::clone(&builtin_hotwords_raw),
@@ -4004,11 +4978,13 @@ pub fn run() {
                 initial_config.enable_dictionary_enhancement;
             let initial_active_provider =
                 initial_config.asr_config.selection.active_provider.clone();
+            let initial_language_mode = initial_config.asr_config.language_mode;
 
             *app_state.enable_post_process.lock().unwrap() = initial_enable_post_process;
             *app_state.enable_dictionary_enhancement.lock().unwrap() =
                 initial_enable_dictionary_enhancement;
             *app_state.realtime_provider.lock().unwrap() = Some(initial_active_provider.clone());
+            *app_state.asr_language_mode.lock().unwrap() = initial_language_mode;
 
             if state_enable_post_process != initial_enable_post_process {
                 tracing::info!(
@@ -4078,6 +5054,33 @@ pub fn run() {
                 &[&asr_qwen_item, &asr_doubao_item, &asr_doubao_ime_item],
             )?;
 
+            let mut language_items = Vec::with_capacity(config::AsrLanguageMode::ALL.len());
+            for mode in config::AsrLanguageMode::ALL {
+                let item = CheckMenuItem::with_id(
+                    app,
+                    format!("{}{}", TRAY_MENU_ID_LANGUAGE_PREFIX, mode.config_key()),
+                    mode.display_name(),
+                    true,
+                    mode == initial_language_mode,
+                    None::<&str>,
+                )?;
+                language_items.push((mode, item));
+            }
+            let language_item_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = language_items
+                .iter()
+                .map(|(_, item)| item as &dyn IsMenuItem<tauri::Wry>)
+                .collect();
+            let language_submenu = Submenu::with_items(app, "识别语言", true, &language_item_refs)?;
+
+            let open_mic_mute_item = CheckMenuItem::with_id(
+                app,
+                TRAY_MENU_ID_OPEN_MIC_MUTE,
+                "暂停麦克风监听",
+                true,
+                false,
+                None::<&str>,
+            )?;
+
             let menu = Menu::with_items(
                 app,
                 &[
@@ -4085,6 +5088,8 @@ pub fn run() {
                     &post_process_item,
                     &dictionary_enhancement_item,
                     &asr_switch_submenu,
+                    &language_submenu,
+                    &open_mic_mute_item,
                     &quit_item,
                 ],
             )?;
@@ -4101,13 +5106,15 @@ pub fn run() {
                 asr_qwen_item: asr_qwen_item.clone(),
                 asr_doubao_item: asr_doubao_item.clone(),
                 asr_doubao_ime_item: asr_doubao_ime_item.clone(),
+                language_items,
+                open_mic_mute_item,
             });
 
             // 创建系统托盘图标
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
-                .tooltip("PushToTalk - AI 语音转写助手")
+                .tooltip(TRAY_TOOLTIP)
                 .on_menu_event(move |app, event| match event.id.as_ref() {
                     TRAY_MENU_ID_SHOW => {
                         if let Some(window) = app.get_webview_window("main") {
@@ -4192,10 +5199,32 @@ pub fn run() {
                             }
                         });
                     }
+                    TRAY_MENU_ID_OPEN_MIC_MUTE => {
+                        let state = app.state::<AppState>();
+                        state.open_mic.set_muted(!state.open_mic.is_muted());
+                        sync_background_listening(app);
+                    }
                     TRAY_MENU_ID_QUIT => {
                         app.exit(0);
                     }
-                    _ => {}
+                    id => {
+                        let Some(mode) = id
+                            .strip_prefix(TRAY_MENU_ID_LANGUAGE_PREFIX)
+                            .and_then(config::AsrLanguageMode::from_config_key)
+                        else {
+                            return;
+                        };
+                        if let Err(e) = switch_asr_language_mode(app, mode) {
+                            tracing::error!("托盘切换识别语言失败: {}", e);
+                            // 点击会自动翻转勾选状态，失败时恢复为当前语言
+                            if let Some(tray_state) = app.try_state::<TrayMenuState>() {
+                                let current =
+                                    *app.state::<AppState>().asr_language_mode.lock().unwrap();
+                                sync_language_checks(&tray_state.language_items, current);
+                            }
+                            let _ = app.emit("error", e);
+                        }
+                    }
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click {
@@ -4215,11 +5244,27 @@ pub fn run() {
             app.manage(app_state);
             let state = app.state::<AppState>();
             let app_handle = app.handle().clone();
+
+            // 熔断状态变化时同步到托盘与前端
+            let health_app_handle = app_handle.clone();
+            state.asr_health.set_listener(move |health| {
+                sync_tray_asr_health(&health_app_handle, health);
+                let _ = health_app_handle.emit("asr_health_changed", health);
+            });
             start_builtin_dictionary_updater(
                 &app_handle,
                 &state.builtin_dictionary_updater_started,
                 &state.builtin_hotwords_raw,
             );
+            audio_device::spawn_device_watcher(
+                app_handle.clone(),
+                Arc::clone(&state.preferred_input_devices),
+            );
+            spawn_background_listening_scheduler(
+                app_handle.clone(),
+                open_mic_triggers,
+                wake_word_triggers,
+            );
 
             Ok(())
         })
@@ -4251,8 +5296,29 @@ pub fn run() {
             get_hotkey_service_active,
             set_hotkey_service_active,
             get_hotkey_debug_info,
+            set_asr_language_mode,
+            get_asr_health,
+            reset_asr_health,
             update_runtime_config,
             add_learned_word,
+            update_dictionary_entry,
+            list_input_devices,
+            set_preferred_input_devices,
+            set_vad_config,
+            set_noise_suppression,
+            set_auto_stop_config,
+            set_open_mic_config,
+            set_open_mic_muted,
+            get_open_mic_state,
+            set_wake_word_config,
+            add_wake_word,
+            remove_wake_word,
+            record_wake_word_sample,
+            set_preroll_config,
+            set_recording_archive_config,
+            list_archived_recordings,
+            delete_archived_recording,
+            retranscribe_archived_recording,
             get_dictionary_entries,
             delete_dictionary_entries,
             dismiss_learning_suggestion,
@@ -10,7 +10,7 @@ use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
 use crate::config::LlmConfig;
-use crate::dictionary_utils::entries_to_words;
+use crate::dictionary_utils::llm_words;
 use crate::openai_client::{ChatOptions, OpenAiClient, OpenAiClientConfig};
 
 /// LLM 文本润色处理器
@@ -27,6 +27,8 @@ pub struct LlmPostProcessor {
 impl LlmPostProcessor {
     const MAX_DICTIONARY_ENTRIES: usize = 200;
     const MAX_DICTIONARY_CHARS: usize = 4000;
+    /// 最多附带的 ASR 候选结果数
+    const MAX_ALTERNATIVES: usize = 4;
     /// 词库增强追加指令（当语句润色和词库增强同时开启时追加到用户预设后）
     const DICTIONARY_ENHANCEMENT_SUFFIX: &'static str = "
 
@@ -132,6 +134,7 @@ impl LlmPostProcessor {
 
     fn build_user_message(
         raw_text: &str,
+        alternatives: &[String],
         dictionary: &[String],
         enable_dictionary_enhancement: bool,
     ) -> String {
@@ -141,8 +144,8 @@ impl LlmPostProcessor {
         message.push_str("<dictionary>\n");
 
         if enable_dictionary_enhancement {
-            // 提纯词库（去除 |auto 后缀）
-            let purified_words = entries_to_words(dictionary);
+            // 提纯词库（去除 |auto 等标记，过滤仅用于 ASR 的词条，按权重排序）
+            let purified_words = llm_words(dictionary);
 
             let mut words: Vec<&str> = purified_words
                 .iter()
@@ -183,6 +186,21 @@ impl LlmPostProcessor {
 
         message.push_str("\n</dictionary>\n\n");
 
+        // ASR 候选结果（仅提供商返回 n-best 时存在），用于辨别同音词
+        let alternatives: Vec<&str> = alternatives
+            .iter()
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty() && *a != raw_text.trim())
+            .take(Self::MAX_ALTERNATIVES)
+            .collect();
+        if !alternatives.is_empty() {
+            message.push_str(
+                "<alternatives>\n<!-- 语音识别的其他候选结果，仅用于辨别同音词，不要合并或照抄 -->\n",
+            );
+            message.push_str(&alternatives.join("\n"));
+            message.push_str("\n</alternatives>\n\n");
+        }
+
         // 待处理文本
         message.push_str("\n<source_text>\n");
         message.push_str(raw_text);
@@ -197,12 +215,14 @@ impl LlmPostProcessor {
     ///
     /// # Arguments
     /// * `raw_text` - ASR 转写的原始文本
+    /// * `alternatives` - ASR 的其他候选结果（n-best，可为空）
     ///
     /// # Returns
     /// * 润色后的文本
     pub async fn polish_transcript(
         &self,
         raw_text: &str,
+        alternatives: &[String],
         dictionary: &[String],
         enable_post_process: bool,
         enable_dictionary_enhancement: bool,
@@ -229,8 +249,12 @@ impl LlmPostProcessor {
             Self::DICTIONARY_ONLY_SYSTEM_PROMPT.to_string()
         };
 
-        let user_message =
-            Self::build_user_message(raw_text, dictionary, enable_dictionary_enhancement);
+        let user_message = Self::build_user_message(
+            raw_text,
+            alternatives,
+            dictionary,
+            enable_dictionary_enhancement,
+        );
 
         self.client
             .chat_simple(&system_prompt, &user_message, ChatOptions::for_polishing())
@@ -287,7 +311,7 @@ mod tests {
 
     #[test]
     fn test_build_user_message_without_dictionary() {
-        let msg = LlmPostProcessor::build_user_message("hello", &[], true);
+        let msg = LlmPostProcessor::build_user_message("hello", &[], &[], true);
         assert!(msg.contains("<source_text>"));
         assert!(!msg.contains("<user_dictionary>"));
     }
@@ -299,7 +323,7 @@ mod tests {
             "  北京  ".to_string(),
             "张三".to_string(),
         ];
-        let msg = LlmPostProcessor::build_user_message("你好", &dict, true);
+        let msg = LlmPostProcessor::build_user_message("你好", &[], &dict, true);
         assert!(msg.contains("<dictionary>"));
         assert!(msg.contains("张三"));
         assert!(msg.contains("北京"));
@@ -309,7 +333,26 @@ mod tests {
     #[test]
     fn test_build_user_message_with_dictionary_disabled() {
         let dict = vec!["张三".to_string()];
-        let msg = LlmPostProcessor::build_user_message("你好", &dict, false);
+        let msg = LlmPostProcessor::build_user_message("你好", &[], &dict, false);
         assert!(!msg.contains("<user_dictionary>"));
     }
+
+    #[test]
+    fn test_build_user_message_with_alternatives() {
+        let msg = LlmPostProcessor::build_user_message("hello", &[], &[], true);
+        assert!(!msg.contains("<alternatives>"));
+
+        let alternatives = vec![
+            "我又回了".to_string(),
+            "我用回了".to_string(),
+            " ".to_string(),
+        ];
+        let msg = LlmPostProcessor::build_user_message("我用回了", &alternatives, &[], true);
+        let start = msg.find("<alternatives>").unwrap();
+        let end = msg.find("</alternatives>").unwrap();
+        let block = &msg[start..end];
+        assert!(block.contains("我又回了"));
+        assert!(!block.contains("我用回了"));
+        assert!(end < msg.find("<source_text>").unwrap());
+    }
 }
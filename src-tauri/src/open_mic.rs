@@ -0,0 +1,245 @@
+// 开放麦克风模块
+// 无需热键：录音流在会话之间保持打开，以低开销做语音检测；检测到持续语音后自动开始听写会话，
+// 静音后自动结束，之后走与热键听写相同的 TNL / LLM / 插入流程。
+// 触发判定所用的语音由采集引擎的预录缓冲补回会话开头，不会丢失第一个字。
+
+use crossbeam_channel::{Receiver, Sender};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::audio_capture::{CaptureSink, TARGET_SAMPLE_RATE};
+use crate::beep_player;
+use crate::config::{OpenMicConfig, VadConfig};
+use crate::vad::VoiceDetector;
+
+/// 语音检测的分析块长度（0.1秒 @ 16kHz）
+const ANALYSIS_SAMPLES: usize = 1600;
+/// 预录缓冲在触发所需语音之外多保留的时长（覆盖起始的弱辅音）
+pub const PREROLL_MARGIN_MS: u32 = 500;
+/// 触发后（通常即会话结束后）的冷却时长，避免句尾余音立即再次触发
+const REARM_COOLDOWN_MS: u64 = 800;
+
+/// 持续语音触发判定：按音频时长累计连续语音，提示音可听见期间的音频不计入
+pub struct SpeechTrigger {
+    trigger_samples: u64,
+    voiced_samples: u64,
+    cooldown_samples: u64,
+}
+
+impl SpeechTrigger {
+    pub fn new(trigger_ms: u32) -> Self {
+        Self {
+            trigger_samples: ms_to_samples(trigger_ms as u64),
+            voiced_samples: 0,
+            cooldown_samples: 0,
+        }
+    }
+
+    /// 记录一段音频的语音判决，满足触发条件时返回 true 并进入冷却
+    pub fn push(&mut self, samples: usize, is_voice: bool, beep_audible: bool) -> bool {
+        let samples = samples as u64;
+        if self.cooldown_samples > 0 {
+            self.cooldown_samples = self.cooldown_samples.saturating_sub(samples);
+            self.voiced_samples = 0;
+            return false;
+        }
+        if !is_voice || beep_audible {
+            self.voiced_samples = 0;
+            return false;
+        }
+
+        self.voiced_samples += samples;
+        if self.voiced_samples < self.trigger_samples {
+            return false;
+        }
+        self.voiced_samples = 0;
+        self.cooldown_samples = ms_to_samples(REARM_COOLDOWN_MS);
+        true
+    }
+}
+
+fn ms_to_samples(ms: u64) -> u64 {
+    ms * TARGET_SAMPLE_RATE as u64 / 1000
+}
+
+/// 后台监听 sink：会话之间逐块做语音检测，检测到持续语音时发出触发信号
+pub struct OpenMicSink {
+    trigger: SpeechTrigger,
+    vad: VoiceDetector,
+    pending: Vec<f32>,
+    sender: Sender<()>,
+}
+
+impl OpenMicSink {
+    pub fn new(config: &OpenMicConfig, vad: &VadConfig, sender: Sender<()>) -> Self {
+        Self {
+            trigger: SpeechTrigger::new(config.trigger_ms),
+            vad: VoiceDetector::new(vad, TARGET_SAMPLE_RATE),
+            pending: Vec::with_capacity(ANALYSIS_SAMPLES * 2),
+            sender,
+        }
+    }
+}
+
+impl CaptureSink for OpenMicSink {
+    fn write(&mut self, frame: &[f32]) {
+        self.pending.extend_from_slice(frame);
+        let blocks = self.pending.len() / ANALYSIS_SAMPLES;
+
+        for block in self.pending[..blocks * ANALYSIS_SAMPLES].chunks_exact(ANALYSIS_SAMPLES) {
+            let is_voice = self.vad.is_voice(block);
+            if self
+                .trigger
+                .push(block.len(), is_voice, beep_player::is_beep_audible())
+            {
+                tracing::info!("开放麦克风：检测到持续语音");
+                let _ = self.sender.try_send(());
+            }
+        }
+        self.pending.drain(..blocks * ANALYSIS_SAMPLES);
+    }
+}
+
+/// 开放麦克风运行状态（静音开关、待开始的会话标记、触发信号通道）
+pub struct OpenMicController {
+    muted: AtomicBool,
+    session_requested: AtomicBool,
+    sender: Sender<()>,
+}
+
+impl OpenMicController {
+    /// 创建控制器，返回的接收端交给会话调度任务
+    pub fn new() -> (Self, Receiver<()>) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        (
+            Self {
+                muted: AtomicBool::new(false),
+                session_requested: AtomicBool::new(false),
+                sender,
+            },
+            receiver,
+        )
+    }
+
+    pub fn trigger_sender(&self) -> Sender<()> {
+        self.sender.clone()
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::SeqCst)
+    }
+
+    pub fn set_muted(&self, muted: bool) {
+        self.muted.store(muted, Ordering::SeqCst);
+    }
+
+    /// 标记下一次开始的录音由开放麦克风发起
+    pub fn request_session(&self) {
+        self.session_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// 取出开放麦克风会话标记（开始录音时调用）
+    pub fn take_session_request(&self) -> bool {
+        self.session_requested.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// 开放麦克风状态（推送给前端的隐私指示）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OpenMicState {
+    pub enabled: bool,
+    pub muted: bool,
+    /// 麦克风是否正在被监听
+    pub listening: bool,
+}
+
+const BADGE_COLOR: [u8; 4] = [220, 38, 38, 255];
+const BADGE_BORDER_COLOR: [u8; 4] = [255, 255, 255, 255];
+
+/// 在托盘图标右下角叠加红点，后台监听麦克风时不打开设置也能看到
+///
+/// `rgba` 为逐像素 RGBA 数据，返回同尺寸的新图像；数据长度与尺寸不符时原样返回
+pub fn with_listening_badge(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut output = rgba.to_vec();
+    if rgba.len() != width as usize * height as usize * 4 {
+        return output;
+    }
+
+    let size = width.min(height) as f32;
+    let radius = size * 0.22;
+    let border = (size * 0.06).max(1.0);
+    let center_x = width as f32 - radius - border;
+    let center_y = height as f32 - radius - border;
+    for y in 0..height {
+        for x in 0..width {
+            let distance = (x as f32 + 0.5 - center_x).hypot(y as f32 + 0.5 - center_y);
+            let color = if distance <= radius {
+                BADGE_COLOR
+            } else if distance <= radius + border {
+                BADGE_BORDER_COLOR
+            } else {
+                continue;
+            };
+            let offset = (y as usize * width as usize + x as usize) * 4;
+            output[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: usize = ANALYSIS_SAMPLES;
+
+    #[test]
+    fn triggers_on_sustained_speech_only() {
+        let mut trigger = SpeechTrigger::new(400);
+
+        // 零散的短促声音（咳嗽、键盘）不触发
+        for _ in 0..10 {
+            assert!(!trigger.push(BLOCK, true, false));
+            assert!(!trigger.push(BLOCK, false, false));
+        }
+
+        assert!(!trigger.push(BLOCK, true, false));
+        assert!(!trigger.push(BLOCK, true, false));
+        assert!(!trigger.push(BLOCK, true, false));
+        assert!(trigger.push(BLOCK, true, false));
+
+        // 冷却期内持续说话不重复触发
+        for _ in 0..8 {
+            assert!(!trigger.push(BLOCK, true, false));
+        }
+        let fired = (0..4).filter(|_| trigger.push(BLOCK, true, false)).count();
+        assert_eq!(fired, 1);
+    }
+
+    #[test]
+    fn ignores_audio_while_beep_is_audible() {
+        let mut trigger = SpeechTrigger::new(300);
+
+        for _ in 0..20 {
+            assert!(!trigger.push(BLOCK, true, true));
+        }
+        // 提示音结束后重新累计
+        assert!(!trigger.push(BLOCK, true, false));
+        assert!(!trigger.push(BLOCK, true, false));
+        assert!(trigger.push(BLOCK, true, false));
+    }
+
+    #[test]
+    fn listening_badge_marks_bottom_right_corner_only() {
+        let (width, height) = (32, 32);
+        let icon = vec![10u8; width * height * 4];
+        let badged = with_listening_badge(&icon, width as u32, height as u32);
+        assert_eq!(badged.len(), icon.len());
+
+        let pixel = |x: usize, y: usize| &badged[(y * width + x) * 4..(y * width + x) * 4 + 4];
+        assert_eq!(pixel(0, 0), &[10, 10, 10, 10]);
+        assert_eq!(pixel(width - 9, height - 9), &BADGE_COLOR);
+
+        // 尺寸不符时不修改
+        assert_eq!(with_listening_badge(&icon, 16, 16), icon);
+    }
+}
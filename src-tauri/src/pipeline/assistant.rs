@@ -11,9 +11,11 @@ use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 
 use super::types::{PipelineResult, TranscriptionContext, TranscriptionMode};
+use crate::asr::AsrTranscript;
 use crate::assistant_processor::AssistantProcessor;
 use crate::clipboard_manager::{insert_text_with_context, ClipboardGuard};
 use crate::config::AppConfig;
+use crate::dictionary_utils::entries_to_words;
 use crate::learning::coordinator::start_learning_observation;
 use crate::tnl::TnlEngine;
 
@@ -53,14 +55,15 @@ impl AssistantPipeline {
         app: &AppHandle,
         processor: Option<AssistantProcessor>,
         clipboard_guard: Option<ClipboardGuard>,
-        asr_result: Result<String>,
+        asr_result: Result<AsrTranscript>,
         asr_time_ms: u64,
         context: TranscriptionContext,
         target_hwnd: Option<isize>, // 目标窗口句柄（用于焦点恢复）
         dictionary: Vec<String>,
     ) -> Result<PipelineResult> {
         // 1. 解包 ASR 结果（用户指令）
-        let asr_instruction = asr_result?;
+        let transcript = asr_result?;
+        let asr_instruction = transcript.text().to_string();
         tracing::info!(
             "AssistantPipeline: 收到用户指令: {} (ASR耗时: {}ms)",
             asr_instruction,
@@ -155,11 +158,12 @@ impl AssistantPipeline {
             Some(llm_time_ms),
             TranscriptionMode::Assistant,
             inserted,
-        ))
+        )
+        .with_transcript(transcript))
     }
 
     fn build_tnl_engine(dictionary: Vec<String>) -> TnlEngine {
-        TnlEngine::new(dictionary)
+        TnlEngine::new(entries_to_words(&dictionary))
     }
 
     /// 插入文本到当前光标位置
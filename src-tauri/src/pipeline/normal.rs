@@ -11,7 +11,9 @@ use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 
 use super::types::{PipelineResult, TranscriptionContext, TranscriptionMode};
+use crate::asr::AsrTranscript;
 use crate::config::AppConfig;
+use crate::dictionary_utils::entries_to_words;
 use crate::learning::coordinator::start_learning_observation;
 use crate::llm_post_processor::LlmPostProcessor;
 use crate::text_inserter::TextInserter;
@@ -54,13 +56,14 @@ impl NormalPipeline {
         dictionary: Vec<String>,
         enable_dictionary_enhancement: bool,
         text_inserter: &mut Option<TextInserter>,
-        asr_result: Result<String>,
+        asr_result: Result<AsrTranscript>,
         asr_time_ms: u64,
         _context: TranscriptionContext, // 普通模式不使用上下文
         target_hwnd: Option<isize>,     // 目标窗口句柄（用于焦点恢复）
     ) -> Result<PipelineResult> {
         // 1. 解包 ASR 结果
-        let asr_text = asr_result?;
+        let transcript = asr_result?;
+        let asr_text = transcript.text().to_string();
         tracing::info!(
             "NormalPipeline: 收到 ASR 结果: {} (耗时: {}ms)",
             asr_text,
@@ -75,7 +78,7 @@ impl NormalPipeline {
                 .unwrap_or(true);
 
             if tnl_enabled {
-                let engine = TnlEngine::new(dictionary.clone());
+                let engine = TnlEngine::new(entries_to_words(&dictionary));
                 let tnl_result = engine.normalize(&asr_text);
                 if tnl_result.changed {
                     tracing::info!(
@@ -102,6 +105,7 @@ impl NormalPipeline {
             &dictionary,
             enable_dictionary_enhancement,
             &text,
+            transcript.alternatives(),
         )
         .await;
 
@@ -150,7 +154,8 @@ impl NormalPipeline {
             llm_time_ms,
             TranscriptionMode::Normal,
             inserted,
-        ))
+        )
+        .with_transcript(transcript))
     }
 
     /// 可选的 LLM 后处理
@@ -164,6 +169,7 @@ impl NormalPipeline {
         dictionary: &[String],
         enable_dictionary_enhancement: bool,
         text: &str,
+        alternatives: &[String],
     ) -> (String, Option<String>, Option<u64>) {
         if !enable_post_process && !enable_dictionary_enhancement {
             return (text.to_string(), None, None);
@@ -182,6 +188,7 @@ impl NormalPipeline {
             match processor
                 .polish_transcript(
                     text,
+                    alternatives,
                     dictionary,
                     enable_post_process,
                     enable_dictionary_enhancement,
@@ -7,6 +7,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::asr::AsrTranscript;
+
 /// 转录处理模式
 ///
 /// 决定 ASR 结果如何被后续处理
@@ -57,6 +59,9 @@ pub struct PipelineResult {
     pub mode: TranscriptionMode,
     /// 是否已自动插入文本
     pub inserted: bool,
+    /// 完整的 ASR 转录结果（分段、时间戳、置信度）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<AsrTranscript>,
 }
 
 impl PipelineResult {
@@ -79,6 +84,13 @@ impl PipelineResult {
             total_time_ms: asr_time_ms + llm_time_ms.unwrap_or(0),
             mode,
             inserted,
+            transcript: None,
         }
     }
+
+    /// 附带完整的 ASR 转录结果
+    pub fn with_transcript(mut self, transcript: AsrTranscript) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
 }
@@ -0,0 +1,334 @@
+// 录音归档
+//
+// 开启后，每次转写结果插入完成后，在后台线程把 `stop_recording_to_memory` / `stop_streaming`
+// 得到的 WAV 以 FLAC 保存到配置目录的 recordings 子目录，旁边的同名 JSON 记录转写结果。提供商把关键句识别错时，
+// 可以换提供商、润色预设或 TNL 设置重新转写，结果追加到同一条记录。
+// 每次归档后按保留天数与总大小上限清理，从最旧的录音开始删除
+
+use crate::asr::{self, AsrTranscript};
+use crate::config::{AudioUploadFormat, RecordingArchiveConfig, TriggerMode};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// 一次转写结果（录音时的结果或重新转写）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTranscription {
+    /// 完成时间（Unix 毫秒）
+    pub created_at: u64,
+    /// ASR 提供商（config_key）；录音时的结果可能经过多个提供商调度，此时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// 润色预设 ID（未润色时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset_id: Option<String>,
+    /// 是否经过 TNL 规范化（录音时的结果沿用当时的配置，未记录）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tnl: Option<bool>,
+    pub text: String,
+    /// 润色 / 规范化前的 ASR 原文（与最终文本不同时才有值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<AsrTranscript>,
+}
+
+/// 归档的一次录音
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedRecording {
+    pub id: String,
+    /// 录音结束时间（Unix 毫秒）
+    pub created_at: u64,
+    pub duration_ms: u64,
+    pub mode: TriggerMode,
+    /// 音频文件名（FLAC 编码失败时退回 WAV）
+    pub audio_file: String,
+    pub size_bytes: u64,
+    /// 录音时的转写结果（转写失败或尚未完成时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription: Option<ArchivedTranscription>,
+    /// 重新转写的结果（按时间先后）
+    #[serde(default)]
+    pub retranscriptions: Vec<ArchivedTranscription>,
+}
+
+pub fn archive_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?;
+    let dir = config_dir.join("PushToTalk").join("recordings");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// 归档一次录音（连同转写结果一次写入）并按保留策略清理。涉及编码与磁盘 IO，应在阻塞线程调用
+pub fn save(
+    wav: &[u8],
+    mode: TriggerMode,
+    transcription: Option<ArchivedTranscription>,
+    config: &RecordingArchiveConfig,
+) -> Result<ArchivedRecording> {
+    let record = save_in(&archive_dir()?, wav, mode, transcription, now_ms())?;
+    if let Err(e) = apply_retention(config) {
+        tracing::warn!("录音归档清理失败: {}", e);
+    }
+    Ok(record)
+}
+
+/// 按保留天数与总大小上限清理旧录音，返回删除的条数
+pub fn apply_retention(config: &RecordingArchiveConfig) -> Result<usize> {
+    let removed = enforce_retention(
+        &archive_dir()?,
+        config.max_age_days as u64 * DAY_MS,
+        config.max_total_mb as u64 * 1024 * 1024,
+        now_ms(),
+    )?;
+    if removed > 0 {
+        tracing::info!("录音归档：按保留策略清理 {} 条旧录音", removed);
+    }
+    Ok(removed)
+}
+
+fn save_in(
+    dir: &Path,
+    wav: &[u8],
+    mode: TriggerMode,
+    transcription: Option<ArchivedTranscription>,
+    now: u64,
+) -> Result<ArchivedRecording> {
+    let reader = hound::WavReader::new(Cursor::new(wav))?;
+    let duration_ms = reader.duration() as u64 * 1000 / reader.spec().sample_rate.max(1) as u64;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let encoded = asr::encode_for_upload(wav, AudioUploadFormat::Flac);
+    let extension = match encoded.format {
+        AudioUploadFormat::Flac => "flac",
+        _ => "wav",
+    };
+    let audio_file = format!("{}.{}", id, extension);
+    std::fs::write(dir.join(&audio_file), &encoded.data)?;
+
+    let record = ArchivedRecording {
+        id,
+        created_at: now,
+        duration_ms,
+        mode,
+        audio_file,
+        size_bytes: encoded.data.len() as u64,
+        transcription,
+        retranscriptions: Vec::new(),
+    };
+    write_record(dir, &record)?;
+    tracing::info!(
+        "录音已归档: {} ({}ms, {:.1} KB)",
+        record.id,
+        record.duration_ms,
+        record.size_bytes as f64 / 1024.0
+    );
+    Ok(record)
+}
+
+/// 修改一条归档记录并写回
+pub fn update(id: &str, f: impl FnOnce(&mut ArchivedRecording)) -> Result<ArchivedRecording> {
+    let dir = archive_dir()?;
+    let mut record = read_record(&dir, id)?;
+    f(&mut record);
+    write_record(&dir, &record)?;
+    Ok(record)
+}
+
+pub fn get(id: &str) -> Result<ArchivedRecording> {
+    read_record(&archive_dir()?, id)
+}
+
+/// 所有归档录音，最新的在前
+pub fn list() -> Result<Vec<ArchivedRecording>> {
+    let mut records = list_in(&archive_dir()?)?;
+    records.reverse();
+    Ok(records)
+}
+
+/// 读出归档录音的 WAV（FLAC 先解码）
+pub fn load_wav(record: &ArchivedRecording) -> Result<Vec<u8>> {
+    let data = std::fs::read(archive_dir()?.join(&record.audio_file))?;
+    if record.audio_file.ends_with(".flac") {
+        asr::decode_flac_to_wav(&data)
+    } else {
+        Ok(data)
+    }
+}
+
+pub fn delete(id: &str) -> Result<()> {
+    let dir = archive_dir()?;
+    let record = read_record(&dir, id)?;
+    remove_record(&dir, &record);
+    Ok(())
+}
+
+/// 删除超过保留时长的录音，再从最旧的开始删除直到总大小不超过上限；最新一条始终保留。
+/// 上限为 0 表示不限
+fn enforce_retention(dir: &Path, max_age_ms: u64, max_total_bytes: u64, now: u64) -> Result<usize> {
+    let mut records = list_in(dir)?;
+    // 最新一条不参与清理
+    let Some(newest) = records.pop() else {
+        return Ok(0);
+    };
+
+    let mut total: u64 = records.iter().map(|r| r.size_bytes).sum::<u64>() + newest.size_bytes;
+    let mut removed = 0;
+    for record in &records {
+        let expired = max_age_ms > 0 && now.saturating_sub(record.created_at) > max_age_ms;
+        let over_size = max_total_bytes > 0 && total > max_total_bytes;
+        if !expired && !over_size {
+            continue;
+        }
+        remove_record(dir, record);
+        total -= record.size_bytes;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// 目录中的所有记录，最旧的在前；无法解析的记录跳过
+fn list_in(dir: &Path) -> Result<Vec<ArchivedRecording>> {
+    let mut records = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(serde_json::from_str::<ArchivedRecording>(&json)?))
+        {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!("跳过无法读取的归档记录 {:?}: {}", path, e),
+        }
+    }
+    records.sort_by_key(|r| r.created_at);
+    Ok(records)
+}
+
+/// 记录 ID 来自前端，只接受 UUID 字符，避免拼出目录外的路径
+fn record_path(dir: &Path, id: &str) -> Result<PathBuf> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        anyhow::bail!("无效的录音 ID: {}", id);
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+fn read_record(dir: &Path, id: &str) -> Result<ArchivedRecording> {
+    let path = record_path(dir, id)?;
+    let json = std::fs::read_to_string(&path).map_err(|_| anyhow::anyhow!("录音不存在: {}", id))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn write_record(dir: &Path, record: &ArchivedRecording) -> Result<()> {
+    let path = record_path(dir, &record.id)?;
+    std::fs::write(path, serde_json::to_string_pretty(record)?)?;
+    Ok(())
+}
+
+fn remove_record(dir: &Path, record: &ArchivedRecording) {
+    for path in [
+        dir.join(&record.audio_file),
+        dir.join(format!("{}.json", record.id)),
+    ] {
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("删除归档文件 {:?} 失败: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(len: usize) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for i in 0..len {
+            let t = i as f32 / 16000.0;
+            writer
+                .write_sample(((t * 300.0 * std::f32::consts::TAU).sin() * 6000.0) as i16)
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn archived_recording_round_trips_as_flac() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let dir = temp.path();
+        let wav = wav_bytes(16000 * 2);
+        let transcription = ArchivedTranscription {
+            created_at: 900,
+            provider: None,
+            preset_id: None,
+            tnl: None,
+            text: "你好".to_string(),
+            original_text: None,
+            transcript: Some(AsrTranscript::from_text("你好")),
+        };
+        let record = save_in(
+            dir,
+            &wav,
+            TriggerMode::Dictation,
+            Some(transcription),
+            1_000,
+        )
+        .unwrap();
+
+        assert_eq!(record.duration_ms, 2000);
+        assert!(record.audio_file.ends_with(".flac"));
+        assert!(record.size_bytes < wav.len() as u64);
+        let flac = std::fs::read(dir.join(&record.audio_file)).unwrap();
+        assert_eq!(asr::decode_flac_to_wav(&flac).unwrap(), wav);
+
+        let loaded = read_record(dir, &record.id).unwrap();
+        assert_eq!(loaded.transcription.unwrap().text, "你好");
+
+        assert!(read_record(dir, "../config").is_err());
+    }
+
+    #[test]
+    fn retention_drops_expired_then_oldest_but_keeps_newest() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let dir = temp.path();
+        let wav = wav_bytes(16000);
+        let now = 100 * DAY_MS;
+        let expired = save_in(dir, &wav, TriggerMode::Dictation, None, now - 40 * DAY_MS).unwrap();
+        let old = save_in(dir, &wav, TriggerMode::AiAssistant, None, now - 2 * DAY_MS).unwrap();
+        let recent = save_in(dir, &wav, TriggerMode::Dictation, None, now - DAY_MS).unwrap();
+        let newest = save_in(dir, &wav, TriggerMode::Dictation, None, now).unwrap();
+
+        assert_eq!(enforce_retention(dir, 30 * DAY_MS, 0, now).unwrap(), 1);
+        assert!(!dir.join(&expired.audio_file).exists());
+
+        // 上限只够两条：删掉最旧的一条
+        let limit = recent.size_bytes + newest.size_bytes + old.size_bytes / 2;
+        assert_eq!(enforce_retention(dir, 0, limit, now).unwrap(), 1);
+        let ids: Vec<String> = list_in(dir).unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![recent.id.clone(), newest.id.clone()]);
+
+        // 上限小于单条录音时，只保留最新一条
+        assert_eq!(enforce_retention(dir, 0, 1, now).unwrap(), 1);
+        let ids: Vec<String> = list_in(dir).unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![newest.id]);
+    }
+}
@@ -0,0 +1,492 @@
+// 语音活动检测（VAD）模块
+//
+// 提供两种检测器（在配置中选择）：
+// - RMS：固定能量阈值（旧方案），小声说话会被丢弃，键盘声、风扇声会被放行
+// - GMM：WebRTC 风格的统计模型。按 20ms 帧将音频分到 6 个子带（80Hz~4kHz）取对数能量，
+//   每个子带分别用噪声 / 语音两个高斯混合模型计算对数似然比，加权求和后按激进程度判决；
+//   噪声模型在非语音帧上自适应，并用最小值统计跟踪底噪上升（如风扇启动），
+//   要求连续 60ms 以上判为语音才算说话，过滤键盘敲击等短促瞬态
+
+use crate::audio_utils::is_voice_active;
+use crate::config::{VadConfig, VadEngine};
+
+/// 分析帧长
+const FRAME_MS: usize = 20;
+/// 子带划分（Hz），与 WebRTC VAD 一致，覆盖语音主要能量
+const BANDS: [(f64, f64); 6] = [
+    (80.0, 250.0),
+    (250.0, 500.0),
+    (500.0, 1000.0),
+    (1000.0, 2000.0),
+    (2000.0, 3000.0),
+    (3000.0, 4000.0),
+];
+/// 各子带在总似然比中的权重（中频语音信息最多）
+const BAND_WEIGHTS: [f64; 6] = [0.6, 1.0, 1.2, 1.2, 1.0, 0.7];
+/// 噪声模型两个分量相对噪声均值的偏移、标准差与权重（dB）
+const NOISE_OFFSETS: [f64; 2] = [0.0, 4.0];
+const NOISE_STDS: [f64; 2] = [3.0, 4.0];
+const NOISE_WEIGHTS: [f64; 2] = [0.6, 0.4];
+/// 语音模型两个分量（弱 / 强语音）相对噪声均值的初始偏移、最小间隔、标准差与权重（dB）
+const SPEECH_OFFSETS: [f64; 2] = [15.0, 30.0];
+const SPEECH_MIN_GAPS: [f64; 2] = [8.0, 14.0];
+const SPEECH_STDS: [f64; 2] = [8.0, 8.0];
+const SPEECH_WEIGHTS: [f64; 2] = [0.5, 0.5];
+/// 底噪初始值（dB），实际更低时立即跟随
+const INITIAL_NOISE_DB: f64 = -80.0;
+/// 最小值统计的块长（75 × 20ms = 1.5 秒），底噪取相邻两块内的最低能量
+const NOISE_BLOCK_FRAMES: usize = 75;
+/// 噪声均值相对底噪的上限（dB），防止语音间隙把噪声模型抬高
+const MAX_NOISE_ABOVE_FLOOR_DB: f64 = 6.0;
+/// 噪声 / 语音模型的自适应速率
+const NOISE_ADAPT_RATE: f64 = 0.05;
+const NOISE_DROP_RATE: f64 = 0.2;
+const SPEECH_ADAPT_RATE: f64 = 0.05;
+/// 单个子带似然比的截断范围，避免极端值主导
+const MAX_BAND_LLR: f64 = 20.0;
+/// 低于该 RMS 的帧视为数字静音，不做模型判断
+const ABS_MIN_RMS: f32 = 0.0008;
+/// 判为说话所需的最少连续语音帧数（3 × 20ms = 60ms）
+const MIN_SPEECH_RUN: usize = 3;
+
+/// 各激进程度（0~3）的判决阈值：任一子带似然比 / 加权总似然比
+const BAND_THRESHOLDS: [f64; 4] = [8.0, 10.0, 12.0, 14.0];
+const TOTAL_THRESHOLDS: [f64; 4] = [14.0, 18.0, 24.0, 30.0];
+
+/// 二阶带通滤波器（RBJ，峰值增益 0dB）
+#[derive(Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    fn bandpass(low: f64, high: f64, sample_rate: f64) -> Self {
+        let center = (low * high).sqrt();
+        let q = center / (high - low);
+        let w0 = 2.0 * std::f64::consts::PI * center / sample_rate;
+        let alpha = w0.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: alpha / a0,
+            b2: -alpha / a0,
+            a1: -2.0 * w0.cos() / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        // 转置直接 II 型
+        let y = self.b0 * x + self.z1;
+        self.z1 = -self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+fn log_gaussian(x: f64, mean: f64, std: f64) -> f64 {
+    let z = (x - mean) / std;
+    -0.5 * z * z - std.ln() - 0.5 * (2.0 * std::f64::consts::PI).ln()
+}
+
+/// 两分量高斯混合的对数似然，同时返回各分量的后验概率
+fn log_mixture(x: f64, means: [f64; 2], stds: [f64; 2], weights: [f64; 2]) -> (f64, [f64; 2]) {
+    let terms = [
+        weights[0].ln() + log_gaussian(x, means[0], stds[0]),
+        weights[1].ln() + log_gaussian(x, means[1], stds[1]),
+    ];
+    let max = terms[0].max(terms[1]);
+    let sum = (terms[0] - max).exp() + (terms[1] - max).exp();
+    let log_likelihood = max + sum.ln();
+    let responsibilities = [
+        (terms[0] - log_likelihood).exp(),
+        (terms[1] - log_likelihood).exp(),
+    ];
+    (log_likelihood, responsibilities)
+}
+
+/// 单个子带的滤波器与模型状态
+struct BandModel {
+    filters: [Biquad; 2],
+    noise_mean: f64,
+    /// 最小值统计：上一块与当前块的最低能量
+    previous_block_min: f64,
+    block_min: f64,
+    block_frames: usize,
+    speech_means: [f64; 2],
+}
+
+impl BandModel {
+    fn new(low: f64, high: f64, sample_rate: f64) -> Self {
+        // 高于奈奎斯特频率的子带收窄到可用范围
+        let high = high.min(sample_rate * 0.45);
+        let low = low.min(high * 0.8);
+        let filter = Biquad::bandpass(low, high, sample_rate);
+        let mut band = Self {
+            filters: [filter, filter],
+            noise_mean: INITIAL_NOISE_DB,
+            previous_block_min: INITIAL_NOISE_DB,
+            block_min: f64::INFINITY,
+            block_frames: 0,
+            speech_means: [0.0; 2],
+        };
+        band.reset_noise(INITIAL_NOISE_DB);
+        band
+    }
+
+    fn reset_noise(&mut self, level: f64) {
+        self.noise_mean = level;
+        self.previous_block_min = level;
+        self.block_min = f64::INFINITY;
+        self.block_frames = 0;
+        self.speech_means = [level + SPEECH_OFFSETS[0], level + SPEECH_OFFSETS[1]];
+    }
+
+    /// 滤波后的帧对数能量（dB）
+    fn energy_db(&mut self, frame: &[f32]) -> f64 {
+        let energy = frame
+            .iter()
+            .map(|&s| {
+                let y = self
+                    .filters
+                    .iter_mut()
+                    .fold(s as f64, |x, filter| filter.process(x));
+                y * y
+            })
+            .sum::<f64>()
+            / frame.len().max(1) as f64;
+        10.0 * (energy + 1e-12).log10()
+    }
+
+    fn noise_means(&self) -> [f64; 2] {
+        [
+            self.noise_mean + NOISE_OFFSETS[0],
+            self.noise_mean + NOISE_OFFSETS[1],
+        ]
+    }
+
+    /// 语音 / 噪声对数似然比，返回值附带语音模型各分量的后验概率
+    fn llr(&self, x: f64) -> (f64, [f64; 2]) {
+        let (noise, _) = log_mixture(x, self.noise_means(), NOISE_STDS, NOISE_WEIGHTS);
+        let (speech, responsibilities) =
+            log_mixture(x, self.speech_means, SPEECH_STDS, SPEECH_WEIGHTS);
+        (
+            (speech - noise).clamp(-MAX_BAND_LLR, MAX_BAND_LLR),
+            responsibilities,
+        )
+    }
+
+    fn adapt(&mut self, x: f64, is_speech: bool, responsibilities: [f64; 2]) {
+        // 最小值统计：底噪立即跟随下降，持续不变的噪声（如风扇启动）1.5~3 秒内被吸收进噪声模型
+        self.block_min = self.block_min.min(x);
+        self.block_frames += 1;
+        if self.block_frames >= NOISE_BLOCK_FRAMES {
+            self.previous_block_min = self.block_min;
+            self.block_min = f64::INFINITY;
+            self.block_frames = 0;
+        }
+        let floor = self.previous_block_min.min(self.block_min);
+
+        if is_speech {
+            for (mean, r) in self.speech_means.iter_mut().zip(responsibilities) {
+                *mean += SPEECH_ADAPT_RATE * r * (x - *mean);
+            }
+        } else {
+            let rate = if x < self.noise_mean {
+                NOISE_DROP_RATE
+            } else {
+                NOISE_ADAPT_RATE
+            };
+            self.noise_mean += rate * (x - self.noise_mean);
+        }
+        self.noise_mean = self
+            .noise_mean
+            .clamp(floor, floor + MAX_NOISE_ABOVE_FLOOR_DB);
+
+        // 语音模型始终保持在噪声模型之上
+        self.speech_means[0] = self.speech_means[0].max(self.noise_mean + SPEECH_MIN_GAPS[0]);
+        self.speech_means[1] = self.speech_means[1].max(self.speech_means[0] + SPEECH_MIN_GAPS[1]);
+    }
+}
+
+/// WebRTC 风格的子带高斯混合模型 VAD（逐帧判决）
+pub struct GmmVad {
+    bands: Vec<BandModel>,
+    frame_len: usize,
+    band_threshold: f64,
+    total_threshold: f64,
+}
+
+impl GmmVad {
+    pub fn new(sample_rate: u32, aggressiveness: u8) -> Self {
+        let level = aggressiveness.min(3) as usize;
+        Self {
+            bands: BANDS
+                .iter()
+                .map(|&(low, high)| BandModel::new(low, high, sample_rate as f64))
+                .collect(),
+            frame_len: (sample_rate as usize * FRAME_MS / 1000).max(1),
+            band_threshold: BAND_THRESHOLDS[level],
+            total_threshold: TOTAL_THRESHOLDS[level],
+        }
+    }
+
+    pub fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    /// 判断一帧是否为语音，并据此更新模型
+    pub fn process_frame(&mut self, frame: &[f32]) -> bool {
+        let energies: Vec<f64> = self.bands.iter_mut().map(|b| b.energy_db(frame)).collect();
+        self.decide(&energies, frame)
+    }
+
+    fn decide(&mut self, energies: &[f64], frame: &[f32]) -> bool {
+        let scored: Vec<(f64, [f64; 2])> = self
+            .bands
+            .iter()
+            .zip(energies)
+            .map(|(band, &x)| band.llr(x))
+            .collect();
+        let total: f64 = scored
+            .iter()
+            .zip(BAND_WEIGHTS)
+            .map(|((llr, _), weight)| llr * weight)
+            .sum();
+        let max_band = scored
+            .iter()
+            .map(|(llr, _)| *llr)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let loud_enough = crate::audio_utils::calculate_rms(frame) >= ABS_MIN_RMS;
+        let is_speech =
+            loud_enough && (total > self.total_threshold || max_band > self.band_threshold);
+
+        for ((band, &x), (_, responsibilities)) in self.bands.iter_mut().zip(energies).zip(scored) {
+            band.adapt(x, is_speech, responsibilities);
+        }
+        is_speech
+    }
+
+    /// 离线分析整段录音的逐帧判决：先用各子带最低能量初始化底噪，再逐帧判决
+    pub fn analyze(samples: &[f32], sample_rate: u32, aggressiveness: u8) -> Vec<bool> {
+        let mut vad = Self::new(sample_rate, aggressiveness);
+        let frame_len = vad.frame_len;
+        let energies: Vec<Vec<f64>> = samples
+            .chunks(frame_len)
+            .map(|frame| vad.bands.iter_mut().map(|b| b.energy_db(frame)).collect())
+            .collect();
+
+        for (i, band) in vad.bands.iter_mut().enumerate() {
+            let floor = energies.iter().map(|e| e[i]).fold(f64::INFINITY, f64::min);
+            if floor.is_finite() {
+                band.reset_noise(floor);
+            }
+        }
+
+        samples
+            .chunks(frame_len)
+            .zip(&energies)
+            .map(|(frame, e)| vad.decide(e, frame))
+            .collect()
+    }
+}
+
+/// 把逐帧判决收紧为“连续 MIN_SPEECH_RUN 帧以上才算语音”
+pub fn smooth_speech_frames(frames: &[bool]) -> Vec<bool> {
+    let mut smoothed = vec![false; frames.len()];
+    let mut start = 0;
+    while start < frames.len() {
+        if !frames[start] {
+            start += 1;
+            continue;
+        }
+        let end = frames[start..]
+            .iter()
+            .position(|&f| !f)
+            .map_or(frames.len(), |n| start + n);
+        if end - start >= MIN_SPEECH_RUN {
+            smoothed[start..end].fill(true);
+        }
+        start = end;
+    }
+    smoothed
+}
+
+/// 按配置选择的流式语音检测器（16kHz），录音中逐块调用
+pub struct VoiceDetector {
+    gmm: Option<GmmVad>,
+    pending: Vec<f32>,
+    speech_run: usize,
+}
+
+impl VoiceDetector {
+    pub fn new(config: &VadConfig, sample_rate: u32) -> Self {
+        let gmm = match config.engine {
+            VadEngine::Rms => None,
+            VadEngine::Gmm => Some(GmmVad::new(sample_rate, config.aggressiveness)),
+        };
+        Self {
+            gmm,
+            pending: Vec::new(),
+            speech_run: 0,
+        }
+    }
+
+    /// 判断一块音频是否包含语音（GMM：块内出现连续 60ms 以上的语音帧，跨块累计）
+    pub fn is_voice(&mut self, samples: &[f32]) -> bool {
+        let Some(gmm) = self.gmm.as_mut() else {
+            return is_voice_active(samples);
+        };
+
+        self.pending.extend_from_slice(samples);
+        let frame_len = gmm.frame_len();
+        let frames = self.pending.len() / frame_len;
+        let mut detected = false;
+        for frame in self.pending.chunks_exact(frame_len) {
+            if gmm.process_frame(frame) {
+                self.speech_run += 1;
+                detected |= self.speech_run >= MIN_SPEECH_RUN;
+            } else {
+                self.speech_run = 0;
+            }
+        }
+        self.pending.drain(..frames * frame_len);
+        detected
+    }
+}
+
+/// 判断整段（短）录音是否包含语音
+pub fn contains_speech(samples: &[f32], sample_rate: u32, config: &VadConfig) -> bool {
+    match config.engine {
+        VadEngine::Rms => is_voice_active(samples),
+        VadEngine::Gmm => {
+            let frames = GmmVad::analyze(samples, sample_rate, config.aggressiveness);
+            smooth_speech_frames(&frames).contains(&true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    /// 简单的伪随机噪声（xorshift），避免测试依赖随机数库
+    fn noise(len: usize, amplitude: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed.max(1);
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    /// 合成浊音：150Hz 基频的谐波叠加共振峰包络，按 4Hz 音节节奏调制，每 1.5 秒停顿 0.3 秒
+    fn voiced(len: usize, amplitude: f32) -> Vec<f32> {
+        let formants = [(700.0, 1.0), (1200.0, 0.7), (2500.0, 0.3)];
+        let raw: Vec<f32> = (0..len)
+            .map(|i| {
+                let t = i as f64 / RATE as f64;
+                let envelope = if t % 1.5 < 1.2 {
+                    0.55 + 0.45 * (2.0 * std::f64::consts::PI * 4.0 * t).sin()
+                } else {
+                    0.0
+                };
+                let sum: f64 = (1..=25)
+                    .map(|h| {
+                        let f = 150.0 * h as f64;
+                        let gain: f64 = formants
+                            .iter()
+                            .map(|&(fc, g)| g / (1.0 + ((f - fc) / 150.0).powi(2)))
+                            .sum();
+                        gain * (2.0 * std::f64::consts::PI * f * t).sin()
+                    })
+                    .sum();
+                (sum * envelope) as f32
+            })
+            .collect();
+        let rms = crate::audio_utils::calculate_rms(&raw);
+        raw.iter().map(|s| s / rms * amplitude).collect()
+    }
+
+    fn mix(a: &[f32], b: &[f32]) -> Vec<f32> {
+        a.iter().zip(b).map(|(x, y)| x + y).collect()
+    }
+
+    fn gmm(aggressiveness: u8) -> VadConfig {
+        VadConfig {
+            engine: VadEngine::Gmm,
+            aggressiveness,
+        }
+    }
+
+    /// 流式检测：跳过前 2 秒适应期后，有语音的块所占比例
+    fn voiced_chunk_ratio(config: &VadConfig, audio: &[f32]) -> f32 {
+        let mut detector = VoiceDetector::new(config, RATE);
+        let decisions: Vec<bool> = audio.chunks(3200).map(|c| detector.is_voice(c)).collect();
+        let tail = &decisions[10..];
+        tail.iter().filter(|&&d| d).count() as f32 / tail.len() as f32
+    }
+
+    #[test]
+    fn gmm_passes_quiet_speech_that_rms_drops() {
+        let room = noise(RATE as usize * 6, 0.0003, 7);
+        let mut audio = room.clone();
+        let speech = voiced(RATE as usize * 3, 0.002);
+        let start = RATE as usize * 3;
+        audio[start..].copy_from_slice(&mix(&room[start..], &speech));
+
+        let speech_part = &audio[start + 1600..];
+        assert!(!is_voice_active(speech_part), "RMS 阈值会丢弃小声说话");
+        assert!(contains_speech(speech_part, RATE, &gmm(1)));
+
+        // 流式：前 3 秒只有底噪不应判为语音，后 3 秒说话应大部分判为语音
+        let mut detector = VoiceDetector::new(&gmm(1), RATE);
+        let decisions: Vec<bool> = audio.chunks(3200).map(|c| detector.is_voice(c)).collect();
+        assert!(!decisions[5..15].contains(&true));
+        assert!(decisions[16..].iter().filter(|&&d| d).count() >= 12);
+    }
+
+    #[test]
+    fn gmm_rejects_steady_fan_noise() {
+        let fan = noise(RATE as usize * 8, 0.02, 11);
+        assert!(is_voice_active(&fan), "RMS 阈值会放行风扇声");
+        assert!(voiced_chunk_ratio(&gmm(1), &fan) < 0.05);
+        assert!(!contains_speech(&fan[..8000], RATE, &gmm(1)));
+    }
+
+    #[test]
+    fn gmm_rejects_keyboard_clicks() {
+        let mut audio = noise(RATE as usize * 6, 0.0003, 3);
+        // 每 150ms 一次约 8ms 的衰减宽带瞬态
+        let click = noise(128, 0.3, 5);
+        for (n, start) in (0..audio.len() - 128).step_by(2400).enumerate() {
+            for (i, s) in click.iter().enumerate() {
+                audio[start + i] +=
+                    s * (-(i as f32) / 24.0).exp() * if n % 2 == 0 { 1.0 } else { 0.7 };
+            }
+        }
+        assert!(is_voice_active(&audio), "RMS 阈值会放行键盘声");
+        assert!(voiced_chunk_ratio(&gmm(1), &audio) < 0.1);
+    }
+
+    #[test]
+    fn smoothing_drops_short_runs() {
+        let frames = [true, false, true, true, false, true, true, true, false];
+        assert_eq!(
+            smooth_speech_frames(&frames),
+            [false, false, false, false, false, true, true, true, false]
+        );
+    }
+}
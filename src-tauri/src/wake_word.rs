@@ -0,0 +1,735 @@
+// 唤醒词模块
+// 在热键之外，可选用本地关键词检测开始听写或 AI 助手会话：用户为每个唤醒词录制几遍样本，
+// 后台监听时提取 MFCC 特征并与样本做子序列 DTW 匹配，纯 CPU 运行，无需下载模型。
+// 触发后由预录缓冲补回唤醒词及其后的语音，转写完成后再从文本开头去掉唤醒词。
+
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender};
+use pinyin::ToPinyin;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::audio_capture::{encode_wav, CaptureSink, TARGET_SAMPLE_RATE};
+use crate::config::{TriggerMode, VadConfig, WakeWordConfig};
+use crate::vad::VoiceDetector;
+
+/// 每个唤醒词至少需要的样本数（用样本间距离标定检测阈值）
+pub const MIN_SAMPLES: usize = 2;
+/// 预录缓冲在最长样本之外多保留的时长
+pub const PREROLL_MARGIN_MS: u32 = 500;
+/// 检测阈值下限：样本几乎相同时（如重复录入同一段音频）样本间距离接近 0，
+/// 按比例得到的阈值会让匹配度变成 inf / NaN
+const MIN_THRESHOLD: f32 = 2.0;
+
+/// 特征帧长 25ms、帧移 10ms（@ 16kHz）
+const FRAME_LEN: usize = 400;
+const HOP_LEN: usize = 160;
+const FFT_SIZE: usize = 512;
+const MEL_BANDS: usize = 26;
+/// 倒谱系数个数（去掉 c0，对音量不敏感）
+const CEPSTRA: usize = 12;
+/// 每隔多少帧做一次匹配（50ms）
+const EVAL_INTERVAL_FRAMES: usize = 5;
+/// 触发后的冷却帧数，避免同一次唤醒重复触发
+const COOLDOWN_FRAMES: usize = 150;
+/// 历史特征相对最长样本的倍数（容纳语速变化）
+const HISTORY_FACTOR: f32 = 1.5;
+/// 语音检测的分析块长度（0.1秒 @ 16kHz）
+const ANALYSIS_SAMPLES: usize = 1600;
+/// 最近多少个分析块内有语音才做匹配
+const VOICE_HOLD_BLOCKS: usize = 10;
+/// 录制样本的有效时长范围（毫秒）
+const MIN_SAMPLE_MS: usize = 250;
+const MAX_SAMPLE_MS: usize = 2500;
+
+type Feature = [f32; CEPSTRA];
+
+/// 流式 MFCC 特征提取
+struct MfccExtractor {
+    window: Vec<f32>,
+    /// 每个 mel 频带的 (频点, 权重)
+    filters: Vec<Vec<(usize, f32)>>,
+    dct: Vec<[f32; MEL_BANDS]>,
+    pending: Vec<f32>,
+}
+
+impl MfccExtractor {
+    fn new() -> Self {
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos()
+            })
+            .collect();
+
+        let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+        let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+        let (low, high) = (hz_to_mel(20.0), hz_to_mel(7600.0));
+        let bins: Vec<f32> = (0..MEL_BANDS + 2)
+            .map(|i| {
+                let hz = mel_to_hz(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32);
+                hz * FFT_SIZE as f32 / TARGET_SAMPLE_RATE as f32
+            })
+            .collect();
+        let filters = (0..MEL_BANDS)
+            .map(|band| {
+                let (left, center, right) = (bins[band], bins[band + 1], bins[band + 2]);
+                (left.ceil() as usize..=right.floor() as usize)
+                    .filter_map(|bin| {
+                        let f = bin as f32;
+                        let weight = if f <= center {
+                            (f - left) / (center - left)
+                        } else {
+                            (right - f) / (right - center)
+                        };
+                        (weight > 0.0).then_some((bin, weight))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let dct = (1..=CEPSTRA)
+            .map(|k| {
+                let mut row = [0.0; MEL_BANDS];
+                for (m, value) in row.iter_mut().enumerate() {
+                    *value = (std::f32::consts::PI * k as f32 * (m as f32 + 0.5)
+                        / MEL_BANDS as f32)
+                        .cos();
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            window,
+            filters,
+            dct,
+            pending: Vec::with_capacity(FRAME_LEN * 2),
+        }
+    }
+
+    /// 追加音频，返回新产生的特征帧
+    fn push(&mut self, samples: &[f32]) -> Vec<Feature> {
+        self.pending.extend_from_slice(samples);
+        let mut features = Vec::new();
+        let mut start = 0;
+        while start + FRAME_LEN <= self.pending.len() {
+            features.push(self.frame_feature(&self.pending[start..start + FRAME_LEN]));
+            start += HOP_LEN;
+        }
+        self.pending.drain(..start);
+        features
+    }
+
+    fn frame_feature(&self, frame: &[f32]) -> Feature {
+        // 预加重后加窗
+        let mut windowed = [0.0; FRAME_LEN];
+        for i in 0..FRAME_LEN {
+            let previous = if i > 0 { frame[i - 1] } else { 0.0 };
+            windowed[i] = (frame[i] - 0.97 * previous) * self.window[i];
+        }
+        let spectrum = power_spectrum(&windowed);
+
+        let mut log_energies = [0.0; MEL_BANDS];
+        for (energy, filter) in log_energies.iter_mut().zip(&self.filters) {
+            let sum: f32 = filter.iter().map(|&(bin, w)| spectrum[bin] * w).sum();
+            *energy = (sum + 1e-8).ln();
+        }
+
+        let mut feature = [0.0; CEPSTRA];
+        for (value, row) in feature.iter_mut().zip(&self.dct) {
+            *value = row.iter().zip(&log_energies).map(|(c, e)| c * e).sum();
+        }
+        feature
+    }
+}
+
+/// 基 2 FFT 求功率谱（输入不足 FFT_SIZE 时补零）
+fn power_spectrum(frame: &[f32]) -> Vec<f32> {
+    let mut re = [0.0f32; FFT_SIZE];
+    let mut im = [0.0f32; FFT_SIZE];
+    re[..frame.len()].copy_from_slice(frame);
+
+    let bits = FFT_SIZE.trailing_zeros();
+    for i in 0..FFT_SIZE {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            re.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= FFT_SIZE {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..FFT_SIZE).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+
+    (0..=FFT_SIZE / 2)
+        .map(|k| re[k] * re[k] + im[k] * im[k])
+        .collect()
+}
+
+fn features_of(samples: &[f32]) -> Vec<Feature> {
+    MfccExtractor::new().push(samples)
+}
+
+fn frame_distance(a: &Feature, b: &Feature) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// 子序列 DTW：模板完整匹配、在历史特征中起止位置不限，返回按路径长度归一化的最小平均帧距离
+fn subsequence_dtw(template: &[Feature], history: &[Feature]) -> f32 {
+    if template.is_empty() || history.is_empty() {
+        return f32::INFINITY;
+    }
+
+    // (累计距离, 路径长度)，按行滚动
+    let mut previous: Vec<(f32, u32)> = history
+        .iter()
+        .map(|h| (frame_distance(&template[0], h), 1))
+        .collect();
+    let mut current = vec![(0.0, 0); history.len()];
+    let average = |(cost, len): (f32, u32)| cost / len as f32;
+
+    for t in &template[1..] {
+        for (j, h) in history.iter().enumerate() {
+            let mut best = previous[j];
+            if j > 0 {
+                for candidate in [previous[j - 1], current[j - 1]] {
+                    if average(candidate) < average(best) {
+                        best = candidate;
+                    }
+                }
+            }
+            current[j] = (best.0 + frame_distance(t, h), best.1 + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous
+        .into_iter()
+        .map(average)
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// 唤醒词命中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakeWordHit {
+    pub phrase: String,
+    pub mode: TriggerMode,
+}
+
+struct SpottedWord {
+    phrase: String,
+    mode: TriggerMode,
+    templates: Vec<Vec<Feature>>,
+    threshold: f32,
+}
+
+/// 关键词检测器：维护最近的特征帧，定期与各唤醒词样本匹配
+pub struct KeywordSpotter {
+    words: Vec<SpottedWord>,
+    extractor: MfccExtractor,
+    history: VecDeque<Feature>,
+    history_capacity: usize,
+    frames_since_eval: usize,
+    cooldown_frames: usize,
+}
+
+impl KeywordSpotter {
+    /// 由唤醒词及其样本音频（16kHz）创建，样本不足的唤醒词被忽略；没有可用唤醒词时返回 None
+    pub fn new(words: Vec<(String, TriggerMode, Vec<Vec<f32>>)>, sensitivity: f32) -> Option<Self> {
+        // 灵敏度越高，允许的距离超出样本间平均距离越多
+        let factor = 1.1 + 0.5 * sensitivity.clamp(0.0, 1.0);
+
+        let words: Vec<SpottedWord> = words
+            .into_iter()
+            .filter_map(|(phrase, mode, samples)| {
+                let templates: Vec<Vec<Feature>> = samples
+                    .iter()
+                    .map(|s| features_of(s))
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                if templates.len() < MIN_SAMPLES {
+                    tracing::warn!("唤醒词「{}」样本不足 {} 个，已忽略", phrase, MIN_SAMPLES);
+                    return None;
+                }
+
+                let mut distances = Vec::new();
+                for (i, a) in templates.iter().enumerate() {
+                    for b in &templates[i + 1..] {
+                        distances.push(subsequence_dtw(a, b).max(subsequence_dtw(b, a)));
+                    }
+                }
+                let threshold = (distances.iter().sum::<f32>() / distances.len() as f32 * factor)
+                    .max(MIN_THRESHOLD);
+                Some(SpottedWord {
+                    phrase,
+                    mode,
+                    templates,
+                    threshold,
+                })
+            })
+            .collect();
+
+        let longest = words
+            .iter()
+            .flat_map(|w| w.templates.iter().map(Vec::len))
+            .max()?;
+        let history_capacity = (longest as f32 * HISTORY_FACTOR) as usize;
+        Some(Self {
+            words,
+            extractor: MfccExtractor::new(),
+            history: VecDeque::with_capacity(history_capacity),
+            history_capacity,
+            frames_since_eval: 0,
+            cooldown_frames: 0,
+        })
+    }
+
+    /// 按配置读取样本文件创建
+    pub fn from_config(config: &WakeWordConfig) -> Option<Self> {
+        let words = config
+            .words
+            .iter()
+            .map(|word| {
+                let samples = word
+                    .samples
+                    .iter()
+                    .filter_map(|name| match load_sample(name) {
+                        Ok(samples) => Some(samples),
+                        Err(e) => {
+                            tracing::warn!("读取唤醒词样本 {} 失败: {}", name, e);
+                            None
+                        }
+                    })
+                    .collect();
+                (word.phrase.clone(), word.trigger_mode, samples)
+            })
+            .collect();
+        Self::new(words, config.sensitivity)
+    }
+
+    /// 最长样本的时长（毫秒），预录缓冲需覆盖整个唤醒词
+    pub fn max_template_ms(&self) -> u32 {
+        let frames = self
+            .words
+            .iter()
+            .flat_map(|w| w.templates.iter().map(Vec::len))
+            .max()
+            .unwrap_or(0);
+        ((frames * HOP_LEN + FRAME_LEN) * 1000 / TARGET_SAMPLE_RATE as usize) as u32
+    }
+
+    /// 追加一段音频；`voice_recent` 为 false 时只更新历史不做匹配（节省 CPU）
+    pub fn push(&mut self, samples: &[f32], voice_recent: bool) -> Option<WakeWordHit> {
+        let mut hit = None;
+        for feature in self.extractor.push(samples) {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back(feature);
+            self.frames_since_eval += 1;
+            self.cooldown_frames = self.cooldown_frames.saturating_sub(1);
+
+            if hit.is_none()
+                && voice_recent
+                && self.cooldown_frames == 0
+                && self.frames_since_eval >= EVAL_INTERVAL_FRAMES
+            {
+                self.frames_since_eval = 0;
+                hit = self.evaluate();
+            }
+        }
+        hit
+    }
+
+    /// 匹配各唤醒词，取相对阈值最接近的命中
+    fn evaluate(&mut self) -> Option<WakeWordHit> {
+        let history = self.history.make_contiguous();
+        let mut best: Option<(f32, &SpottedWord)> = None;
+        for word in &self.words {
+            let score = word
+                .templates
+                .iter()
+                .map(|template| {
+                    let window =
+                        ((template.len() as f32 * HISTORY_FACTOR) as usize).min(history.len());
+                    subsequence_dtw(template, &history[history.len() - window..])
+                })
+                .fold(f32::INFINITY, f32::min)
+                / word.threshold;
+            if score < 1.0 && best.is_none_or(|(best_score, _)| score < best_score) {
+                best = Some((score, word));
+            }
+        }
+
+        let (score, word) = best?;
+        tracing::debug!("唤醒词「{}」匹配度 {:.2}", word.phrase, score);
+        let hit = WakeWordHit {
+            phrase: word.phrase.clone(),
+            mode: word.mode,
+        };
+        self.cooldown_frames = COOLDOWN_FRAMES;
+        self.history.clear();
+        Some(hit)
+    }
+}
+
+/// 后台监听 sink：逐块做语音检测，有语音时运行关键词检测，命中时发出触发信号
+pub struct WakeWordSink {
+    spotter: KeywordSpotter,
+    vad: VoiceDetector,
+    pending: Vec<f32>,
+    blocks_since_voice: usize,
+    sender: Sender<WakeWordHit>,
+}
+
+impl WakeWordSink {
+    pub fn new(spotter: KeywordSpotter, vad: &VadConfig, sender: Sender<WakeWordHit>) -> Self {
+        Self {
+            spotter,
+            vad: VoiceDetector::new(vad, TARGET_SAMPLE_RATE),
+            pending: Vec::with_capacity(ANALYSIS_SAMPLES * 2),
+            blocks_since_voice: usize::MAX,
+            sender,
+        }
+    }
+}
+
+impl CaptureSink for WakeWordSink {
+    fn write(&mut self, frame: &[f32]) {
+        self.pending.extend_from_slice(frame);
+        let blocks = self.pending.len() / ANALYSIS_SAMPLES;
+
+        for block in self.pending[..blocks * ANALYSIS_SAMPLES].chunks_exact(ANALYSIS_SAMPLES) {
+            if self.vad.is_voice(block) {
+                self.blocks_since_voice = 0;
+            } else {
+                self.blocks_since_voice = self.blocks_since_voice.saturating_add(1);
+            }
+            let voice_recent = self.blocks_since_voice <= VOICE_HOLD_BLOCKS;
+            if let Some(hit) = self.spotter.push(block, voice_recent) {
+                tracing::info!("检测到唤醒词「{}」→ {:?}", hit.phrase, hit.mode);
+                let _ = self.sender.try_send(hit);
+            }
+        }
+        self.pending.drain(..blocks * ANALYSIS_SAMPLES);
+    }
+}
+
+/// 唤醒词会话状态（触发信号通道、待开始与进行中会话的唤醒词）
+pub struct WakeWordController {
+    pending: Mutex<Option<String>>,
+    active: Mutex<Option<String>>,
+    sender: Sender<WakeWordHit>,
+}
+
+impl WakeWordController {
+    /// 创建控制器，返回的接收端交给会话调度任务
+    pub fn new() -> (Self, Receiver<WakeWordHit>) {
+        let (sender, receiver) = crossbeam_channel::bounded(1);
+        (
+            Self {
+                pending: Mutex::new(None),
+                active: Mutex::new(None),
+                sender,
+            },
+            receiver,
+        )
+    }
+
+    pub fn trigger_sender(&self) -> Sender<WakeWordHit> {
+        self.sender.clone()
+    }
+
+    /// 标记下一次开始的录音由该唤醒词发起
+    pub fn request_session(&self, phrase: String) {
+        *self.pending.lock().unwrap() = Some(phrase);
+    }
+
+    pub fn cancel_request(&self) {
+        self.pending.lock().unwrap().take();
+    }
+
+    /// 开始录音时调用：取出待开始的唤醒词作为本次会话的唤醒词（热键触发时清空），返回是否由唤醒词发起
+    pub fn begin_session(&self) -> bool {
+        let phrase = self.pending.lock().unwrap().take();
+        let triggered = phrase.is_some();
+        *self.active.lock().unwrap() = phrase;
+        triggered
+    }
+
+    /// 取出本次会话的唤醒词（转写完成后去掉文本开头的唤醒词）
+    pub fn take_session_phrase(&self) -> Option<String> {
+        self.active.lock().unwrap().take()
+    }
+}
+
+/// 唤醒词样本目录
+pub fn samples_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?;
+    let dir = config_dir.join("PushToTalk").join("wake_words");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 读取样本文件（16kHz 单声道 WAV）
+fn load_sample(name: &str) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(samples_dir()?.join(name))?;
+    reader
+        .samples::<i16>()
+        .map(|s| Ok(s? as f32 / 32768.0))
+        .collect()
+}
+
+/// 保存录制的样本（16kHz WAV）：裁掉首尾静音后写入样本目录，返回文件名
+pub fn save_sample(word_id: &str, wav: &[u8]) -> Result<String> {
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(wav))?;
+    let samples = reader
+        .samples::<i16>()
+        .map(|s| Ok(s? as f32 / 32768.0))
+        .collect::<Result<Vec<f32>>>()?;
+    let trimmed = trim_sample(&samples)?;
+
+    let name = format!("{}_{}.wav", word_id, uuid::Uuid::new_v4().simple());
+    std::fs::write(samples_dir()?.join(&name), encode_wav(&trimmed)?)?;
+    Ok(name)
+}
+
+/// 删除样本文件（文件已不存在时忽略）
+pub fn remove_samples(names: &[String]) {
+    let Ok(dir) = samples_dir() else {
+        return;
+    };
+    for name in names {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+}
+
+/// 裁掉录制样本首尾的静音；没有声音或时长不合适时返回错误
+pub fn trim_sample(samples: &[f32]) -> Result<Vec<f32>> {
+    const WINDOW: usize = 160;
+    const PADDING: usize = 1600;
+
+    let energies: Vec<f32> = samples
+        .chunks(WINDOW)
+        .map(|c| (c.iter().map(|s| s * s).sum::<f32>() / c.len() as f32).sqrt())
+        .collect();
+    let peak = energies.iter().copied().fold(0.0, f32::max);
+    if peak < 0.01 {
+        return Err(anyhow::anyhow!("没有录到声音，请靠近麦克风重试"));
+    }
+
+    let threshold = (peak * 0.1).max(0.005);
+    let first = energies.iter().position(|&e| e >= threshold).unwrap_or(0);
+    let last = energies.iter().rposition(|&e| e >= threshold).unwrap_or(0);
+    let start = (first * WINDOW).saturating_sub(PADDING);
+    let end = ((last + 1) * WINDOW + PADDING).min(samples.len());
+
+    let duration_ms = (end - start) * 1000 / TARGET_SAMPLE_RATE as usize;
+    if duration_ms < MIN_SAMPLE_MS + 2 * PADDING * 1000 / TARGET_SAMPLE_RATE as usize {
+        return Err(anyhow::anyhow!("唤醒词太短，请完整说一遍"));
+    }
+    if duration_ms > MAX_SAMPLE_MS {
+        return Err(anyhow::anyhow!("唤醒词太长，请只说唤醒词本身"));
+    }
+    Ok(samples[start..end].to_vec())
+}
+
+/// 比较用的读音键：汉字取拼音，字母数字转小写，其余字符忽略
+fn phonetic_key(ch: char) -> Option<String> {
+    if let Some(pinyin) = ch.to_pinyin() {
+        Some(pinyin.plain().to_string())
+    } else if ch.is_alphanumeric() {
+        Some(ch.to_lowercase().collect())
+    } else {
+        None
+    }
+}
+
+/// 去掉转写文本开头的唤醒词（按读音比较，容忍同音字、大小写与标点）；未匹配时原样返回
+pub fn strip_wake_phrase(text: &str, phrase: &str) -> String {
+    let target: String = phrase.chars().filter_map(phonetic_key).collect();
+    if target.is_empty() {
+        return text.to_string();
+    }
+
+    let mut key = String::new();
+    let mut best: Option<(f64, usize)> = None;
+    for (index, ch) in text.char_indices() {
+        let Some(part) = phonetic_key(ch) else {
+            continue;
+        };
+        key.push_str(&part);
+        if key.len() > target.len() * 3 / 2 {
+            break;
+        }
+        let similarity = strsim::normalized_levenshtein(&key, &target);
+        if similarity >= 0.8 && best.is_none_or(|(s, _)| similarity > s) {
+            best = Some((similarity, index + ch.len_utf8()));
+        }
+    }
+
+    let Some((_, end)) = best else {
+        return text.to_string();
+    };
+    text[end..]
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 由若干音段组成的合成“词语”：每段为带谐波的基频，`stretch` 模拟语速，`detune` 模拟音高变化
+    fn synth_word(notes: &[(f32, usize)], stretch: f32, detune: f32) -> Vec<f32> {
+        let mut samples = Vec::new();
+        let mut phase = 0.0f32;
+        for &(freq, ms) in notes {
+            let len = (ms as f32 * stretch) as usize * 16;
+            let freq = freq * detune;
+            for _ in 0..len {
+                phase += 2.0 * std::f32::consts::PI * freq / 16000.0;
+                samples.push(
+                    0.2 * phase.sin() + 0.1 * (2.0 * phase).sin() + 0.05 * (3.0 * phase).sin(),
+                );
+            }
+        }
+        samples
+    }
+
+    fn noise(len: usize, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * 0.003
+            })
+            .collect()
+    }
+
+    fn mixed(signal: &[f32], seed: u32) -> Vec<f32> {
+        signal
+            .iter()
+            .zip(noise(signal.len(), seed))
+            .map(|(s, n)| s + n)
+            .collect()
+    }
+
+    const DICTATE: [(f32, usize); 3] = [(220.0, 150), (660.0, 120), (330.0, 180)];
+    const ASSIST: [(f32, usize); 3] = [(440.0, 160), (180.0, 120), (880.0, 150)];
+
+    fn run(spotter: &mut KeywordSpotter, audio: &[f32]) -> Vec<WakeWordHit> {
+        audio
+            .chunks(ANALYSIS_SAMPLES)
+            .filter_map(|block| spotter.push(block, true))
+            .collect()
+    }
+
+    #[test]
+    fn spots_enrolled_words_and_routes_by_mode() {
+        let enroll = |notes: &[(f32, usize)]| {
+            vec![
+                mixed(&synth_word(notes, 1.0, 1.0), 1),
+                mixed(&synth_word(notes, 1.1, 0.98), 2),
+                mixed(&synth_word(notes, 0.92, 1.02), 3),
+            ]
+        };
+        let mut spotter = KeywordSpotter::new(
+            vec![
+                (
+                    "嘿听写".to_string(),
+                    TriggerMode::Dictation,
+                    enroll(&DICTATE),
+                ),
+                (
+                    "嘿助手".to_string(),
+                    TriggerMode::AiAssistant,
+                    enroll(&ASSIST),
+                ),
+            ],
+            0.5,
+        )
+        .unwrap();
+
+        // 背景噪声与其他声音不触发
+        let unrelated = [(300.0, 200), (1200.0, 200), (500.0, 200)];
+        let mut audio = noise(16000, 7);
+        audio.extend(mixed(&synth_word(&unrelated, 1.0, 1.0), 8));
+        audio.extend(noise(16000, 9));
+        assert!(run(&mut spotter, &audio).is_empty());
+
+        // 语速、音高略有不同的唤醒词仍能识别，并路由到各自的模式
+        let mut audio = mixed(&synth_word(&ASSIST, 1.05, 1.01), 10);
+        audio.extend(noise(8000, 11));
+        let hits = run(&mut spotter, &audio);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].mode, TriggerMode::AiAssistant);
+
+        let mut audio = noise(32000, 12);
+        audio.extend(mixed(&synth_word(&DICTATE, 0.95, 0.99), 13));
+        audio.extend(noise(8000, 14));
+        let hits = run(&mut spotter, &audio);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].phrase, "嘿听写");
+    }
+
+    #[test]
+    fn identical_samples_still_get_a_usable_threshold() {
+        let sample = mixed(&synth_word(&DICTATE, 1.0, 1.0), 1);
+        let mut spotter = KeywordSpotter::new(
+            vec![(
+                "嘿听写".to_string(),
+                TriggerMode::Dictation,
+                vec![sample.clone(), sample.clone()],
+            )],
+            0.5,
+        )
+        .unwrap();
+        assert_eq!(spotter.words[0].threshold, MIN_THRESHOLD);
+
+        let mut audio = noise(16000, 7);
+        audio.extend(mixed(&synth_word(&ASSIST, 1.0, 1.0), 8));
+        audio.extend(noise(16000, 9));
+        assert!(run(&mut spotter, &audio).is_empty());
+
+        let mut audio = noise(16000, 10);
+        audio.extend(&sample);
+        audio.extend(noise(8000, 11));
+        assert_eq!(run(&mut spotter, &audio).len(), 1);
+    }
+
+    #[test]
+    fn strips_wake_phrase_from_transcript() {
+        assert_eq!(
+            strip_wake_phrase("嘿，助手，帮我写封邮件。", "嘿助手"),
+            "帮我写封邮件。"
+        );
+        // ASR 常见的同音字
+        assert_eq!(strip_wake_phrase("黑助手帮我翻译", "嘿助手"), "帮我翻译");
+        assert_eq!(
+            strip_wake_phrase("Hey, dictate. Send it now", "hey dictate"),
+            "Send it now"
+        );
+        // 开头不是唤醒词时保持原样
+        assert_eq!(strip_wake_phrase("今天天气不错", "嘿助手"), "今天天气不错");
+    }
+}
@@ -108,6 +108,10 @@ function App() {
     editingIndex,
     editingValue,
     setEditingValue,
+    editingWeight,
+    setEditingWeight,
+    editingScope,
+    setEditingScope,
     handleAddWord,
     handleDeleteWord,
     handleStartEdit,
@@ -777,6 +781,10 @@ function App() {
             editingIndex={editingIndex}
             editingValue={editingValue}
             setEditingValue={setEditingValue}
+            editingWeight={editingWeight}
+            setEditingWeight={setEditingWeight}
+            editingScope={editingScope}
+            setEditingScope={setEditingScope}
             handleAddWord={handleAddWord}
             handleDeleteWord={handleDeleteWord}
             handleStartEdit={handleStartEdit}
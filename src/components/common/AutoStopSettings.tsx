@@ -0,0 +1,123 @@
+/**
+ * AutoStopSettings - 免提自动结束设置
+ *
+ * 切换模式与松手锁定模式下，说话后静音达到设定时长或录音达到最长时长时自动结束并转写，
+ * 无需再次按下快捷键。按住模式不受影响。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Timer } from "lucide-react";
+import { Toggle } from "./Toggle";
+import type { AppConfig, AutoStopConfig } from "../../types";
+
+const DEFAULT_AUTO_STOP: AutoStopConfig = { enabled: false, silence_ms: 2000, max_duration_secs: 300 };
+
+const SILENCE_OPTIONS = [1000, 1500, 2000, 3000, 5000];
+
+const MAX_DURATION_OPTIONS = [
+  { value: 60, label: "1 分钟" },
+  { value: 120, label: "2 分钟" },
+  { value: 300, label: "5 分钟" },
+  { value: 600, label: "10 分钟" },
+  { value: 0, label: "不限制" },
+];
+
+export type AutoStopSettingsProps = {
+  disabled?: boolean;
+};
+
+export function AutoStopSettings({ disabled }: AutoStopSettingsProps) {
+  const [config, setConfig] = useState<AutoStopConfig>(DEFAULT_AUTO_STOP);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => setConfig(loaded.auto_stop ?? DEFAULT_AUTO_STOP))
+      .catch((error) => console.error("加载自动结束配置失败:", error));
+  }, []);
+
+  const save = async (next: AutoStopConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_auto_stop_config", {
+        enabled: next.enabled,
+        silenceMs: next.silence_ms,
+        maxDurationSecs: next.max_duration_secs,
+      });
+    } catch (error) {
+      console.error("保存自动结束配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  const selectClassName =
+    "flex-1 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50";
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "p-2 rounded-xl",
+              config.enabled
+                ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <Timer size={16} />
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">免提自动结束</div>
+            <div className="text-[11px] text-stone-400 font-semibold">
+              {config.enabled ? "切换 / 松手模式下说完自动转写" : "再次按下快捷键结束"}
+            </div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void save({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <div className="flex gap-2">
+          <select
+            value={config.silence_ms}
+            disabled={disabled}
+            onChange={(e) => void save({ ...config, silence_ms: Number(e.target.value) })}
+            className={selectClassName}
+          >
+            {!SILENCE_OPTIONS.includes(config.silence_ms) && (
+              <option value={config.silence_ms}>静音 {config.silence_ms / 1000} 秒后结束</option>
+            )}
+            {SILENCE_OPTIONS.map((ms) => (
+              <option key={ms} value={ms}>
+                静音 {ms / 1000} 秒后结束
+              </option>
+            ))}
+          </select>
+          <select
+            value={config.max_duration_secs}
+            disabled={disabled}
+            onChange={(e) => void save({ ...config, max_duration_secs: Number(e.target.value) })}
+            className={selectClassName}
+          >
+            {!MAX_DURATION_OPTIONS.some((option) => option.value === config.max_duration_secs) && (
+              <option value={config.max_duration_secs}>最长 {config.max_duration_secs} 秒</option>
+            )}
+            {MAX_DURATION_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                最长：{option.label}
+              </option>
+            ))}
+          </select>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,140 @@
+/**
+ * InputDeviceSelector - 录音输入设备偏好
+ *
+ * 按顺序列出首选设备：录音时使用第一个已连接的设备，均未连接时使用系统默认设备。
+ * 设备插拔时后端推送 input_devices_changed，列表实时刷新。
+ */
+
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ArrowDown, ArrowUp, X } from "lucide-react";
+import type { AppConfig, InputDeviceInfo } from "../../types";
+
+export type InputDeviceSelectorProps = {
+  disabled?: boolean;
+};
+
+export function InputDeviceSelector({ disabled }: InputDeviceSelectorProps) {
+  const [devices, setDevices] = useState<InputDeviceInfo[]>([]);
+  const [preferred, setPreferred] = useState<string[]>([]);
+
+  const refreshDevices = useCallback(async () => {
+    try {
+      setDevices(await invoke<InputDeviceInfo[]>("list_input_devices"));
+    } catch (error) {
+      console.error("枚举输入设备失败:", error);
+    }
+  }, []);
+
+  useEffect(() => {
+    void refreshDevices();
+    invoke<AppConfig>("load_config")
+      .then((config) => setPreferred(config.preferred_input_devices ?? []))
+      .catch((error) => console.error("加载首选输入设备失败:", error));
+
+    let unlisten: (() => void) | undefined;
+    void listen<InputDeviceInfo[]>("input_devices_changed", (event) => {
+      setDevices(event.payload);
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [refreshDevices]);
+
+  const savePreferred = async (next: string[]) => {
+    const previous = preferred;
+    setPreferred(next);
+    try {
+      await invoke("set_preferred_input_devices", { devices: next });
+    } catch (error) {
+      console.error("保存首选输入设备失败:", error);
+      setPreferred(previous);
+    }
+  };
+
+  const move = (index: number, offset: number) => {
+    const target = index + offset;
+    if (target < 0 || target >= preferred.length) return;
+    const next = [...preferred];
+    [next[index], next[target]] = [next[target], next[index]];
+    void savePreferred(next);
+  };
+
+  const connected = new Set(devices.map((d) => d.name));
+  const activeDevice =
+    preferred.find((name) => connected.has(name)) ??
+    devices.find((d) => d.is_default)?.name;
+  const addable = devices.filter((d) => !preferred.includes(d.name));
+
+  return (
+    <div className="space-y-2">
+      {preferred.map((name, index) => (
+        <div
+          key={name}
+          className="flex items-center gap-2 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm"
+        >
+          <span className="text-xs font-bold text-stone-400 w-4">{index + 1}</span>
+          <span className="flex-1 truncate text-[var(--ink)]" title={name}>{name}</span>
+          <span
+            className={`text-[11px] font-semibold ${connected.has(name) ? "text-green-600" : "text-stone-400"}`}
+          >
+            {name === activeDevice ? "使用中" : connected.has(name) ? "已连接" : "未连接"}
+          </span>
+          <button
+            onClick={() => move(index, -1)}
+            disabled={disabled || index === 0}
+            className="p-1 text-stone-400 hover:text-[var(--ink)] disabled:opacity-30"
+            title="上移"
+          >
+            <ArrowUp size={14} />
+          </button>
+          <button
+            onClick={() => move(index, 1)}
+            disabled={disabled || index === preferred.length - 1}
+            className="p-1 text-stone-400 hover:text-[var(--ink)] disabled:opacity-30"
+            title="下移"
+          >
+            <ArrowDown size={14} />
+          </button>
+          <button
+            onClick={() => void savePreferred(preferred.filter((n) => n !== name))}
+            disabled={disabled}
+            className="p-1 text-stone-400 hover:text-red-500 disabled:opacity-30"
+            title="移除"
+          >
+            <X size={14} />
+          </button>
+        </div>
+      ))}
+
+      <select
+        value=""
+        disabled={disabled || addable.length === 0}
+        onChange={(e) => {
+          if (e.target.value) void savePreferred([...preferred, e.target.value]);
+        }}
+        onFocus={() => void refreshDevices()}
+        className="w-full px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50"
+      >
+        <option value="">
+          {addable.length === 0 ? "没有更多可添加的设备" : "添加首选设备…"}
+        </option>
+        {addable.map((device) => (
+          <option key={device.name} value={device.name}>
+            {device.name}
+            {device.is_default ? "（系统默认）" : ""}
+          </option>
+        ))}
+      </select>
+
+      <div className="text-[11px] text-stone-400 font-semibold">
+        {preferred.length === 0
+          ? `未设置首选设备，使用系统默认设备${activeDevice ? `：${activeDevice}` : ""}`
+          : activeDevice && !preferred.includes(activeDevice)
+            ? `首选设备均未连接，暂用系统默认设备：${activeDevice}`
+            : "按顺序使用第一个已连接的设备，重新插入后下次录音自动切换回来"}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,100 @@
+/**
+ * NoiseSuppressionSettings - 背景降噪设置
+ *
+ * 在自动增益之前用 RNNoise 抑制空调、风扇和远处人声，实时识别与备用录音都会经过降噪。
+ * 强度较低时保留部分原始声音，语音更自然。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Wind } from "lucide-react";
+import { Toggle } from "./Toggle";
+import type { AppConfig, NoiseSuppressionConfig } from "../../types";
+
+const DEFAULT_NOISE_SUPPRESSION: NoiseSuppressionConfig = { enabled: false, strength: 1 };
+
+const STRENGTH_OPTIONS = [
+  { value: 0.5, label: "轻度" },
+  { value: 0.75, label: "中等" },
+  { value: 1, label: "强力" },
+];
+
+export type NoiseSuppressionSettingsProps = {
+  disabled?: boolean;
+};
+
+export function NoiseSuppressionSettings({ disabled }: NoiseSuppressionSettingsProps) {
+  const [config, setConfig] = useState<NoiseSuppressionConfig>(DEFAULT_NOISE_SUPPRESSION);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => setConfig(loaded.noise_suppression ?? DEFAULT_NOISE_SUPPRESSION))
+      .catch((error) => console.error("加载降噪配置失败:", error));
+  }, []);
+
+  const save = async (next: NoiseSuppressionConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_noise_suppression", {
+        enabled: next.enabled,
+        strength: next.strength,
+      });
+    } catch (error) {
+      console.error("保存降噪配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  // 旧配置或手动编辑的强度不在预设中时，归到最接近的档位显示
+  const selectedStrength = STRENGTH_OPTIONS.reduce((best, option) =>
+    Math.abs(option.value - config.strength) < Math.abs(best.value - config.strength) ? option : best,
+  ).value;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "p-2 rounded-xl",
+              config.enabled
+                ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <Wind size={16} />
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">背景降噪</div>
+            <div className="text-[11px] text-stone-400 font-semibold">
+              {config.enabled ? "抑制空调、风扇和远处人声" : "保留原始录音"}
+            </div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void save({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <select
+          value={selectedStrength}
+          disabled={disabled}
+          onChange={(e) => void save({ ...config, strength: Number(e.target.value) })}
+          className="w-full px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50"
+        >
+          {STRENGTH_OPTIONS.map((option) => (
+            <option key={option.value} value={option.value}>
+              降噪强度：{option.label}
+            </option>
+          ))}
+        </select>
+      )}
+    </div>
+  );
+}
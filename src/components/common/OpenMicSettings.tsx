@@ -0,0 +1,177 @@
+/**
+ * OpenMicSettings - 开放麦克风设置
+ *
+ * 无需快捷键：服务运行时麦克风保持监听，检测到持续说话自动开始听写，静音后自动结束并插入文字。
+ * 监听中托盘图标带红点，此处也显示隐私指示；可在此处或托盘菜单临时静音（仅本次运行）。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { MicOff, Radio } from "lucide-react";
+import { Toggle } from "./Toggle";
+import type { AppConfig, OpenMicConfig, OpenMicState } from "../../types";
+
+const DEFAULT_OPEN_MIC: OpenMicConfig = {
+  enabled: false,
+  trigger_ms: 400,
+  silence_ms: 1500,
+  max_duration_secs: 60,
+};
+
+const TRIGGER_OPTIONS = [
+  { value: 300, label: "灵敏" },
+  { value: 400, label: "标准" },
+  { value: 700, label: "稳健" },
+];
+
+const SILENCE_OPTIONS = [1000, 1500, 2000, 3000];
+
+export type OpenMicSettingsProps = {
+  disabled?: boolean;
+};
+
+export function OpenMicSettings({ disabled }: OpenMicSettingsProps) {
+  const [config, setConfig] = useState<OpenMicConfig>(DEFAULT_OPEN_MIC);
+  const [micState, setMicState] = useState<OpenMicState | null>(null);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => setConfig(loaded.open_mic ?? DEFAULT_OPEN_MIC))
+      .catch((error) => console.error("加载开放麦克风配置失败:", error));
+    invoke<OpenMicState>("get_open_mic_state")
+      .then(setMicState)
+      .catch((error) => console.error("获取开放麦克风状态失败:", error));
+
+    let unlisten: (() => void) | undefined;
+    void listen<OpenMicState>("open_mic_state", (event) => {
+      setMicState(event.payload);
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  const save = async (next: OpenMicConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_open_mic_config", {
+        enabled: next.enabled,
+        triggerMs: next.trigger_ms,
+        silenceMs: next.silence_ms,
+        maxDurationSecs: next.max_duration_secs,
+      });
+    } catch (error) {
+      console.error("保存开放麦克风配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  const setMuted = async (muted: boolean) => {
+    try {
+      await invoke("set_open_mic_muted", { muted });
+    } catch (error) {
+      console.error("切换开放麦克风静音失败:", error);
+    }
+  };
+
+  const listening = micState?.listening ?? false;
+  const muted = micState?.muted ?? false;
+
+  const statusText = !config.enabled
+    ? "按快捷键开始听写"
+    : muted
+      ? "已静音，麦克风未监听"
+      : listening
+        ? "麦克风监听中，开口即可听写"
+        : "启动服务后开始监听";
+
+  const selectClassName =
+    "flex-1 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50";
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "relative p-2 rounded-xl",
+              listening
+                ? "bg-[rgba(217,119,87,0.12)] text-[var(--crail)]"
+                : config.enabled
+                  ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                  : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <Radio size={16} />
+            {listening && (
+              <span className="absolute -top-0.5 -right-0.5 w-2 h-2 rounded-full bg-red-500 animate-pulse" />
+            )}
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">开放麦克风</div>
+            <div className="text-[11px] text-stone-400 font-semibold">{statusText}</div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void save({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <>
+          <div className="flex gap-2">
+            <select
+              value={config.trigger_ms}
+              disabled={disabled}
+              onChange={(e) => void save({ ...config, trigger_ms: Number(e.target.value) })}
+              className={selectClassName}
+            >
+              {!TRIGGER_OPTIONS.some((option) => option.value === config.trigger_ms) && (
+                <option value={config.trigger_ms}>连续说话 {config.trigger_ms}ms 后开始</option>
+              )}
+              {TRIGGER_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  触发：{option.label}
+                </option>
+              ))}
+            </select>
+            <select
+              value={config.silence_ms}
+              disabled={disabled}
+              onChange={(e) => void save({ ...config, silence_ms: Number(e.target.value) })}
+              className={selectClassName}
+            >
+              {!SILENCE_OPTIONS.includes(config.silence_ms) && (
+                <option value={config.silence_ms}>静音 {config.silence_ms / 1000} 秒后结束</option>
+              )}
+              {SILENCE_OPTIONS.map((ms) => (
+                <option key={ms} value={ms}>
+                  静音 {ms / 1000} 秒后结束
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex items-center justify-between px-1">
+            <div className="flex items-center gap-2 text-xs text-stone-500 font-semibold">
+              <MicOff size={14} />
+              暂停麦克风监听（含唤醒词与预录缓冲，与托盘菜单同步）
+            </div>
+            <Toggle
+              checked={muted}
+              onCheckedChange={(next) => void setMuted(next)}
+              size="sm"
+              variant="orange"
+            />
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,94 @@
+/**
+ * PrerollSettings - 预录缓冲设置
+ *
+ * 快捷键服务运行时录音流保持打开，始终保留最近一段音频，按下快捷键后补在录音开头，
+ * 避免蓝牙耳机等设备打开录音流较慢时丢掉第一个字。托盘「暂停麦克风监听」会同时暂停预录。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { History } from "lucide-react";
+import { Toggle } from "./Toggle";
+import type { AppConfig, PrerollConfig } from "../../types";
+
+const DEFAULT_PREROLL: PrerollConfig = { enabled: false, duration_ms: 300 };
+
+const DURATION_OPTIONS = [200, 300, 500, 1000];
+
+export type PrerollSettingsProps = {
+  disabled?: boolean;
+};
+
+export function PrerollSettings({ disabled }: PrerollSettingsProps) {
+  const [config, setConfig] = useState<PrerollConfig>(DEFAULT_PREROLL);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => setConfig(loaded.preroll ?? DEFAULT_PREROLL))
+      .catch((error) => console.error("加载预录缓冲配置失败:", error));
+  }, []);
+
+  const save = async (next: PrerollConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_preroll_config", {
+        enabled: next.enabled,
+        durationMs: next.duration_ms,
+      });
+    } catch (error) {
+      console.error("保存预录缓冲配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "p-2 rounded-xl",
+              config.enabled
+                ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <History size={16} />
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">预录缓冲</div>
+            <div className="text-[11px] text-stone-400 font-semibold">
+              {config.enabled ? "麦克风常开，补上按键前的第一个字" : "按下快捷键后才打开麦克风"}
+            </div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void save({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <select
+          value={config.duration_ms}
+          disabled={disabled}
+          onChange={(e) => void save({ ...config, duration_ms: Number(e.target.value) })}
+          className="w-full px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50"
+        >
+          {!DURATION_OPTIONS.includes(config.duration_ms) && (
+            <option value={config.duration_ms}>保留按键前 {config.duration_ms} 毫秒</option>
+          )}
+          {DURATION_OPTIONS.map((ms) => (
+            <option key={ms} value={ms}>
+              保留按键前 {ms} 毫秒
+            </option>
+          ))}
+        </select>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,287 @@
+/**
+ * RecordingArchiveSettings - 录音归档设置
+ *
+ * 开启后每次录音以 FLAC 保存到本地并关联转写结果，按保留天数与总大小自动清理。
+ * 提供商识别错了关键句时，可换提供商、润色预设或 TNL 设置重新转写（只显示结果，不插入文本）。
+ */
+
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Archive, RefreshCw, RotateCcw, Trash2 } from "lucide-react";
+import { Toggle } from "./Toggle";
+import { ASR_PROVIDERS } from "../../constants";
+import type {
+  AppConfig,
+  ArchivedRecording,
+  AsrProvider,
+  LlmPreset,
+  RecordingArchiveConfig,
+} from "../../types";
+
+const DEFAULT_ARCHIVE: RecordingArchiveConfig = { enabled: false, max_age_days: 30, max_total_mb: 500 };
+
+const AGE_OPTIONS = [
+  { value: 7, label: "保留 7 天" },
+  { value: 30, label: "保留 30 天" },
+  { value: 90, label: "保留 90 天" },
+  { value: 0, label: "不按时间清理" },
+];
+
+const SIZE_OPTIONS = [
+  { value: 200, label: "最多 200 MB" },
+  { value: 500, label: "最多 500 MB" },
+  { value: 2048, label: "最多 2 GB" },
+  { value: 0, label: "不限大小" },
+];
+
+// 列表只展示最近的若干条，避免设置页过长
+const VISIBLE_RECORDINGS = 20;
+
+const formatTime = (ms: number) =>
+  new Date(ms).toLocaleString("zh-CN", { month: "2-digit", day: "2-digit", hour: "2-digit", minute: "2-digit" });
+
+const latestText = (recording: ArchivedRecording) =>
+  (recording.retranscriptions[recording.retranscriptions.length - 1] ?? recording.transcription)?.text;
+
+export type RecordingArchiveSettingsProps = {
+  disabled?: boolean;
+};
+
+export function RecordingArchiveSettings({ disabled }: RecordingArchiveSettingsProps) {
+  const [config, setConfig] = useState<RecordingArchiveConfig>(DEFAULT_ARCHIVE);
+  const [presets, setPresets] = useState<LlmPreset[]>([]);
+  const [recordings, setRecordings] = useState<ArchivedRecording[]>([]);
+  const [provider, setProvider] = useState<AsrProvider>("qwen");
+  const [presetId, setPresetId] = useState("");
+  const [enableTnl, setEnableTnl] = useState(true);
+  const [busyId, setBusyId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(() => {
+    invoke<ArchivedRecording[]>("list_archived_recordings")
+      .then(setRecordings)
+      .catch((error) => console.error("加载归档录音失败:", error));
+  }, []);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => {
+        setConfig(loaded.recording_archive ?? DEFAULT_ARCHIVE);
+        setPresets(loaded.llm_config?.presets ?? []);
+        setProvider(loaded.asr_config?.selection?.active_provider ?? "qwen");
+        setEnableTnl(loaded.tnl_config?.enabled ?? true);
+      })
+      .catch((error) => console.error("加载录音归档配置失败:", error));
+    refresh();
+  }, [refresh]);
+
+  const saveSettings = async (next: RecordingArchiveConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_recording_archive_config", {
+        enabled: next.enabled,
+        maxAgeDays: next.max_age_days,
+        maxTotalMb: next.max_total_mb,
+      });
+      refresh();
+    } catch (error) {
+      console.error("保存录音归档配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  const retranscribe = async (id: string) => {
+    setError(null);
+    setBusyId(id);
+    try {
+      const updated = await invoke<ArchivedRecording>("retranscribe_archived_recording", {
+        id,
+        provider,
+        presetId: presetId || null,
+        enableTnl,
+      });
+      setRecordings((current) => current.map((r) => (r.id === id ? updated : r)));
+    } catch (error) {
+      setError(String(error));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  const remove = async (id: string) => {
+    setError(null);
+    try {
+      await invoke("delete_archived_recording", { id });
+      setRecordings((current) => current.filter((r) => r.id !== id));
+    } catch (error) {
+      setError(String(error));
+    }
+  };
+
+  const totalMb = recordings.reduce((sum, r) => sum + r.size_bytes, 0) / (1024 * 1024);
+
+  const inputClassName =
+    "px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50";
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "p-2 rounded-xl",
+              config.enabled
+                ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <Archive size={16} />
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">录音归档</div>
+            <div className="text-[11px] text-stone-400 font-semibold">
+              {config.enabled
+                ? `已归档 ${recordings.length} 条（${totalMb.toFixed(1)} MB），可重新转写`
+                : "录音转写后即丢弃"}
+            </div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void saveSettings({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <div className="flex gap-2">
+          <select
+            value={config.max_age_days}
+            disabled={disabled}
+            onChange={(e) => void saveSettings({ ...config, max_age_days: Number(e.target.value) })}
+            className={`flex-1 min-w-0 ${inputClassName}`}
+          >
+            {AGE_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+          <select
+            value={config.max_total_mb}
+            disabled={disabled}
+            onChange={(e) => void saveSettings({ ...config, max_total_mb: Number(e.target.value) })}
+            className={`flex-1 min-w-0 ${inputClassName}`}
+          >
+            {SIZE_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </div>
+      )}
+
+      {recordings.length > 0 && (
+        <>
+          <div className="flex items-center gap-2">
+            <select
+              value={provider}
+              disabled={disabled}
+              onChange={(e) => setProvider(e.target.value as AsrProvider)}
+              className={`flex-1 min-w-0 ${inputClassName}`}
+            >
+              {(Object.keys(ASR_PROVIDERS) as AsrProvider[]).map((key) => (
+                <option key={key} value={key}>
+                  {ASR_PROVIDERS[key].name}
+                </option>
+              ))}
+            </select>
+            <select
+              value={presetId}
+              disabled={disabled}
+              onChange={(e) => setPresetId(e.target.value)}
+              className={`flex-1 min-w-0 ${inputClassName}`}
+            >
+              <option value="">不润色</option>
+              {presets.map((preset) => (
+                <option key={preset.id} value={preset.id}>
+                  润色：{preset.name}
+                </option>
+              ))}
+            </select>
+            <label
+              className="flex items-center gap-1 text-xs font-semibold text-stone-500 shrink-0"
+              title="技术术语规范化"
+            >
+              <input
+                type="checkbox"
+                checked={enableTnl}
+                disabled={disabled}
+                onChange={(e) => setEnableTnl(e.target.checked)}
+              />
+              TNL
+            </label>
+            <button
+              type="button"
+              onClick={refresh}
+              className="p-2 rounded-xl bg-white border border-[var(--stone)] text-stone-500 hover:text-[var(--steel)]"
+              aria-label="刷新归档列表"
+            >
+              <RefreshCw size={14} />
+            </button>
+          </div>
+
+          {recordings.slice(0, VISIBLE_RECORDINGS).map((recording) => {
+            const busy = busyId === recording.id;
+            return (
+              <div
+                key={recording.id}
+                className="flex items-center justify-between gap-2 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl"
+              >
+                <div className="min-w-0">
+                  <div className="text-sm font-semibold text-[var(--ink)] truncate">
+                    {latestText(recording) || <span className="text-stone-400">（无转写结果）</span>}
+                  </div>
+                  <div className="text-[11px] text-stone-400 font-semibold">
+                    {formatTime(recording.created_at)} · {(recording.duration_ms / 1000).toFixed(1)} 秒 ·{" "}
+                    {recording.mode === "ai_assistant" ? "AI 助手" : "听写"}
+                    {recording.retranscriptions.length > 0 && ` · 重新转写 ${recording.retranscriptions.length} 次`}
+                  </div>
+                </div>
+                <div className="flex items-center gap-1 shrink-0">
+                  <button
+                    type="button"
+                    onClick={() => void retranscribe(recording.id)}
+                    disabled={disabled || busyId !== null}
+                    className={[
+                      "flex items-center gap-1 px-2 py-1 rounded-lg text-xs font-semibold disabled:opacity-50",
+                      busy ? "bg-[var(--steel)] text-white animate-pulse" : "text-[var(--steel)] hover:bg-[var(--panel)]",
+                    ].join(" ")}
+                  >
+                    <RotateCcw size={12} />
+                    {busy ? "转写中…" : "重新转写"}
+                  </button>
+                  <button
+                    type="button"
+                    onClick={() => void remove(recording.id)}
+                    disabled={disabled || busyId !== null}
+                    className="p-1 rounded-lg text-stone-400 hover:text-[var(--crail)] disabled:opacity-50"
+                    aria-label="删除归档录音"
+                  >
+                    <Trash2 size={14} />
+                  </button>
+                </div>
+              </div>
+            );
+          })}
+
+          {error && <div className="text-[11px] text-[var(--crail)] font-semibold">{error}</div>}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,79 @@
+/**
+ * VadSettings - 语音活动检测设置
+ *
+ * 能量阈值适合安静环境；GMM 模型按子带频谱区分人声与稳态噪声，嘈杂环境下更可靠。
+ * 灵敏度越高越严格：更少把噪声当作语音，但轻声细语也更容易被丢弃。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { AppConfig, VadConfig, VadEngine } from "../../types";
+
+const DEFAULT_VAD_CONFIG: VadConfig = { engine: "rms", aggressiveness: 1 };
+
+const AGGRESSIVENESS_LABELS = ["宽松", "标准", "严格", "非常严格"];
+
+export type VadSettingsProps = {
+  disabled?: boolean;
+};
+
+export function VadSettings({ disabled }: VadSettingsProps) {
+  const [vadConfig, setVadConfig] = useState<VadConfig>(DEFAULT_VAD_CONFIG);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((config) => setVadConfig(config.vad_config ?? DEFAULT_VAD_CONFIG))
+      .catch((error) => console.error("加载语音检测配置失败:", error));
+  }, []);
+
+  const save = async (next: VadConfig) => {
+    const previous = vadConfig;
+    setVadConfig(next);
+    try {
+      await invoke("set_vad_config", {
+        engine: next.engine,
+        aggressiveness: next.aggressiveness,
+      });
+    } catch (error) {
+      console.error("保存语音检测配置失败:", error);
+      setVadConfig(previous);
+    }
+  };
+
+  const selectClassName =
+    "flex-1 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50";
+
+  return (
+    <div className="space-y-2">
+      <div className="flex gap-2">
+        <select
+          value={vadConfig.engine}
+          disabled={disabled}
+          onChange={(e) => void save({ ...vadConfig, engine: e.target.value as VadEngine })}
+          className={selectClassName}
+        >
+          <option value="rms">能量阈值（默认）</option>
+          <option value="gmm">GMM 模型（抗噪）</option>
+        </select>
+        <select
+          value={vadConfig.aggressiveness}
+          disabled={disabled || vadConfig.engine === "rms"}
+          onChange={(e) => void save({ ...vadConfig, aggressiveness: Number(e.target.value) })}
+          className={selectClassName}
+        >
+          {AGGRESSIVENESS_LABELS.map((label, level) => (
+            <option key={level} value={level}>
+              灵敏度：{label}
+            </option>
+          ))}
+        </select>
+      </div>
+
+      <div className="text-[11px] text-stone-400 font-semibold">
+        {vadConfig.engine === "gmm"
+          ? "按频谱区分人声与风扇、键盘声，开放式办公室推荐"
+          : "按音量判断，安静环境下开销最低"}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,242 @@
+/**
+ * WakeWordSettings - 唤醒词设置
+ *
+ * 为每个唤醒词录制几遍样本，后台在本地检测（不上传音频），命中后开始听写或 AI 助手会话，
+ * 说完静音后自动结束，转写结果会去掉开头的唤醒词。与开放麦克风共用静音开关。
+ */
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Ear, Mic, Plus, Trash2 } from "lucide-react";
+import { Toggle } from "./Toggle";
+import type { AppConfig, TriggerMode, WakeWord, WakeWordConfig } from "../../types";
+
+const DEFAULT_WAKE_WORD: WakeWordConfig = { enabled: false, sensitivity: 0.5, words: [] };
+
+// 至少 2 个样本才会启用检测，录满 3 个更稳定
+const MIN_SAMPLES = 2;
+const RECOMMENDED_SAMPLES = 3;
+
+const SENSITIVITY_OPTIONS = [
+  { value: 0.2, label: "低（减少误唤醒）" },
+  { value: 0.5, label: "中" },
+  { value: 0.8, label: "高（更容易唤醒）" },
+];
+
+const MODE_LABELS: Record<TriggerMode, string> = {
+  dictation: "听写",
+  ai_assistant: "AI 助手",
+};
+
+export type WakeWordSettingsProps = {
+  disabled?: boolean;
+};
+
+export function WakeWordSettings({ disabled }: WakeWordSettingsProps) {
+  const [config, setConfig] = useState<WakeWordConfig>(DEFAULT_WAKE_WORD);
+  const [phrase, setPhrase] = useState("");
+  const [mode, setMode] = useState<TriggerMode>("dictation");
+  const [recordingId, setRecordingId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<AppConfig>("load_config")
+      .then((loaded) => setConfig(loaded.wake_word ?? DEFAULT_WAKE_WORD))
+      .catch((error) => console.error("加载唤醒词配置失败:", error));
+  }, []);
+
+  const saveSettings = async (next: WakeWordConfig) => {
+    const previous = config;
+    setConfig(next);
+    try {
+      await invoke("set_wake_word_config", {
+        enabled: next.enabled,
+        sensitivity: next.sensitivity,
+      });
+    } catch (error) {
+      console.error("保存唤醒词配置失败:", error);
+      setConfig(previous);
+    }
+  };
+
+  const replaceWord = (word: WakeWord) =>
+    setConfig((current) => ({
+      ...current,
+      words: current.words.map((w) => (w.id === word.id ? word : w)),
+    }));
+
+  const addWord = async () => {
+    if (!phrase.trim()) return;
+    setError(null);
+    try {
+      const word = await invoke<WakeWord>("add_wake_word", { phrase, triggerMode: mode });
+      setConfig((current) => ({ ...current, words: [...current.words, word] }));
+      setPhrase("");
+    } catch (error) {
+      setError(String(error));
+    }
+  };
+
+  const removeWord = async (id: string) => {
+    setError(null);
+    try {
+      await invoke("remove_wake_word", { id });
+      setConfig((current) => ({ ...current, words: current.words.filter((w) => w.id !== id) }));
+    } catch (error) {
+      setError(String(error));
+    }
+  };
+
+  const recordSample = async (id: string) => {
+    setError(null);
+    setRecordingId(id);
+    try {
+      replaceWord(await invoke<WakeWord>("record_wake_word_sample", { id }));
+    } catch (error) {
+      setError(String(error));
+    } finally {
+      setRecordingId(null);
+    }
+  };
+
+  // 旧配置或手动编辑的灵敏度不在预设中时，归到最接近的档位显示
+  const selectedSensitivity = SENSITIVITY_OPTIONS.reduce((best, option) =>
+    Math.abs(option.value - config.sensitivity) < Math.abs(best.value - config.sensitivity) ? option : best,
+  ).value;
+
+  const readyCount = config.words.filter((w) => w.samples.length >= MIN_SAMPLES).length;
+
+  const inputClassName =
+    "px-3 py-2 bg-white border border-[var(--stone)] rounded-xl text-sm text-stone-600 focus:outline-none focus:border-[var(--steel)] disabled:opacity-50";
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div className="flex items-center gap-3">
+          <div
+            className={[
+              "p-2 rounded-xl",
+              config.enabled
+                ? "bg-[rgba(120,140,93,0.12)] text-[var(--sage)]"
+                : "bg-white border border-[var(--stone)] text-stone-500",
+            ].join(" ")}
+          >
+            <Ear size={16} />
+          </div>
+          <div>
+            <div className="text-sm font-bold text-[var(--ink)]">唤醒词</div>
+            <div className="text-[11px] text-stone-400 font-semibold">
+              {config.enabled ? `本地检测 ${readyCount} 个唤醒词，说出后开始会话` : "仅使用快捷键"}
+            </div>
+          </div>
+        </div>
+        <Toggle
+          checked={config.enabled}
+          onCheckedChange={(enabled) => void saveSettings({ ...config, enabled })}
+          disabled={disabled}
+          size="sm"
+          variant="green"
+        />
+      </div>
+
+      {config.enabled && (
+        <>
+          <select
+            value={selectedSensitivity}
+            disabled={disabled}
+            onChange={(e) => void saveSettings({ ...config, sensitivity: Number(e.target.value) })}
+            className={`w-full ${inputClassName}`}
+          >
+            {SENSITIVITY_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                灵敏度：{option.label}
+              </option>
+            ))}
+          </select>
+
+          {config.words.map((word) => {
+            const recording = recordingId === word.id;
+            return (
+              <div
+                key={word.id}
+                className="flex items-center justify-between gap-2 px-3 py-2 bg-white border border-[var(--stone)] rounded-xl"
+              >
+                <div className="min-w-0">
+                  <div className="text-sm font-semibold text-[var(--ink)] truncate">
+                    {word.phrase}
+                    <span className="ml-2 text-[11px] text-stone-400">→ {MODE_LABELS[word.trigger_mode]}</span>
+                  </div>
+                  <div className="text-[11px] text-stone-400 font-semibold">
+                    {recording
+                      ? "请说出唤醒词…"
+                      : word.samples.length < MIN_SAMPLES
+                        ? `已录 ${word.samples.length} 遍，至少需要 ${MIN_SAMPLES} 遍`
+                        : `已录 ${word.samples.length} 遍`}
+                  </div>
+                </div>
+                <div className="flex items-center gap-1 shrink-0">
+                  <button
+                    type="button"
+                    onClick={() => void recordSample(word.id)}
+                    disabled={disabled || recordingId !== null}
+                    className={[
+                      "flex items-center gap-1 px-2 py-1 rounded-lg text-xs font-semibold disabled:opacity-50",
+                      recording
+                        ? "bg-[var(--crail)] text-white animate-pulse"
+                        : "text-[var(--steel)] hover:bg-[var(--panel)]",
+                    ].join(" ")}
+                  >
+                    <Mic size={12} />
+                    {word.samples.length < RECOMMENDED_SAMPLES ? "录制" : "再录一遍"}
+                  </button>
+                  <button
+                    type="button"
+                    onClick={() => void removeWord(word.id)}
+                    disabled={disabled || recordingId !== null}
+                    className="p-1 rounded-lg text-stone-400 hover:text-[var(--crail)] disabled:opacity-50"
+                    aria-label="删除唤醒词"
+                  >
+                    <Trash2 size={14} />
+                  </button>
+                </div>
+              </div>
+            );
+          })}
+
+          <div className="flex gap-2">
+            <input
+              value={phrase}
+              disabled={disabled}
+              onChange={(e) => setPhrase(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") void addWord();
+              }}
+              placeholder="如：嘿 助手"
+              className={`flex-1 min-w-0 ${inputClassName}`}
+            />
+            <select
+              value={mode}
+              disabled={disabled}
+              onChange={(e) => setMode(e.target.value as TriggerMode)}
+              className={inputClassName}
+            >
+              <option value="dictation">听写</option>
+              <option value="ai_assistant">AI 助手</option>
+            </select>
+            <button
+              type="button"
+              onClick={() => void addWord()}
+              disabled={disabled || !phrase.trim()}
+              className="p-2 rounded-xl bg-white border border-[var(--stone)] text-stone-500 hover:text-[var(--steel)] disabled:opacity-50"
+              aria-label="添加唤醒词"
+            >
+              <Plus size={16} />
+            </button>
+          </div>
+
+          {error && <div className="text-[11px] text-[var(--crail)] font-semibold">{error}</div>}
+        </>
+      )}
+    </div>
+  );
+}
@@ -8,3 +8,11 @@ export * from "./ThemeSelector";
 export * from "./LlmConnectionConfig";
 export * from "./GlobalNoticeBar";
 
+export * from "./InputDeviceSelector";
+export * from "./VadSettings";
+export * from "./NoiseSuppressionSettings";
+export * from "./PrerollSettings";
+export * from "./AutoStopSettings";
+export * from "./OpenMicSettings";
+export * from "./WakeWordSettings";
+export * from "./RecordingArchiveSettings";
@@ -1,4 +1,4 @@
-import type { HotkeyKey, LlmPreset, LlmConfig, AssistantConfig, AsrProvider, AsrProviderMeta, LearningConfig, SharedLlmConfig } from '../types';
+import type { HotkeyKey, LlmPreset, LlmConfig, AssistantConfig, AsrLanguageMode, AsrProvider, AsrProviderMeta, AudioUploadFormat, LearningConfig, SharedLlmConfig } from '../types';
 
 // 按键显示名称映射
 export const KEY_DISPLAY_NAMES: Record<HotkeyKey, string> = {
@@ -127,8 +127,36 @@ export const ASR_PROVIDERS: Record<AsrProvider, AsrProviderMeta> = {
     model: 'SenseVoiceSmall',
     docsUrl: 'https://cloud.siliconflow.cn/',
   },
+  local_whisper: {
+    name: '本地 Whisper',
+    model: 'whisper.cpp (离线)',
+    docsUrl: 'https://huggingface.co/ggerganov/whisper.cpp',
+  },
 };
 
+// 识别语言选项（与后端 AsrLanguageMode 对应；不支持的语言由后端降级为自动识别）
+export const ASR_LANGUAGE_OPTIONS: { value: AsrLanguageMode; label: string }[] = [
+  { value: 'auto', label: '自动识别（推荐）' },
+  { value: 'zh', label: '中文优先' },
+  { value: 'zh_en', label: '中英混说' },
+  { value: 'en', label: '英语' },
+  { value: 'yue', label: '粤语' },
+  { value: 'ja', label: '日语' },
+  { value: 'ko', label: '韩语' },
+];
+
+export const normalizeAsrLanguageMode = (mode: unknown): AsrLanguageMode =>
+  ASR_LANGUAGE_OPTIONS.find((option) => option.value === mode)?.value ?? 'auto';
+
+export const ASR_UPLOAD_FORMAT_OPTIONS: { value: AudioUploadFormat; label: string }[] = [
+  { value: 'wav', label: 'WAV 原始音频（默认）' },
+  { value: 'flac', label: 'FLAC 无损压缩' },
+  { value: 'ogg_opus', label: 'Ogg Opus（体积最小）' },
+];
+
+// 可单独开启压缩上传的内置 HTTP 提供商
+export const ASR_UPLOAD_FORMAT_PROVIDERS: AsrProvider[] = ['qwen', 'doubao'];
+
 // 默认双热键配置
 export const DEFAULT_DUAL_HOTKEY_CONFIG = {
   dictation: {
@@ -142,7 +170,7 @@ export const DEFAULT_DUAL_HOTKEY_CONFIG = {
 export const FALLBACK_ASR_PROVIDER = 'doubao_ime' as AsrProvider;
 
 // 合法的 ASR Provider 列表（用于 localStorage 迁移校验等）
-export const VALID_ASR_PROVIDERS: AsrProvider[] = ['qwen', 'doubao', 'doubao_ime', 'siliconflow'];
+export const VALID_ASR_PROVIDERS: AsrProvider[] = ['qwen', 'doubao', 'doubao_ime', 'siliconflow', 'local_whisper'];
 
 // 默认 ASR 缓存
 export const DEFAULT_ASR_CACHE = {
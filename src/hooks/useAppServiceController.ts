@@ -19,10 +19,11 @@ import {
   DEFAULT_LLM_CONFIG,
   FALLBACK_ASR_PROVIDER,
   VALID_ASR_PROVIDERS,
+  normalizeAsrLanguageMode,
   normalizeLearningConfig,
 } from "../constants";
 import { isAsrConfigValid, normalizeAsrConfigWithFallback, getAsrProviderDisplayName } from "../utils";
-import { entriesToWords, parseEntry, entriesToStorageFormat } from "../utils/dictionaryUtils";
+import { parseEntry, entriesToStorageFormat } from "../utils/dictionaryUtils";
 import {
   fetchBuiltinDomains,
   getBuiltinWordsForDomains,
@@ -36,18 +37,19 @@ const buildRuntimeDictionary = (
   dictionaryEntries: DictionaryEntry[],
   builtinDomains: string[],
 ): string[] => {
-  const userWords = entriesToWords(dictionaryEntries);
+  // 个人词条保留权重与作用范围标记，由后端按提供商转换
+  const userEntries = entriesToStorageFormat(dictionaryEntries);
   const builtinWords = getBuiltinWordsForDomains(builtinDomains);
-  if (builtinWords.length === 0) return userWords;
+  if (builtinWords.length === 0) return userEntries;
 
   const merged = new Set<string>();
   const result: string[] = [];
 
-  for (const word of userWords) {
-    if (merged.has(word)) continue;
-    merged.add(word);
-    result.push(word);
-  }
+  dictionaryEntries.forEach((entry, index) => {
+    if (merged.has(entry.word)) return;
+    merged.add(entry.word);
+    result.push(userEntries[index]);
+  });
 
   for (const word of builtinWords) {
     if (merged.has(word)) continue;
@@ -421,7 +423,7 @@ export function useAppServiceController({
                 enable_fallback: false,
                 fallback_provider: null,
               },
-              language_mode: parsedCache.language_mode === 'zh' ? 'zh' : 'auto',
+              language_mode: normalizeAsrLanguageMode(parsedCache.language_mode),
             };
 
             let localDictionary: string[] = [];
@@ -478,7 +480,7 @@ export function useAppServiceController({
       const loadedAsrConfig: AsrConfig | null = config.asr_config
         ? {
             ...config.asr_config,
-            language_mode: config.asr_config.language_mode === "zh" ? "zh" : "auto",
+            language_mode: normalizeAsrLanguageMode(config.asr_config.language_mode),
           }
         : null;
 
@@ -0,0 +1,56 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { ProviderHealth } from "../types";
+
+export type UseAsrHealthResult = {
+  health: ProviderHealth[];
+  refreshHealth: () => Promise<void>;
+  resetHealth: (provider?: string) => Promise<void>;
+};
+
+export function useAsrHealth(): UseAsrHealthResult {
+  const [health, setHealth] = useState<ProviderHealth[]>([]);
+
+  const refreshHealth = useCallback(async () => {
+    try {
+      setHealth(await invoke<ProviderHealth[]>("get_asr_health"));
+    } catch (error) {
+      console.error("获取 ASR 健康状态失败:", error);
+    }
+  }, []);
+
+  const resetHealth = useCallback(
+    async (provider?: string) => {
+      try {
+        await invoke("reset_asr_health", { provider: provider ?? null });
+      } catch (error) {
+        console.error("重置 ASR 熔断状态失败:", error);
+      }
+      await refreshHealth();
+    },
+    [refreshHealth],
+  );
+
+  // 熔断状态变化时刷新（事件只在状态切换时发送，延迟等统计以快照为准）
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    const setupListener = async () => {
+      unlisten = await listen("asr_health_changed", () => {
+        refreshHealth();
+      });
+    };
+
+    refreshHealth();
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [refreshHealth]);
+
+  return { health, refreshHealth, resetHealth };
+}
@@ -2,8 +2,8 @@ import type React from "react";
 import { useEffect, useRef, useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { DictionaryEntry } from "../types";
-import { parseEntry } from "../utils/dictionaryUtils";
+import type { DictionaryEntry, DictionaryScope } from "../types";
+import { defaultWeight, parseEntry } from "../utils/dictionaryUtils";
 
 export type UseDictionaryResult = {
   dictionary: DictionaryEntry[];
@@ -18,6 +18,10 @@ export type UseDictionaryResult = {
   editingIndex: number | null;
   editingValue: string;
   setEditingValue: React.Dispatch<React.SetStateAction<string>>;
+  editingWeight: number;
+  setEditingWeight: React.Dispatch<React.SetStateAction<number>>;
+  editingScope: DictionaryScope;
+  setEditingScope: React.Dispatch<React.SetStateAction<DictionaryScope>>;
 
   handleAddWord: () => void;
   handleDeleteWord: (word: string) => void;
@@ -37,6 +41,8 @@ export function useDictionary(initialDictionary: string[] = []): UseDictionaryRe
   const [duplicateHint, setDuplicateHint] = useState(false);
   const [editingIndex, setEditingIndex] = useState<number | null>(null);
   const [editingValue, setEditingValue] = useState("");
+  const [editingWeight, setEditingWeight] = useState(defaultWeight("manual"));
+  const [editingScope, setEditingScope] = useState<DictionaryScope>("all");
   const duplicateHintTimeoutRef = useRef<number | null>(null);
 
   useEffect(() => {
@@ -144,8 +150,11 @@ export function useDictionary(initialDictionary: string[] = []): UseDictionaryRe
 
   // 开始编辑
   const handleStartEdit = useCallback((index: number) => {
+    const entry = dictionary[index];
     setEditingIndex(index);
-    setEditingValue(dictionary[index]?.word || "");
+    setEditingValue(entry?.word || "");
+    setEditingWeight(entry ? entry.weight ?? defaultWeight(entry.source) : defaultWeight("manual"));
+    setEditingScope(entry?.scope ?? "all");
   }, [dictionary]);
 
   // 保存编辑
@@ -163,20 +172,37 @@ export function useDictionary(initialDictionary: string[] = []): UseDictionaryRe
       return;
     }
 
-    if (word && word !== currentEntry.word) {
-      try {
+    const currentWeight = currentEntry.weight ?? defaultWeight(currentEntry.source);
+    const currentScope = currentEntry.scope ?? "all";
+    const renamed = !!word && word !== currentEntry.word;
+    const attributesChanged = editingWeight !== currentWeight || editingScope !== currentScope;
+
+    try {
+      if (renamed) {
         // 删除旧词条，添加新词条（保持来源）
         await invoke("delete_dictionary_entries", { words: [currentEntry.word] });
         await invoke("add_learned_word", { word, source: currentEntry.source });
-        // 不需要手动刷新，事件监听会自动刷新
-      } catch (error) {
-        console.error("更新词汇失败:", error);
       }
+      if (renamed || attributesChanged) {
+        // 等于默认值时不写入显式权重，保持存储格式简洁
+        const weight =
+          currentEntry.weight === undefined && editingWeight === defaultWeight(currentEntry.source)
+            ? null
+            : editingWeight;
+        await invoke("update_dictionary_entry", {
+          word: renamed ? word : currentEntry.word,
+          weight,
+          scope: editingScope,
+        });
+      }
+      // 不需要手动刷新，事件监听会自动刷新
+    } catch (error) {
+      console.error("更新词汇失败:", error);
     }
 
     setEditingIndex(null);
     setEditingValue("");
-  }, [editingIndex, editingValue, dictionary]);
+  }, [editingIndex, editingValue, editingWeight, editingScope, dictionary]);
 
   // 取消编辑
   const handleCancelEdit = useCallback(() => {
@@ -194,6 +220,10 @@ export function useDictionary(initialDictionary: string[] = []): UseDictionaryRe
     editingIndex,
     editingValue,
     setEditingValue,
+    editingWeight,
+    setEditingWeight,
+    editingScope,
+    setEditingScope,
     handleAddWord,
     handleDeleteWord,
     handleStartEdit,
@@ -17,7 +17,7 @@ export type UseHotkeyRecordingResult = {
   setRecordingMode: React.Dispatch<React.SetStateAction<HotkeyRecordingMode>>;
   recordingKeys: HotkeyKey[];
   hotkeyError: string | null;
-  resetHotkeyToDefault: (mode: "dictation" | "assistant" | "release") => void;
+  resetHotkeyToDefault: (mode: HotkeyRecordingMode) => void;
 };
 
 export function useHotkeyRecording({
@@ -115,6 +115,8 @@ export function useHotkeyRecording({
           ...nextDualHotkeyConfig.dictation,
           release_mode_keys: keysArray,
         };
+      } else if (recordingMode === "language") {
+        nextDualHotkeyConfig.language_switch_keys = keysArray;
       } else {
         nextDualHotkeyConfig.assistant = {
           ...nextDualHotkeyConfig.assistant,
@@ -146,7 +148,7 @@ export function useHotkeyRecording({
     setDualHotkeyConfig,
   ]);
 
-  const resetHotkeyToDefault = (mode: "dictation" | "assistant" | "release") => {
+  const resetHotkeyToDefault = (mode: HotkeyRecordingMode) => {
     const defaultDictationKeys = ["control_left", "meta_left"] as HotkeyKey[];
     const defaultAssistantKeys = ["alt_left", "space"] as HotkeyKey[];
     const defaultReleaseKeys = ["f2"] as HotkeyKey[];
@@ -169,6 +171,9 @@ export function useHotkeyRecording({
             release_mode_keys: defaultReleaseKeys,
           },
         };
+      } else if (mode === "language") {
+        // 语言切换快捷键默认不启用
+        next = { ...prev, language_switch_keys: null };
       } else {
         next = {
           ...prev,
@@ -1,9 +1,29 @@
 import type { Dispatch, SetStateAction } from "react";
-import { AlertCircle, Sparkles } from "lucide-react";
-import type { AsrConfig, AsrProvider } from "../types";
-import { ASR_PROVIDERS } from "../constants";
+import { AlertCircle, RotateCcw, Sparkles } from "lucide-react";
+import type { AsrConfig, AsrErrorKind, AsrProvider, CircuitState } from "../types";
+import { ASR_LANGUAGE_OPTIONS, ASR_PROVIDERS, ASR_UPLOAD_FORMAT_OPTIONS, ASR_UPLOAD_FORMAT_PROVIDERS } from "../constants";
 import { ApiKeyInput, Toggle, ConfigSelect } from "../components/common";
 import { useConfigSave } from "../contexts/ConfigSaveContext";
+import { useAsrHealth } from "../hooks/useAsrHealth";
+
+const CIRCUIT_LABELS: Record<CircuitState, { label: string; className: string }> = {
+  closed: { label: "正常", className: "bg-emerald-50 text-emerald-700 border-emerald-200" },
+  open: { label: "熔断中", className: "bg-red-50 text-red-700 border-red-200" },
+  half_open: { label: "恢复探测中", className: "bg-amber-50 text-amber-700 border-amber-200" },
+};
+
+const ERROR_KIND_LABELS: Record<AsrErrorKind, string> = {
+  auth: "鉴权失败",
+  rate_limited: "限流",
+  timeout: "超时",
+  network: "网络错误",
+  server: "服务端错误",
+  invalid_audio: "音频无效",
+  other: "其他错误",
+};
+
+const providerLabel = (provider: string) =>
+  ASR_PROVIDERS[provider as AsrProvider]?.name ?? provider;
 
 export type AsrPageProps = {
   asrConfig: AsrConfig;
@@ -27,6 +47,7 @@ export function AsrPage({
   const externalOnlySyncStatus = isExternalSyncing
     ? ("syncing" as const)
     : undefined;
+  const { health, resetHealth } = useAsrHealth();
 
   return (
     <div className="mx-auto max-w-3xl space-y-6 font-sans">
@@ -157,12 +178,44 @@ export function AsrPage({
                 }}
                 syncStatus={externalOnlySyncStatus}
                 disabled={isRunning}
-                options={[
-                  { value: "auto", label: "自动识别（推荐）" },
-                  { value: "zh", label: "中文优先" },
-                ]}
+                options={ASR_LANGUAGE_OPTIONS}
               />
             </div>
+
+            {ASR_UPLOAD_FORMAT_PROVIDERS.includes(asrConfig.selection.active_provider) && (
+              <div className="space-y-2">
+                <label className="text-xs font-bold text-stone-500">上传格式</label>
+                <ConfigSelect
+                  value={asrConfig.upload_formats?.[asrConfig.selection.active_provider] ?? "wav"}
+                  onChange={(format) => {
+                    setAsrConfig((prev) => ({
+                      ...prev,
+                      upload_formats: {
+                        ...prev.upload_formats,
+                        [prev.selection.active_provider]: format,
+                      },
+                    }));
+                  }}
+                  onCommit={async (format) => {
+                    await saveImmediately({
+                      asrConfig: {
+                        ...asrConfig,
+                        upload_formats: {
+                          ...asrConfig.upload_formats,
+                          [asrConfig.selection.active_provider]: format,
+                        },
+                      },
+                    });
+                  }}
+                  syncStatus={externalOnlySyncStatus}
+                  disabled={isRunning}
+                  options={ASR_UPLOAD_FORMAT_OPTIONS}
+                />
+                <p className="text-xs text-stone-400 ml-1">
+                  仅对当前提供商生效，接口不支持的格式会自动降级为 FLAC 或 WAV。
+                </p>
+              </div>
+            )}
           </div>
         </div>
 
@@ -236,6 +289,61 @@ export function AsrPage({
           )}
         </div>
       </div>
+
+      <div className="bg-white border border-[var(--stone)] rounded-2xl p-6 space-y-4">
+        <div className="flex items-center justify-between">
+          <span className="text-xs font-bold text-stone-500 uppercase tracking-widest">服务健康</span>
+          <button
+            type="button"
+            onClick={() => resetHealth()}
+            disabled={health.length === 0}
+            className="flex items-center gap-1 px-2 py-1 text-xs font-semibold text-stone-500 hover:text-[var(--ink)] disabled:opacity-40 transition-colors"
+          >
+            <RotateCcw size={12} />
+            重置
+          </button>
+        </div>
+
+        {health.length === 0 ? (
+          <div className="text-xs text-stone-400">暂无调用记录</div>
+        ) : (
+          <div className="space-y-2">
+            {health.map((item) => {
+              const circuit = CIRCUIT_LABELS[item.circuit];
+              return (
+                <div
+                  key={item.provider}
+                  className="flex items-center justify-between gap-3 p-3 bg-[var(--paper)] border border-[var(--stone)] rounded-xl text-xs"
+                >
+                  <div className="space-y-1 min-w-0">
+                    <div className="flex items-center gap-2">
+                      <span className="font-bold text-stone-700">{providerLabel(item.provider)}</span>
+                      <span className={`px-1.5 py-0.5 border rounded-md font-semibold ${circuit.className}`}>
+                        {circuit.label}
+                      </span>
+                    </div>
+                    <div className="text-stone-400 truncate">
+                      成功 {item.successes} / 失败 {item.failures}
+                      {item.avg_latency_ms !== null && ` · 平均 ${item.avg_latency_ms}ms`}
+                      {item.last_error_kind && ` · 最近错误：${ERROR_KIND_LABELS[item.last_error_kind]}`}
+                      {item.retry_in_ms !== null && ` · ${Math.ceil(item.retry_in_ms / 1000)}s 后重试`}
+                    </div>
+                  </div>
+                  {item.circuit !== "closed" && (
+                    <button
+                      type="button"
+                      onClick={() => resetHealth(item.provider)}
+                      className="flex-shrink-0 px-2 py-1 text-xs font-semibold text-[var(--steel)] hover:text-[var(--ink)] transition-colors"
+                    >
+                      恢复
+                    </button>
+                  )}
+                </div>
+              );
+            })}
+          </div>
+        )}
+      </div>
     </div>
   );
 }
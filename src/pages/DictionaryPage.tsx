@@ -1,7 +1,7 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { AlertCircle, CheckCircle2, Plus, Trash2 } from "lucide-react";
 import { SourceBadge } from "../components/learning/SourceBadge";
-import type { DictionaryEntry } from "../types";
+import type { DictionaryEntry, DictionaryScope } from "../types";
 import {
   BUILTIN_DICTIONARY_DOMAINS,
   BUILTIN_DICTIONARY_LIMIT,
@@ -10,6 +10,18 @@ import {
   getBuiltinWordsForDomains,
   setBuiltinDomainsSnapshot,
 } from "../utils/builtinDictionary";
+import { MAX_DICTIONARY_WEIGHT, MIN_DICTIONARY_WEIGHT } from "../utils/dictionaryUtils";
+
+const SCOPE_LABELS: Record<DictionaryScope, string> = {
+  all: "识别+润色",
+  asr: "仅识别",
+  llm: "仅润色",
+};
+
+const WEIGHT_OPTIONS = Array.from(
+  { length: MAX_DICTIONARY_WEIGHT - MIN_DICTIONARY_WEIGHT + 1 },
+  (_, i) => MIN_DICTIONARY_WEIGHT + i,
+);
 
 export type DictionaryPageProps = {
   dictionary: DictionaryEntry[];
@@ -20,6 +32,10 @@ export type DictionaryPageProps = {
   editingIndex: number | null;
   editingValue: string;
   setEditingValue: (next: string) => void;
+  editingWeight: number;
+  setEditingWeight: (next: number) => void;
+  editingScope: DictionaryScope;
+  setEditingScope: (next: DictionaryScope) => void;
   handleAddWord: () => void;
   handleDeleteWord: (id: string) => void;
   handleStartEdit: (index: number) => void;
@@ -44,6 +60,10 @@ export function DictionaryPage({
   editingIndex,
   editingValue,
   setEditingValue,
+  editingWeight,
+  setEditingWeight,
+  editingScope,
+  setEditingScope,
   handleAddWord,
   handleDeleteWord: _handleDeleteWord,
   handleStartEdit,
@@ -210,7 +230,7 @@ export function DictionaryPage({
           <>
             <div className="flex items-center gap-2 p-3 bg-[rgba(120,113,108,0.08)] border border-[rgba(120,113,108,0.18)] rounded-xl text-xs text-[var(--ink)]">
               <AlertCircle size={14} className="flex-shrink-0 text-[var(--ink)]" />
-              <span>添加常用词汇（专业术语、人名、产品名等），提升语音识别准确率。</span>
+              <span>添加常用词汇（专业术语、人名、产品名等），提升语音识别准确率。编辑词条可设置权重（1~10），提供商热词数量受限时优先保留高权重词条。</span>
             </div>
 
             <div className="space-y-2">
@@ -337,6 +357,32 @@ export function DictionaryPage({
                       className="w-28 px-2 py-0.5 bg-transparent text-sm focus:outline-none text-stone-700 disabled:opacity-60"
                       autoFocus
                     />
+                    <select
+                      value={editingWeight}
+                      disabled={isRunning}
+                      onChange={(e) => setEditingWeight(Number(e.target.value))}
+                      className="bg-transparent text-xs text-stone-500 focus:outline-none disabled:opacity-60"
+                      title="权重"
+                    >
+                      {WEIGHT_OPTIONS.map((weight) => (
+                        <option key={weight} value={weight}>
+                          ×{weight}
+                        </option>
+                      ))}
+                    </select>
+                    <select
+                      value={editingScope}
+                      disabled={isRunning}
+                      onChange={(e) => setEditingScope(e.target.value as DictionaryScope)}
+                      className="bg-transparent text-xs text-stone-500 focus:outline-none disabled:opacity-60"
+                      title="作用范围"
+                    >
+                      {(Object.keys(SCOPE_LABELS) as DictionaryScope[]).map((scope) => (
+                        <option key={scope} value={scope}>
+                          {SCOPE_LABELS[scope]}
+                        </option>
+                      ))}
+                    </select>
                     <button
                       onClick={handleSaveEdit}
                       disabled={isRunning}
@@ -373,6 +419,16 @@ export function DictionaryPage({
                       {entry.word}
                     </span>
                     <SourceBadge source={entry.source} />
+                    {entry.weight !== undefined && (
+                      <span className="text-[10px] font-semibold text-stone-400" title="权重">
+                        ×{entry.weight}
+                      </span>
+                    )}
+                    {entry.scope && entry.scope !== "all" && (
+                      <span className="text-[10px] font-semibold text-stone-400">
+                        {SCOPE_LABELS[entry.scope]}
+                      </span>
+                    )}
                     <div className="flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity ml-1">
                       <button
                         onClick={(e) => {
@@ -1,4 +1,4 @@
-import { Keyboard, Mic, MessageSquare, RotateCcw, ToggleLeft } from "lucide-react";
+import { Keyboard, Languages, Mic, MessageSquare, RotateCcw, ToggleLeft } from "lucide-react";
 import type { AppStatus, DualHotkeyConfig, HotkeyKey, HotkeyRecordingMode } from "../types";
 import { formatHotkeyDisplay, formatHotkeyKeysDisplay } from "../utils";
 
@@ -11,7 +11,7 @@ export type HotkeysPageProps = {
   recordingKeys: HotkeyKey[];
   hotkeyError: string | null;
   dualHotkeyConfig: DualHotkeyConfig;
-  resetHotkeyToDefault: (mode: "dictation" | "assistant" | "release") => void;
+  resetHotkeyToDefault: (mode: HotkeyRecordingMode) => void;
 };
 
 const RenderKeys = ({ text, isRecording }: { text: string; isRecording?: boolean }) => {
@@ -245,6 +245,64 @@ export function HotkeysPage({
               )}
             </div>
           </div>
+
+          {/* Language Switch */}
+          <div className="p-5 flex items-center justify-between group hover:bg-[var(--paper)] transition-colors">
+            <div className="flex items-center gap-4">
+              <div className="w-10 h-10 rounded-full bg-emerald-50 text-emerald-600 flex items-center justify-center border border-emerald-100">
+                <Languages size={20} />
+              </div>
+              <div>
+                <div className="text-sm font-bold text-[var(--ink)]">
+                  切换识别语言
+                </div>
+                <div className="mt-1.5">
+                  <RenderKeys
+                    text={
+                      isRecordingHotkey && recordingMode === "language"
+                        ? recordingKeys.join(" + ")
+                        : formatHotkeyKeysDisplay(dualHotkeyConfig.language_switch_keys ?? [])
+                    }
+                    isRecording={isRecordingHotkey && recordingMode === "language"}
+                  />
+                </div>
+                <div className="text-[10px] text-stone-400 mt-1 font-medium">
+                  未录音时按下，在当前引擎支持的语言间循环切换
+                </div>
+              </div>
+            </div>
+            <div className="flex items-center gap-2">
+              {isRecordingHotkey && recordingMode === "language" ? (
+                <button
+                  onClick={() => setIsRecordingHotkey(false)}
+                  className="px-3 py-1.5 rounded-lg border border-[var(--stone)] bg-white text-xs font-bold hover:bg-stone-50"
+                >
+                  取消
+                </button>
+              ) : (
+                <>
+                  <button
+                    onClick={() => {
+                      setRecordingMode("language");
+                      setIsRecordingHotkey(true);
+                    }}
+                    disabled={!canRecord}
+                    className="px-3 py-1.5 rounded-lg border border-[var(--stone)] bg-white text-[var(--ink)] text-xs font-bold hover:border-stone-400 disabled:opacity-50 transition-colors"
+                  >
+                    录制
+                  </button>
+                  <button
+                    onClick={() => resetHotkeyToDefault("language")}
+                    disabled={!canRecord}
+                    className="p-1.5 rounded-lg text-stone-400 hover:text-[var(--ink)] hover:bg-stone-100 disabled:opacity-30 transition-colors"
+                    title="清除"
+                  >
+                    <RotateCcw size={16} />
+                  </button>
+                </>
+              )}
+            </div>
+          </div>
         </div>
       </div>
     </div>
@@ -1,7 +1,7 @@
-import { Download, Power, RefreshCw, SlidersHorizontal, VolumeX, GraduationCap, Settings2, HelpCircle } from "lucide-react";
+import { Download, Power, RefreshCw, SlidersHorizontal, VolumeX, GraduationCap, Settings2, HelpCircle, Mic, AudioLines } from "lucide-react";
 import { useState } from "react";
 import type { AppStatus, UpdateStatus, LearningConfig, SharedLlmConfig } from "../types";
-import { Toggle, ThemeSelector, LlmConnectionConfig, Tooltip } from "../components/common";
+import { Toggle, ThemeSelector, LlmConnectionConfig, Tooltip, InputDeviceSelector, VadSettings, NoiseSuppressionSettings, PrerollSettings, AutoStopSettings, OpenMicSettings, WakeWordSettings, RecordingArchiveSettings } from "../components/common";
 import { RedDot } from "../components/common/RedDot";
 import { SettingsModal } from "../components/modals/SettingsModal";
 import { normalizeLearningConfig } from "../constants";
@@ -132,6 +132,56 @@ export function PreferencesPage({
           />
         </div>
 
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl space-y-3">
+          <div className="flex items-center gap-3">
+            <div className="p-2 rounded-xl bg-white border border-[var(--stone)] text-stone-500">
+              <Mic size={16} />
+            </div>
+            <div>
+              <div className="text-sm font-bold text-[var(--ink)]">录音输入设备</div>
+              <div className="text-[11px] text-stone-400 font-semibold">耳机、USB 麦克风按优先级自动选择</div>
+            </div>
+          </div>
+          <InputDeviceSelector disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl space-y-3">
+          <div className="flex items-center gap-3">
+            <div className="p-2 rounded-xl bg-white border border-[var(--stone)] text-stone-500">
+              <AudioLines size={16} />
+            </div>
+            <div>
+              <div className="text-sm font-bold text-[var(--ink)]">语音检测</div>
+              <div className="text-[11px] text-stone-400 font-semibold">区分人声与风扇、键盘等背景噪声</div>
+            </div>
+          </div>
+          <VadSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <NoiseSuppressionSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <PrerollSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <AutoStopSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <OpenMicSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <WakeWordSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
+        <div className="p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
+          <RecordingArchiveSettings disabled={status === "recording" || status === "transcribing"} />
+        </div>
+
         <div className="flex items-center justify-between p-4 bg-[var(--paper)] border border-[var(--stone)] rounded-2xl">
           <div className="flex items-center gap-3">
             <div
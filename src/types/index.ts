@@ -23,33 +23,103 @@ export interface HotkeyConfig {
 export interface DualHotkeyConfig {
   dictation: HotkeyConfig;  // 听写模式（默认 Ctrl+Win）
   assistant: HotkeyConfig;  // AI助手模式（默认 Alt+Space）
+  language_switch_keys?: HotkeyKey[] | null;  // 循环切换识别语言（未设置则不启用）
 }
 
 // ASR 配置
-export type AsrProvider = 'qwen' | 'doubao' | 'doubao_ime' | 'siliconflow';
-export type AsrLanguageMode = 'zh' | 'auto';
+export type AsrProvider = 'qwen' | 'doubao' | 'doubao_ime' | 'siliconflow' | 'local_whisper';
+export type AsrLanguageMode = 'auto' | 'zh' | 'zh_en' | 'en' | 'yue' | 'ja' | 'ko';
+
+// HTTP 提供商上传音频格式：默认 WAV，压缩格式按提供商单独开启，接口不支持时依次降级为 FLAC、WAV
+export type AudioUploadFormat = 'wav' | 'flac' | 'ogg_opus';
 
 export interface AsrCredentials {
   qwen_api_key: string;
   sensevoice_api_key: string;
   doubao_app_id: string;
   doubao_access_token: string;
-  // 豆包输入法 ASR 凭据 (自动注册获取，无需用户配置)
+  // 豆包输入法 ASR 凭据：旧版字段，后端已迁移到独立的凭据存储，始终为空
   doubao_ime_device_id: string;
   doubao_ime_token: string;
   doubao_ime_cdid: string;
 }
 
+export type AsrRacePolicy = 'sequential' | 'race' | 'hedged' | 'best_of_n';
+
+export interface AsrRaceStrategy {
+  policy: AsrRacePolicy;
+  max_retries: number;
+  retry_backoff_ms: number;
+  attempt_timeout_ms: number;
+  hedge_delay_ms: number;
+}
+
+// ASR 提供商健康状态（熔断器）
+export type AsrErrorKind =
+  | 'auth' | 'rate_limited' | 'timeout' | 'network' | 'server' | 'invalid_audio' | 'other';
+export type CircuitState = 'closed' | 'open' | 'half_open';
+
+export interface ProviderHealth {
+  provider: string;  // 内置提供商名称、`openai:<id>` 或 `ws:<id>`
+  circuit: CircuitState;
+  consecutive_failures: number;
+  successes: number;
+  failures: number;
+  last_latency_ms: number | null;
+  avg_latency_ms: number | null;
+  last_error_kind: AsrErrorKind | null;
+  last_error: string | null;
+  retry_in_ms: number | null;  // 熔断中：距离允许探测的剩余时间
+}
+
 export interface AsrSelection {
   active_provider: AsrProvider;
   enable_fallback: boolean;
   fallback_provider: AsrProvider | null;
+  // 备用提供商有序列表（为空时沿用 fallback_provider）
+  providers?: AsrProvider[];
+  strategy?: AsrRaceStrategy;
+}
+
+// 本地 Whisper 配置（离线 ASR）
+export interface LocalWhisperConfig {
+  model_path: string;
+  threads: number; // 0 表示自动
+}
+
+// OpenAI 兼容转写服务实例，选择时使用 `openai:<id>` 作为提供商标识
+export interface OpenAiAsrProvider {
+  id: string;
+  name: string;
+  endpoint: string; // 基础 URL 或完整的 /audio/transcriptions 地址
+  api_key: string;
+  model: string;
+  language: string; // 留空跟随 language_mode
+  prompt: string; // 词库词条会追加在其后
+  response_format: 'json' | 'verbose_json' | 'text';
+  upload_format?: AudioUploadFormat; // 默认 wav
+}
+
+// 自建 WebSocket 流式服务协议
+export type WebSocketAsrProtocol = 'funasr_2pass' | 'funasr_online' | 'sherpa_onnx' | 'vosk';
+
+// 自建 WebSocket 流式服务实例（FunASR / sherpa-onnx / Vosk），选择时使用 `ws:<id>` 作为提供商标识
+export interface WebSocketAsrProvider {
+  id: string;
+  name: string;
+  endpoint: string; // 如 ws://127.0.0.1:10095
+  protocol: WebSocketAsrProtocol;
 }
 
 export interface AsrConfig {
   credentials: AsrCredentials;
   selection: AsrSelection;
   language_mode: AsrLanguageMode;
+  local_whisper?: LocalWhisperConfig;
+  openai_providers?: OpenAiAsrProvider[];
+  // 内置 HTTP 提供商（千问 / 豆包）单独开启的上传格式，未列出的上传 WAV
+  upload_formats?: Partial<Record<AsrProvider, AudioUploadFormat>>;
+  websocket_providers?: WebSocketAsrProvider[];
 }
 
 // LLM 配置
@@ -127,21 +197,158 @@ export interface AppConfig {
   hotkey_config: HotkeyConfig;            // 保留用于迁移
   dual_hotkey_config: DualHotkeyConfig;
   enable_mute_other_apps: boolean;
-  dictionary: string[];  // 简化格式："word" 或 "word|auto"
+  preferred_input_devices?: string[];  // 首选录音输入设备（按顺序回退，为空使用系统默认）
+  vad_config?: VadConfig;  // 语音活动检测
+  noise_suppression?: NoiseSuppressionConfig;  // 背景降噪
+  auto_stop?: AutoStopConfig;  // 免提自动结束
+  open_mic?: OpenMicConfig;  // 开放麦克风（语音触发听写）
+  wake_word?: WakeWordConfig;  // 唤醒词
+  recording_archive?: RecordingArchiveConfig;  // 录音归档
+  preroll?: PrerollConfig;  // 预录缓冲
+  tnl_config?: TnlConfig;  // 技术术语规范化
+  dictionary: string[];  // 简化格式："word[|auto][|w=N][|asr|llm]"
   builtin_dictionary_domains: string[];  // 内置词库领域列表
   theme: string;
 }
 
+// 录音输入设备
+export interface InputDeviceInfo {
+  name: string;
+  is_default: boolean;
+}
+
+// 语音活动检测引擎：rms = 能量阈值，gmm = 子带高斯混合模型
+export type VadEngine = "rms" | "gmm";
+
+export interface VadConfig {
+  engine: VadEngine;
+  aggressiveness: number;  // 0~3，越大越严格
+}
+
+// 免提自动结束（切换模式与松手锁定模式）
+export interface AutoStopConfig {
+  enabled: boolean;
+  silence_ms: number;  // 说话后静音多久自动结束
+  max_duration_secs: number;  // 最长录音时长，0 表示不限制
+}
+
+// 预录缓冲：快捷键服务运行时麦克风常开，保留最近的音频补在每次录音开头
+export interface PrerollConfig {
+  enabled: boolean;
+  duration_ms: number;  // 预录时长（100~1000 毫秒）
+}
+
+// 开放麦克风：检测到持续语音自动开始听写，静音后自动结束
+export interface OpenMicConfig {
+  enabled: boolean;
+  trigger_ms: number;  // 连续语音多久后开始听写
+  silence_ms: number;  // 说话后静音多久结束听写
+  max_duration_secs: number;  // 单次听写最长时长
+}
+
+// 触发模式：听写 / AI 助手
+export type TriggerMode = "dictation" | "ai_assistant";
+
+// 唤醒词：录制样本后在后台检测，命中时开始对应模式的会话
+export interface WakeWord {
+  id: string;
+  phrase: string;  // 转写后从文本开头去掉
+  trigger_mode: TriggerMode;
+  samples: string[];  // 样本文件名
+}
+
+export interface WakeWordConfig {
+  enabled: boolean;
+  sensitivity: number;  // 0~1，越高越容易唤醒
+  words: WakeWord[];
+}
+
+// TNL 技术术语规范化（ASR 结果进入润色前执行）
+export interface TnlConfig {
+  enabled: boolean;
+}
+
+// 录音归档：每次录音以 FLAC 保存到本地，可换提供商 / 预设重新转写
+export interface RecordingArchiveConfig {
+  enabled: boolean;
+  max_age_days: number;  // 保留天数，0 表示不按时间清理
+  max_total_mb: number;  // 总大小上限，0 表示不限
+}
+
+// 一次转写结果（录音时的结果或重新转写）
+export interface ArchivedTranscription {
+  created_at: number;  // Unix 毫秒
+  provider?: string;  // 重新转写使用的提供商
+  preset_id?: string;  // 润色预设（未润色为空）
+  tnl?: boolean;
+  text: string;
+  original_text?: string;  // 润色 / 规范化前的 ASR 原文
+  transcript?: AsrTranscript;
+}
+
+export interface ArchivedRecording {
+  id: string;
+  created_at: number;  // Unix 毫秒
+  duration_ms: number;
+  mode: TriggerMode;
+  audio_file: string;
+  size_bytes: number;
+  transcription?: ArchivedTranscription;
+  retranscriptions: ArchivedTranscription[];
+}
+
+// 开放麦克风运行状态（open_mic_state 事件）
+export interface OpenMicState {
+  enabled: boolean;
+  muted: boolean;  // 托盘或设置页临时静音（仅本次运行）
+  listening: boolean;  // 麦克风正在被监听
+}
+
+// 背景降噪（RNNoise，在 AGC 之前）
+export interface NoiseSuppressionConfig {
+  enabled: boolean;
+  strength: number;  // 0~1，越大降噪越强
+}
+
+// 词条作用范围：全部 / 仅 ASR 热词 / 仅 LLM 词库增强
+export type DictionaryScope = "all" | "asr" | "llm";
+
 // 词库条目
 export interface DictionaryEntry {
   id: string;
   word: string;
   source: "manual" | "auto";
+  weight?: number;  // 热词权重 1~10，未设置时按来源取默认值（手动 5，自动 3）
+  scope?: DictionaryScope;  // 缺省视为 "all"
   added_at: number;  // Unix timestamp (seconds)
   frequency: number;
   last_used_at: number | null;  // Unix timestamp (seconds)
 }
 
+// ASR 逐词信息（时间单位毫秒，置信度 0~1，提供商未返回时缺省）
+export interface TranscriptWord {
+  text: string;
+  start_ms?: number;
+  end_ms?: number;
+  confidence?: number;
+}
+
+// ASR 分段（句子 / utterance）
+export interface TranscriptSegment {
+  text: string;
+  start_ms?: number;
+  end_ms?: number;
+  confidence?: number;
+  words?: TranscriptWord[];
+}
+
+// 完整的 ASR 转录结果
+export interface AsrTranscript {
+  text: string;
+  segments?: TranscriptSegment[];
+  alternatives?: string[]; // 其他候选结果（n-best）
+}
+
 // 转录结果
 export interface TranscriptionResult {
   text: string;
@@ -152,6 +359,7 @@ export interface TranscriptionResult {
   total_time_ms: number;
   mode?: string; // "normal" | "assistant"
   inserted?: boolean;
+  transcript?: AsrTranscript; // ASR 原始结果（分段、时间戳、置信度）
 }
 
 // 历史记录
@@ -197,7 +405,7 @@ export interface UsageStats {
 }
 
 // 热键录制模式
-export type HotkeyRecordingMode = 'dictation' | 'assistant' | 'release';
+export type HotkeyRecordingMode = 'dictation' | 'assistant' | 'release' | 'language';
 
 // ============================================================================
 // 自动词库学习相关类型
@@ -4,7 +4,15 @@
  * 统一的词典 ID 生成和格式转换逻辑
  */
 
-import type { DictionaryEntry } from "../types";
+import type { DictionaryEntry, DictionaryScope } from "../types";
+
+export const MIN_DICTIONARY_WEIGHT = 1;
+export const MAX_DICTIONARY_WEIGHT = 10;
+
+/** 未显式设置权重时的默认值（与后端 dictionary_utils 保持一致） */
+export function defaultWeight(source: DictionaryEntry["source"]): number {
+  return source === "auto" ? 3 : 5;
+}
 
 /**
  * 生成唯一的词典条目 ID
@@ -23,16 +31,33 @@ export function generateDictionaryId(): string {
 /**
  * 解析词典字符串格式
  *
- * @param entry - "word" 或 "word|auto"
+ * @param entry - "word[|auto][|w=N][|asr|llm]"，附加字段均可省略
  * @returns DictionaryEntry
  */
 export function parseEntry(entry: string): DictionaryEntry {
-  const parts = entry.split("|");
+  const [word, ...fields] = entry.split("|");
   const now = Date.now();
+  let source: DictionaryEntry["source"] = "manual";
+  let weight: number | undefined;
+  let scope: DictionaryScope = "all";
+  for (const field of fields.map((f) => f.trim())) {
+    if (field === "auto") {
+      source = "auto";
+    } else if (field === "asr" || field === "llm") {
+      scope = field;
+    } else if (field.startsWith("w=")) {
+      const parsed = Number.parseInt(field.slice(2), 10);
+      if (Number.isFinite(parsed)) {
+        weight = Math.min(MAX_DICTIONARY_WEIGHT, Math.max(MIN_DICTIONARY_WEIGHT, parsed));
+      }
+    }
+  }
   return {
     id: generateDictionaryId(),
-    word: parts[0],
-    source: parts[1] === "auto" ? "auto" : "manual",
+    word: word.trim(),
+    source,
+    weight,
+    scope,
     added_at: Math.floor(now / 1000),
     frequency: 0,
     last_used_at: null,
@@ -40,22 +65,32 @@ export function parseEntry(entry: string): DictionaryEntry {
 }
 
 /**
- * 从 DictionaryEntry[] 提取词汇字符串（用于 ASR API）
+ * 将单个 DictionaryEntry 转换为存储格式
+ */
+export function entryToStorageFormat(entry: DictionaryEntry): string {
+  let result = entry.word;
+  if (entry.source === "auto") result += "|auto";
+  if (entry.weight !== undefined) result += `|w=${entry.weight}`;
+  if (entry.scope && entry.scope !== "all") result += `|${entry.scope}`;
+  return result;
+}
+
+/**
+ * 从 DictionaryEntry[] 提取纯词汇字符串（不含权重与作用范围）
  */
 export function entriesToWords(entries: DictionaryEntry[]): string[] {
   return entries.map((e) => e.word);
 }
 
 /**
- * 将 DictionaryEntry[] 转换为存储格式（保留 source 信息）
+ * 将 DictionaryEntry[] 转换为存储格式（保留来源、权重与作用范围）
  *
  * - source = "manual" -> "word"
  * - source = "auto" -> "word|auto"
+ * - 显式权重 / 非默认范围追加 "|w=N" / "|asr" / "|llm"
  */
 export function entriesToStorageFormat(entries: DictionaryEntry[]): string[] {
-  return entries.map((e) =>
-    e.source === "auto" ? `${e.word}|auto` : e.word
-  );
+  return entries.map(entryToStorageFormat);
 }
 
 /**
@@ -14,6 +14,9 @@ type OverlayStatus = "recording" | "transcribing";
 // 静音阈值常量（与后端 NOISE_FLOOR 对齐）
 const SILENCE_THRESHOLD = 0.005;
 
+// 中间识别结果最多显示的字符数（保留末尾，即最新说出的内容）
+const PARTIAL_MAX_CHARS = 14;
+
 // Hook 返回类型
 interface AudioVisualizationState {
   level: number;
@@ -212,6 +215,9 @@ export default function OverlayWindow() {
   const [isLocked, setIsLocked] = useState(false);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [theme, setTheme] = useState("light");
+  const [partialText, setPartialText] = useState("");
+  // 免提自动结束倒计时（剩余毫秒数）
+  const [autoStopRemaining, setAutoStopRemaining] = useState<number | null>(null);
 
   const { level: audioLevel, time: animationTime } = useSmoothAudioLevel(status === "recording");
 
@@ -249,6 +255,16 @@ export default function OverlayWindow() {
         setStatus("recording");
         setIsLocked(false);
         setIsSubmitting(false);
+        setPartialText("");
+        setAutoStopRemaining(null);
+      }))) return;
+
+      if (!(await registerListener("auto_stop_countdown", (event) => {
+        setAutoStopRemaining(event.payload as number | null);
+      }))) return;
+
+      if (!(await registerListener("transcription_partial", (event) => {
+        setPartialText(event.payload as string);
       }))) return;
 
       if (!(await registerListener("recording_locked", () => {
@@ -259,6 +275,7 @@ export default function OverlayWindow() {
 
       if (!(await registerListener("recording_stopped", () => {
         setStatus("transcribing");
+        setAutoStopRemaining(null);
       }))) return;
 
       if (!(await registerListener("transcribing", () => {
@@ -269,18 +286,24 @@ export default function OverlayWindow() {
         setStatus("recording");
         setIsLocked(false);
         setIsSubmitting(false);
+        setPartialText("");
+        setAutoStopRemaining(null);
       }))) return;
 
       if (!(await registerListener("error", () => {
         setStatus("recording");
         setIsLocked(false);
         setIsSubmitting(false);
+        setPartialText("");
+        setAutoStopRemaining(null);
       }))) return;
 
       if (!(await registerListener("transcription_cancelled", () => {
         setStatus("recording");
         setIsLocked(false);
         setIsSubmitting(false);
+        setPartialText("");
+        setAutoStopRemaining(null);
       }))) return;
     };
 
@@ -365,6 +388,16 @@ export default function OverlayWindow() {
           <LoadingIndicator />
         )}
       </div>
+      {status === "recording" && autoStopRemaining !== null && (
+        <div className="overlay-partial">
+          {(autoStopRemaining / 1000).toFixed(1)} 秒后自动结束
+        </div>
+      )}
+      {status === "recording" && autoStopRemaining === null && partialText && (
+        <div className="overlay-partial">
+          {Array.from(partialText).slice(-PARTIAL_MAX_CHARS).join("")}
+        </div>
+      )}
     </div>
   );
 }